@@ -189,6 +189,31 @@ impl ZBuf {
         }
     }
 
+    /// Returns a reader over the content of this [`ZBuf`][ZBuf].
+    ///
+    /// The returned [`ZBufReader`][ZBufReader] implements [`std::io::Read`], [`std::io::BufRead`]
+    /// and [`bytes::Buf`]: it allows to deserialize the payload directly from its [`ZSlice`][ZSlice]s,
+    /// without the copy required by [`contiguous()`][ZBuf::contiguous] on non-contiguous buffers.
+    ///
+    /// ```
+    /// use std::io::Read;
+    /// use zenoh_buffers::{ZBuf, ZSlice};
+    ///
+    /// let zbuf = ZBuf::from(vec![ZSlice::from(vec![0_u8, 1]), ZSlice::from(vec![2_u8, 3])]);
+    /// let mut bytes = vec![];
+    /// zbuf.reader().read_to_end(&mut bytes).unwrap();
+    /// assert_eq!(bytes, vec![0_u8, 1, 2, 3]);
+    /// ```
+    #[inline(always)]
+    pub fn reader(&self) -> ZBufReader<'_> {
+        ZBufReader {
+            inner: self,
+            read: 0,
+            slice: 0,
+            byte: 0,
+        }
+    }
+
     #[inline(always)]
     pub fn zslices_num(&self) -> usize {
         match &self.slices {
@@ -322,6 +347,7 @@ impl fmt::Debug for ZBuf {
                     match $slice.buf {
                         ZSliceBuffer::NetSharedBuffer(_) => write!(f, " BUF:")?,
                         ZSliceBuffer::NetOwnedBuffer(_) => write!(f, " BUF:")?,
+                        ZSliceBuffer::Bytes(_) => write!(f, " BUF:")?,
                         ZSliceBuffer::ShmBuffer(_) => write!(f, " SHM_BUF:")?,
                         ZSliceBuffer::ShmInfo(_) => write!(f, " SHM_INFO:")?,
                     }
@@ -404,6 +430,20 @@ impl<'a> ZBufReader<'a> {
     fn curr_slice(&self) -> Option<&ZSlice> {
         self.inner.get_zslice(self.slice)
     }
+    // Returns the unread bytes of the first slice that has some, if any
+    fn curr_chunk(&self) -> &'a [u8] {
+        let inner: &'a ZBuf = self.inner;
+        let mut byte = self.byte;
+        let mut index = self.slice;
+        while let Some(slice) = inner.get_zslice(index) {
+            if byte < slice.len() {
+                return &slice.as_slice()[byte..];
+            }
+            byte = 0;
+            index += 1;
+        }
+        &[]
+    }
     // #[inline(always)]
     // fn curr_slice_mut(&mut self) -> Option<&mut ZSlice> {
     //     self.inner.get_zslice_mut(self.slice)
@@ -495,6 +535,42 @@ impl<'a> io::Read for ZBufReader<'a> {
     }
 }
 
+impl<'a> io::BufRead for ZBufReader<'a> {
+    #[inline]
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        Ok(self.curr_chunk())
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        let amt = amt.min(crate::reader::Reader::remaining(self));
+        self.skip_bytes_no_check(amt);
+    }
+}
+
+impl<'a> bytes::Buf for ZBufReader<'a> {
+    #[inline]
+    fn remaining(&self) -> usize {
+        crate::reader::Reader::remaining(self)
+    }
+
+    #[inline]
+    fn chunk(&self) -> &[u8] {
+        self.curr_chunk()
+    }
+
+    #[inline]
+    fn advance(&mut self, cnt: usize) {
+        assert!(
+            cnt <= crate::reader::Reader::remaining(self),
+            "ZBufReader can not advance by {} bytes, only {} remain",
+            cnt,
+            crate::reader::Reader::remaining(self)
+        );
+        self.skip_bytes_no_check(cnt);
+    }
+}
+
 /*************************************/
 /*            ZBUF FROM              */
 /*************************************/
@@ -522,6 +598,19 @@ impl From<Vec<ZSlice>> for ZBuf {
     }
 }
 
+impl From<bytes::Bytes> for ZBuf {
+    fn from(buf: bytes::Bytes) -> ZBuf {
+        ZBuf::from(ZSlice::from(buf))
+    }
+}
+
+impl From<Vec<bytes::Bytes>> for ZBuf {
+    fn from(bufs: Vec<bytes::Bytes>) -> ZBuf {
+        let v: Vec<ZSlice> = bufs.into_iter().map(ZSlice::from).collect();
+        ZBuf::from(v)
+    }
+}
+
 impl<'a> From<Vec<IoSlice<'a>>> for ZBuf {
     fn from(slices: Vec<IoSlice>) -> ZBuf {
         let v: Vec<ZSlice> = slices.iter().map(ZSlice::from).collect();
@@ -566,6 +655,8 @@ impl PartialEq for ZBuf {
     }
 }
 
+impl Eq for ZBuf {}
+
 #[cfg(feature = "shared-memory")]
 impl From<Arc<SharedMemoryBuf>> for ZBuf {
     fn from(smb: Arc<SharedMemoryBuf>) -> ZBuf {
@@ -643,12 +734,7 @@ impl<'a> crate::traits::reader::Reader for ZBufReader<'a> {
 impl<'a> crate::traits::reader::HasReader for &'a ZBuf {
     type Reader = ZBufReader<'a>;
     fn reader(self) -> Self::Reader {
-        ZBufReader {
-            inner: self,
-            read: 0,
-            slice: 0,
-            byte: 0,
-        }
+        ZBuf::reader(self)
     }
 }
 impl crate::traits::buffer::ConstructibleBuffer for ZBuf {
@@ -835,4 +921,41 @@ mod tests {
         // assert_eq!(buf1.readable(), 0);
         // assert_eq!(buf1.len(), dest.readable());
     }
+
+    #[test]
+    fn test_zbuf_reader_chunks() {
+        use bytes::Buf;
+        use std::io::{BufRead, Read};
+
+        let zbuf = ZBuf::from(vec![
+            ZSlice::from(vec![0_u8, 1, 2]),
+            ZSlice::from(vec![]),
+            ZSlice::from(vec![3_u8, 4]),
+        ]);
+
+        // BufRead gives access to the slices without copy
+        let mut reader = zbuf.reader();
+        assert_eq!(reader.fill_buf().unwrap(), &[0_u8, 1, 2]);
+        reader.consume(2);
+        assert_eq!(reader.fill_buf().unwrap(), &[2_u8]);
+        reader.consume(1);
+        assert_eq!(reader.fill_buf().unwrap(), &[3_u8, 4]);
+        reader.consume(2);
+        assert!(reader.fill_buf().unwrap().is_empty());
+
+        // Buf allows to read across the slices
+        let mut reader = zbuf.reader();
+        assert_eq!(Buf::remaining(&reader), 5);
+        assert_eq!(reader.chunk(), &[0_u8, 1, 2]);
+        assert_eq!(reader.get_u16(), 0x0001);
+        assert_eq!(reader.get_u16(), 0x0203);
+        assert_eq!(reader.chunk(), &[4_u8]);
+        reader.advance(1);
+        assert!(!reader.has_remaining());
+
+        // Read copies the bytes across the slices
+        let mut bytes = vec![];
+        zbuf.reader().read_to_end(&mut bytes).unwrap();
+        assert_eq!(bytes, vec![0_u8, 1, 2, 3, 4]);
+    }
 }
@@ -33,6 +33,7 @@ use zenoh_core::{zread, zwrite, Result as ZResult};
 pub enum ZSliceBuffer {
     NetSharedBuffer(Arc<RecyclingObject<Box<[u8]>>>),
     NetOwnedBuffer(Arc<Vec<u8>>),
+    Bytes(bytes::Bytes),
     #[cfg(feature = "shared-memory")]
     ShmBuffer(Arc<SharedMemoryBuf>),
     #[cfg(feature = "shared-memory")]
@@ -44,6 +45,7 @@ impl ZSliceBuffer {
         match self {
             Self::NetSharedBuffer(buf) => buf,
             Self::NetOwnedBuffer(buf) => buf.as_slice(),
+            Self::Bytes(buf) => buf.as_ref(),
             #[cfg(feature = "shared-memory")]
             Self::ShmBuffer(buf) => buf.as_slice(),
             #[cfg(feature = "shared-memory")]
@@ -53,16 +55,20 @@ impl ZSliceBuffer {
 
     #[allow(clippy::missing_safety_doc)]
     #[allow(clippy::mut_from_ref)]
-    unsafe fn as_mut_slice(&self) -> &mut [u8] {
+    unsafe fn as_mut_slice(&self) -> Option<&mut [u8]> {
         match self {
             Self::NetSharedBuffer(buf) => {
-                &mut (*(Arc::as_ptr(buf) as *mut RecyclingObject<Box<[u8]>>))
+                Some(&mut (*(Arc::as_ptr(buf) as *mut RecyclingObject<Box<[u8]>>)))
             }
-            Self::NetOwnedBuffer(buf) => &mut (*(Arc::as_ptr(buf) as *mut Vec<u8>)),
+            Self::NetOwnedBuffer(buf) => Some(&mut (*(Arc::as_ptr(buf) as *mut Vec<u8>))),
+            // The storage of a Bytes may be static or shared with other Bytes
+            Self::Bytes(_) => None,
             #[cfg(feature = "shared-memory")]
-            Self::ShmBuffer(buf) => (*(Arc::as_ptr(buf) as *mut SharedMemoryBuf)).as_mut_slice(),
+            Self::ShmBuffer(buf) => {
+                Some((*(Arc::as_ptr(buf) as *mut SharedMemoryBuf)).as_mut_slice())
+            }
             #[cfg(feature = "shared-memory")]
-            Self::ShmInfo(buf) => &mut (*(Arc::as_ptr(buf) as *mut Vec<u8>)),
+            Self::ShmInfo(buf) => Some(&mut (*(Arc::as_ptr(buf) as *mut Vec<u8>))),
         }
     }
 }
@@ -161,6 +167,12 @@ impl From<Vec<u8>> for ZSliceBuffer {
     }
 }
 
+impl From<bytes::Bytes> for ZSliceBuffer {
+    fn from(buf: bytes::Bytes) -> Self {
+        Self::Bytes(buf)
+    }
+}
+
 impl<'a> From<&IoSlice<'a>> for ZSliceBuffer {
     fn from(buf: &IoSlice) -> Self {
         Self::NetOwnedBuffer(buf.to_vec().into())
@@ -243,10 +255,27 @@ impl ZSlice {
     /// This function retrieves a mutable slice from a non-mutable reference.
     /// Mutating the content of the slice without proper syncrhonization is considered
     /// undefined behavior in Rust. To use with extreme caution.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the slice is backed by a [`bytes::Bytes`], see [`ZSlice::try_as_mut_slice`].
     #[allow(clippy::mut_from_ref)]
     #[inline]
     pub unsafe fn as_mut_slice(&self) -> &mut [u8] {
-        &mut self.buf.as_mut_slice()[self.start..self.end]
+        self.try_as_mut_slice()
+            .expect("A ZSlice backed by a Bytes can not be mutated")
+    }
+
+    /// # Safety
+    ///
+    /// Same as [`ZSlice::as_mut_slice`], but returns `None` if the slice is backed by
+    /// a [`bytes::Bytes`], whose storage may be static or shared and thus can never be mutated.
+    #[allow(clippy::mut_from_ref)]
+    #[inline]
+    pub unsafe fn try_as_mut_slice(&self) -> Option<&mut [u8]> {
+        self.buf
+            .as_mut_slice()
+            .map(|buf| &mut buf[self.start..self.end])
     }
 
     #[inline]
@@ -257,7 +286,9 @@ impl ZSlice {
     #[inline]
     pub fn get_kind(&self) -> ZSliceKind {
         match &self.buf {
-            ZSliceBuffer::NetSharedBuffer(_) | ZSliceBuffer::NetOwnedBuffer(_) => ZSliceKind::Net,
+            ZSliceBuffer::NetSharedBuffer(_)
+            | ZSliceBuffer::NetOwnedBuffer(_)
+            | ZSliceBuffer::Bytes(_) => ZSliceKind::Net,
             #[cfg(feature = "shared-memory")]
             ZSliceBuffer::ShmBuffer(_) | ZSliceBuffer::ShmInfo(_) => ZSliceKind::Shm,
         }
@@ -472,6 +503,17 @@ impl From<Vec<u8>> for ZSlice {
     }
 }
 
+impl From<bytes::Bytes> for ZSlice {
+    fn from(buf: bytes::Bytes) -> Self {
+        let end = buf.len();
+        Self {
+            buf: buf.into(),
+            start: 0,
+            end,
+        }
+    }
+}
+
 impl<'a> From<&IoSlice<'a>> for ZSlice {
     fn from(buf: &IoSlice) -> Self {
         let end = buf.len();
@@ -537,5 +579,9 @@ mod tests {
         }
         println!("[02] {:?} {:?}", buf.as_slice(), zslice.as_slice());
         assert_eq!(buf.as_slice(), zslice.as_slice());
+
+        let zslice: ZSlice = bytes::Bytes::from_static(&[0_u8; 16]).into();
+        println!("[03] {:?}", zslice.as_slice());
+        assert!(unsafe { zslice.try_as_mut_slice() }.is_none());
     }
 }
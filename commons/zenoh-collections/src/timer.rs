@@ -18,6 +18,7 @@ use async_trait::async_trait;
 use flume::{bounded, Receiver, RecvError, Sender};
 use std::sync::{Arc, Weak};
 use zenoh_core::zconfigurable;
+use zenoh_sync::Executor;
 
 use std::cmp::Ordering as ComparisonOrdering;
 use std::collections::BinaryHeap;
@@ -187,6 +188,8 @@ pub struct Timer {
 }
 
 impl Timer {
+    /// The timer task runs on the executor of the calling context, on its thread pool
+    /// if `spawn_blocking`.
     pub fn new(spawn_blocking: bool) -> Timer {
         // Create the channels
         let (ev_sender, ev_receiver) = bounded::<(bool, TimedEvent)>(*TIMER_EVENTS_CHANNEL_SIZE);
@@ -208,16 +211,18 @@ impl Timer {
                 .await;
             log::trace!("A - Timer task no longer running...");
         };
+        let executor = Executor::current();
         if spawn_blocking {
-            task::spawn_blocking(|| task::block_on(fut));
+            executor.spawn_blocking(|| task::block_on(fut));
         } else {
-            task::spawn(fut);
+            executor.spawn(fut);
         }
 
         // Return the timer object
         timer
     }
 
+    /// Restarts the timer task after a [`stop`](Timer::stop), like [`new`](Timer::new).
     pub fn start(&mut self, spawn_blocking: bool) {
         if self.sl_sender.is_none() {
             // Create the channels
@@ -238,10 +243,11 @@ impl Timer {
                     .await;
                 log::trace!("A - Timer task no longer running...");
             };
+            let executor = Executor::current();
             if spawn_blocking {
-                task::spawn_blocking(|| task::block_on(fut));
+                executor.spawn_blocking(|| task::block_on(fut));
             } else {
-                task::spawn(fut);
+                executor.spawn(fut);
             }
         }
     }
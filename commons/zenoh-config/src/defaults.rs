@@ -113,16 +113,25 @@ impl Default for TransportMulticastConf {
         Self {
             join_interval: Some(2500),
             max_sessions: Some(1000),
+            max_rate: Some(0),
         }
     }
 }
 
 impl Default for QoSConf {
     fn default() -> Self {
-        Self { enabled: true }
+        Self {
+            enabled: true,
+            dscp: DscpConf::default(),
+            so_priority: SoPriorityConf::default(),
+        }
     }
 }
 
+impl DscpConf {
+    pub const MAX: u8 = 63;
+}
+
 impl Default for LinkTxConf {
     #[allow(clippy::unnecessary_cast)]
     fn default() -> Self {
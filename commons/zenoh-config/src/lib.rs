@@ -86,6 +86,88 @@ fn config_keys() {
     dbg!(c.keys());
 }
 
+/// The direction of the messages an interceptor configuration applies to.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum InterceptorFlow {
+    Egress,
+    Ingress,
+}
+
+/// A downsampling rule: the data messages on each key matching `key_expr` are forwarded
+/// at most `freq` times per second, the others being dropped. `freq` must be strictly positive.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct DownsamplingRuleConf {
+    pub key_expr: OwnedKeyExpr,
+    pub freq: f64,
+}
+
+/// A set of downsampling rules applied to the messages of the given flow
+/// on the links bound to the given network interfaces (all links if `None`).
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct DownsamplingItemConf {
+    pub interfaces: Option<Vec<String>>,
+    pub flow: InterceptorFlow,
+    pub rules: Vec<DownsamplingRuleConf>,
+}
+
+/// The permission granted by an access control rule.
+#[derive(Debug, Default, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Permission {
+    Allow,
+    #[default]
+    Deny,
+}
+
+/// The actions controlled by the access control rules.
+///
+/// The replies to a query are controlled along with the `Get` on their key expressions,
+/// and the pulls of a pull subscriber along with the `DeclareSubscriber`.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AclAction {
+    Put,
+    Get,
+    DeclareSubscriber,
+    DeclareQueryable,
+}
+
+/// An access control rule: it grants or denies the given `actions` on the given `key_exprs`
+/// to the remote peers matching all of the given `interfaces`, `zids`, `cert_common_names`,
+/// `usernames` and `public_keys` (any peer if `None`), for the messages of the given `flows`
+/// (both flows if `None`).
+///
+/// The `usernames` and `public_keys` (PKCS#1 PEM-encoded RSA public keys) are the identities
+/// authenticated during the transport establishment by the `transport/auth` authenticators.
+/// The `cert_common_names` are the common names of the certificates presented by the remote
+/// peers on TLS and QUIC links.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct AclConfigRule {
+    pub interfaces: Option<Vec<String>>,
+    pub zids: Option<Vec<ZenohId>>,
+    pub cert_common_names: Option<Vec<String>>,
+    pub usernames: Option<Vec<String>>,
+    pub public_keys: Option<Vec<String>>,
+    pub key_exprs: Vec<OwnedKeyExpr>,
+    pub actions: Vec<AclAction>,
+    pub flows: Option<Vec<InterceptorFlow>>,
+    pub permission: Permission,
+}
+
+/// The secret used to encrypt the payloads published on the given `key_exprs`,
+/// given either inline (`secret`) or as the path of a file containing it (`secret_file`).
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct EncryptionKeyConf {
+    pub key_exprs: Vec<OwnedKeyExpr>,
+    pub secret: Option<String>,
+    pub secret_file: Option<String>,
+}
+
 fn treat_error_as_none<'a, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
 where
     T: serde::de::Deserialize<'a>,
@@ -201,6 +283,25 @@ validated_struct::validator! {
             /// A list of key-expressions for which all included publishers will be aggregated into.
             publishers: Vec<OwnedKeyExpr>,
         },
+
+        /// Configuration of the downsampling of data messages.
+        pub downsampling: Vec<DownsamplingItemConf> where (downsampling_validator),
+        /// Configuration of the access control of the messages exchanged with remote peers.
+        pub access_control: #[derive(Default)]
+        AclConfig {
+            /// Whether the access control is enabled or not.
+            enabled: bool,
+            /// The permission of the actions not matching any rule ("allow" or "deny").
+            default_permission: Permission,
+            /// The list of access control rules.
+            rules: Vec<AclConfigRule>,
+        },
+        /// Configuration of the end-to-end encryption of the payloads.
+        pub encryption: #[derive(Default)]
+        EncryptionConf {
+            /// The list of encryption keys and the key-expressions they apply to.
+            keys: Vec<EncryptionKeyConf>,
+        },
         pub transport: #[derive(Default)]
         TransportConf {
             pub unicast: TransportUnicastConf {
@@ -218,11 +319,41 @@ validated_struct::validator! {
                 join_interval: Option<ZInt>,
                 /// Maximum number of multicast sessions (default: 1000)
                 max_sessions: Option<usize>,
+                /// Maximum transmission rate in bytes per second of a multicast link, 0 means unlimited (default: 0)
+                max_rate: Option<ZInt>,
             },
             pub qos: QoSConf {
                 /// Whether QoS is enabled or not.
                 /// If set to `false`, the QoS will be disabled. (default `true`).
-                enabled: bool
+                enabled: bool,
+                /// The IP DSCP values (0-63) to mark the outgoing packets of each priority with,
+                /// allowing the network QoS to honor zenoh priorities end-to-end.
+                /// Priorities with no value are sent with the system default DSCP. (default: none)
+                pub dscp: #[derive(Default)]
+                DscpConf {
+                    control: Option<u8>,
+                    real_time: Option<u8>,
+                    interactive_high: Option<u8>,
+                    interactive_low: Option<u8>,
+                    data_high: Option<u8>,
+                    data: Option<u8>,
+                    data_low: Option<u8>,
+                    background: Option<u8>,
+                } where (dscp_validator),
+                /// The Linux SO_PRIORITY values to send the outgoing packets of each priority with,
+                /// selecting the queue of the network interface they are sent on. Values above 6
+                /// require the CAP_NET_ADMIN capability. (default: none)
+                pub so_priority: #[derive(Default)]
+                SoPriorityConf {
+                    control: Option<u8>,
+                    real_time: Option<u8>,
+                    interactive_high: Option<u8>,
+                    interactive_low: Option<u8>,
+                    data_high: Option<u8>,
+                    data: Option<u8>,
+                    data_low: Option<u8>,
+                    background: Option<u8>,
+                },
             },
             pub link: #[derive(Default)]
             TransportLinkConf {
@@ -278,6 +409,21 @@ validated_struct::validator! {
                     client_auth: Option<bool>,
                     client_private_key: Option<String>,
                     client_certificate: Option<String>,
+                    /// PEM-encoded content of the certificate authority certificate.
+                    /// Takes precedence over `root_ca_certificate` when both are set.
+                    root_ca_certificate_pem: Option<String>,
+                    /// PEM-encoded content of the TLS server private key.
+                    /// Takes precedence over `server_private_key` when both are set.
+                    server_private_key_pem: Option<String>,
+                    /// PEM-encoded content of the TLS server public certificate.
+                    /// Takes precedence over `server_certificate` when both are set.
+                    server_certificate_pem: Option<String>,
+                    /// PEM-encoded content of the TLS client private key.
+                    /// Takes precedence over `client_private_key` when both are set.
+                    client_private_key_pem: Option<String>,
+                    /// PEM-encoded content of the TLS client public certificate.
+                    /// Takes precedence over `client_certificate` when both are set.
+                    client_certificate_pem: Option<String>,
                 },
             },
             pub shared_memory: SharedMemoryConf {
@@ -734,6 +880,65 @@ impl<'a, T> AsRef<dyn Any> for GetGuard<'a, T> {
     }
 }
 
+fn dscp_validator(d: &DscpConf) -> bool {
+    fn check(dscp: &Option<u8>) -> bool {
+        dscp.map(|d| d <= DscpConf::MAX).unwrap_or(true)
+    }
+
+    let DscpConf {
+        control,
+        real_time,
+        interactive_high,
+        interactive_low,
+        data_high,
+        data,
+        data_low,
+        background,
+    } = d;
+    check(control)
+        && check(real_time)
+        && check(interactive_high)
+        && check(interactive_low)
+        && check(data_high)
+        && check(data)
+        && check(data_low)
+        && check(background)
+}
+
+#[test]
+fn config_dscp() {
+    use validated_struct::ValidatedMap;
+    let from_str = serde_json::Deserializer::from_str;
+    let mut config = Config::default();
+    config
+        .insert(
+            "transport/qos/dscp",
+            &mut from_str(r#"{"data": 46, "control": 63}"#),
+        )
+        .unwrap();
+    assert_eq!(*config.transport().qos().dscp().data(), Some(46));
+    assert_eq!(*config.transport().qos().dscp().control(), Some(63));
+    assert_eq!(*config.transport().qos().dscp().real_time(), None);
+    assert!(config
+        .insert("transport/qos/dscp", &mut from_str(r#"{"real_time": 64}"#))
+        .is_err());
+    assert_eq!(*config.transport().qos().dscp().real_time(), None);
+
+    let mut dscp = DscpConf::default();
+    assert!(dscp_validator(&dscp));
+    dscp.set_background(Some(DscpConf::MAX)).unwrap();
+    assert!(dscp_validator(&dscp));
+    dscp.set_background(Some(DscpConf::MAX + 1)).unwrap();
+    assert!(!dscp_validator(&dscp));
+}
+
+fn downsampling_validator(items: &[DownsamplingItemConf]) -> bool {
+    items
+        .iter()
+        .flat_map(|item| item.rules.iter())
+        .all(|rule| rule.freq > 0.0 && rule.freq.is_finite())
+}
+
 fn queue_size_validator(q: &QueueSizeConf) -> bool {
     fn check(size: &usize) -> bool {
         (QueueSizeConf::MIN..=QueueSizeConf::MAX).contains(size)
@@ -0,0 +1,127 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use super::PseudoRng;
+use aes_gcm::aead::{generic_array::GenericArray, Aead, KeyInit, Payload};
+use aes_gcm::Aes256Gcm;
+use rand::RngCore;
+use sha3::{Digest, Sha3_256};
+use zenoh_core::{bail, zerror, Result as ZResult};
+
+/// An authenticated cipher: the bytes are encrypted and authenticated with AES-256-GCM,
+/// along with some associated data that is authenticated but not encrypted.
+///
+/// The encrypted bytes are made of a random nonce, the ciphertext and the authentication tag.
+pub struct AuthCipher {
+    inner: Aes256Gcm,
+    id: [u8; AuthCipher::ID_SIZE],
+}
+
+impl AuthCipher {
+    pub const ID_SIZE: usize = 8;
+    pub const NONCE_SIZE: usize = 12;
+    pub const TAG_SIZE: usize = 16;
+
+    /// Creates a cipher deriving its key and its identifier from the given secret.
+    pub fn new(secret: &[u8]) -> AuthCipher {
+        let key = Sha3_256::new()
+            .chain_update(b"zenoh/auth_cipher/key")
+            .chain_update(secret)
+            .finalize();
+        let id = Sha3_256::new()
+            .chain_update(b"zenoh/auth_cipher/id")
+            .chain_update(secret)
+            .finalize();
+        let mut cipher = AuthCipher {
+            inner: Aes256Gcm::new(GenericArray::from_slice(&key)),
+            id: [0_u8; Self::ID_SIZE],
+        };
+        cipher.id.copy_from_slice(&id[..Self::ID_SIZE]);
+        cipher
+    }
+
+    /// Identifies the secret of this cipher without revealing it.
+    pub fn id(&self) -> &[u8; Self::ID_SIZE] {
+        &self.id
+    }
+
+    pub fn encrypt(
+        &self,
+        associated_data: &[u8],
+        clear: &[u8],
+        prng: &mut PseudoRng,
+    ) -> ZResult<Vec<u8>> {
+        let mut nonce = [0_u8; Self::NONCE_SIZE];
+        prng.fill_bytes(&mut nonce);
+        let payload = Payload {
+            msg: clear,
+            aad: associated_data,
+        };
+        let ciphertext = self
+            .inner
+            .encrypt(GenericArray::from_slice(&nonce), payload)
+            .map_err(|_| zerror!("Unable to encrypt {} bytes", clear.len()))?;
+        let mut bytes = Vec::with_capacity(Self::NONCE_SIZE + ciphertext.len());
+        bytes.extend_from_slice(&nonce);
+        bytes.extend_from_slice(&ciphertext);
+        Ok(bytes)
+    }
+
+    pub fn decrypt(&self, associated_data: &[u8], bytes: &[u8]) -> ZResult<Vec<u8>> {
+        if bytes.len() < Self::NONCE_SIZE + Self::TAG_SIZE {
+            bail!("Invalid bytes length to decrypt: {}", bytes.len());
+        }
+        let (nonce, ciphertext) = bytes.split_at(Self::NONCE_SIZE);
+        let payload = Payload {
+            msg: ciphertext,
+            aad: associated_data,
+        };
+        self.inner
+            .decrypt(GenericArray::from_slice(nonce), payload)
+            .map_err(|_| zerror!("Invalid authentication tag").into())
+    }
+}
+
+mod tests {
+    #[test]
+    fn auth_cipher() {
+        use super::{AuthCipher, PseudoRng};
+        use rand::SeedableRng;
+
+        let mut prng = PseudoRng::from_entropy();
+        let cipher = AuthCipher::new(b"secret");
+        let other = AuthCipher::new(b"other secret");
+
+        for size in [0, 1, 15, 16, 17, 1_024] {
+            let clear = vec![42_u8; size];
+            let encrypted = cipher.encrypt(b"key/expr", &clear, &mut prng).unwrap();
+            assert_eq!(
+                encrypted.len(),
+                AuthCipher::NONCE_SIZE + size + AuthCipher::TAG_SIZE
+            );
+            assert_eq!(cipher.decrypt(b"key/expr", &encrypted).unwrap(), clear);
+
+            // Wrong key, wrong associated data or altered bytes are detected
+            assert!(other.decrypt(b"key/expr", &encrypted).is_err());
+            assert!(cipher.decrypt(b"other/expr", &encrypted).is_err());
+            let mut altered = encrypted.clone();
+            altered[AuthCipher::NONCE_SIZE] ^= 1;
+            assert!(cipher.decrypt(b"key/expr", &altered).is_err());
+        }
+        assert!(cipher.decrypt(b"key/expr", &[0_u8; 8]).is_err());
+
+        // The identifier only depends on the secret
+        assert_eq!(cipher.id(), AuthCipher::new(b"secret").id());
+        assert_ne!(cipher.id(), other.id());
+    }
+}
@@ -17,9 +17,11 @@
 //! This crate is intended for Zenoh's internal use.
 //!
 //! [Click here for Zenoh's documentation](../zenoh/index.html)
+mod auth_cipher;
 mod cipher;
 pub mod hmac;
 mod prng;
 
+pub use auth_cipher::*;
 pub use cipher::*;
 pub use prng::*;
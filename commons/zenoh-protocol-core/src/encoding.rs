@@ -179,9 +179,46 @@ impl Encoding {
             Encoding::WithSuffix(_, s) => s.as_ref(),
         }
     }
+
+    /// Sets the schema of this encoding, replacing the existing one if any.
+    ///
+    /// The schema is carried as a `;schema=<schema>` parameter at the end of the suffix,
+    /// e.g. `application/cbor;schema=my.proto.SensorReading`.
+    pub fn with_schema<IntoCowStr>(self, schema: IntoCowStr) -> Self
+    where
+        IntoCowStr: Into<Cow<'static, str>>,
+    {
+        let schema = schema.into();
+        let encoding = self.without_schema();
+        if schema.is_empty() {
+            encoding
+        } else {
+            encoding.with_suffix(format!("{}{}", Self::SCHEMA_PARAMETER, schema))
+        }
+    }
+
+    /// Returns the schema of this encoding, if any.
+    pub fn schema(&self) -> Option<&str> {
+        let suffix = self.suffix();
+        suffix
+            .find(Self::SCHEMA_PARAMETER)
+            .map(|i| &suffix[i + Self::SCHEMA_PARAMETER.len()..])
+    }
+
+    /// Returns this encoding without its schema.
+    pub fn without_schema(&self) -> Encoding {
+        let suffix = self.suffix();
+        match suffix.find(Self::SCHEMA_PARAMETER) {
+            Some(0) => Encoding::Exact(*self.prefix()),
+            Some(i) => Encoding::WithSuffix(*self.prefix(), suffix[..i].to_string().into()),
+            None => self.clone(),
+        }
+    }
 }
 
 impl Encoding {
+    const SCHEMA_PARAMETER: &'static str = ";schema=";
+
     pub const EMPTY: Encoding = Encoding::Exact(KnownEncoding::Empty);
     pub const APP_OCTET_STREAM: Encoding = Encoding::Exact(KnownEncoding::AppOctetStream);
     pub const APP_CUSTOM: Encoding = Encoding::Exact(KnownEncoding::AppCustom);
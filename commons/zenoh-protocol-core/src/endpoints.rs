@@ -61,6 +61,19 @@ impl EndPoint {
         self.locator.set_addr(addr)
     }
 
+    /// Looks up a parameter either in the endpoint configuration (e.g. `udp/224.0.0.224:7447#ttl=4`)
+    /// or in the locator metadata (e.g. `udp/224.0.0.224:7447?ttl=4`), the former taking precedence.
+    pub fn parameter(&self, key: &str) -> Option<&String> {
+        self.config
+            .as_ref()
+            .and_then(|config| config.get(key))
+            .or_else(|| {
+                self.locator
+                    .metadata()
+                    .and_then(|metadata| metadata.get(key))
+            })
+    }
+
     pub fn extend_configuration(&mut self, extension: impl IntoIterator<Item = (String, String)>) {
         match self.config.is_some() {
             true => match &mut self.config {
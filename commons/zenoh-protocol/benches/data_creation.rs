@@ -50,6 +50,9 @@ fn criterion_benchmark(c: &mut Criterion) {
                         Default::default(),
                         uhlc::ID::try_from([2u8; uhlc::ID::MAX_SIZE]).unwrap(),
                     )),
+                    ack_id: None,
+                    attachment: None,
+                    wildcard: false,
                     source_id: Some(ZenohId::try_from([1_u8; ZenohId::MAX_SIZE]).unwrap()),
                     source_sn: Some(12345),
                 });
@@ -101,6 +104,9 @@ fn criterion_benchmark(c: &mut Criterion) {
             Default::default(),
             uhlc::ID::try_from([1_u8; uhlc::ID::MAX_SIZE]).unwrap(),
         )),
+        ack_id: None,
+        attachment: None,
+        wildcard: false,
         source_id: Some(ZenohId::try_from([1_u8; ZenohId::MAX_SIZE]).unwrap()),
         source_sn: Some(12345),
     });
@@ -14,7 +14,7 @@
 use super::core::Encoding;
 use super::core::*;
 use super::defaults::SEQ_NUM_RES;
-use super::io::{ZBuf, ZSlice};
+use super::io::{SplitBuffer, ZBuf, ZSlice};
 use std::fmt;
 use std::time::Duration;
 use zenoh_protocol_core::whatami::WhatAmIMatcher;
@@ -223,12 +223,17 @@ pub mod zmsg {
             pub const ENCODING: ZInt = 1 << 2; // 0x04
             pub const TIMESTAMP: ZInt = 1 << 3; // 0x08
                                                 // 0x10: Reserved
-                                                // 0x20: Reserved
-                                                // 0x40: Reserved
+            pub const WILDCARD: ZInt = 1 << 5; // 0x20
+                                               // 0x40: Reserved
             pub const SRCID: ZInt = 1 << 7; // 0x80
             pub const SRCSN: ZInt = 1 << 8; // 0x100
             pub const RTRID: ZInt = 1 << 9; // 0x200
             pub const RTRSN: ZInt = 1 << 10; // 0x400
+            pub const ACK: ZInt = 1 << 11; // 0x800
+            pub const ATTACHMENT: ZInt = 1 << 12; // 0x1000
+
+            // The options only sent to the peers that advertised them
+            pub const EXTENSIONS: ZInt = ACK | ATTACHMENT | WILDCARD;
         }
     }
 
@@ -506,13 +511,15 @@ impl Header for Priority {
 /// -  2: Payload encoding
 /// -  3: Payload timestamp
 /// -  4: Reserved
-/// -  5: Reserved
+/// -  5: Wildcard delete
 /// -  6: Reserved
 /// -  7: Payload source_id
 /// -  8: Payload source_sn
 /// -  9: First router_id
 /// - 10: First router_sn
-/// - 11-63: Reserved
+/// - 11: Acknowledgment id
+/// - 12: Attachment
+/// - 13-63: Reserved
 ///
 ///  7 6 5 4 3 2 1 0
 /// +-+-+-+---------+
@@ -528,8 +535,20 @@ impl Header for Priority {
 /// +---------------+
 /// ~   source_sn   ~ if options & (1 << 8)
 /// +---------------+
+/// ~    ack_id     ~ if options & (1 << 11)
+/// +---------------+
+/// ~  attachment   ~ if options & (1 << 12)
+/// +---------------+
 ///
-/// - if options & (1 << 5) then the payload is sliced
+/// - if options & (1 << 0) then the payload is sliced
+/// - if options & (1 << 5) then the data is a delete of all the keys matching its key expression
+///
+/// NOTE: the ack_id and the attachment are encoded after all the other fields, so that the
+///       decoding of the DataInfo is unchanged when they are absent. Peers not supporting them
+///       can not decode them: they are only sent to the peers that advertised them when
+///       establishing the transport (see [`DataInfo::retain_extensions`]).
+///       The wildcard flag is only sent to these peers as well: the others would ignore it
+///       and receive a wildcard delete as a delete of the key expression itself.
 ///
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
@@ -539,6 +558,14 @@ pub struct DataInfo {
     pub kind: SampleKind,
     pub encoding: Option<Encoding>,
     pub timestamp: Option<Timestamp>,
+    /// If present, the first hop receiving the data acknowledges it
+    /// with a final reply carrying this id.
+    pub ack_id: Option<ZInt>,
+    /// The attachment set by the publisher, forwarded along with the data.
+    pub attachment: Option<ZBuf>,
+    /// Set on deletes published on a wildcard key expression: all the keys
+    /// matching the key expression are deleted.
+    pub wildcard: bool,
     pub source_id: Option<ZenohId>,
     pub source_sn: Option<ZInt>,
 }
@@ -547,6 +574,20 @@ impl DataInfo {
     pub fn new() -> DataInfo {
         DataInfo::default()
     }
+
+    /// Removes the optional fields of [`zmsg::data::info::EXTENSIONS`] that are not in
+    /// the given options, i.e. the ones a peer did not advertise.
+    pub fn retain_extensions(&mut self, options: ZInt) {
+        if options & zmsg::data::info::ACK == 0 {
+            self.ack_id = None;
+        }
+        if options & zmsg::data::info::ATTACHMENT == 0 {
+            self.attachment = None;
+        }
+        if options & zmsg::data::info::WILDCARD == 0 {
+            self.wildcard = false;
+        }
+    }
 }
 
 impl Options for DataInfo {
@@ -565,6 +606,15 @@ impl Options for DataInfo {
         if self.timestamp.is_some() {
             options |= zmsg::data::info::TIMESTAMP;
         }
+        if self.ack_id.is_some() {
+            options |= zmsg::data::info::ACK;
+        }
+        if self.attachment.is_some() {
+            options |= zmsg::data::info::ATTACHMENT;
+        }
+        if self.wildcard {
+            options |= zmsg::data::info::WILDCARD;
+        }
         if self.source_id.is_some() {
             options |= zmsg::data::info::SRCID;
         }
@@ -592,6 +642,9 @@ impl Options for DataInfo {
             || self.kind != SampleKind::Put
             || self.encoding.is_some()
             || self.timestamp.is_some()
+            || self.ack_id.is_some()
+            || self.attachment.is_some()
+            || self.wildcard
             || self.source_id.is_some()
             || self.source_sn.is_some()
     }
@@ -955,6 +1008,9 @@ impl Header for Pull {
 ///
 /// QueryBody data structure is optionally included in Query messages
 ///
+/// It carries the value of the query, or only the attachment of the DataInfo when the query
+/// has no value: the DataInfo of a value always has an encoding.
+///
 /// ```text
 ///  7 6 5 4 3 2 1 0
 /// +-+-+-+---------+
@@ -1256,6 +1312,28 @@ impl ZenohMessage {
         }
     }
 
+    /// Removes the optional fields of the [`DataInfo`] of a data message or of the body of
+    /// a query that are not in the given options (see [`DataInfo::retain_extensions`]).
+    /// A query body left without any value is removed.
+    #[inline]
+    pub fn retain_data_info_extensions(&mut self, options: ZInt) {
+        match &mut self.body {
+            ZenohBody::Data(Data {
+                data_info: Some(info),
+                ..
+            }) => info.retain_extensions(options),
+            ZenohBody::Query(Query { body, .. }) => {
+                if let Some(b) = body {
+                    b.data_info.retain_extensions(options);
+                    if !b.data_info.has_options() && b.payload.is_empty() {
+                        *body = None;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
     // -- Message Predicates
     #[inline]
     pub fn is_reliable(&self) -> bool {
@@ -590,12 +590,19 @@ impl MessageReader for ZBufReader<'_> {
         if imsg::has_option(options, zmsg::data::info::TIMESTAMP) {
             info.timestamp = Some(self.read_timestamp()?);
         }
+        info.wildcard = imsg::has_option(options, zmsg::data::info::WILDCARD);
         if imsg::has_option(options, zmsg::data::info::SRCID) {
             info.source_id = Some(self.read_zid()?);
         }
         if imsg::has_option(options, zmsg::data::info::SRCSN) {
             info.source_sn = Some(self.read_zint()?);
         }
+        if imsg::has_option(options, zmsg::data::info::ACK) {
+            info.ack_id = Some(self.read_zint()?);
+        }
+        if imsg::has_option(options, zmsg::data::info::ATTACHMENT) {
+            info.attachment = Some(self.read_zbuf_flat()?);
+        }
 
         Some(info)
     }
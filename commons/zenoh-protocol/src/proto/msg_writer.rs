@@ -423,6 +423,12 @@ impl MessageWriter for WBuf {
         if let Some(sn) = info.source_sn {
             zcheck!(self.write_zint(sn));
         }
+        if let Some(ack_id) = info.ack_id {
+            zcheck!(self.write_zint(ack_id));
+        }
+        if let Some(attachment) = info.attachment.as_ref() {
+            zcheck!(self.write_zbuf_flat(attachment));
+        }
         true
     }
 
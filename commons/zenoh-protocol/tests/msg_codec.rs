@@ -21,7 +21,7 @@ use zenoh_protocol::io::{WBuf, ZBuf};
 use zenoh_protocol::io::{WBufCodec, ZBufCodec};
 use zenoh_protocol::proto::defaults::SEQ_NUM_RES;
 use zenoh_protocol::proto::{
-    Attachment, DataInfo, Declaration, ForgetPublisher, ForgetQueryable, ForgetResource,
+    zmsg, Attachment, DataInfo, Declaration, ForgetPublisher, ForgetQueryable, ForgetResource,
     ForgetSubscriber, FramePayload, MessageReader, MessageWriter, Publisher, QueryBody, Queryable,
     ReplierInfo, ReplyContext, Resource, RoutingContext, Subscriber, TransportMessage,
     ZenohMessage,
@@ -204,6 +204,9 @@ fn gen_data_info() -> DataInfo {
         kind: (gen!(ZInt) % 2).try_into().unwrap(),
         encoding: option_gen!(Encoding::Exact(TryFrom::try_from(gen!(u8) % 21).unwrap())),
         timestamp: option_gen!(gen_timestamp()),
+        ack_id: option_gen!(gen!(ZInt)),
+        attachment: option_gen!(ZBuf::from(gen_buffer(MAX_PAYLOAD_SIZE))),
+        wildcard: gen!(bool),
         #[cfg(feature = "shared-memory")]
         sliced: false,
         source_id: option_gen!(gen_zid()),
@@ -754,6 +757,39 @@ fn codec_data() {
     }
 }
 
+#[test]
+fn data_info_retain_extensions() {
+    let data_info = DataInfo {
+        kind: SampleKind::Delete,
+        timestamp: Some(gen_timestamp()),
+        ack_id: Some(gen!(ZInt)),
+        attachment: Some(ZBuf::from(gen_buffer(MAX_PAYLOAD_SIZE))),
+        wildcard: true,
+        ..DataInfo::new()
+    };
+
+    // A peer supporting all the extensions receives them
+    let mut info = data_info.clone();
+    info.retain_extensions(zmsg::data::info::EXTENSIONS);
+    assert_eq!(info, data_info);
+
+    // A peer without the wildcard extension receives a plain delete
+    let mut info = data_info.clone();
+    info.retain_extensions(zmsg::data::info::EXTENSIONS & !zmsg::data::info::WILDCARD);
+    assert!(!info.wildcard);
+    assert_eq!(info.ack_id, data_info.ack_id);
+    assert_eq!(info.attachment, data_info.attachment);
+
+    // A peer without any extension only receives the base fields
+    let mut info = data_info.clone();
+    info.retain_extensions(0);
+    assert!(!info.wildcard);
+    assert!(info.ack_id.is_none());
+    assert!(info.attachment.is_none());
+    assert_eq!(info.kind, data_info.kind);
+    assert_eq!(info.timestamp, data_info.timestamp);
+}
+
 #[test]
 fn codec_unit() {
     for _ in 0..NUM_ITER {
@@ -0,0 +1,238 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! The executor running the background tasks of zenoh.
+//!
+//! The tasks run on async-std. With the `tokio-runtime` feature, an [`Executor`] created from
+//! within a Tokio runtime spawns its tasks on this runtime instead, and the tasks spawned
+//! from these tasks with [`Executor::current`] do the same. Once the Tokio runtime has been
+//! shut down, the tasks fall back to async-std, where those that had started resume.
+//!
+//! The timers and the sockets are driven by the async-io reactor, which runs on its own
+//! thread and works with any executor polling them.
+use futures::FutureExt;
+#[cfg(feature = "tokio-runtime")]
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+#[cfg(feature = "tokio-runtime")]
+thread_local! {
+    // The executor of the task running on this thread, if spawned by an Executor. The threads
+    // of async-std may enter a Tokio runtime of their own (with its `tokio1` feature), so the
+    // Tokio context alone does not tell which executor a task runs on.
+    static CURRENT: RefCell<Option<Executor>> = const { RefCell::new(None) };
+}
+
+#[derive(Clone, Default)]
+pub struct Executor {
+    #[cfg(feature = "tokio-runtime")]
+    handle: Option<tokio::runtime::Handle>,
+}
+
+impl Executor {
+    /// Returns the executor of the calling context: the executor of the current task if
+    /// spawned by an [`Executor`], otherwise the current Tokio runtime if any (with the
+    /// `tokio-runtime` feature), async-std otherwise.
+    pub fn current() -> Executor {
+        #[cfg(feature = "tokio-runtime")]
+        if let Some(executor) = CURRENT.with(|current| current.borrow().clone()) {
+            return executor;
+        }
+        Executor {
+            #[cfg(feature = "tokio-runtime")]
+            handle: tokio::runtime::Handle::try_current().ok(),
+        }
+    }
+
+    /// Spawns a task. Dropping the returned [`JoinHandle`] detaches the task.
+    pub fn spawn<F, T>(&self, future: F) -> JoinHandle<T>
+    where
+        F: Future<Output = T> + Send + 'static,
+        T: Send + 'static,
+    {
+        #[cfg(feature = "tokio-runtime")]
+        let future = WithExecutor {
+            executor: self.clone(),
+            future: Box::pin(future),
+        };
+        #[cfg(feature = "tokio-runtime")]
+        if let Some(handle) = &self.handle {
+            let (sender, receiver) = flume::bounded(1);
+            // A runtime that is shut down drops its tasks, whether they have started or not
+            handle.spawn(AsyncStdFallback(Some(Box::pin(async move {
+                let _ = sender.send(future.await);
+            }))));
+            return JoinHandle(JoinHandleInner::Tokio(receiver.into_recv_async()));
+        }
+        JoinHandle(JoinHandleInner::AsyncStd(async_std::task::spawn(future)))
+    }
+
+    /// Runs a blocking function on the thread pool of the executor.
+    pub fn spawn_blocking<F, T>(&self, f: F) -> JoinHandle<T>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        #[cfg(feature = "tokio-runtime")]
+        let f = {
+            let executor = self.clone();
+            move || executor.enter(f)
+        };
+        #[cfg(feature = "tokio-runtime")]
+        if let Some(handle) = &self.handle {
+            let (sender, receiver) = flume::bounded(1);
+            let fallback = AsyncStdBlockingFallback(Some(move || {
+                let _ = sender.send(f());
+            }));
+            handle.spawn_blocking(move || {
+                let mut fallback = fallback;
+                if let Some(f) = fallback.0.take() {
+                    f();
+                }
+            });
+            return JoinHandle(JoinHandleInner::Tokio(receiver.into_recv_async()));
+        }
+        JoinHandle(JoinHandleInner::AsyncStd(async_std::task::spawn_blocking(
+            f,
+        )))
+    }
+
+    // Runs `f` with this executor as the current one.
+    #[cfg(feature = "tokio-runtime")]
+    fn enter<R>(&self, f: impl FnOnce() -> R) -> R {
+        let previous = CURRENT.with(|current| current.replace(Some(self.clone())));
+        let _guard = RestoreCurrent(previous);
+        f()
+    }
+}
+
+// Restores the previous current executor when dropped, even if the function entered panics.
+#[cfg(feature = "tokio-runtime")]
+struct RestoreCurrent(Option<Executor>);
+
+#[cfg(feature = "tokio-runtime")]
+impl Drop for RestoreCurrent {
+    fn drop(&mut self) {
+        let previous = self.0.take();
+        // The thread local may already be destroyed if the thread is exiting
+        let _ = CURRENT.try_with(|current| *current.borrow_mut() = previous);
+    }
+}
+
+// Polls the future with its executor as the current one.
+#[cfg(feature = "tokio-runtime")]
+struct WithExecutor<F> {
+    executor: Executor,
+    future: Pin<Box<F>>,
+}
+
+#[cfg(feature = "tokio-runtime")]
+impl<F: Future> Future for WithExecutor<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let future = &mut this.future;
+        this.executor.enter(|| future.as_mut().poll(cx))
+    }
+}
+
+/// A handle to await the output of a task spawned by an [`Executor`].
+///
+/// As with async-std, awaiting the handle of a task that panicked panics as well.
+pub struct JoinHandle<T: 'static>(JoinHandleInner<T>);
+
+enum JoinHandleInner<T: 'static> {
+    AsyncStd(async_std::task::JoinHandle<T>),
+    #[cfg(feature = "tokio-runtime")]
+    Tokio(flume::r#async::RecvFut<'static, T>),
+}
+
+impl<T: Send + 'static> Future for JoinHandle<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match &mut self.get_mut().0 {
+            JoinHandleInner::AsyncStd(handle) => handle.poll_unpin(cx),
+            #[cfg(feature = "tokio-runtime")]
+            JoinHandleInner::Tokio(output) => match output.poll_unpin(cx) {
+                Poll::Ready(Ok(output)) => Poll::Ready(output),
+                // The output is only dropped with the task when it panics
+                Poll::Ready(Err(_)) => panic!("The task has panicked"),
+                Poll::Pending => Poll::Pending,
+            },
+        }
+    }
+}
+
+// Polls the future, and spawns it on async-std if dropped before it has completed.
+#[cfg(feature = "tokio-runtime")]
+struct AsyncStdFallback<F>(Option<Pin<Box<F>>>)
+where
+    F: Future<Output = ()> + Send + 'static;
+
+#[cfg(feature = "tokio-runtime")]
+impl<F> Future for AsyncStdFallback<F>
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        // Taken out while polled, so that a future that panics is not respawned
+        match this.0.take() {
+            Some(mut future) => {
+                let poll = future.as_mut().poll(cx);
+                if poll.is_pending() {
+                    this.0 = Some(future);
+                }
+                poll
+            }
+            None => Poll::Ready(()),
+        }
+    }
+}
+
+#[cfg(feature = "tokio-runtime")]
+impl<F> Drop for AsyncStdFallback<F>
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    fn drop(&mut self) {
+        if let Some(future) = self.0.take() {
+            async_std::task::spawn(future);
+        }
+    }
+}
+
+// Runs the function on the async-std thread pool if dropped before it has been taken out.
+#[cfg(feature = "tokio-runtime")]
+struct AsyncStdBlockingFallback<F>(Option<F>)
+where
+    F: FnOnce() + Send + 'static;
+
+#[cfg(feature = "tokio-runtime")]
+impl<F> Drop for AsyncStdBlockingFallback<F>
+where
+    F: FnOnce() + Send + 'static,
+{
+    fn drop(&mut self) {
+        if let Some(f) = self.0.take() {
+            async_std::task::spawn_blocking(f);
+        }
+    }
+}
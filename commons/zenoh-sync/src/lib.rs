@@ -26,6 +26,8 @@ pub mod backoff;
 pub use backoff::*;
 pub mod condition;
 pub use condition::*;
+pub mod executor;
+pub use executor::*;
 pub mod mvar;
 pub use mvar::*;
 pub mod signal;
@@ -12,7 +12,7 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 use async_std::net::TcpStream;
-use std::net::{IpAddr, Ipv6Addr};
+use std::net::{IpAddr, Ipv6Addr, SocketAddr};
 use std::time::Duration;
 use zenoh_core::{bail, zconfigurable, Result as ZResult};
 
@@ -89,6 +89,77 @@ pub fn set_linger(socket: &TcpStream, dur: Option<Duration>) -> ZResult<()> {
     }
 }
 
+/// Sets the DSCP value (0-63) of the IP packets sent on a socket connected to `addr`.
+/// The DSCP occupies the 6 most significant bits of the IPv4 TOS field and of the IPv6
+/// traffic class.
+#[cfg(unix)]
+pub fn set_dscp<S: std::os::unix::io::AsRawFd>(
+    socket: &S,
+    addr: &SocketAddr,
+    dscp: u8,
+) -> ZResult<()> {
+    let (level, name) = match addr {
+        SocketAddr::V4(_) => (libc::IPPROTO_IP, libc::IP_TOS),
+        SocketAddr::V6(_) => (libc::IPPROTO_IPV6, libc::IPV6_TCLASS),
+    };
+    let value = (dscp as libc::c_int) << 2;
+    unsafe {
+        let ret = libc::setsockopt(
+            socket.as_raw_fd(),
+            level,
+            name,
+            &value as *const libc::c_int as *const libc::c_void,
+            std::mem::size_of_val(&value) as libc::socklen_t,
+        );
+        match ret {
+            0 => Ok(()),
+            err_code => bail!("setsockopt returned {}", err_code),
+        }
+    }
+}
+
+#[cfg(windows)]
+pub fn set_dscp<S: std::os::windows::io::AsRawSocket>(
+    _socket: &S,
+    _addr: &SocketAddr,
+    _dscp: u8,
+) -> ZResult<()> {
+    bail!("Setting the DSCP is not supported on Windows")
+}
+
+/// Sets the Linux `SO_PRIORITY` of the packets sent on a socket, which selects
+/// the queue of the network interface they are sent on.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn set_so_priority<S: std::os::unix::io::AsRawFd>(socket: &S, priority: u8) -> ZResult<()> {
+    let value = priority as libc::c_int;
+    unsafe {
+        let ret = libc::setsockopt(
+            socket.as_raw_fd(),
+            libc::SOL_SOCKET,
+            libc::SO_PRIORITY,
+            &value as *const libc::c_int as *const libc::c_void,
+            std::mem::size_of_val(&value) as libc::socklen_t,
+        );
+        match ret {
+            0 => Ok(()),
+            err_code => bail!("setsockopt returned {}", err_code),
+        }
+    }
+}
+
+#[cfg(all(unix, not(any(target_os = "linux", target_os = "android"))))]
+pub fn set_so_priority<S: std::os::unix::io::AsRawFd>(_socket: &S, _priority: u8) -> ZResult<()> {
+    bail!("SO_PRIORITY is only supported on Linux")
+}
+
+#[cfg(windows)]
+pub fn set_so_priority<S: std::os::windows::io::AsRawSocket>(
+    _socket: &S,
+    _priority: u8,
+) -> ZResult<()> {
+    bail!("SO_PRIORITY is only supported on Linux")
+}
+
 pub fn get_interface(name: &str) -> ZResult<Option<IpAddr>> {
     #[cfg(unix)]
     {
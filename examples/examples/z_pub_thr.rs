@@ -39,7 +39,7 @@ fn main() {
     let mut count: usize = 0;
     let mut start = std::time::Instant::now();
     loop {
-        publisher.put(data.clone()).res().unwrap();
+        publisher.put_now(data.clone()).unwrap();
 
         if print {
             if count < number {
@@ -11,7 +11,7 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
-use async_std::prelude::FutureExt;
+use std::time::Duration;
 use zenoh::config::Config;
 use zenoh::prelude::r#async::*;
 use zenoh::scouting::WhatAmI;
@@ -23,18 +23,13 @@ async fn main() {
 
     println!("Scouting...");
     let receiver = zenoh::scout(WhatAmI::Peer | WhatAmI::Router, Config::default())
+        .timeout(Duration::from_secs(1))
         .res()
         .await
         .unwrap();
 
-    let _ = async {
-        while let Ok(hello) = receiver.recv_async().await {
-            println!("{}", hello);
-        }
+    // the receiver is disconnected when scouting stops
+    while let Ok(hello) = receiver.recv_async().await {
+        println!("{}", hello);
     }
-    .timeout(std::time::Duration::from_secs(1))
-    .await;
-
-    // stop scouting
-    drop(receiver);
 }
@@ -33,6 +33,8 @@ use zenoh_core::{bail, Result as ZResult};
 use zenoh_protocol::proto::{MessageReader, MessageWriter, TransportMessage};
 use zenoh_protocol_core::{EndPoint, Locator};
 
+pub mod tls;
+
 const WBUF_SIZE: usize = 64;
 
 /*************************************/
@@ -46,6 +48,7 @@ pub struct Link {
     pub mtu: u16,
     pub is_reliable: bool,
     pub is_streamed: bool,
+    pub cert_common_name: Option<String>,
 }
 
 #[async_trait]
@@ -73,6 +76,7 @@ impl From<&LinkUnicast> for Link {
             mtu: link.get_mtu(),
             is_reliable: link.is_reliable(),
             is_streamed: link.is_streamed(),
+            cert_common_name: link.get_cert_common_name(),
         }
     }
 }
@@ -92,6 +96,7 @@ impl From<&LinkMulticast> for Link {
             mtu: link.get_mtu(),
             is_reliable: link.is_reliable(),
             is_streamed: false,
+            cert_common_name: None,
         }
     }
 }
@@ -141,6 +146,21 @@ pub trait LinkUnicastTrait: Send + Sync {
     async fn read(&self, buffer: &mut [u8]) -> ZResult<usize>;
     async fn read_exact(&self, buffer: &mut [u8]) -> ZResult<()>;
     async fn close(&self) -> ZResult<()>;
+    /// Set the IP DSCP value of the packets sent from now on, if supported by the link.
+    fn set_dscp(&self, _dscp: u8) -> ZResult<()> {
+        Ok(())
+    }
+    /// Set the Linux SO_PRIORITY of the packets sent from now on, if supported by the link.
+    fn set_so_priority(&self, _priority: u8) -> ZResult<()> {
+        Ok(())
+    }
+    /// Set the priority of the batches written from now on, for the links sending each
+    /// priority on its own stream.
+    fn set_priority(&self, _priority: usize) {}
+    /// The common name of the certificate authenticated by the remote peer, if any.
+    fn get_cert_common_name(&self) -> Option<String> {
+        None
+    }
 }
 
 impl LinkUnicast {
@@ -284,6 +304,14 @@ pub trait LinkMulticastTrait: Send + Sync {
     async fn write_all(&self, buffer: &[u8]) -> ZResult<()>;
     async fn read<'a>(&'a self, buffer: &mut [u8]) -> ZResult<(usize, Cow<'a, Locator>)>;
     async fn close(&self) -> ZResult<()>;
+    /// Set the IP DSCP value of the packets sent from now on, if supported by the link.
+    fn set_dscp(&self, _dscp: u8) -> ZResult<()> {
+        Ok(())
+    }
+    /// Set the Linux SO_PRIORITY of the packets sent from now on, if supported by the link.
+    fn set_so_priority(&self, _priority: u8) -> ZResult<()> {
+        Ok(())
+    }
 }
 
 impl LinkMulticast {
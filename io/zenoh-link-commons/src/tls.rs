@@ -0,0 +1,107 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::io::Cursor;
+use zenoh_core::{zerror, Result as ZResult};
+use zenoh_protocol_core::locators::ArcProperties;
+
+/// Loads some PEM content from the endpoint configuration: either the raw PEM
+/// given for `raw_config_key`, or the content of the file given for `file_config_key`.
+/// Returns `None` if neither of them is configured.
+pub async fn load_pem(
+    config: Option<&ArcProperties>,
+    raw_config_key: &str,
+    file_config_key: &str,
+) -> ZResult<Option<Vec<u8>>> {
+    if let Some(config) = config {
+        if let Some(value) = config.get(raw_config_key) {
+            return Ok(Some(value.as_bytes().to_vec()));
+        } else if let Some(value) = config.get(file_config_key) {
+            return Ok(Some(
+                async_std::fs::read(value)
+                    .await
+                    .map_err(|e| zerror!("Invalid TLS file {}: {}", value, e))?,
+            ));
+        }
+    }
+    Ok(None)
+}
+
+/// Parses the DER-encoded certificates of some PEM content.
+pub fn pem_certificates(pem: &[u8]) -> ZResult<Vec<Vec<u8>>> {
+    rustls_pemfile::certs(&mut Cursor::new(pem))
+        .map_err(|e| zerror!("Invalid TLS certificate: {}", e).into())
+}
+
+/// Parses the first DER-encoded RSA, PKCS8 or EC private key of some PEM content.
+pub fn pem_private_key(pem: &[u8]) -> ZResult<Vec<u8>> {
+    rustls_pemfile::read_all(&mut Cursor::new(pem))
+        .map_err(|e| zerror!("Invalid TLS private key: {}", e))?
+        .drain(..)
+        .find_map(|x| match x {
+            rustls_pemfile::Item::RSAKey(k)
+            | rustls_pemfile::Item::PKCS8Key(k)
+            | rustls_pemfile::Item::ECKey(k) => Some(k),
+            _ => None,
+        })
+        .ok_or_else(|| zerror!("No TLS private key has been provided.").into())
+}
+
+/// Returns the common name of the subject of a DER-encoded certificate, if any.
+pub fn cert_common_name(der: &[u8]) -> Option<String> {
+    match x509_parser::parse_x509_certificate(der) {
+        Ok((_, cert)) => cert
+            .subject()
+            .iter_common_name()
+            .next()
+            .and_then(|cn| cn.as_str().ok())
+            .map(|cn| cn.to_string()),
+        Err(e) => {
+            log::debug!("Unable to parse the remote certificate: {}", e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CERT: &str = "-----BEGIN CERTIFICATE-----
+MIIDLDCCAhSgAwIBAgIIOSrMNOHTWkEwDQYJKoZIhvcNAQELBQAwIDEeMBwGA1UE
+AxMVbWluaWNhIHJvb3QgY2EgNjVhMmE5MB4XDTIyMTEyNTA5NTMyOVoXDTI0MTIy
+NTA5NTMyOVowFDESMBAGA1UEAxMJbG9jYWxob3N0MIIBIjANBgkqhkiG9w0BAQEF
+AAOCAQ8AMIIBCgKCAQEAxeTx0qv8qwy3cWa/MJ+2RegsrvJxfw3A5AKHVe8vLj0U
+z5ipZVA+ydp1NmaXtkxrALazbE7sOpvFKZX7Vf02jL/5TEVPf0NAXq0YKe81x5ZS
+kcLFQrAopS+pC9O7LpOotWqtaYXx3We9fpsg+yaihNqLpKLIeg3vAFB8QCejfrw5
+IJG0J/fvrQLvHs/TkO/Ckb6p6ZnL61aZPWdwmMUUglbUIsjNU4giA2BRTBaaDfDH
+1G/jzfC7lpwH3H9S5siwzSU5YZZR8QgfMPomIbmESaHWjx3t4MB9MzAKn2wASejJ
+YgBCr0NeKkwLLyEC/X9B5in40g66x/JETwRLCpscfQIDAQABo3YwdDAOBgNVHQ8B
+Af8EBAMCBaAwHQYDVR0lBBYwFAYIKwYBBQUHAwEGCCsGAQUFBwMCMAwGA1UdEwEB
+/wQCMAAwHwYDVR0jBBgwFoAUPGIecA4Wf8IBsvT/CbyLOBxaj7wwFAYDVR0RBA0w
+C4IJbG9jYWxob3N0MA0GCSqGSIb3DQEBCwUAA4IBAQACkX6QryVOwVxlm/d8zPyU
+eVOquvwATtGHR1Ra32abgL4o0DSTEs2zsPGLlsyefbs9VVq0l6UOCfnaLBJ02izx
+UjEQcvSuMKjexDPmTEUa3ZJi8xV5Rx+/jOQDSHuMzdSp27OIn3kP/Ym8rVKW/GPD
+ISVQ1D3DTCfe9vo6BO8+k4+JjVLwS0mqSEcNzIe3VqpYOa2Ic6uHsfw1+YFGFPIG
+WUswTbYMCsLT9fcAl3EMTE7Diub9LfPPC51U4EUyTdnWegK6WWKJwwUfFij2Hw8V
+ob5ssEzyjB+/+toNeOgNc8LCPV6iECtY1uuaKRkYHLFVvIGyr0WjBGZbXN+DFgjU
+-----END CERTIFICATE-----";
+
+    #[test]
+    fn tls_cert_common_name() {
+        let certs = pem_certificates(CERT.as_bytes()).unwrap();
+        assert_eq!(certs.len(), 1);
+        assert_eq!(cert_common_name(&certs[0]), Some("localhost".to_string()));
+        assert_eq!(cert_common_name(b"not a certificate"), None);
+    }
+}
@@ -24,6 +24,10 @@ use zenoh_cfg_properties::Properties;
 use zenoh_config::Config;
 use zenoh_core::{bail, Result as ZResult};
 
+#[cfg(feature = "transport_mem")]
+pub use zenoh_link_mem as mem;
+#[cfg(feature = "transport_mem")]
+use zenoh_link_mem::{LinkManagerUnicastMem, MemLocatorInspector, MEM_LOCATOR_PREFIX};
 #[cfg(feature = "transport_quic")]
 pub use zenoh_link_quic as quic;
 #[cfg(feature = "transport_quic")]
@@ -60,13 +64,18 @@ use zenoh_link_serial::{LinkManagerUnicastSerial, SerialLocatorInspector, SERIAL
 #[cfg(feature = "transport_ws")]
 pub use zenoh_link_ws as ws;
 #[cfg(feature = "transport_ws")]
-use zenoh_link_ws::{LinkManagerUnicastWs, WsLocatorInspector, WS_LOCATOR_PREFIX};
+use zenoh_link_ws::{
+    LinkManagerUnicastWs, WsConfigurator, WsLocatorInspector, WssConfigurator, WssLocatorInspector,
+    WSS_LOCATOR_PREFIX, WS_LOCATOR_PREFIX,
+};
 
 pub use zenoh_link_commons::*;
 pub use zenoh_protocol_core::{EndPoint, Locator};
 
 #[derive(Default, Clone)]
 pub struct LocatorInspector {
+    #[cfg(feature = "transport_mem")]
+    mem_inspector: MemLocatorInspector,
     #[cfg(feature = "transport_quic")]
     quic_inspector: QuicLocatorInspector,
     #[cfg(feature = "transport_tcp")]
@@ -77,6 +86,8 @@ pub struct LocatorInspector {
     udp_inspector: UdpLocatorInspector,
     #[cfg(feature = "transport_ws")]
     ws_inspector: WsLocatorInspector,
+    #[cfg(feature = "transport_ws")]
+    wss_inspector: WssLocatorInspector,
     #[cfg(feature = "transport_serial")]
     serial_inspector: SerialLocatorInspector,
 }
@@ -86,6 +97,8 @@ impl LocatorInspector {
         use zenoh_link_commons::LocatorInspector;
         let protocol = locator.protocol();
         match protocol {
+            #[cfg(feature = "transport_mem")]
+            MEM_LOCATOR_PREFIX => self.mem_inspector.is_multicast(locator).await,
             #[cfg(feature = "transport_tcp")]
             TCP_LOCATOR_PREFIX => self.tcp_inspector.is_multicast(locator).await,
             #[cfg(feature = "transport_udp")]
@@ -98,6 +111,8 @@ impl LocatorInspector {
             UNIXSOCKSTREAM_LOCATOR_PREFIX => Ok(false),
             #[cfg(feature = "transport_ws")]
             WS_LOCATOR_PREFIX => self.ws_inspector.is_multicast(locator).await,
+            #[cfg(feature = "transport_ws")]
+            WSS_LOCATOR_PREFIX => self.wss_inspector.is_multicast(locator).await,
             #[cfg(feature = "transport_serial")]
             SERIAL_LOCATOR_PREFIX => self.serial_inspector.is_multicast(locator).await,
             _ => bail!("Unsupported protocol: {}.", protocol),
@@ -110,6 +125,10 @@ pub struct LinkConfigurator {
     quic_inspector: QuicConfigurator,
     #[cfg(feature = "transport_tls")]
     tls_inspector: TlsConfigurator,
+    #[cfg(feature = "transport_ws")]
+    ws_inspector: WsConfigurator,
+    #[cfg(feature = "transport_ws")]
+    wss_inspector: WssConfigurator,
 }
 impl LinkConfigurator {
     #[allow(unused_variables, unused_mut)]
@@ -144,6 +163,17 @@ impl LinkConfigurator {
                 self.tls_inspector.inspect_config(config).await,
             );
         }
+        #[cfg(feature = "transport_ws")]
+        {
+            insert_config(
+                WS_LOCATOR_PREFIX.into(),
+                self.ws_inspector.inspect_config(config).await,
+            );
+            insert_config(
+                WSS_LOCATOR_PREFIX.into(),
+                self.wss_inspector.inspect_config(config).await,
+            );
+        }
         (configs, errors)
     }
 }
@@ -157,6 +187,8 @@ pub struct LinkManagerBuilderUnicast;
 impl LinkManagerBuilderUnicast {
     pub fn make(_manager: NewLinkChannelSender, protocol: &str) -> ZResult<LinkManagerUnicast> {
         match protocol {
+            #[cfg(feature = "transport_mem")]
+            MEM_LOCATOR_PREFIX => Ok(Arc::new(LinkManagerUnicastMem::new(_manager))),
             #[cfg(feature = "transport_tcp")]
             TCP_LOCATOR_PREFIX => Ok(Arc::new(LinkManagerUnicastTcp::new(_manager))),
             #[cfg(feature = "transport_udp")]
@@ -171,6 +203,8 @@ impl LinkManagerBuilderUnicast {
             }
             #[cfg(feature = "transport_ws")]
             WS_LOCATOR_PREFIX => Ok(Arc::new(LinkManagerUnicastWs::new(_manager))),
+            #[cfg(feature = "transport_ws")]
+            WSS_LOCATOR_PREFIX => Ok(Arc::new(LinkManagerUnicastWs::new_secure(_manager))),
             #[cfg(feature = "transport_serial")]
             SERIAL_LOCATOR_PREFIX => Ok(Arc::new(LinkManagerUnicastSerial::new(_manager))),
             _ => bail!("Unicast not supported for {} protocol", protocol),
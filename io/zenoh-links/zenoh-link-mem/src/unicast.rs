@@ -0,0 +1,274 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use zenoh_core::Result as ZResult;
+use zenoh_core::{bail, zerror, zlock, zread, zwrite};
+use zenoh_link_commons::{
+    LinkManagerUnicastTrait, LinkUnicast, LinkUnicastTrait, NewLinkChannelSender,
+};
+use zenoh_protocol_core::{EndPoint, Locator};
+
+use super::{MEM_DEFAULT_MTU, MEM_LOCATOR_PREFIX};
+
+zenoh_core::lazy_static! {
+    // The listeners of all the link managers of the process, indexed by address.
+    static ref MEM_LISTENERS: Mutex<HashMap<String, NewLinkChannelSender>> =
+        Mutex::new(HashMap::new());
+}
+
+// Used to give a unique address to the connecting side of the links. The counter is
+// appended after a '~' so that the address can not be mistaken for an endpoint configuration.
+static MEM_LINK_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+pub struct LinkUnicastMem {
+    // The batches sent to the other side of the link (None once closed)
+    tx: Mutex<Option<flume::Sender<Vec<u8>>>>,
+    // The batches received from the other side of the link
+    rx: flume::Receiver<Vec<u8>>,
+    src_locator: Locator,
+    dst_locator: Locator,
+}
+
+impl LinkUnicastMem {
+    // Creates the two connected sides of a link between `src` and `dst`.
+    fn pair(src: &str, dst: &str) -> (LinkUnicastMem, LinkUnicastMem) {
+        let (tx1, rx1) = flume::unbounded();
+        let (tx2, rx2) = flume::unbounded();
+        let src = Locator::new(MEM_LOCATOR_PREFIX, &src);
+        let dst = Locator::new(MEM_LOCATOR_PREFIX, &dst);
+        (
+            LinkUnicastMem {
+                tx: Mutex::new(Some(tx1)),
+                rx: rx2,
+                src_locator: src.clone(),
+                dst_locator: dst.clone(),
+            },
+            LinkUnicastMem {
+                tx: Mutex::new(Some(tx2)),
+                rx: rx1,
+                src_locator: dst,
+                dst_locator: src,
+            },
+        )
+    }
+}
+
+#[async_trait]
+impl LinkUnicastTrait for LinkUnicastMem {
+    async fn close(&self) -> ZResult<()> {
+        log::trace!("Closing MEM link: {}", self);
+        // Dropping the sender makes the reads on the other side fail
+        zlock!(self.tx).take();
+        Ok(())
+    }
+
+    async fn write(&self, buffer: &[u8]) -> ZResult<usize> {
+        let tx = zlock!(self.tx).clone();
+        match tx {
+            Some(tx) if tx.send(buffer.to_vec()).is_ok() => Ok(buffer.len()),
+            _ => {
+                let e = zerror!("Write error on MEM link {}: link closed", self);
+                log::trace!("{}", e);
+                Err(e.into())
+            }
+        }
+    }
+
+    async fn write_all(&self, buffer: &[u8]) -> ZResult<()> {
+        self.write(buffer).await.map(|_| ())
+    }
+
+    async fn read(&self, buffer: &mut [u8]) -> ZResult<usize> {
+        let batch = self.rx.recv_async().await.map_err(|_| {
+            let e = zerror!("Read error on MEM link {}: link closed", self);
+            log::trace!("{}", e);
+            e
+        })?;
+        if batch.len() > buffer.len() {
+            bail!(
+                "Read error on MEM link {}: received {} bytes in a {} bytes buffer",
+                self,
+                batch.len(),
+                buffer.len()
+            );
+        }
+        buffer[..batch.len()].copy_from_slice(&batch);
+        Ok(batch.len())
+    }
+
+    async fn read_exact(&self, buffer: &mut [u8]) -> ZResult<()> {
+        let n = self.read(buffer).await?;
+        if n != buffer.len() {
+            bail!(
+                "Read error on MEM link {}: received {} bytes instead of {}",
+                self,
+                n,
+                buffer.len()
+            );
+        }
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn get_src(&self) -> &Locator {
+        &self.src_locator
+    }
+
+    #[inline(always)]
+    fn get_dst(&self) -> &Locator {
+        &self.dst_locator
+    }
+
+    #[inline(always)]
+    fn get_mtu(&self) -> u16 {
+        *MEM_DEFAULT_MTU
+    }
+
+    #[inline(always)]
+    fn is_reliable(&self) -> bool {
+        true
+    }
+
+    #[inline(always)]
+    fn is_streamed(&self) -> bool {
+        false
+    }
+}
+
+impl fmt::Display for LinkUnicastMem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} => {}", &self.src_locator, &self.dst_locator)?;
+        Ok(())
+    }
+}
+
+impl fmt::Debug for LinkUnicastMem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Mem")
+            .field("src", &self.src_locator)
+            .field("dst", &self.dst_locator)
+            .finish()
+    }
+}
+
+/*************************************/
+/*          LISTENER                 */
+/*************************************/
+pub struct LinkManagerUnicastMem {
+    manager: NewLinkChannelSender,
+    listeners: Arc<RwLock<HashMap<String, EndPoint>>>,
+}
+
+impl LinkManagerUnicastMem {
+    pub fn new(manager: NewLinkChannelSender) -> Self {
+        Self {
+            manager,
+            listeners: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+}
+
+impl Drop for LinkManagerUnicastMem {
+    fn drop(&mut self) {
+        let mut global = zlock!(MEM_LISTENERS);
+        for addr in zread!(self.listeners).keys() {
+            global.remove(addr);
+        }
+    }
+}
+
+#[async_trait]
+impl LinkManagerUnicastTrait for LinkManagerUnicastMem {
+    async fn new_link(&self, endpoint: EndPoint) -> ZResult<LinkUnicast> {
+        let dst = endpoint.locator.address();
+        let listener = zlock!(MEM_LISTENERS).get(dst).cloned().ok_or_else(|| {
+            let e = zerror!(
+                "Can not create a new MEM link bound to {}: no listener",
+                dst
+            );
+            log::warn!("{}", e);
+            e
+        })?;
+
+        let src = format!(
+            "{}~{}",
+            dst,
+            MEM_LINK_COUNTER.fetch_add(1, Ordering::SeqCst)
+        );
+        let (link, remote) = LinkUnicastMem::pair(&src, dst);
+
+        // Communicate the other side of the link to the listening transport manager
+        listener
+            .send_async(LinkUnicast(Arc::new(remote)))
+            .await
+            .map_err(|e| {
+                let e = zerror!("Can not create a new MEM link bound to {}: {}", dst, e);
+                log::warn!("{}", e);
+                e
+            })?;
+
+        Ok(LinkUnicast(Arc::new(link)))
+    }
+
+    async fn new_listener(&self, endpoint: EndPoint) -> ZResult<Locator> {
+        let addr = endpoint.locator.address().to_owned();
+        {
+            let mut global = zlock!(MEM_LISTENERS);
+            if global.contains_key(&addr) {
+                let e = zerror!(
+                    "Can not create a new MEM listener on {}: address already in use",
+                    addr
+                );
+                log::warn!("{}", e);
+                return Err(e.into());
+            }
+            global.insert(addr.clone(), self.manager.clone());
+        }
+
+        let locator = endpoint.locator.clone();
+        zwrite!(self.listeners).insert(addr, endpoint);
+
+        Ok(locator)
+    }
+
+    async fn del_listener(&self, endpoint: &EndPoint) -> ZResult<()> {
+        let addr = endpoint.locator.address();
+
+        zwrite!(self.listeners).remove(addr).ok_or_else(|| {
+            let e = zerror!(
+                "Can not delete the MEM listener because it has not been found: {}",
+                addr
+            );
+            log::trace!("{}", e);
+            e
+        })?;
+        zlock!(MEM_LISTENERS).remove(addr);
+        Ok(())
+    }
+
+    fn get_listeners(&self) -> Vec<EndPoint> {
+        zread!(self.listeners).values().cloned().collect()
+    }
+
+    fn get_locators(&self) -> Vec<Locator> {
+        zread!(self.listeners)
+            .values()
+            .map(|x| x.locator.clone())
+            .collect()
+    }
+}
@@ -20,12 +20,15 @@
 use async_std::net::ToSocketAddrs;
 use async_trait::async_trait;
 use config::{
-    TLS_ROOT_CA_CERTIFICATE_FILE, TLS_SERVER_CERTIFICATE_FILE, TLS_SERVER_PRIVATE_KEY_FILE,
+    TLS_CLIENT_AUTH, TLS_CLIENT_CERTIFICATE_FILE, TLS_CLIENT_CERTIFICATE_RAW,
+    TLS_CLIENT_PRIVATE_KEY_FILE, TLS_CLIENT_PRIVATE_KEY_RAW, TLS_ROOT_CA_CERTIFICATE_FILE,
+    TLS_ROOT_CA_CERTIFICATE_RAW, TLS_SERVER_CERTIFICATE_FILE, TLS_SERVER_CERTIFICATE_RAW,
+    TLS_SERVER_PRIVATE_KEY_FILE, TLS_SERVER_PRIVATE_KEY_RAW,
 };
 use std::net::SocketAddr;
 use webpki::{DnsName, DnsNameRef};
 use zenoh_cfg_properties::Properties;
-use zenoh_config::{Config, Locator};
+use zenoh_config::{Config, Locator, ZN_FALSE, ZN_TRUE};
 use zenoh_core::{bail, zconfigurable, zerror, Result as ZResult};
 use zenoh_link_commons::{ConfigurationInspector, LocatorInspector};
 
@@ -35,6 +38,10 @@ pub use unicast::*;
 // Default ALPN protocol
 pub const ALPN_QUIC_HTTP: &[&[u8]] = &[b"hq-29"];
 
+// QUIC mandates TLS 1.3
+pub const QUIC_TLS_PROTOCOL_VERSIONS: &[&rustls::SupportedProtocolVersion] =
+    &[&rustls::version::TLS13];
+
 // Default MTU (QUIC PDU) in bytes.
 // NOTE: Since QUIC is a byte-stream oriented transport, theoretically it has
 //       no limit regarding the MTU. However, given the batching strategy
@@ -81,6 +88,54 @@ impl ConfigurationInspector<Config> for QuicConfigurator {
                 tls_server_certificate.into(),
             );
         }
+        if let Some(tls_client_auth) = c.client_auth() {
+            match tls_client_auth {
+                true => properties.insert(TLS_CLIENT_AUTH.into(), ZN_TRUE.into()),
+                false => properties.insert(TLS_CLIENT_AUTH.into(), ZN_FALSE.into()),
+            };
+        }
+        if let Some(tls_client_private_key) = c.client_private_key() {
+            properties.insert(
+                TLS_CLIENT_PRIVATE_KEY_FILE.into(),
+                tls_client_private_key.into(),
+            );
+        }
+        if let Some(tls_client_certificate) = c.client_certificate() {
+            properties.insert(
+                TLS_CLIENT_CERTIFICATE_FILE.into(),
+                tls_client_certificate.into(),
+            );
+        }
+        if let Some(tls_ca_certificate) = c.root_ca_certificate_pem() {
+            properties.insert(
+                TLS_ROOT_CA_CERTIFICATE_RAW.into(),
+                tls_ca_certificate.into(),
+            );
+        }
+        if let Some(tls_server_private_key) = c.server_private_key_pem() {
+            properties.insert(
+                TLS_SERVER_PRIVATE_KEY_RAW.into(),
+                tls_server_private_key.into(),
+            );
+        }
+        if let Some(tls_server_certificate) = c.server_certificate_pem() {
+            properties.insert(
+                TLS_SERVER_CERTIFICATE_RAW.into(),
+                tls_server_certificate.into(),
+            );
+        }
+        if let Some(tls_client_private_key) = c.client_private_key_pem() {
+            properties.insert(
+                TLS_CLIENT_PRIVATE_KEY_RAW.into(),
+                tls_client_private_key.into(),
+            );
+        }
+        if let Some(tls_client_certificate) = c.client_certificate_pem() {
+            properties.insert(
+                TLS_CLIENT_CERTIFICATE_RAW.into(),
+                tls_client_certificate.into(),
+            );
+        }
 
         Ok(properties)
     }
@@ -97,6 +152,16 @@ zconfigurable! {
     // Amount of time in microseconds to throttle the accept loop upon an error.
     // Default set to 100 ms.
     static ref QUIC_ACCEPT_THROTTLE_TIME: u64 = 100_000;
+    // Whether 0-RTT is attempted when reconnecting to a QUIC endpoint for which a TLS
+    // session ticket is available (opt-in). The data sent in 0-RTT is not protected
+    // against replay attacks. If the server rejects it, it is sent again once the
+    // connection is established.
+    static ref QUIC_ENABLE_0RTT: bool = false;
+    // Maximum amount of data written in 0-RTT and kept to be sent again if the server rejects it.
+    // Beyond, the writes wait for the server to accept or reject the 0-RTT data.
+    static ref QUIC_MAX_EARLY_DATA: usize = 65_536;
+    // Maximum number of TLS sessions stored for resumption (i.e. 0-RTT) purposes.
+    static ref QUIC_SESSION_CACHE_SIZE: usize = 256;
 }
 
 pub mod config {
@@ -110,6 +175,15 @@ pub mod config {
 
     pub const TLS_SERVER_CERTIFICATE_FILE: &str = ZN_TLS_SERVER_CERTIFICATE_STR;
     pub const TLS_SERVER_CERTIFICATE_RAW: &str = "tls_server_certificate_raw";
+
+    pub const TLS_CLIENT_PRIVATE_KEY_FILE: &str = ZN_TLS_CLIENT_PRIVATE_KEY_STR;
+    pub const TLS_CLIENT_PRIVATE_KEY_RAW: &str = "tls_client_private_key_raw";
+
+    pub const TLS_CLIENT_CERTIFICATE_FILE: &str = ZN_TLS_CLIENT_CERTIFICATE_STR;
+    pub const TLS_CLIENT_CERTIFICATE_RAW: &str = "tls_client_certificate_raw";
+
+    pub const TLS_CLIENT_AUTH: &str = ZN_TLS_CLIENT_AUTH_STR;
+    pub const TLS_CLIENT_AUTH_DEFAULT: &str = ZN_TLS_CLIENT_AUTH_DEFAULT;
 }
 
 async fn get_quic_addr(address: &Locator) -> ZResult<SocketAddr> {
@@ -14,36 +14,67 @@
 
 use crate::{
     config::*, get_quic_addr, get_quic_dns, ALPN_QUIC_HTTP, QUIC_ACCEPT_THROTTLE_TIME,
-    QUIC_DEFAULT_MTU, QUIC_LOCATOR_PREFIX,
+    QUIC_DEFAULT_MTU, QUIC_ENABLE_0RTT, QUIC_LOCATOR_PREFIX, QUIC_MAX_EARLY_DATA,
+    QUIC_SESSION_CACHE_SIZE, QUIC_TLS_PROTOCOL_VERSIONS,
 };
+use async_std::channel::{self, Receiver, Sender};
 use async_std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
 use async_std::prelude::FutureExt;
 use async_std::sync::Mutex as AsyncMutex;
 use async_std::task;
-use async_std::task::JoinHandle;
 use async_trait::async_trait;
+use std::collections::hash_map::Entry;
 use std::collections::HashMap;
 use std::fmt;
-use std::io::BufReader;
 use std::net::IpAddr;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::{Arc, RwLock};
 use std::time::Duration;
 use zenoh_core::{bail, Result as ZResult};
 use zenoh_core::{zasynclock, zerror, zread, zwrite};
+use zenoh_link_commons::tls::{cert_common_name, load_pem, pem_certificates, pem_private_key};
 use zenoh_link_commons::{
     LinkManagerUnicastTrait, LinkUnicast, LinkUnicastTrait, NewLinkChannelSender,
 };
-use zenoh_protocol_core::{EndPoint, Locator};
-use zenoh_sync::Signal;
+use zenoh_protocol_core::locators::ArcProperties;
+use zenoh_protocol_core::{EndPoint, Locator, Priority};
+use zenoh_sync::{Executor, JoinHandle, Signal};
+
+// A connection established with 0-RTT, whose acceptance by the server is not yet known
+struct ZeroRtt {
+    accepted: quinn::ZeroRttAccepted,
+    // The data written so far, to be written again if the server rejects the 0-RTT data
+    early_data: Vec<u8>,
+}
+
+type Frame = ZResult<Vec<u8>>;
+
+// The frames received on all the streams of a link, and the frame being read
+struct FrameReader {
+    frames: Receiver<Frame>,
+    frame: Vec<u8>,
+    pos: usize,
+}
 
+// Each priority is sent on its own stream, so that a congested priority does not delay the
+// others: the first one on the bidirectional stream opened with the connection, the other ones
+// on unidirectional streams opened on demand. On streamed links, the transport writes every
+// batch at once, prefixed with its 16-bit length: the batches are read whole from each stream
+// and handed to the transport in their order of arrival.
 pub struct LinkUnicastQuic {
     connection: quinn::Connection,
     src_addr: SocketAddr,
     src_locator: Locator,
     dst_locator: Locator,
     send: AsyncMutex<quinn::SendStream>,
-    recv: AsyncMutex<quinn::RecvStream>,
+    send_priorities: AsyncMutex<HashMap<usize, quinn::SendStream>>,
+    priority: AtomicUsize,
+    // The stream opened with the connection and the frames sender, until the reception starts
+    recv: AsyncMutex<Option<(quinn::RecvStream, Sender<Frame>)>>,
+    receiving: AtomicBool,
+    reader: AsyncMutex<FrameReader>,
+    zero_rtt: AsyncMutex<Option<ZeroRtt>>,
+    zero_rtt_pending: AtomicBool,
 }
 
 impl LinkUnicastQuic {
@@ -53,7 +84,9 @@ impl LinkUnicastQuic {
         dst_locator: Locator,
         send: quinn::SendStream,
         recv: quinn::RecvStream,
+        zero_rtt_accepted: Option<quinn::ZeroRttAccepted>,
     ) -> LinkUnicastQuic {
+        let (frames_tx, frames_rx) = channel::bounded(Priority::NUM);
         // Build the Quic object
         LinkUnicastQuic {
             connection,
@@ -61,8 +94,120 @@ impl LinkUnicastQuic {
             src_locator: Locator::new(QUIC_LOCATOR_PREFIX, &src_addr),
             dst_locator,
             send: AsyncMutex::new(send),
-            recv: AsyncMutex::new(recv),
+            send_priorities: AsyncMutex::new(HashMap::new()),
+            priority: AtomicUsize::new(0),
+            recv: AsyncMutex::new(Some((recv, frames_tx))),
+            receiving: AtomicBool::new(false),
+            reader: AsyncMutex::new(FrameReader {
+                frames: frames_rx,
+                frame: vec![],
+                pos: 0,
+            }),
+            zero_rtt_pending: AtomicBool::new(zero_rtt_accepted.is_some()),
+            zero_rtt: AsyncMutex::new(zero_rtt_accepted.map(|accepted| ZeroRtt {
+                accepted,
+                early_data: vec![],
+            })),
+        }
+    }
+
+    // Wait for the server to accept or reject the 0-RTT data of the connection. If rejected,
+    // the stream opened in 0-RTT is lost: open a new one and write the early data again.
+    // Nothing can be read before the server received the early data, so this is done on read,
+    // or on write once the early data reached its maximum size.
+    async fn confirm_zero_rtt(&self) -> ZResult<()> {
+        if !self.zero_rtt_pending.load(Ordering::Acquire) {
+            return Ok(());
+        }
+        let mut send = zasynclock!(self.send);
+        let mut guard = zasynclock!(self.zero_rtt);
+        if let Some(ZeroRtt {
+            accepted,
+            early_data,
+        }) = guard.take()
+        {
+            if !accepted.await {
+                log::debug!(
+                    "0-RTT rejected on QUIC link {}: resending on a new stream",
+                    self
+                );
+                let (mut new_send, new_recv) =
+                    self.connection.open_bi().await.map_err(|e| {
+                        zerror!("Can not reopen a stream on QUIC link {}: {}", self, e)
+                    })?;
+                new_send
+                    .write_all(&early_data)
+                    .await
+                    .map_err(|e| zerror!("Write error on QUIC link {}: {}", self, e))?;
+                *send = new_send;
+                if let Some((recv, _)) = zasynclock!(self.recv).as_mut() {
+                    *recv = new_recv;
+                }
+            }
         }
+        self.zero_rtt_pending.store(false, Ordering::Release);
+        Ok(())
+    }
+
+    // Write on the first stream and keep the data written while the 0-RTT data is not accepted.
+    // Returns false if the 0-RTT data had to be confirmed to bound the early data.
+    async fn write_early_data(&self, buffer: &[u8]) -> ZResult<bool> {
+        {
+            let mut send = zasynclock!(self.send);
+            let mut guard = zasynclock!(self.zero_rtt);
+            match guard.as_mut() {
+                Some(zero_rtt)
+                    if zero_rtt.early_data.len() + buffer.len() <= *QUIC_MAX_EARLY_DATA =>
+                {
+                    send.write_all(buffer).await.map_err(|e| {
+                        log::trace!("Write error on QUIC link {}: {}", self, e);
+                        zerror!(e)
+                    })?;
+                    zero_rtt.early_data.extend_from_slice(buffer);
+                    return Ok(true);
+                }
+                Some(_) => {}
+                None => return Ok(false),
+            }
+        }
+        self.confirm_zero_rtt().await?;
+        Ok(false)
+    }
+
+    // Start reading the frames of the first stream and of the streams opened by the peer
+    async fn start_receiving(&self) {
+        if let Some((recv, frames)) = zasynclock!(self.recv).take() {
+            Executor::current().spawn(read_frames(recv, frames.clone()));
+            Executor::current().spawn(accept_streams(self.connection.clone(), frames));
+        }
+        self.receiving.store(true, Ordering::Release);
+    }
+}
+
+async fn read_frame(stream: &mut quinn::RecvStream) -> Result<Vec<u8>, quinn::ReadExactError> {
+    let mut length = [0_u8; 2];
+    stream.read_exact(&mut length).await?;
+    let mut frame = vec![0_u8; 2 + u16::from_le_bytes(length) as usize];
+    frame[..2].copy_from_slice(&length);
+    stream.read_exact(&mut frame[2..]).await?;
+    Ok(frame)
+}
+
+async fn read_frames(mut stream: quinn::RecvStream, frames: Sender<Frame>) {
+    loop {
+        let frame = read_frame(&mut stream)
+            .await
+            .map_err(|e| zerror!("Read error on QUIC stream {}: {}", stream.id(), e).into());
+        let failed = frame.is_err();
+        if frames.send(frame).await.is_err() || failed {
+            break;
+        }
+    }
+}
+
+async fn accept_streams(connection: quinn::Connection, frames: Sender<Frame>) {
+    while let Ok(stream) = connection.accept_uni().await {
+        Executor::current().spawn(read_frames(stream, frames.clone()));
     }
 }
 
@@ -70,16 +215,25 @@ impl LinkUnicastQuic {
 impl LinkUnicastTrait for LinkUnicastQuic {
     async fn close(&self) -> ZResult<()> {
         log::trace!("Closing QUIC link: {}", self);
-        // Flush the QUIC stream
+        // Flush the QUIC streams
         let mut guard = zasynclock!(self.send);
         if let Err(e) = guard.finish().await {
             log::trace!("Error closing QUIC stream {}: {}", self, e);
         }
+        for stream in zasynclock!(self.send_priorities).values_mut() {
+            if let Err(e) = stream.finish().await {
+                log::trace!("Error closing QUIC stream {}: {}", self, e);
+            }
+        }
         self.connection.close(quinn::VarInt::from_u32(0), &[0]);
         Ok(())
     }
 
+    // Partial writes are only consistent on a single stream: they are made on the first one
     async fn write(&self, buffer: &[u8]) -> ZResult<usize> {
+        if self.zero_rtt_pending.load(Ordering::Acquire) && self.write_early_data(buffer).await? {
+            return Ok(buffer.len());
+        }
         let mut guard = zasynclock!(self.send);
         guard.write(buffer).await.map_err(|e| {
             log::trace!("Write error on QUIC link {}: {}", self, e);
@@ -88,41 +242,75 @@ impl LinkUnicastTrait for LinkUnicastQuic {
     }
 
     async fn write_all(&self, buffer: &[u8]) -> ZResult<()> {
-        let mut guard = zasynclock!(self.send);
-        guard.write_all(buffer).await.map_err(|e| {
+        // Until the 0-RTT data is accepted, all the priorities are written on the first stream
+        if self.zero_rtt_pending.load(Ordering::Acquire) && self.write_early_data(buffer).await? {
+            return Ok(());
+        }
+        let priority = self.priority.load(Ordering::Acquire);
+        let res = if priority == 0 {
+            zasynclock!(self.send).write_all(buffer).await
+        } else {
+            let mut guard = zasynclock!(self.send_priorities);
+            let stream = match guard.entry(priority) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    let stream = self.connection.open_uni().await.map_err(|e| {
+                        zerror!("Can not open a stream on QUIC link {}: {}", self, e)
+                    })?;
+                    entry.insert(stream)
+                }
+            };
+            stream.write_all(buffer).await
+        };
+        res.map_err(|e| {
             log::trace!("Write error on QUIC link {}: {}", self, e);
             zerror!(e).into()
         })
     }
 
     async fn read(&self, buffer: &mut [u8]) -> ZResult<usize> {
-        let mut guard = zasynclock!(self.recv);
-        guard
-            .read(buffer)
-            .await
-            .map_err(|e| {
-                let e = zerror!("Read error on QUIC link {}: {}", self, e);
+        if !self.receiving.load(Ordering::Acquire) {
+            self.confirm_zero_rtt().await?;
+            self.start_receiving().await;
+        }
+        let mut guard = zasynclock!(self.reader);
+        let reader = &mut *guard;
+        if reader.pos == reader.frame.len() {
+            reader.frame = reader.frames.recv().await.map_err(|_| {
+                zerror!(
+                    "Read error on QUIC link {}: connection has been closed",
+                    self
+                )
+            })??;
+            reader.pos = 0;
+        }
+        let n = buffer.len().min(reader.frame.len() - reader.pos);
+        buffer[..n].copy_from_slice(&reader.frame[reader.pos..reader.pos + n]);
+        reader.pos += n;
+        Ok(n)
+    }
+
+    async fn read_exact(&self, buffer: &mut [u8]) -> ZResult<()> {
+        let mut n = 0;
+        while n < buffer.len() {
+            n += self.read(&mut buffer[n..]).await.map_err(|e| {
                 log::trace!("{}", &e);
                 e
-            })?
-            .ok_or_else(|| {
-                let e = zerror!(
-                    "Read error on QUIC link {}: stream {} has been closed",
-                    self,
-                    guard.id()
-                );
-                log::trace!("{}", &e);
-                e.into()
-            })
+            })?;
+        }
+        Ok(())
     }
 
-    async fn read_exact(&self, buffer: &mut [u8]) -> ZResult<()> {
-        let mut guard = zasynclock!(self.recv);
-        guard.read_exact(buffer).await.map_err(|e| {
-            let e = zerror!("Read error on QUIC link {}: {}", self, e);
-            log::trace!("{}", &e);
-            e.into()
-        })
+    fn set_priority(&self, priority: usize) {
+        self.priority.store(priority, Ordering::Release);
+    }
+
+    fn get_cert_common_name(&self) -> Option<String> {
+        // The certificates presented by the remote peer, if authenticated
+        self.connection
+            .peer_identity()
+            .and_then(|identity| identity.downcast::<Vec<rustls::Certificate>>().ok())
+            .and_then(|certs| certs.first().and_then(|cert| cert_common_name(&cert.0)))
     }
 
     #[inline(always)]
@@ -207,6 +395,9 @@ impl ListenerUnicastQuic {
 pub struct LinkManagerUnicastQuic {
     manager: NewLinkChannelSender,
     listeners: Arc<RwLock<HashMap<SocketAddr, ListenerUnicastQuic>>>,
+    // TLS sessions are shared among all the client connections of this manager
+    // so that reconnections can be resumed with 0-RTT.
+    session_storage: Arc<dyn rustls::client::StoresClientSessions>,
 }
 
 impl LinkManagerUnicastQuic {
@@ -214,6 +405,9 @@ impl LinkManagerUnicastQuic {
         Self {
             manager,
             listeners: Arc::new(RwLock::new(HashMap::new())),
+            session_storage: rustls::client::ClientSessionMemoryCache::new(
+                *QUIC_SESSION_CACHE_SIZE,
+            ),
         }
     }
 }
@@ -231,42 +425,57 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastQuic {
         let mut root_cert_store = rustls::RootCertStore::empty();
 
         // Read the certificates
-        let f = if let Some(config) = config {
-            if let Some(value) = config.get(TLS_ROOT_CA_CERTIFICATE_RAW) {
-                value.as_bytes().to_vec()
-            } else if let Some(value) = config.get(TLS_ROOT_CA_CERTIFICATE_FILE) {
-                async_std::fs::read(value)
-                    .await
-                    .map_err(|e| zerror!("Invalid QUIC CA certificate file: {}", e))?
-            } else {
-                vec![]
-            }
-        } else {
-            vec![]
-        };
-
-        let certificates = if f.is_empty() {
-            rustls_native_certs::load_native_certs()
+        let certificates = match load_pem(
+            config.as_ref(),
+            TLS_ROOT_CA_CERTIFICATE_RAW,
+            TLS_ROOT_CA_CERTIFICATE_FILE,
+        )
+        .await?
+        {
+            Some(f) if !f.is_empty() => pem_certificates(&f)
                 .map_err(|e| zerror!("Invalid QUIC CA certificate file: {}", e))?
                 .drain(..)
-                .map(|x| rustls::Certificate(x.0))
-                .collect::<Vec<rustls::Certificate>>()
-        } else {
-            rustls_pemfile::certs(&mut BufReader::new(f.as_slice()))
+                .map(rustls::Certificate)
+                .collect::<Vec<rustls::Certificate>>(),
+            _ => rustls_native_certs::load_native_certs()
                 .map_err(|e| zerror!("Invalid QUIC CA certificate file: {}", e))?
                 .drain(..)
-                .map(rustls::Certificate)
-                .collect::<Vec<rustls::Certificate>>()
+                .map(|x| rustls::Certificate(x.0))
+                .collect::<Vec<rustls::Certificate>>(),
         };
         for c in certificates.iter() {
             root_cert_store.add(c).map_err(|e| zerror!("{}", e))?;
         }
 
-        let mut client_crypto = rustls::ClientConfig::builder()
-            .with_safe_defaults()
-            .with_root_certificates(root_cert_store)
-            .with_no_client_auth();
+        let client_crypto = rustls::ClientConfig::builder()
+            .with_safe_default_cipher_suites()
+            .with_safe_default_kx_groups()
+            .with_protocol_versions(QUIC_TLS_PROTOCOL_VERSIONS)
+            .map_err(|e| zerror!("Can not create a new QUIC link bound to {}: {}", host, e))?
+            .with_root_certificates(root_cert_store);
+        let mut client_crypto = if get_client_auth(config.as_ref())? {
+            log::debug!("Loading QUIC client authentication key and certificate...");
+            let certificates = load_certificates(
+                config.as_ref(),
+                TLS_CLIENT_CERTIFICATE_RAW,
+                TLS_CLIENT_CERTIFICATE_FILE,
+            )
+            .await?;
+            let private_key = load_private_key(
+                config.as_ref(),
+                TLS_CLIENT_PRIVATE_KEY_RAW,
+                TLS_CLIENT_PRIVATE_KEY_FILE,
+            )
+            .await?;
+            client_crypto
+                .with_single_cert(certificates, private_key)
+                .map_err(|e| zerror!("Can not create a new QUIC link bound to {}: {}", host, e))?
+        } else {
+            client_crypto.with_no_client_auth()
+        };
         client_crypto.alpn_protocols = ALPN_QUIC_HTTP.iter().map(|&x| x.into()).collect();
+        client_crypto.session_storage = self.session_storage.clone();
+        client_crypto.enable_early_data = *QUIC_ENABLE_0RTT;
 
         let ip_addr: IpAddr = if addr.is_ipv4() {
             Ipv4Addr::UNSPECIFIED.into()
@@ -281,11 +490,32 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastQuic {
             .local_addr()
             .map_err(|e| zerror!("Can not create a new QUIC link bound to {}: {}", host, e))?;
 
-        let quic_conn = quic_endpoint
+        let connecting = quic_endpoint
             .connect(addr, host)
-            .map_err(|e| zerror!("Can not create a new QUIC link bound to {}: {}", host, e))?
-            .await
             .map_err(|e| zerror!("Can not create a new QUIC link bound to {}: {}", host, e))?;
+        // Attempt a 0-RTT connection if enabled and a previous TLS session is available,
+        // otherwise fallback on a regular 1-RTT handshake.
+        let (quic_conn, zero_rtt_accepted) = if *QUIC_ENABLE_0RTT {
+            match connecting.into_0rtt() {
+                Ok((quic_conn, accepted)) => {
+                    log::trace!("Resuming QUIC connection to {} with 0-RTT", host);
+                    (quic_conn, Some(accepted))
+                }
+                Err(connecting) => (
+                    connecting.await.map_err(|e| {
+                        zerror!("Can not create a new QUIC link bound to {}: {}", host, e)
+                    })?,
+                    None,
+                ),
+            }
+        } else {
+            (
+                connecting.await.map_err(|e| {
+                    zerror!("Can not create a new QUIC link bound to {}: {}", host, e)
+                })?,
+                None,
+            )
+        };
 
         let (send, recv) = quic_conn
             .open_bi()
@@ -298,6 +528,7 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastQuic {
             dst_locator.to_owned(),
             send,
             recv,
+            zero_rtt_accepted,
         ));
 
         Ok(LinkUnicast(link))
@@ -312,58 +543,59 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastQuic {
         let locator = &endpoint.locator;
         let addr = get_quic_addr(locator).await?;
 
-        let f = if let Some(value) = config.get(TLS_SERVER_CERTIFICATE_RAW) {
-            value.as_bytes().to_vec()
-        } else if let Some(value) = config.get(TLS_SERVER_CERTIFICATE_FILE) {
-            async_std::fs::read(value)
-                .await
-                .map_err(|e| zerror!("Invalid QUIC CA certificate file: {}", e))?
-        } else {
-            bail!("No QUIC CA certificate has been provided.");
-        };
-        let certificates = rustls_pemfile::certs(&mut BufReader::new(f.as_slice()))
-            .map_err(|e| zerror!("Invalid QUIC CA certificate file: {}", e))?
-            .drain(..)
-            .map(rustls::Certificate)
-            .collect();
-
-        // Private keys
-        let f = if let Some(value) = config.get(TLS_SERVER_PRIVATE_KEY_RAW) {
-            value.as_bytes().to_vec()
-        } else if let Some(value) = config.get(TLS_SERVER_PRIVATE_KEY_FILE) {
-            async_std::fs::read(value)
-                .await
-                .map_err(|e| zerror!("Invalid QUIC CA certificate file: {}", e))?
-        } else {
-            bail!("No QUIC CA private key has been provided.");
-        };
-        let private_key = rustls::PrivateKey(
-            rustls_pemfile::read_all(&mut BufReader::new(f.as_slice()))
-                .map_err(|e| zerror!("Invalid QUIC CA private key file: {}", e))?
-                .iter()
-                .filter_map(|x| match x {
-                    rustls_pemfile::Item::RSAKey(k)
-                    | rustls_pemfile::Item::PKCS8Key(k)
-                    | rustls_pemfile::Item::ECKey(k) => Some(k.to_vec()),
-                    _ => None,
-                })
-                .take(1)
-                .next()
-                .ok_or_else(|| zerror!("No QUIC CA private key has been provided."))?,
-        );
+        let certificates = load_certificates(
+            Some(config),
+            TLS_SERVER_CERTIFICATE_RAW,
+            TLS_SERVER_CERTIFICATE_FILE,
+        )
+        .await?;
+        let private_key = load_private_key(
+            Some(config),
+            TLS_SERVER_PRIVATE_KEY_RAW,
+            TLS_SERVER_PRIVATE_KEY_FILE,
+        )
+        .await?;
 
         // Server config
-        let mut server_crypto = rustls::ServerConfig::builder()
-            .with_safe_defaults()
-            .with_no_client_auth()
-            .with_single_cert(certificates, private_key)?;
+        let server_crypto = rustls::ServerConfig::builder()
+            .with_safe_default_cipher_suites()
+            .with_safe_default_kx_groups()
+            .with_protocol_versions(QUIC_TLS_PROTOCOL_VERSIONS)
+            .map_err(|e| zerror!("Can not create a new QUIC listener on {}: {}", addr, e))?;
+        let mut server_crypto = if get_client_auth(Some(config))? {
+            // Clients are required to present a certificate signed by the root CA
+            let certs = load_certificates(
+                Some(config),
+                TLS_ROOT_CA_CERTIFICATE_RAW,
+                TLS_ROOT_CA_CERTIFICATE_FILE,
+            )
+            .await
+            .map_err(|_| {
+                zerror!("Missing root certificates while client authentication is enabled.")
+            })?;
+            let mut root_cert_store = rustls::RootCertStore::empty();
+            for c in certs.iter() {
+                root_cert_store.add(c).map_err(|e| zerror!("{}", e))?;
+            }
+            server_crypto
+                .with_client_cert_verifier(rustls::server::AllowAnyAuthenticatedClient::new(
+                    root_cert_store,
+                ))
+                .with_single_cert(certificates, private_key)?
+        } else {
+            server_crypto
+                .with_no_client_auth()
+                .with_single_cert(certificates, private_key)?
+        };
         server_crypto.alpn_protocols = ALPN_QUIC_HTTP.iter().map(|&x| x.into()).collect();
+        // QUIC requires the maximum early data size to be either 0 or u32::MAX
+        server_crypto.max_early_data_size = if *QUIC_ENABLE_0RTT { u32::MAX } else { 0 };
         let mut server_config = quinn::ServerConfig::with_crypto(Arc::new(server_crypto));
 
-        // We do not accept unidireactional streams.
+        // The priorities other than the first one are sent on unidirectional streams.
         Arc::get_mut(&mut server_config.transport)
             .unwrap()
-            .max_concurrent_uni_streams(0_u8.into());
+            .max_concurrent_uni_streams((Priority::NUM as u8).into());
         // For the time being we only allow one bidirectional stream
         Arc::get_mut(&mut server_config.transport)
             .unwrap()
@@ -389,7 +621,7 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastQuic {
         let c_manager = self.manager.clone();
         let c_listeners = self.listeners.clone();
         let c_addr = local_addr;
-        let handle = task::spawn(async move {
+        let handle = Executor::current().spawn(async move {
             // Wait for the accept loop to terminate
             let res = accept_task(quic_endpoint, c_active, c_signal, c_manager).await;
             zwrite!(c_listeners).remove(&c_addr);
@@ -516,7 +748,7 @@ async fn accept_task(
             }
         };
 
-        // Get the bideractional streams. Note that we don't allow unidirectional streams.
+        // Get the bidirectional stream. The unidirectional ones are accepted by the link.
         let (send, recv) = match quic_conn.accept_bi().await {
             Ok(stream) => stream,
             Err(e) => {
@@ -534,6 +766,7 @@ async fn accept_task(
             Locator::new(QUIC_LOCATOR_PREFIX, &dst_addr),
             send,
             recv,
+            None,
         ));
 
         // Communicate the new link to the initial transport manager
@@ -544,3 +777,47 @@ async fn accept_task(
 
     Ok(())
 }
+
+fn get_client_auth(config: Option<&ArcProperties>) -> ZResult<bool> {
+    match config.and_then(|c| c.get(TLS_CLIENT_AUTH)) {
+        Some(value) => Ok(value.parse()?),
+        None => Ok(TLS_CLIENT_AUTH_DEFAULT.parse()?),
+    }
+}
+
+async fn load_certificates(
+    config: Option<&ArcProperties>,
+    raw_config_key: &str,
+    file_config_key: &str,
+) -> ZResult<Vec<rustls::Certificate>> {
+    let f = load_pem(config, raw_config_key, file_config_key)
+        .await?
+        .ok_or_else(|| {
+            zerror!(
+                "Neither {} nor {} have been provided.",
+                raw_config_key,
+                file_config_key
+            )
+        })?;
+    Ok(pem_certificates(&f)?
+        .drain(..)
+        .map(rustls::Certificate)
+        .collect())
+}
+
+async fn load_private_key(
+    config: Option<&ArcProperties>,
+    raw_config_key: &str,
+    file_config_key: &str,
+) -> ZResult<rustls::PrivateKey> {
+    let f = load_pem(config, raw_config_key, file_config_key)
+        .await?
+        .ok_or_else(|| {
+            zerror!(
+                "Neither {} nor {} have been provided.",
+                raw_config_key,
+                file_config_key
+            )
+        })?;
+    Ok(rustls::PrivateKey(pem_private_key(&f)?))
+}
@@ -30,6 +30,10 @@ use zenoh_protocol_core::{EndPoint, Locator};
 // Maximum MTU (Serial PDU) in bytes.
 const SERIAL_MAX_MTU: u16 = z_serial::MAX_MTU as u16;
 
+// Minimum MTU (Serial PDU) in bytes: smaller MTUs would not allow to
+// exchange the transport session initialization messages.
+const SERIAL_MIN_MTU: u16 = 64;
+
 const DEFAULT_BAUDRATE: u32 = 9_600;
 
 const DEFAULT_EXCLUSIVE: bool = true;
@@ -62,30 +66,70 @@ pub fn get_unix_path(locator: &Locator) -> &Path {
     locator.address().as_ref()
 }
 
+// Serial parameters can be given either in the locator metadata
+// (e.g. `serial//dev/ttyUSB0?baudrate=115200`) or in the endpoint configuration
+// (e.g. `serial//dev/ttyUSB0#baudrate=115200`), the latter taking precedence.
+// NOTE: the framing and the CRC are fixed by z-serial, hence they are not configurable.
 pub fn get_baud_rate(endpoint: &EndPoint) -> u32 {
-    match &endpoint.config {
-        Some(config) => {
-            if let Some(baudrate) = config.get(config::PORT_BAUD_RATE_RAW) {
-                return u32::from_str(baudrate).unwrap_or(DEFAULT_BAUDRATE);
+    match endpoint.parameter(config::PORT_BAUD_RATE_RAW) {
+        Some(baudrate) => match u32::from_str(baudrate) {
+            Ok(baudrate) if baudrate > 0 => baudrate,
+            _ => {
+                log::warn!(
+                    "Invalid Serial baudrate {}: using the default baudrate {}",
+                    baudrate,
+                    DEFAULT_BAUDRATE
+                );
+                DEFAULT_BAUDRATE
             }
-            DEFAULT_BAUDRATE
-        }
+        },
         None => DEFAULT_BAUDRATE,
     }
 }
 
 pub fn get_exclusive(endpoint: &EndPoint) -> bool {
-    match &endpoint.config {
-        Some(config) => {
-            if let Some(exclusive) = config.get(config::PORT_EXCLUSIVE_RAW) {
-                return bool::from_str(exclusive).unwrap_or(DEFAULT_EXCLUSIVE);
-            }
+    match endpoint.parameter(config::PORT_EXCLUSIVE_RAW) {
+        Some(exclusive) => bool::from_str(exclusive).unwrap_or_else(|_| {
+            log::warn!(
+                "Invalid Serial exclusive {}: using the default {}",
+                exclusive,
+                DEFAULT_EXCLUSIVE
+            );
             DEFAULT_EXCLUSIVE
-        }
+        }),
         None => DEFAULT_EXCLUSIVE,
     }
 }
 
+/// Returns the MTU to be used on the serial link. Zenoh messages larger than
+/// the MTU are fragmented by the transport before being framed on the wire.
+/// The MTU can't exceed the maximum frame size supported by the serial framing.
+pub fn get_mtu(endpoint: &EndPoint) -> u16 {
+    match endpoint.parameter(config::PORT_MTU_RAW) {
+        Some(mtu) => match u16::from_str(mtu) {
+            Ok(mtu) if mtu > SERIAL_MAX_MTU => {
+                log::warn!(
+                    "Serial MTU {} exceeds the maximum MTU {}: using {}",
+                    mtu,
+                    SERIAL_MAX_MTU,
+                    SERIAL_MAX_MTU
+                );
+                SERIAL_MAX_MTU
+            }
+            Ok(mtu) if mtu >= SERIAL_MIN_MTU => mtu,
+            _ => {
+                log::warn!(
+                    "Invalid Serial MTU {}: using the default MTU {}",
+                    mtu,
+                    *SERIAL_DEFAULT_MTU
+                );
+                *SERIAL_DEFAULT_MTU
+            }
+        },
+        None => *SERIAL_DEFAULT_MTU,
+    }
+}
+
 pub fn get_unix_path_as_string(locator: &Locator) -> String {
     locator.address().to_owned()
 }
@@ -93,4 +137,56 @@ pub fn get_unix_path_as_string(locator: &Locator) -> String {
 pub mod config {
     pub const PORT_BAUD_RATE_RAW: &str = "baudrate";
     pub const PORT_EXCLUSIVE_RAW: &str = "exclusive";
+    pub const PORT_MTU_RAW: &str = "mtu";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn endpoint(s: &str) -> EndPoint {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn serial_parameters() {
+        // Defaults
+        let e = endpoint("serial//dev/ttyUSB0");
+        assert_eq!(get_baud_rate(&e), DEFAULT_BAUDRATE);
+        assert_eq!(get_exclusive(&e), DEFAULT_EXCLUSIVE);
+        assert_eq!(get_mtu(&e), *SERIAL_DEFAULT_MTU);
+
+        // Locator metadata
+        let e = endpoint("serial//dev/ttyUSB0?baudrate=115200;exclusive=false;mtu=256");
+        assert_eq!(get_baud_rate(&e), 115_200);
+        assert!(!get_exclusive(&e));
+        assert_eq!(get_mtu(&e), 256);
+
+        // Endpoint configuration
+        let e = endpoint("serial//dev/ttyUSB0#baudrate=57600;mtu=512");
+        assert_eq!(get_baud_rate(&e), 57_600);
+        assert_eq!(get_mtu(&e), 512);
+
+        // The endpoint configuration takes precedence over the locator metadata
+        let e = endpoint("serial//dev/ttyUSB0?baudrate=115200;mtu=256#baudrate=57600");
+        assert_eq!(get_baud_rate(&e), 57_600);
+        assert_eq!(get_mtu(&e), 256);
+    }
+
+    #[test]
+    fn serial_invalid_parameters() {
+        let e = endpoint("serial//dev/ttyUSB0?baudrate=fast;exclusive=maybe;mtu=big");
+        assert_eq!(get_baud_rate(&e), DEFAULT_BAUDRATE);
+        assert_eq!(get_exclusive(&e), DEFAULT_EXCLUSIVE);
+        assert_eq!(get_mtu(&e), *SERIAL_DEFAULT_MTU);
+
+        let e = endpoint("serial//dev/ttyUSB0?baudrate=0");
+        assert_eq!(get_baud_rate(&e), DEFAULT_BAUDRATE);
+
+        // The MTU is bounded by the serial framing
+        let e = endpoint(&format!("serial//dev/ttyUSB0?mtu={}", SERIAL_MAX_MTU + 1));
+        assert_eq!(get_mtu(&e), SERIAL_MAX_MTU);
+        let e = endpoint(&format!("serial//dev/ttyUSB0?mtu={}", SERIAL_MIN_MTU - 1));
+        assert_eq!(get_mtu(&e), *SERIAL_DEFAULT_MTU);
+    }
 }
@@ -14,8 +14,7 @@
 
 use async_std::prelude::*;
 use async_std::sync::Mutex as AsyncMutex;
-use async_std::task::JoinHandle;
-use async_std::task::{self};
+use async_std::task;
 use async_trait::async_trait;
 use std::cell::UnsafeCell;
 use std::collections::HashMap;
@@ -30,15 +29,14 @@ use zenoh_link_commons::{
     NewLinkChannelSender,
 };
 use zenoh_protocol_core::{EndPoint, Locator};
-use zenoh_sync::Signal;
+use zenoh_sync::{Executor, JoinHandle, Signal};
 
 use z_serial::ZSerial;
 
-use crate::get_exclusive;
+use crate::{get_exclusive, get_mtu};
 
 use super::{
-    get_baud_rate, get_unix_path_as_string, SERIAL_ACCEPT_THROTTLE_TIME, SERIAL_DEFAULT_MTU,
-    SERIAL_LOCATOR_PREFIX,
+    get_baud_rate, get_unix_path_as_string, SERIAL_ACCEPT_THROTTLE_TIME, SERIAL_LOCATOR_PREFIX,
 };
 
 struct LinkUnicastSerial {
@@ -55,6 +53,8 @@ struct LinkUnicastSerial {
     src_locator: Locator,
     // The serial destination path (random UUIDv4)
     dst_locator: Locator,
+    // The MTU of the serial link
+    mtu: u16,
     // A flag that tells if the link is connected or not
     is_connected: Arc<AtomicBool>,
     // Locks for reading and writing ends of the serial.
@@ -70,12 +70,14 @@ impl LinkUnicastSerial {
         port: UnsafeCell<ZSerial>,
         src_path: &str,
         dst_path: &str,
+        mtu: u16,
         is_connected: Arc<AtomicBool>,
     ) -> Self {
         Self {
             port,
             src_locator: Locator::new(SERIAL_LOCATOR_PREFIX, &src_path),
             dst_locator: Locator::new(SERIAL_LOCATOR_PREFIX, &dst_path),
+            mtu,
             is_connected,
             write_lock: AsyncMutex::new(()),
             read_lock: AsyncMutex::new(()),
@@ -178,7 +180,7 @@ impl LinkUnicastTrait for LinkUnicastSerial {
 
     #[inline(always)]
     fn get_mtu(&self) -> u16 {
-        *SERIAL_DEFAULT_MTU
+        self.mtu
     }
 
     #[inline(always)]
@@ -259,7 +261,8 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastSerial {
         let path = get_unix_path_as_string(&endpoint.locator);
         let baud_rate = get_baud_rate(&endpoint);
         let exclusive = get_exclusive(&endpoint);
-        log::trace!("Opening Serial Link on device {path:?}, with baudrate {baud_rate} and exclusive set as {exclusive}");
+        let mtu = get_mtu(&endpoint);
+        log::trace!("Opening Serial Link on device {path:?}, with baudrate {baud_rate}, MTU {mtu} and exclusive set as {exclusive}");
         let port = ZSerial::new(path.clone(), baud_rate, exclusive).map_err(|e| {
             let e = zerror!(
                 "Can not create a new Serial link bound to {:?}: {}",
@@ -275,6 +278,7 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastSerial {
             UnsafeCell::new(port),
             &path,
             &path,
+            mtu,
             Arc::new(AtomicBool::new(true)),
         ));
 
@@ -285,7 +289,8 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastSerial {
         let path = get_unix_path_as_string(&endpoint.locator);
         let baud_rate = get_baud_rate(&endpoint);
         let exclusive = get_exclusive(&endpoint);
-        log::trace!("Creating Serial listener on device {path:?}, with baudrate {baud_rate} and exclusive set as {exclusive}");
+        let mtu = get_mtu(&endpoint);
+        log::trace!("Creating Serial listener on device {path:?}, with baudrate {baud_rate}, MTU {mtu} and exclusive set as {exclusive}");
         let port = ZSerial::new(path.clone(), baud_rate, exclusive).map_err(|e| {
             let e = zerror!(
                 "Can not create a new Serial link bound to {:?}: {}",
@@ -303,6 +308,7 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastSerial {
             UnsafeCell::new(port),
             &path,
             &dst_path,
+            mtu,
             is_connected.clone(),
         ));
 
@@ -314,7 +320,7 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastSerial {
         let c_signal = signal.clone();
         let c_manager = self.manager.clone();
         let c_listeners = self.listeners.clone();
-        let handle = task::spawn(async move {
+        let handle = Executor::current().spawn(async move {
             // Wait for the accept loop to terminate
             let res = accept_read_task(
                 link,
@@ -14,7 +14,6 @@
 use async_std::net::{SocketAddr, TcpListener, TcpStream};
 use async_std::prelude::*;
 use async_std::task;
-use async_std::task::JoinHandle;
 use async_trait::async_trait;
 use std::collections::HashMap;
 use std::convert::TryInto;
@@ -28,7 +27,7 @@ use zenoh_link_commons::{
     LinkManagerUnicastTrait, LinkUnicast, LinkUnicastTrait, NewLinkChannelSender,
 };
 use zenoh_protocol_core::{EndPoint, Locator};
-use zenoh_sync::Signal;
+use zenoh_sync::{Executor, JoinHandle, Signal};
 
 use super::{
     get_tcp_addrs, TCP_ACCEPT_THROTTLE_TIME, TCP_DEFAULT_MTU, TCP_LINGER_TIMEOUT,
@@ -96,6 +95,23 @@ impl LinkUnicastTrait for LinkUnicastTcp {
         })
     }
 
+    fn set_dscp(&self, dscp: u8) -> ZResult<()> {
+        zenoh_util::net::set_dscp(&self.socket, &self.dst_addr, dscp)
+            .map_err(|e| zerror!("Can not set DSCP {} on TCP link {}: {}", dscp, self, e).into())
+    }
+
+    fn set_so_priority(&self, priority: u8) -> ZResult<()> {
+        zenoh_util::net::set_so_priority(&self.socket, priority).map_err(|e| {
+            zerror!(
+                "Can not set SO_PRIORITY {} on TCP link {}: {}",
+                priority,
+                self,
+                e
+            )
+            .into()
+        })
+    }
+
     async fn write(&self, buffer: &[u8]) -> ZResult<usize> {
         (&self.socket).write(buffer).await.map_err(|e| {
             let e = zerror!("Write error on TCP link {}: {}", self, e);
@@ -307,7 +323,7 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastTcp {
                     let c_manager = self.manager.clone();
                     let c_listeners = self.listeners.clone();
                     let c_addr = local_addr;
-                    let handle = task::spawn(async move {
+                    let handle = Executor::current().spawn(async move {
                         // Wait for the accept loop to terminate
                         let res = accept_task(socket, c_active, c_signal, c_manager).await;
                         zwrite!(c_listeners).remove(&c_addr);
@@ -22,8 +22,10 @@ use std::{convert::TryFrom, net::SocketAddr};
 use async_std::net::ToSocketAddrs;
 use async_trait::async_trait;
 use config::{
-    TLS_CLIENT_AUTH, TLS_CLIENT_CERTIFICATE_FILE, TLS_CLIENT_PRIVATE_KEY_FILE,
-    TLS_ROOT_CA_CERTIFICATE_FILE, TLS_SERVER_CERTIFICATE_FILE, TLS_SERVER_PRIVATE_KEY_FILE,
+    TLS_CLIENT_AUTH, TLS_CLIENT_CERTIFICATE_FILE, TLS_CLIENT_CERTIFICATE_RAW,
+    TLS_CLIENT_PRIVATE_KEY_FILE, TLS_CLIENT_PRIVATE_KEY_RAW, TLS_ROOT_CA_CERTIFICATE_FILE,
+    TLS_ROOT_CA_CERTIFICATE_RAW, TLS_SERVER_CERTIFICATE_FILE, TLS_SERVER_CERTIFICATE_RAW,
+    TLS_SERVER_PRIVATE_KEY_FILE, TLS_SERVER_PRIVATE_KEY_RAW,
 };
 use zenoh_cfg_properties::Properties;
 use zenoh_config::{Config, ZN_FALSE, ZN_TRUE};
@@ -98,6 +100,36 @@ impl ConfigurationInspector<Config> for TlsConfigurator {
                 tls_client_certificate.into(),
             );
         }
+        if let Some(tls_ca_certificate) = c.root_ca_certificate_pem() {
+            properties.insert(
+                TLS_ROOT_CA_CERTIFICATE_RAW.into(),
+                tls_ca_certificate.into(),
+            );
+        }
+        if let Some(tls_server_private_key) = c.server_private_key_pem() {
+            properties.insert(
+                TLS_SERVER_PRIVATE_KEY_RAW.into(),
+                tls_server_private_key.into(),
+            );
+        }
+        if let Some(tls_server_certificate) = c.server_certificate_pem() {
+            properties.insert(
+                TLS_SERVER_CERTIFICATE_RAW.into(),
+                tls_server_certificate.into(),
+            );
+        }
+        if let Some(tls_client_private_key) = c.client_private_key_pem() {
+            properties.insert(
+                TLS_CLIENT_PRIVATE_KEY_RAW.into(),
+                tls_client_private_key.into(),
+            );
+        }
+        if let Some(tls_client_certificate) = c.client_certificate_pem() {
+            properties.insert(
+                TLS_CLIENT_CERTIFICATE_RAW.into(),
+                tls_client_certificate.into(),
+            );
+        }
 
         Ok(properties)
     }
@@ -114,6 +146,9 @@ zconfigurable! {
     // Amount of time in microseconds to throttle the accept loop upon an error.
     // Default set to 100 ms.
     static ref TLS_ACCEPT_THROTTLE_TIME: u64 = 100_000;
+    // Amount of time in milliseconds an incoming connection is given to complete the TLS handshake.
+    // Default set to 10 s.
+    static ref TLS_HANDSHAKE_TIMEOUT: u64 = 10_000;
 }
 
 pub mod config {
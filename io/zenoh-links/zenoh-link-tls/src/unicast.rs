@@ -14,19 +14,17 @@ use crate::TLS_LOCATOR_PREFIX;
 //
 use crate::{
     config::*, get_tls_addr, get_tls_host, get_tls_server_name, TLS_ACCEPT_THROTTLE_TIME,
-    TLS_DEFAULT_MTU, TLS_LINGER_TIMEOUT,
+    TLS_DEFAULT_MTU, TLS_HANDSHAKE_TIMEOUT, TLS_LINGER_TIMEOUT,
 };
 use async_rustls::rustls::server::AllowAnyAuthenticatedClient;
 use async_rustls::rustls::version::TLS13;
 pub use async_rustls::rustls::*;
 pub use async_rustls::webpki::*;
 use async_rustls::{TlsAcceptor, TlsConnector, TlsStream};
-use async_std::fs;
 use async_std::net::{SocketAddr, TcpListener, TcpStream};
 use async_std::prelude::FutureExt;
 use async_std::sync::Mutex as AsyncMutex;
 use async_std::task;
-use async_std::task::JoinHandle;
 use async_trait::async_trait;
 use futures::io::AsyncReadExt;
 use futures::io::AsyncWriteExt;
@@ -42,12 +40,13 @@ use std::sync::{Arc, RwLock};
 use std::time::Duration;
 use zenoh_core::Result as ZResult;
 use zenoh_core::{zasynclock, zerror, zread, zwrite};
+use zenoh_link_commons::tls::{cert_common_name, load_pem};
 use zenoh_link_commons::{
     LinkManagerUnicastTrait, LinkUnicast, LinkUnicastTrait, NewLinkChannelSender,
 };
 use zenoh_protocol_core::locators::ArcProperties;
 use zenoh_protocol_core::{EndPoint, Locator};
-use zenoh_sync::Signal;
+use zenoh_sync::{Executor, JoinHandle, Signal};
 
 pub struct LinkUnicastTls {
     // The underlying socket as returned from the async-rustls library
@@ -59,12 +58,16 @@ pub struct LinkUnicastTls {
     //       the same stream: there is only one task at the time that writes on
     //       the stream and only one task at the time that reads from the stream.
     inner: UnsafeCell<TlsStream<TcpStream>>,
+    // A handle on the underlying TCP stream, used to set its socket options
+    tcp_stream: TcpStream,
     // The source socket address of this link (address used on the local host)
     src_addr: SocketAddr,
     src_locator: Locator,
     // The destination socket address of this link (address used on the local host)
     dst_addr: SocketAddr,
     dst_locator: Locator,
+    // The common name of the certificate presented by the remote peer
+    cert_common_name: Option<String>,
     // Make sure there are no concurrent read or writes
     write_mtx: AsyncMutex<()>,
     read_mtx: AsyncMutex<()>,
@@ -105,13 +108,22 @@ impl LinkUnicastTls {
             );
         }
 
+        // Retrieve the common name of the remote certificate, if any
+        let (_, tls_state) = socket.get_ref();
+        let cert_common_name = tls_state
+            .peer_certificates()
+            .and_then(|certs| certs.first())
+            .and_then(|cert| cert_common_name(&cert.0));
+
         // Build the Tls object
         LinkUnicastTls {
+            tcp_stream: tcp_stream.clone(),
             inner: UnsafeCell::new(socket),
             src_addr,
             src_locator: Locator::new(TLS_LOCATOR_PREFIX, &src_addr),
             dst_addr,
             dst_locator: Locator::new(TLS_LOCATOR_PREFIX, &dst_addr),
+            cert_common_name,
             write_mtx: AsyncMutex::new(()),
             read_mtx: AsyncMutex::new(()),
         }
@@ -142,6 +154,23 @@ impl LinkUnicastTrait for LinkUnicastTls {
         res.map_err(|e| zerror!(e).into())
     }
 
+    fn set_dscp(&self, dscp: u8) -> ZResult<()> {
+        zenoh_util::net::set_dscp(&self.tcp_stream, &self.dst_addr, dscp)
+            .map_err(|e| zerror!("Can not set DSCP {} on TLS link {}: {}", dscp, self, e).into())
+    }
+
+    fn set_so_priority(&self, priority: u8) -> ZResult<()> {
+        zenoh_util::net::set_so_priority(&self.tcp_stream, priority).map_err(|e| {
+            zerror!(
+                "Can not set SO_PRIORITY {} on TLS link {}: {}",
+                priority,
+                self,
+                e
+            )
+            .into()
+        })
+    }
+
     async fn write(&self, buffer: &[u8]) -> ZResult<usize> {
         let _guard = zasynclock!(self.write_mtx);
         self.get_sock_mut().write(buffer).await.map_err(|e| {
@@ -198,6 +227,11 @@ impl LinkUnicastTrait for LinkUnicastTls {
     fn is_streamed(&self) -> bool {
         true
     }
+
+    #[inline(always)]
+    fn get_cert_common_name(&self) -> Option<String> {
+        self.cert_common_name.clone()
+    }
 }
 
 impl Drop for LinkUnicastTls {
@@ -359,7 +393,7 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastTls {
         let c_manager = self.manager.clone();
         let c_listeners = self.listeners.clone();
         let c_addr = local_addr;
-        let handle = task::spawn(async move {
+        let handle = Executor::current().spawn(async move {
             // Wait for the accept loop to terminate
             let res = accept_task(socket, acceptor, c_active, c_signal, c_manager).await;
             zwrite!(c_listeners).remove(&c_addr);
@@ -480,24 +514,37 @@ async fn accept_task(
                 continue;
             }
         };
-        // Accept the TLS connection
-        let tls_stream = match acceptor.accept(tcp_stream).await {
-            Ok(stream) => TlsStream::Server(stream),
-            Err(e) => {
-                let e = format!("Can not accept TLS connection: {}", e);
-                log::warn!("{}", e);
-                continue;
+        // Perform the TLS handshake in its own task so that a slow or malicious
+        // peer can not stall the accept loop
+        let c_acceptor = acceptor.clone();
+        let c_manager = manager.clone();
+        Executor::current().spawn(async move {
+            let timeout = Duration::from_millis(*TLS_HANDSHAKE_TIMEOUT);
+            let tls_stream = match c_acceptor.accept(tcp_stream).timeout(timeout).await {
+                Ok(Ok(stream)) => TlsStream::Server(stream),
+                Ok(Err(e)) => {
+                    log::debug!("Can not accept TLS connection from {}: {}", dst_addr, e);
+                    return;
+                }
+                Err(_) => {
+                    log::debug!(
+                        "Can not accept TLS connection from {}: handshake timed out after {:?}",
+                        dst_addr,
+                        timeout
+                    );
+                    return;
+                }
+            };
+
+            log::debug!("Accepted TLS connection on {:?}: {:?}", src_addr, dst_addr);
+            // Create the new link object
+            let link = Arc::new(LinkUnicastTls::new(tls_stream, src_addr, dst_addr));
+
+            // Communicate the new link to the initial transport manager
+            if let Err(e) = c_manager.send_async(LinkUnicast(link)).await {
+                log::error!("{}-{}: {}", file!(), line!(), e)
             }
-        };
-
-        log::debug!("Accepted TLS connection on {:?}: {:?}", src_addr, dst_addr);
-        // Create the new link object
-        let link = Arc::new(LinkUnicastTls::new(tls_stream, src_addr, dst_addr));
-
-        // Communicate the new link to the initial transport manager
-        if let Err(e) = manager.send_async(LinkUnicast(link)).await {
-            log::error!("{}-{}: {}", file!(), line!(), e)
-        }
+        });
     }
 
     Ok(())
@@ -645,21 +692,17 @@ async fn load_tls_key(
     tls_private_key_raw_config_key: &str,
     tls_private_key_file_config_key: &str,
 ) -> ZResult<Vec<u8>> {
-    if let Some(value) = config.get(tls_private_key_raw_config_key) {
-        return Ok(value.as_bytes().to_vec());
-    } else if let Some(value) = config.get(tls_private_key_file_config_key) {
-        return Ok(fs::read(value)
-            .await
-            .map_err(|e| zerror!("Invalid TLS private key file: {}", e))?)
-        .and_then(|result| {
-            if result.is_empty() {
-                Err(zerror!("Empty TLS key.").into())
-            } else {
-                Ok(result)
-            }
-        });
-    }
-    Err(zerror!("Missing TLS private key.").into())
+    let key = load_pem(
+        Some(config),
+        tls_private_key_raw_config_key,
+        tls_private_key_file_config_key,
+    )
+    .await?
+    .ok_or_else(|| zerror!("Missing TLS private key."))?;
+    if key.is_empty() {
+        return Err(zerror!("Empty TLS key.").into());
+    }
+    Ok(key)
 }
 
 async fn load_tls_certificate(
@@ -667,14 +710,13 @@ async fn load_tls_certificate(
     tls_certificate_raw_config_key: &str,
     tls_certificate_file_config_key: &str,
 ) -> ZResult<Vec<u8>> {
-    if let Some(value) = config.get(tls_certificate_raw_config_key) {
-        return Ok(value.as_bytes().to_vec());
-    } else if let Some(value) = config.get(tls_certificate_file_config_key) {
-        return Ok(fs::read(value)
-            .await
-            .map_err(|e| zerror!("Invalid TLS certificate file: {}", e))?);
-    }
-    Err(zerror!("Missing tls certificates.").into())
+    load_pem(
+        Some(config),
+        tls_certificate_raw_config_key,
+        tls_certificate_file_config_key,
+    )
+    .await?
+    .ok_or_else(|| zerror!("Missing tls certificates.").into())
 }
 
 fn load_trust_anchors(config: &ArcProperties) -> ZResult<Option<RootCertStore>> {
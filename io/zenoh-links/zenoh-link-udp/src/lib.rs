@@ -66,6 +66,9 @@ zconfigurable! {
     // Amount of time in microseconds to throttle the accept loop upon an error.
     // Default set to 100 ms.
     static ref UDP_ACCEPT_THROTTLE_TIME: u64 = 100_000;
+    // Default TTL (IPv4) or hop limit (IPv6) of the multicast datagrams.
+    // Default set to 1 to not leave the local network.
+    static ref UDP_MULTICAST_DEFAULT_TTL: u32 = 1;
 }
 
 #[derive(Default, Clone, Copy)]
@@ -88,6 +91,7 @@ impl LocatorInspector for UdpLocatorInspector {
 
 pub mod config {
     pub const UDP_MULTICAST_SRC_IFACE: &str = "src_iface";
+    pub const UDP_MULTICAST_TTL: &str = "ttl";
 }
 
 pub(crate) async fn get_udp_addrs(locator: &Locator) -> ZResult<Vec<SocketAddr>> {
@@ -11,11 +11,12 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
-use super::{config::*, UDP_DEFAULT_MTU};
+use super::{config::*, UDP_DEFAULT_MTU, UDP_MULTICAST_DEFAULT_TTL};
 use crate::{get_udp_addrs, socket_addr_to_udp_locator};
 use async_std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, UdpSocket};
 use async_trait::async_trait;
 use socket2::{Domain, Protocol, Socket, Type};
+use std::str::FromStr;
 use std::sync::Arc;
 use std::{borrow::Cow, fmt};
 use zenoh_core::{bail, zerror, Error as ZError, Result as ZResult};
@@ -90,6 +91,23 @@ impl LinkMulticastTrait for LinkMulticastUdp {
         Ok(())
     }
 
+    fn set_dscp(&self, dscp: u8) -> ZResult<()> {
+        zenoh_util::net::set_dscp(&self.unicast_socket, &self.multicast_addr, dscp)
+            .map_err(|e| zerror!("Can not set DSCP {} on UDP link {}: {}", dscp, self, e).into())
+    }
+
+    fn set_so_priority(&self, priority: u8) -> ZResult<()> {
+        zenoh_util::net::set_so_priority(&self.unicast_socket, priority).map_err(|e| {
+            zerror!(
+                "Can not set SO_PRIORITY {} on UDP link {}: {}",
+                priority,
+                self,
+                e
+            )
+            .into()
+        })
+    }
+
     async fn read<'a>(&'a self, buffer: &mut [u8]) -> ZResult<(usize, Cow<'a, Locator>)> {
         loop {
             let (n, addr) = self.mcast_sock.recv_from(buffer).await.map_err(|e| {
@@ -155,6 +173,7 @@ impl LinkManagerMulticastUdp {
         &self,
         mcast_addr: &SocketAddr,
         iface: Option<&str>,
+        ttl: u32,
     ) -> ZResult<(UdpSocket, UdpSocket, SocketAddr)> {
         let domain = match mcast_addr.ip() {
             IpAddr::V4(_) => Domain::IPV4,
@@ -216,10 +235,32 @@ impl LinkManagerMulticastUdp {
             }
         };
 
-        // Establish a unicast UDP socket
-        let ucast_sock = UdpSocket::bind(SocketAddr::new(local_addr, 0))
-            .await
+        // Establish a unicast UDP socket used to send on the multicast group
+        let ucast_sock = Socket::new(domain, Type::DGRAM, Some(Protocol::UDP))
+            .map_err(|e| zerror!("{}: {}", mcast_addr, e))?;
+        ucast_sock
+            .bind(&SocketAddr::new(local_addr, 0).into())
             .map_err(|e| zerror!("{}: {}", mcast_addr, e))?;
+        // Configure the TTL and the outgoing interface of the multicast datagrams
+        match local_addr {
+            IpAddr::V4(src_ip4) => {
+                ucast_sock
+                    .set_multicast_ttl_v4(ttl)
+                    .map_err(|e| zerror!("{}: {}", mcast_addr, e))?;
+                if !src_ip4.is_unspecified() {
+                    ucast_sock
+                        .set_multicast_if_v4(&src_ip4)
+                        .map_err(|e| zerror!("{}: {}", mcast_addr, e))?;
+                }
+            }
+            IpAddr::V6(_) => {
+                ucast_sock
+                    .set_multicast_hops_v6(ttl)
+                    .map_err(|e| zerror!("{}: {}", mcast_addr, e))?;
+            }
+        }
+        // Build the async_std unicast UdpSocket
+        let ucast_sock: UdpSocket = std::net::UdpSocket::from(ucast_sock).into();
 
         // Establish a multicast UDP socket
         let mcast_sock = Socket::new(domain, Type::DGRAM, Some(Protocol::UDP))
@@ -281,16 +322,17 @@ impl LinkManagerMulticastTrait for LinkManagerMulticastUdp {
             .filter(|a| a.ip().is_multicast())
             .collect::<Vec<SocketAddr>>();
 
-        let iface = if let Some(config) = &endpoint.config {
-            config.get(UDP_MULTICAST_SRC_IFACE)
-        } else {
-            None
+        let iface = endpoint.parameter(UDP_MULTICAST_SRC_IFACE);
+        let ttl = match endpoint.parameter(UDP_MULTICAST_TTL) {
+            Some(ttl) => u32::from_str(ttl)
+                .map_err(|e| zerror!("Invalid {} for {}: {}", UDP_MULTICAST_TTL, endpoint, e))?,
+            None => *UDP_MULTICAST_DEFAULT_TTL,
         };
 
         let mut errs: Vec<ZError> = vec![];
         for mcast_addr in mcast_addrs.drain(..) {
             match self
-                .new_link_inner(&mcast_addr, iface.map(|x| x.as_str()))
+                .new_link_inner(&mcast_addr, iface.map(|x| x.as_str()), ttl)
                 .await
             {
                 Ok((mcast_sock, ucast_sock, ucast_addr)) => {
@@ -317,3 +359,29 @@ impl LinkManagerMulticastTrait for LinkManagerMulticastUdp {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn multicast_ttl() {
+        let mcast_addr: SocketAddr = "224.0.0.224:7447".parse().unwrap();
+        let (_, ucast_sock, _) = async_std::task::block_on(LinkManagerMulticastUdp.new_link_inner(
+            &mcast_addr,
+            None,
+            *UDP_MULTICAST_DEFAULT_TTL,
+        ))
+        .unwrap();
+        assert_eq!(
+            ucast_sock.multicast_ttl_v4().unwrap(),
+            *UDP_MULTICAST_DEFAULT_TTL
+        );
+
+        // A non-default TTL is set on the socket sending to the group
+        let (_, ucast_sock, _) =
+            async_std::task::block_on(LinkManagerMulticastUdp.new_link_inner(&mcast_addr, None, 8))
+                .unwrap();
+        assert_eq!(ucast_sock.multicast_ttl_v4().unwrap(), 8);
+    }
+}
@@ -16,7 +16,6 @@ use async_std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, UdpSocket};
 use async_std::prelude::*;
 use async_std::sync::Mutex as AsyncMutex;
 use async_std::task;
-use async_std::task::JoinHandle;
 use async_trait::async_trait;
 use std::collections::HashMap;
 use std::fmt;
@@ -33,7 +32,7 @@ use zenoh_link_commons::{
     NewLinkChannelSender,
 };
 use zenoh_protocol_core::{EndPoint, Locator};
-use zenoh_sync::{Mvar, Signal};
+use zenoh_sync::{Executor, JoinHandle, Mvar, Signal};
 
 use super::{
     get_udp_addrs, socket_addr_to_udp_locator, UDP_ACCEPT_THROTTLE_TIME, UDP_DEFAULT_MTU,
@@ -165,6 +164,40 @@ impl LinkUnicastTrait for LinkUnicastUdp {
         }
     }
 
+    fn set_dscp(&self, dscp: u8) -> ZResult<()> {
+        match &self.variant {
+            LinkUnicastUdpVariant::Connected(link) => {
+                zenoh_util::net::set_dscp(&*link.socket, &self.dst_addr, dscp).map_err(|e| {
+                    zerror!("Can not set DSCP {} on UDP link {}: {}", dscp, self, e).into()
+                })
+            }
+            LinkUnicastUdpVariant::Unconnected(_) => bail!(
+                "Setting DSCP is only supported on connected UDP links: {}",
+                self
+            ),
+        }
+    }
+
+    fn set_so_priority(&self, priority: u8) -> ZResult<()> {
+        match &self.variant {
+            LinkUnicastUdpVariant::Connected(link) => {
+                zenoh_util::net::set_so_priority(&*link.socket, priority).map_err(|e| {
+                    zerror!(
+                        "Can not set SO_PRIORITY {} on UDP link {}: {}",
+                        priority,
+                        self,
+                        e
+                    )
+                    .into()
+                })
+            }
+            LinkUnicastUdpVariant::Unconnected(_) => bail!(
+                "Setting SO_PRIORITY is only supported on connected UDP links: {}",
+                self
+            ),
+        }
+    }
+
     async fn write(&self, buffer: &[u8]) -> ZResult<usize> {
         match &self.variant {
             LinkUnicastUdpVariant::Connected(link) => link.write(buffer).await,
@@ -409,7 +442,7 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastUdp {
                     let c_manager = self.manager.clone();
                     let c_listeners = self.listeners.clone();
                     let c_addr = local_addr;
-                    let handle = task::spawn(async move {
+                    let handle = Executor::current().spawn(async move {
                         // Wait for the accept loop to terminate
                         let res = accept_read_task(socket, c_active, c_signal, c_manager).await;
                         zwrite!(c_listeners).remove(&c_addr);
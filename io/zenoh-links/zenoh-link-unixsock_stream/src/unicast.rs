@@ -16,7 +16,6 @@ use async_std::os::unix::net::{UnixListener, UnixStream};
 use async_std::path::PathBuf;
 use async_std::prelude::FutureExt;
 use async_std::task;
-use async_std::task::JoinHandle;
 use async_trait::async_trait;
 use futures::io::AsyncReadExt;
 use futures::io::AsyncWriteExt;
@@ -35,7 +34,7 @@ use zenoh_link_commons::{
     LinkManagerUnicastTrait, LinkUnicast, LinkUnicastTrait, NewLinkChannelSender,
 };
 use zenoh_protocol_core::{EndPoint, Locator};
-use zenoh_sync::Signal;
+use zenoh_sync::{Executor, JoinHandle, Signal};
 
 use super::{
     get_unix_path, get_unix_path_as_string, UNIXSOCKSTREAM_DEFAULT_MTU,
@@ -376,7 +375,7 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastUnixSocketStream {
         let c_manager = self.manager.clone();
         let c_listeners = self.listeners.clone();
         let c_path = local_path_str.to_owned();
-        let handle = task::spawn(async move {
+        let handle = Executor::current().spawn(async move {
             // Wait for the accept loop to terminate
             let res = accept_task(socket, c_active, c_signal, c_manager).await;
             zwrite!(c_listeners).remove(&c_path);
@@ -19,11 +19,20 @@
 //! [Click here for Zenoh's documentation](../zenoh/index.html)
 use async_std::net::ToSocketAddrs;
 use async_trait::async_trait;
+use config::{
+    TLS_ROOT_CA_CERTIFICATE_FILE, TLS_ROOT_CA_CERTIFICATE_RAW, TLS_SERVER_CERTIFICATE_FILE,
+    TLS_SERVER_CERTIFICATE_RAW, TLS_SERVER_PRIVATE_KEY_FILE, TLS_SERVER_PRIVATE_KEY_RAW,
+    WS_PING_INTERVAL,
+};
+use std::convert::TryFrom;
 use std::net::SocketAddr;
+use tokio_rustls::rustls::ServerName;
 use url::Url;
-use zenoh_core::{bail, zconfigurable, Result as ZResult};
-use zenoh_link_commons::LocatorInspector;
-use zenoh_protocol_core::Locator;
+use zenoh_cfg_properties::Properties;
+use zenoh_config::Config;
+use zenoh_core::{bail, zconfigurable, zerror, Result as ZResult};
+use zenoh_link_commons::{ConfigurationInspector, LocatorInspector};
+use zenoh_protocol_core::{Locator, ZInt};
 mod unicast;
 pub use unicast::*;
 
@@ -36,6 +45,7 @@ pub use unicast::*;
 const WS_MAX_MTU: u16 = u16::MAX;
 
 pub const WS_LOCATOR_PREFIX: &str = "ws";
+pub const WSS_LOCATOR_PREFIX: &str = "wss";
 
 #[derive(Default, Clone, Copy)]
 pub struct WsLocatorInspector;
@@ -49,12 +59,115 @@ impl LocatorInspector for WsLocatorInspector {
     }
 }
 
+#[derive(Default, Clone, Copy)]
+pub struct WssLocatorInspector;
+#[async_trait]
+impl LocatorInspector for WssLocatorInspector {
+    fn protocol(&self) -> &str {
+        WSS_LOCATOR_PREFIX
+    }
+    async fn is_multicast(&self, _locator: &Locator) -> ZResult<bool> {
+        Ok(false)
+    }
+}
+
+// Pings are sent as often as the zenoh KeepAlive messages, i.e. keep_alive times per lease.
+fn insert_ping_interval(config: &Config, properties: &mut Properties) {
+    let c = config.transport().link().tx();
+    if let (Some(lease), Some(keep_alive)) = (c.lease(), c.keep_alive()) {
+        if keep_alive > 0 {
+            let interval = lease / keep_alive as ZInt;
+            properties.insert(WS_PING_INTERVAL.into(), interval.to_string());
+        }
+    }
+}
+
+#[derive(Default, Clone, Copy, Debug)]
+pub struct WsConfigurator;
+#[async_trait]
+impl ConfigurationInspector<Config> for WsConfigurator {
+    async fn inspect_config(&self, config: &Config) -> ZResult<Properties> {
+        let mut properties = Properties::default();
+        insert_ping_interval(config, &mut properties);
+        Ok(properties)
+    }
+}
+
+#[derive(Default, Clone, Copy, Debug)]
+pub struct WssConfigurator;
+#[async_trait]
+impl ConfigurationInspector<Config> for WssConfigurator {
+    async fn inspect_config(&self, config: &Config) -> ZResult<Properties> {
+        let mut properties = Properties::default();
+        insert_ping_interval(config, &mut properties);
+
+        let c = config.transport().link().tls();
+        if let Some(tls_ca_certificate) = c.root_ca_certificate() {
+            properties.insert(
+                TLS_ROOT_CA_CERTIFICATE_FILE.into(),
+                tls_ca_certificate.into(),
+            );
+        }
+        if let Some(tls_server_private_key) = c.server_private_key() {
+            properties.insert(
+                TLS_SERVER_PRIVATE_KEY_FILE.into(),
+                tls_server_private_key.into(),
+            );
+        }
+        if let Some(tls_server_certificate) = c.server_certificate() {
+            properties.insert(
+                TLS_SERVER_CERTIFICATE_FILE.into(),
+                tls_server_certificate.into(),
+            );
+        }
+        if let Some(tls_ca_certificate) = c.root_ca_certificate_pem() {
+            properties.insert(
+                TLS_ROOT_CA_CERTIFICATE_RAW.into(),
+                tls_ca_certificate.into(),
+            );
+        }
+        if let Some(tls_server_private_key) = c.server_private_key_pem() {
+            properties.insert(
+                TLS_SERVER_PRIVATE_KEY_RAW.into(),
+                tls_server_private_key.into(),
+            );
+        }
+        if let Some(tls_server_certificate) = c.server_certificate_pem() {
+            properties.insert(
+                TLS_SERVER_CERTIFICATE_RAW.into(),
+                tls_server_certificate.into(),
+            );
+        }
+
+        Ok(properties)
+    }
+}
+
 zconfigurable! {
     // Default MTU (TCP PDU) in bytes.
     static ref WS_DEFAULT_MTU: u16 = WS_MAX_MTU;
     // Amount of time in microseconds to throttle the accept loop upon an error.
     // Default set to 100 ms.
     static ref TCP_ACCEPT_THROTTLE_TIME: u64 = 100_000;
+    // Amount of time in milliseconds an incoming connection is given to complete the TLS and
+    // WebSocket handshakes. Default set to 10 s.
+    static ref WS_HANDSHAKE_TIMEOUT: u64 = 10_000;
+}
+
+pub mod config {
+    use zenoh_cfg_properties::config::*;
+
+    pub const TLS_ROOT_CA_CERTIFICATE_FILE: &str = ZN_TLS_ROOT_CA_CERTIFICATE_STR;
+    pub const TLS_ROOT_CA_CERTIFICATE_RAW: &str = "tls_root_ca_certificate_raw";
+
+    pub const TLS_SERVER_PRIVATE_KEY_FILE: &str = ZN_TLS_SERVER_PRIVATE_KEY_STR;
+    pub const TLS_SERVER_PRIVATE_KEY_RAW: &str = "tls_server_private_key_raw";
+
+    pub const TLS_SERVER_CERTIFICATE_FILE: &str = ZN_TLS_SERVER_CERTIFICATE_STR;
+    pub const TLS_SERVER_CERTIFICATE_RAW: &str = "tls_server_certificate_raw";
+
+    // Interval in milliseconds between two WebSocket pings sent on an idle link.
+    pub const WS_PING_INTERVAL: &str = "ws_ping_interval";
 }
 
 pub async fn get_ws_addr(address: &Locator) -> ZResult<SocketAddr> {
@@ -65,6 +178,21 @@ pub async fn get_ws_addr(address: &Locator) -> ZResult<SocketAddr> {
     }
 }
 
+pub fn get_ws_host(address: &Locator) -> ZResult<&str> {
+    // The port follows the last ':', IPv6 addresses being enclosed in brackets (e.g. [::1]:7447)
+    match address.address().rsplit_once(':') {
+        Some((host, _)) => Ok(host.trim_start_matches('[').trim_end_matches(']')),
+        None => bail!("Invalid WebSocket locator address: {}", address),
+    }
+}
+
+pub fn get_ws_server_name(address: &Locator) -> ZResult<ServerName> {
+    match address.address().parse::<SocketAddr>() {
+        Ok(addr) => Ok(ServerName::IpAddress(addr.ip())),
+        Err(_) => Ok(ServerName::try_from(get_ws_host(address)?).map_err(|e| zerror!(e))?),
+    }
+}
+
 pub async fn get_ws_url(address: &Locator) -> ZResult<Url> {
     match Url::parse(&format!(
         "{}://{}",
@@ -15,7 +15,6 @@
 use async_std::prelude::*;
 use async_std::sync::Mutex as AsyncMutex;
 use async_std::task;
-use async_std::task::JoinHandle;
 use async_trait::async_trait;
 use futures_util::stream::SplitSink;
 use futures_util::stream::SplitStream;
@@ -24,30 +23,105 @@ use futures_util::StreamExt;
 use std::collections::HashMap;
 use std::fmt;
 use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::pin::Pin;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, RwLock};
+use std::task::{Context, Poll};
 use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
 use tokio::net::{TcpListener, TcpStream};
+use tokio_rustls::rustls::{
+    Certificate, ClientConfig, OwnedTrustAnchor, PrivateKey, RootCertStore, ServerConfig,
+};
+use tokio_rustls::{TlsAcceptor, TlsConnector};
 use tokio_tungstenite::accept_async;
 use tokio_tungstenite::tungstenite::Message;
-use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+use tokio_tungstenite::WebSocketStream;
 use zenoh_core::bail;
 use zenoh_core::zasynclock;
 use zenoh_core::Result as ZResult;
 use zenoh_core::{zerror, zread, zwrite};
+use zenoh_link_commons::tls::{load_pem, pem_certificates, pem_private_key};
 use zenoh_link_commons::{
     LinkManagerUnicastTrait, LinkUnicast, LinkUnicastTrait, NewLinkChannelSender,
 };
+use zenoh_protocol_core::locators::ArcProperties;
 use zenoh_protocol_core::{EndPoint, Locator};
-use zenoh_sync::Signal;
+use zenoh_sync::{Executor, JoinHandle, Signal};
+
+use super::config::*;
+use super::{
+    get_ws_addr, get_ws_server_name, get_ws_url, TCP_ACCEPT_THROTTLE_TIME, WSS_LOCATOR_PREFIX,
+    WS_DEFAULT_MTU, WS_HANDSHAKE_TIMEOUT, WS_LOCATOR_PREFIX,
+};
+
+// The stream on top of which the WebSocket protocol runs: either a plain
+// TCP stream (ws) or a TLS stream (wss) on the client or server side.
+pub enum WsStream {
+    Plain(TcpStream),
+    ClientTls(Box<tokio_rustls::client::TlsStream<TcpStream>>),
+    ServerTls(Box<tokio_rustls::server::TlsStream<TcpStream>>),
+}
+
+impl WsStream {
+    fn tcp(&self) -> &TcpStream {
+        match self {
+            WsStream::Plain(s) => s,
+            WsStream::ClientTls(s) => s.get_ref().0,
+            WsStream::ServerTls(s) => s.get_ref().0,
+        }
+    }
+}
+
+impl AsyncRead for WsStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        match self.get_mut() {
+            WsStream::Plain(s) => Pin::new(s).poll_read(cx, buf),
+            WsStream::ClientTls(s) => Pin::new(s.as_mut()).poll_read(cx, buf),
+            WsStream::ServerTls(s) => Pin::new(s.as_mut()).poll_read(cx, buf),
+        }
+    }
+}
+
+impl AsyncWrite for WsStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        match self.get_mut() {
+            WsStream::Plain(s) => Pin::new(s).poll_write(cx, buf),
+            WsStream::ClientTls(s) => Pin::new(s.as_mut()).poll_write(cx, buf),
+            WsStream::ServerTls(s) => Pin::new(s.as_mut()).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        match self.get_mut() {
+            WsStream::Plain(s) => Pin::new(s).poll_flush(cx),
+            WsStream::ClientTls(s) => Pin::new(s.as_mut()).poll_flush(cx),
+            WsStream::ServerTls(s) => Pin::new(s.as_mut()).poll_flush(cx),
+        }
+    }
 
-use super::{get_ws_addr, get_ws_url, TCP_ACCEPT_THROTTLE_TIME, WS_DEFAULT_MTU, WS_LOCATOR_PREFIX};
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        match self.get_mut() {
+            WsStream::Plain(s) => Pin::new(s).poll_shutdown(cx),
+            WsStream::ClientTls(s) => Pin::new(s.as_mut()).poll_shutdown(cx),
+            WsStream::ServerTls(s) => Pin::new(s.as_mut()).poll_shutdown(cx),
+        }
+    }
+}
 
 pub struct LinkUnicastWs {
     // The inbound message stream as returned from the futures_util::stream::StreamExt::split method
-    recv: AsyncMutex<SplitStream<WebSocketStream<MaybeTlsStream<TcpStream>>>>,
+    recv: AsyncMutex<SplitStream<WebSocketStream<WsStream>>>,
     // // The outbound message stream as returned from the futures_util::stream::StreamExt::split method
-    send: AsyncMutex<SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>>,
+    send: AsyncMutex<SplitSink<WebSocketStream<WsStream>, Message>>,
     // The source socket address of this link (address used on the local host)
     src_addr: SocketAddr,
     src_locator: Locator,
@@ -56,16 +130,22 @@ pub struct LinkUnicastWs {
     dst_locator: Locator,
     // The leftovers if reading less than what available on the web socket.
     leftovers: AsyncMutex<Option<(Vec<u8>, usize, usize)>>,
+    // The interval after which a ping is sent if nothing has been received on the link
+    ping_interval: Option<Duration>,
+    // A duplicate handle on the underlying TCP socket, used to set its socket options
+    tcp_socket: Option<socket2::Socket>,
 }
 
 impl LinkUnicastWs {
     fn new(
-        socket: WebSocketStream<MaybeTlsStream<TcpStream>>,
+        socket: WebSocketStream<WsStream>,
         src_addr: SocketAddr,
         dst_addr: SocketAddr,
+        protocol: &str,
+        ping_interval: Option<Duration>,
     ) -> LinkUnicastWs {
         // Set the TCP nodelay option
-        if let Err(err) = get_stream(&socket).set_nodelay(true) {
+        if let Err(err) = socket.get_ref().tcp().set_nodelay(true) {
             log::warn!(
                 "Unable to set NODEALY option on TCP link {} => {}: {}",
                 src_addr,
@@ -74,6 +154,19 @@ impl LinkUnicastWs {
             );
         }
 
+        let tcp_socket = match socket2::SockRef::from(socket.get_ref().tcp()).try_clone() {
+            Ok(tcp_socket) => Some(tcp_socket),
+            Err(err) => {
+                log::warn!(
+                    "Unable to duplicate the TCP socket of WebSocket link {} => {}: {}",
+                    src_addr,
+                    dst_addr,
+                    err
+                );
+                None
+            }
+        };
+
         let (send, recv) = socket.split();
         let send = AsyncMutex::new(send);
         let recv = AsyncMutex::new(recv);
@@ -84,43 +177,72 @@ impl LinkUnicastWs {
             recv,
             send,
             src_addr,
-            src_locator: Locator::new(WS_LOCATOR_PREFIX, &src_addr),
+            src_locator: Locator::new(protocol, &src_addr),
             dst_addr,
-            dst_locator: Locator::new(WS_LOCATOR_PREFIX, &dst_addr),
+            dst_locator: Locator::new(protocol, &dst_addr),
             leftovers: AsyncMutex::new(None),
+            ping_interval,
+            tcp_socket,
+        }
+    }
+
+    async fn next(&self, guard: &mut SplitStream<WebSocketStream<WsStream>>) -> ZResult<Message> {
+        loop {
+            let msg = match self.ping_interval {
+                Some(interval) => match guard.next().timeout(interval).await {
+                    Ok(msg) => msg,
+                    Err(_) => {
+                        // Nothing has been received for a while: ping the remote end so that
+                        // idle connections are not closed by proxies in between. Liveness is
+                        // still enforced by the session lease.
+                        log::trace!("Sending Ping on WebSocket link {}", self);
+                        let mut send = zasynclock!(self.send);
+                        send.send(Message::Ping(vec![])).await.map_err(|e| {
+                            zerror!("Error sending Ping on WebSocket link {}: {}", self, e)
+                        })?;
+                        continue;
+                    }
+                },
+                None => guard.next().await,
+            };
+            return match msg {
+                Some(Ok(msg)) => Ok(msg),
+                Some(Err(e)) => bail!("Error when receiving from WebSocket link {}: {}", self, e),
+                None => bail!("Error when receiving from WebSocket link {}: None", self),
+            };
         }
     }
 
     async fn recv(&self) -> ZResult<Vec<u8>> {
         let mut guard = zasynclock!(self.recv);
 
-        match guard.next().await {
-            Some(msg) => match msg {
-                Ok(msg) => match msg {
-                    Message::Binary(ws_bytes) => Ok(ws_bytes),
-                    Message::Ping(_) => bail!(
-                        "Received wrong message type (Ping) from WebSocket link {}",
-                        self
-                    ),
-                    Message::Pong(_) => bail!(
-                        "Received wrong message type (Pong) from WebSocket link {}",
-                        self
-                    ),
-                    Message::Text(_) => bail!(
-                        "Received wrong message type (Text) from WebSocket link {}",
-                        self
-                    ),
-                    Message::Frame(_) => bail!(
-                        "Received wrong message type (Frame) from WebSocket link {}",
-                        self
-                    ),
-                    Message::Close(_) => {
-                        bail!("Receiving from an already closed WS Link: {}", self)
+        loop {
+            match self.next(&mut guard).await? {
+                Message::Binary(ws_bytes) => return Ok(ws_bytes),
+                // Pings are answered with a Pong by the WebSocket stream itself: flush the
+                // sink so that the Pong is sent without waiting for the next zenoh message.
+                Message::Ping(_) => {
+                    log::trace!("Received Ping on WebSocket link {}", self);
+                    let mut send = zasynclock!(self.send);
+                    if let Err(e) = send.flush().await {
+                        log::trace!("Error sending Pong on WebSocket link {}: {}", self, e);
                     }
-                },
-                Err(e) => bail!("Error when receiving from WebSocket link {}: {}", self, e),
-            },
-            None => bail!("Error when receiving from WebSocket link {}: None", self),
+                }
+                Message::Pong(_) => {
+                    log::trace!("Received Pong on WebSocket link {}", self);
+                }
+                Message::Text(_) => bail!(
+                    "Received wrong message type (Text) from WebSocket link {}",
+                    self
+                ),
+                Message::Frame(_) => bail!(
+                    "Received wrong message type (Frame) from WebSocket link {}",
+                    self
+                ),
+                Message::Close(_) => {
+                    bail!("Receiving from an already closed WS Link: {}", self)
+                }
+            }
         }
     }
 }
@@ -138,6 +260,38 @@ impl LinkUnicastTrait for LinkUnicastWs {
         })
     }
 
+    fn set_dscp(&self, dscp: u8) -> ZResult<()> {
+        let tcp_socket = self
+            .tcp_socket
+            .as_ref()
+            .ok_or_else(|| zerror!("No TCP socket available on WebSocket link {}", self))?;
+        zenoh_util::net::set_dscp(tcp_socket, &self.dst_addr, dscp).map_err(|e| {
+            zerror!(
+                "Can not set DSCP {} on WebSocket link {}: {}",
+                dscp,
+                self,
+                e
+            )
+            .into()
+        })
+    }
+
+    fn set_so_priority(&self, priority: u8) -> ZResult<()> {
+        let tcp_socket = self
+            .tcp_socket
+            .as_ref()
+            .ok_or_else(|| zerror!("No TCP socket available on WebSocket link {}", self))?;
+        zenoh_util::net::set_so_priority(tcp_socket, priority).map_err(|e| {
+            zerror!(
+                "Can not set SO_PRIORITY {} on WebSocket link {}: {}",
+                priority,
+                self,
+                e
+            )
+            .into()
+        })
+    }
+
     async fn write(&self, buffer: &[u8]) -> ZResult<usize> {
         let mut guard = zasynclock!(self.send);
         let msg = buffer.into();
@@ -277,23 +431,67 @@ impl ListenerUnicastWs {
 pub struct LinkManagerUnicastWs {
     manager: NewLinkChannelSender,
     listeners: Arc<RwLock<HashMap<SocketAddr, ListenerUnicastWs>>>,
+    protocol: &'static str,
 }
 
 impl LinkManagerUnicastWs {
+    /// Creates a manager for plain WebSocket links (`ws/` locators).
     pub fn new(manager: NewLinkChannelSender) -> Self {
         Self {
             manager,
             listeners: Arc::new(RwLock::new(HashMap::new())),
+            protocol: WS_LOCATOR_PREFIX,
         }
     }
+
+    /// Creates a manager for WebSocket over TLS links (`wss/` locators).
+    pub fn new_secure(manager: NewLinkChannelSender) -> Self {
+        Self {
+            manager,
+            listeners: Arc::new(RwLock::new(HashMap::new())),
+            protocol: WSS_LOCATOR_PREFIX,
+        }
+    }
+
+    fn is_secure(&self) -> bool {
+        self.protocol == WSS_LOCATOR_PREFIX
+    }
 }
 
 #[async_trait]
 impl LinkManagerUnicastTrait for LinkManagerUnicastWs {
     async fn new_link(&self, endpoint: EndPoint) -> ZResult<LinkUnicast> {
         let dst_url = get_ws_url(&endpoint.locator).await?;
+        let addr = get_ws_addr(&endpoint.locator).await?;
+
+        let tcp_stream = TcpStream::connect(addr).await.map_err(|e| {
+            zerror!(
+                "Can not create a new WebSocket link bound to {}: {}",
+                dst_url,
+                e
+            )
+        })?;
+
+        let stream = if self.is_secure() {
+            let server_name = get_ws_server_name(&endpoint.locator)?;
+            let client_config = load_tls_client_config(endpoint.config.as_ref()).await?;
+            let connector = TlsConnector::from(Arc::new(client_config));
+            let tls_stream = connector
+                .connect(server_name, tcp_stream)
+                .await
+                .map_err(|e| {
+                    zerror!(
+                        "Can not create a new WebSocket link bound to {}: {}",
+                        dst_url,
+                        e
+                    )
+                })?;
+            WsStream::ClientTls(Box::new(tls_stream))
+        } else {
+            WsStream::Plain(tcp_stream)
+        };
 
-        let (stream, _) = tokio_tungstenite::connect_async(&dst_url)
+        let (stream, _) = tokio_tungstenite::client_async(&dst_url, stream)
             .await
             .map_err(|e| {
                 zerror!(
@@ -303,7 +501,7 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastWs {
                 )
             })?;
 
-        let src_addr = get_stream(&stream).local_addr().map_err(|e| {
+        let src_addr = stream.get_ref().tcp().local_addr().map_err(|e| {
             zerror!(
                 "Can not create a new WebSocket link bound to {}: {}",
                 dst_url,
@@ -311,7 +509,7 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastWs {
             )
         })?;
 
-        let dst_addr = get_stream(&stream).peer_addr().map_err(|e| {
+        let dst_addr = stream.get_ref().tcp().peer_addr().map_err(|e| {
             zerror!(
                 "Can not create a new WebSocket link bound to {}: {}",
                 dst_url,
@@ -319,13 +517,31 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastWs {
             )
         })?;
 
-        let link = Arc::new(LinkUnicastWs::new(stream, src_addr, dst_addr));
+        let ping_interval = get_ping_interval(endpoint.config.as_ref())?;
+        let link = Arc::new(LinkUnicastWs::new(
+            stream,
+            src_addr,
+            dst_addr,
+            self.protocol,
+            ping_interval,
+        ));
 
         Ok(LinkUnicast(link))
     }
 
     async fn new_listener(&self, mut endpoint: EndPoint) -> ZResult<Locator> {
         let addr = get_ws_addr(&endpoint.locator).await?;
+        let ping_interval = get_ping_interval(endpoint.config.as_ref())?;
+
+        // Initialize the TlsAcceptor, if any
+        let acceptor = if self.is_secure() {
+            let server_config = load_tls_server_config(endpoint.config.as_ref())
+                .await
+                .map_err(|e| zerror!("Cannot create a new WebSocket listener on {addr}. {e}"))?;
+            Some(TlsAcceptor::from(Arc::new(server_config)))
+        } else {
+            None
+        };
 
         // Bind the TCP socket
         let socket = TcpListener::bind(addr).await.map_err(|e| {
@@ -356,9 +572,19 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastWs {
         let c_manager = self.manager.clone();
         let c_listeners = self.listeners.clone();
         let c_addr = local_addr;
-        let handle = task::spawn(async move {
+        let c_protocol = self.protocol;
+        let handle = Executor::current().spawn(async move {
             // Wait for the accept loop to terminate
-            let res = accept_task(socket, c_active, c_signal, c_manager).await;
+            let res = accept_task(
+                socket,
+                acceptor,
+                c_active,
+                c_signal,
+                c_manager,
+                c_protocol,
+                ping_interval,
+            )
+            .await;
             zwrite!(c_listeners).remove(&c_addr);
             res
         });
@@ -411,7 +637,7 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastWs {
                         for ipaddr in ipaddrs {
                             if !ipaddr.is_loopback() && !ipaddr.is_multicast() && ipaddr.is_ipv4() {
                                 let mut l = Locator::new(
-                                    WS_LOCATOR_PREFIX,
+                                    self.protocol,
                                     &SocketAddr::new(ipaddr, key.port()),
                                 );
                                 l.metadata = value.endpoint.locator.metadata.clone();
@@ -427,7 +653,7 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastWs {
                         for ipaddr in ipaddrs {
                             if !ipaddr.is_loopback() && !ipaddr.is_multicast() && ipaddr.is_ipv6() {
                                 let mut l = Locator::new(
-                                    WS_LOCATOR_PREFIX,
+                                    self.protocol,
                                     &SocketAddr::new(ipaddr, key.port()),
                                 );
                                 l.metadata = value.endpoint.locator.metadata.clone();
@@ -449,9 +675,12 @@ impl LinkManagerUnicastTrait for LinkManagerUnicastWs {
 
 async fn accept_task(
     socket: TcpListener,
+    acceptor: Option<TlsAcceptor>,
     active: Arc<AtomicBool>,
     signal: Signal,
     manager: NewLinkChannelSender,
+    protocol: &'static str,
+    ping_interval: Option<Duration>,
 ) -> ZResult<()> {
     enum Action {
         Accept((TcpStream, SocketAddr)),
@@ -504,32 +733,135 @@ async fn accept_task(
             dst_addr
         );
 
-        let stream = accept_async(MaybeTlsStream::Plain(stream))
-            .await
-            .map_err(|e| {
-                let e = zerror!("Error when creating the WebSocket session: {}", e);
-                log::trace!("{}", e);
-                e
-            })?;
-        // Create the new link object
-        let link = Arc::new(LinkUnicastWs::new(stream, src_addr, dst_addr));
+        // Perform the TLS and WebSocket handshakes in their own task so that a slow or
+        // malicious peer can not stall the accept loop
+        let c_acceptor = acceptor.clone();
+        let c_manager = manager.clone();
+        Executor::current().spawn(async move {
+            let timeout = Duration::from_millis(*WS_HANDSHAKE_TIMEOUT);
+            let stream = match handshake(stream, c_acceptor).timeout(timeout).await {
+                Ok(Ok(stream)) => stream,
+                Ok(Err(e)) => {
+                    log::debug!("{}: {}", dst_addr, e);
+                    return;
+                }
+                Err(_) => {
+                    log::debug!(
+                        "{}: WebSocket handshake timed out after {:?}",
+                        dst_addr,
+                        timeout
+                    );
+                    return;
+                }
+            };
 
-        // Communicate the new link to the initial transport manager
-        if let Err(e) = manager.send_async(LinkUnicast(link)).await {
-            log::error!("{}-{}: {}", file!(), line!(), e)
-        }
+            // Create the new link object
+            let link = Arc::new(LinkUnicastWs::new(
+                stream,
+                src_addr,
+                dst_addr,
+                protocol,
+                ping_interval,
+            ));
+
+            // Communicate the new link to the initial transport manager
+            if let Err(e) = c_manager.send_async(LinkUnicast(link)).await {
+                log::error!("{}-{}: {}", file!(), line!(), e)
+            }
+        });
     }
 
     Ok(())
 }
 
-fn get_stream(ws_stream: &WebSocketStream<MaybeTlsStream<TcpStream>>) -> &TcpStream {
-    match ws_stream.get_ref() {
-        MaybeTlsStream::Plain(s) => s,
-        // This two are available only if the TLS features are enabled for
-        // tokio_tungstenite.
-        // MaybeTlsStream::NativeTls(s) => s.get_ref().get_ref(),
-        // MaybeTlsStream::Rustls(s) => s.get_ref().0
-        _ => panic!(),
+async fn handshake(
+    stream: TcpStream,
+    acceptor: Option<TlsAcceptor>,
+) -> ZResult<WebSocketStream<WsStream>> {
+    let stream = match acceptor {
+        Some(acceptor) => match acceptor.accept(stream).await {
+            Ok(tls_stream) => WsStream::ServerTls(Box::new(tls_stream)),
+            Err(e) => bail!("Error accepting TLS (WebSocket) connection: {}", e),
+        },
+        None => WsStream::Plain(stream),
+    };
+    accept_async(stream)
+        .await
+        .map_err(|e| zerror!("Error when creating the WebSocket session: {}", e).into())
+}
+
+fn get_ping_interval(config: Option<&ArcProperties>) -> ZResult<Option<Duration>> {
+    match config.and_then(|c| c.get(WS_PING_INTERVAL)) {
+        Some(value) => match value.parse::<u64>() {
+            Ok(0) => Ok(None),
+            Ok(ms) => Ok(Some(Duration::from_millis(ms))),
+            Err(e) => bail!("Invalid {}: {}: {}", WS_PING_INTERVAL, value, e),
+        },
+        None => Ok(None),
+    }
+}
+
+async fn load_tls_server_config(config: Option<&ArcProperties>) -> ZResult<ServerConfig> {
+    let certificate = load_pem(
+        config,
+        TLS_SERVER_CERTIFICATE_RAW,
+        TLS_SERVER_CERTIFICATE_FILE,
+    )
+    .await?
+    .ok_or_else(|| zerror!("No TLS server certificate has been provided."))?;
+    let certs: Vec<Certificate> = pem_certificates(&certificate)?
+        .drain(..)
+        .map(Certificate)
+        .collect();
+
+    let private_key = load_pem(
+        config,
+        TLS_SERVER_PRIVATE_KEY_RAW,
+        TLS_SERVER_PRIVATE_KEY_FILE,
+    )
+    .await?
+    .ok_or_else(|| zerror!("No TLS server private key has been provided."))?;
+    let key = PrivateKey(pem_private_key(&private_key)?);
+
+    ServerConfig::builder()
+        .with_safe_defaults()
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .map_err(|e| zerror!(e).into())
+}
+
+async fn load_tls_client_config(config: Option<&ArcProperties>) -> ZResult<ClientConfig> {
+    let mut root_cert_store = RootCertStore::empty();
+    match load_pem(
+        config,
+        TLS_ROOT_CA_CERTIFICATE_RAW,
+        TLS_ROOT_CA_CERTIFICATE_FILE,
+    )
+    .await?
+    {
+        Some(ca) => {
+            for c in pem_certificates(&ca)?.into_iter() {
+                root_cert_store
+                    .add(&Certificate(c))
+                    .map_err(|e| zerror!("Invalid TLS root CA certificate: {}", e))?;
+            }
+        }
+        None => {
+            log::debug!("Field 'root_ca_certificate' not specified. Loading default Web PKI certificates instead.");
+            root_cert_store.add_server_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.0.iter().map(
+                |ta| {
+                    OwnedTrustAnchor::from_subject_spki_name_constraints(
+                        ta.subject,
+                        ta.spki,
+                        ta.name_constraints,
+                    )
+                },
+            ));
+        }
     }
+
+    Ok(ClientConfig::builder()
+        .with_safe_defaults()
+        .with_root_certificates(root_cert_store)
+        .with_no_client_auth())
 }
@@ -157,6 +157,12 @@ impl SerializationBatch {
         self.is_streamed
     }
 
+    /// Get the size in bytes of the memory buffer of the [`SerializationBatch`][SerializationBatch].
+    #[inline(always)]
+    pub(crate) fn capacity(&self) -> usize {
+        self.buffer.as_ref().capacity()
+    }
+
     /// Clear the [`SerializationBatch`][SerializationBatch] memory buffer and related internal state.
     #[inline(always)]
     pub(crate) fn clear(&mut self) {
@@ -0,0 +1,141 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use super::protocol::core::Priority;
+
+/// The socket option values to mark the packets of a conduit with.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Marks {
+    pub(crate) dscp: Option<u8>,
+    pub(crate) so_priority: Option<u8>,
+}
+
+/// Keeps track of the marks of a link, so that its socket options are only
+/// updated when the conduit of the batches sent on it changes.
+pub(crate) struct LinkMarking {
+    marks: Vec<Marks>,
+    last: Marks,
+}
+
+impl LinkMarking {
+    /// Create the marking of a link with the given number of conduits. Without QoS,
+    /// a single conduit carries all the messages with the default priority.
+    pub(crate) fn new(
+        dscp: &[Option<u8>; Priority::NUM],
+        so_priority: &[Option<u8>; Priority::NUM],
+        conduits: usize,
+    ) -> LinkMarking {
+        let marks = if conduits == Priority::NUM {
+            dscp.iter()
+                .zip(so_priority.iter())
+                .map(|(d, p)| Marks {
+                    dscp: *d,
+                    so_priority: *p,
+                })
+                .collect()
+        } else {
+            let default = Priority::default() as usize;
+            vec![Marks {
+                dscp: dscp[default],
+                so_priority: so_priority[default],
+            }]
+        };
+        LinkMarking {
+            marks,
+            last: Marks::default(),
+        }
+    }
+
+    /// Returns the marks to set on the link before sending a batch of the given
+    /// conduit, leaving out the ones already set. Once the link has been marked,
+    /// the conduits without a value reset it to the default one.
+    pub(crate) fn next(&mut self, conduit: usize) -> Marks {
+        fn update(last: &mut Option<u8>, value: Option<u8>) -> Option<u8> {
+            match value.or(last.map(|_| 0)) {
+                Some(v) if *last != Some(v) => {
+                    *last = Some(v);
+                    Some(v)
+                }
+                _ => None,
+            }
+        }
+
+        let marks = self.marks[conduit];
+        Marks {
+            dscp: update(&mut self.last.dscp, marks.dscp),
+            so_priority: update(&mut self.last.so_priority, marks.so_priority),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn marking_qos() {
+        let mut dscp = [None; Priority::NUM];
+        dscp[Priority::Control as usize] = Some(48);
+        dscp[Priority::RealTime as usize] = Some(46);
+        dscp[Priority::Background as usize] = Some(8);
+        let mut so_priority = [None; Priority::NUM];
+        so_priority[Priority::RealTime as usize] = Some(6);
+
+        let mut marking = LinkMarking::new(&dscp, &so_priority, Priority::NUM);
+
+        // Nothing is set until a priority with a mark is sent
+        let marks = marking.next(Priority::Data as usize);
+        assert_eq!(marks, Marks::default());
+
+        let marks = marking.next(Priority::Control as usize);
+        assert_eq!(marks.dscp, Some(48));
+        assert_eq!(marks.so_priority, None);
+
+        // The marks already set are not set again
+        let marks = marking.next(Priority::Control as usize);
+        assert_eq!(marks, Marks::default());
+
+        let marks = marking.next(Priority::RealTime as usize);
+        assert_eq!(marks.dscp, Some(46));
+        assert_eq!(marks.so_priority, Some(6));
+
+        let marks = marking.next(Priority::Background as usize);
+        assert_eq!(marks.dscp, Some(8));
+        assert_eq!(marks.so_priority, Some(0));
+
+        // The priorities without a mark reset the link to the default values
+        let marks = marking.next(Priority::Data as usize);
+        assert_eq!(marks.dscp, Some(0));
+        assert_eq!(marks.so_priority, None);
+
+        let marks = marking.next(Priority::DataLow as usize);
+        assert_eq!(marks, Marks::default());
+    }
+
+    #[test]
+    fn marking_no_qos() {
+        let mut dscp = [None; Priority::NUM];
+        dscp[Priority::RealTime as usize] = Some(46);
+        dscp[Priority::default() as usize] = Some(10);
+        let so_priority = [None; Priority::NUM];
+
+        // Without QoS, the single conduit is marked as the default priority
+        let mut marking = LinkMarking::new(&dscp, &so_priority, 1);
+        let marks = marking.next(0);
+        assert_eq!(marks.dscp, Some(10));
+        assert_eq!(marks.so_priority, None);
+
+        let marks = marking.next(0);
+        assert_eq!(marks, Marks::default());
+    }
+}
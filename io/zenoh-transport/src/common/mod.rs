@@ -14,6 +14,7 @@
 pub(crate) mod batch;
 pub(crate) mod conduit;
 pub(crate) mod defragmentation;
+pub(crate) mod marking;
 pub(crate) mod pipeline;
 pub(crate) mod seq_num;
 #[cfg(feature = "stats")]
@@ -19,13 +19,16 @@ use super::protocol::proto::{TransportMessage, ZenohMessage};
 use async_std::prelude::FutureExt;
 use flume::{bounded, Receiver, Sender};
 use ringbuffer_spsc::{RingBuffer, RingBufferReader, RingBufferWriter};
-use std::sync::atomic::{AtomicBool, AtomicU16, Ordering};
+use std::convert::TryFrom;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicU16, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex, MutexGuard};
 use std::thread;
 use std::time::Duration;
 use zenoh_config::QueueSizeConf;
 use zenoh_core::zlock;
 use zenoh_protocol::proto::MessageWriter;
+use zenoh_sync::Condition;
 
 // It's faster to work directly with nanoseconds.
 // Backoff will never last more the u32::MAX nanoseconds.
@@ -56,6 +59,7 @@ struct StageInOut {
     s_out_w: RingBufferWriter<SerializationBatch, RBLEN>,
     bytes: Arc<AtomicU16>,
     backoff: Arc<AtomicBool>,
+    sealed: Arc<AtomicU64>,
 }
 
 impl StageInOut {
@@ -69,6 +73,7 @@ impl StageInOut {
 
     #[inline]
     fn move_batch(&mut self, batch: SerializationBatch) {
+        self.sealed.fetch_add(1, Ordering::Relaxed);
         let _ = self.s_out_w.push(batch);
         self.bytes.store(0, Ordering::Relaxed);
         let _ = self.n_out_w.try_send(());
@@ -272,6 +277,16 @@ impl StageIn {
 
         false
     }
+
+    // Moves a batch sealed by another pipeline to the stage out, in exchange of a batch
+    // of the refill stage, so that the number of batches of the pipeline is constant.
+    fn push_batch(&mut self, batch: SerializationBatch) -> bool {
+        if self.s_ref.pull().is_none() {
+            return false;
+        }
+        self.s_out.move_batch(batch);
+        true
+    }
 }
 
 // The result of the pull operation
@@ -320,6 +335,7 @@ struct StageOutIn {
     s_out_r: RingBufferReader<SerializationBatch, RBLEN>,
     current: Arc<Mutex<Option<SerializationBatch>>>,
     backoff: Backoff,
+    sealed: Arc<AtomicU64>,
 }
 
 impl StageOutIn {
@@ -353,6 +369,7 @@ impl StageOutIn {
                     // An incomplete (non-empty) batch is available in the state IN pipeline.
                     match g.take() {
                         Some(mut batch) => {
+                            self.sealed.fetch_add(1, Ordering::Relaxed);
                             batch.write_len();
                             self.backoff.stop();
                             return Pull::Some(batch);
@@ -431,6 +448,19 @@ impl StageOut {
     }
 }
 
+// Inner structure to keep track of the batches leaving the initial stage of the pipeline.
+// The batches of a priority are written on the link in the order they are sealed.
+struct StageFlush {
+    current: Arc<Mutex<Option<SerializationBatch>>>,
+    sealed: Arc<AtomicU64>,
+}
+
+// Inner structure counting the batches written on the link for each priority
+struct Written {
+    count: Mutex<Vec<u64>>,
+    condition: Condition,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub(crate) struct TransmissionPipelineConf {
     pub(crate) is_streamed: bool,
@@ -460,6 +490,7 @@ impl TransmissionPipeline {
     ) -> (TransmissionPipelineProducer, TransmissionPipelineConsumer) {
         let mut stage_in = vec![];
         let mut stage_out = vec![];
+        let mut stage_flush = vec![];
 
         let default_queue_size = [config.queue_size[Priority::default() as usize]];
         let size_iter = if conduit.len() == 1 {
@@ -500,6 +531,7 @@ impl TransmissionPipeline {
             // Counters for signaling
             let bytes = Arc::new(AtomicU16::new(0));
             let backoff = Arc::new(AtomicBool::new(false));
+            let sealed = Arc::new(AtomicU64::new(0));
 
             stage_in.push(Mutex::new(StageIn {
                 s_ref: StageInRefill { n_ref_r, s_ref_r },
@@ -508,6 +540,7 @@ impl TransmissionPipeline {
                     s_out_w,
                     bytes: bytes.clone(),
                     backoff: backoff.clone(),
+                    sealed: sealed.clone(),
                 },
                 mutex: StageInMutex {
                     current: current.clone(),
@@ -516,25 +549,41 @@ impl TransmissionPipeline {
                 fragbuf: WBuf::new(config.batch_size as usize, false),
             }));
 
+            // The flush tracking for this priority
+            stage_flush.push(StageFlush {
+                current: current.clone(),
+                sealed: sealed.clone(),
+            });
+
             // The stage out for this priority
             stage_out.push(StageOut {
                 s_in: StageOutIn {
                     s_out_r,
                     current,
                     backoff: Backoff::new(bytes, backoff),
+                    sealed,
                 },
                 s_ref: StageOutRefill { n_ref_w, s_ref_w },
             });
         }
 
         let active = Arc::new(AtomicBool::new(true));
+        let written = Arc::new(Written {
+            count: Mutex::new(vec![0; stage_out.len()]),
+            condition: Condition::new(),
+        });
         let producer = TransmissionPipelineProducer {
+            is_streamed: config.is_streamed,
+            batch_size: config.batch_size,
             stage_in: stage_in.into_boxed_slice().into(),
+            stage_flush: stage_flush.into_boxed_slice().into(),
+            written: written.clone(),
             active: active.clone(),
         };
         let consumer = TransmissionPipelineConsumer {
             stage_out: stage_out.into_boxed_slice(),
             n_out_r,
+            written,
             active,
         };
 
@@ -544,8 +593,12 @@ impl TransmissionPipeline {
 
 #[derive(Clone)]
 pub(crate) struct TransmissionPipelineProducer {
+    is_streamed: bool,
+    batch_size: u16,
     // Each priority queue has its own Mutex
     stage_in: Arc<[Mutex<StageIn>]>,
+    stage_flush: Arc<[StageFlush]>,
+    written: Arc<Written>,
     active: Arc<AtomicBool>,
 }
 
@@ -576,6 +629,20 @@ impl TransmissionPipelineProducer {
         queue.push_transport_message(msg)
     }
 
+    /// Enqueues a batch drained from the pipeline of another link, e.g. upon link failure.
+    /// The batch is sent as is, hence it is only accepted if this pipeline frames the batches
+    /// the same way, with batches at least as large, and has a free batch to exchange it with.
+    pub(crate) fn push_batch(&self, batch: SerializationBatch, priority: usize) -> bool {
+        if batch.is_streamed() != self.is_streamed
+            || batch.capacity() > self.batch_size as usize
+            || priority >= self.stage_in.len()
+        {
+            return false;
+        }
+        let mut queue = zlock!(self.stage_in[priority]);
+        queue.push_batch(batch)
+    }
+
     pub(crate) fn disable(&self) {
         self.active.store(false, Ordering::Relaxed);
 
@@ -588,6 +655,70 @@ impl TransmissionPipelineProducer {
         for ig in in_guards.iter_mut() {
             ig.s_out.notify(u16::MAX);
         }
+
+        // Unblock waiting flushers
+        drop(zlock!(self.written.count));
+        self.written.condition.notify_all();
+    }
+
+    /// Returns a future waiting until the messages pushed so far on the queue of `priority`,
+    /// or on all the queues if `None`, have been written on the link, or until the pipeline
+    /// is disabled. The messages pushed after this call are not waited for.
+    pub(crate) fn flush(&self, priority: Option<Priority>) -> impl Future<Output = ()> + Send {
+        // If the queue is not QoS, it means that we only have one priority with index 0.
+        let indexes = match priority {
+            Some(priority) if self.stage_flush.len() > 1 => {
+                priority as usize..priority as usize + 1
+            }
+            Some(_) => 0..1,
+            None => 0..self.stage_flush.len(),
+        };
+        // The number of batches to write on each queue to include the messages pushed so far
+        let targets = indexes
+            .map(|idx| {
+                let f = &self.stage_flush[idx];
+                let current = zlock!(f.current);
+                let pending = current.as_ref().map_or(false, |b| !b.is_empty());
+                (idx, f.sealed.load(Ordering::Relaxed) + pending as u64)
+            })
+            .collect::<Vec<(usize, u64)>>();
+
+        let written = self.written.clone();
+        let active = self.active.clone();
+        async move {
+            loop {
+                let waiter = {
+                    let count = zlock!(written.count);
+                    if !active.load(Ordering::Relaxed)
+                        || targets.iter().all(|(idx, target)| count[*idx] >= *target)
+                    {
+                        return;
+                    }
+                    written.condition.waiter(count)
+                };
+                waiter.await;
+            }
+        }
+    }
+}
+
+/// Waits until the messages pushed so far on the `pipelines` with `priority`, or with any
+/// priority if `None`, have been written on their links, the higher priorities first.
+pub(crate) async fn flush_pipelines(
+    pipelines: &[TransmissionPipelineProducer],
+    priority: Option<Priority>,
+) {
+    let priorities = match priority {
+        Some(priority) => priority as u8..=priority as u8,
+        None => Priority::MAX as u8..=Priority::MIN as u8,
+    };
+    // Mark the messages to wait for on all the pipelines before waiting for any of them
+    let flushes = priorities
+        .filter_map(|priority| Priority::try_from(priority).ok())
+        .flat_map(|priority| pipelines.iter().map(move |p| p.flush(Some(priority))))
+        .collect::<Vec<_>>();
+    for flush in flushes {
+        flush.await;
     }
 }
 
@@ -595,6 +726,7 @@ pub(crate) struct TransmissionPipelineConsumer {
     // A single Mutex for all the priority queues
     stage_out: Box<[StageOut]>,
     n_out_r: Receiver<()>,
+    written: Arc<Written>,
     active: Arc<AtomicBool>,
 }
 
@@ -629,6 +761,9 @@ impl TransmissionPipelineConsumer {
 
     pub(crate) fn refill(&mut self, batch: SerializationBatch, priority: usize) {
         self.stage_out[priority].refill(batch);
+        // The batch has been written on the link
+        zlock!(self.written.count)[priority] += 1;
+        self.written.condition.notify_all();
     }
 
     pub(crate) fn drain(&mut self) -> Vec<(SerializationBatch, usize)> {
@@ -901,6 +1036,70 @@ mod tests {
         });
     }
 
+    #[test]
+    fn tx_pipeline_flush() {
+        let message = ZenohMessage::make_data(
+            "test".into(),
+            ZBuf::from(vec![0_u8; 8]),
+            Channel {
+                priority: Priority::Control,
+                reliability: Reliability::Reliable,
+            },
+            CongestionControl::Block,
+            None,
+            None,
+            None,
+            None,
+        );
+
+        let tct = TransportConduitTx::make(SEQ_NUM_RES).unwrap();
+        let conduits = vec![tct];
+        let (producer, mut consumer) =
+            TransmissionPipeline::make(TransmissionPipelineConf::default(), conduits.as_slice());
+
+        task::block_on(async {
+            // Nothing to flush
+            producer.flush(None).timeout(TIMEOUT).await.unwrap();
+
+            // The flush waits for the pushed messages to be written
+            assert!(producer.push_zenoh_message(message.clone()));
+            assert!(producer.flush(None).timeout(SLEEP).await.is_err());
+            let (batch, priority) = consumer.pull().await.unwrap();
+            assert!(producer.flush(None).timeout(SLEEP).await.is_err());
+            consumer.refill(batch, priority);
+            producer.flush(None).timeout(TIMEOUT).await.unwrap();
+
+            // Disabling the pipeline unblocks the flush
+            assert!(producer.push_zenoh_message(message.clone()));
+            let flush = task::spawn(producer.flush(None));
+            task::sleep(SLEEP).await;
+            producer.disable();
+            flush.timeout(TIMEOUT).await.unwrap();
+        });
+
+        // With QoS, the flush of a priority only waits for the messages of this priority
+        let conduits = (0..Priority::NUM)
+            .map(|_| TransportConduitTx::make(SEQ_NUM_RES).unwrap())
+            .collect::<Vec<_>>();
+        let (producer, mut consumer) =
+            TransmissionPipeline::make(TransmissionPipelineConf::default(), conduits.as_slice());
+
+        task::block_on(async {
+            assert!(producer.push_zenoh_message(message));
+            let flush = producer.flush(Some(Priority::Control));
+            producer
+                .flush(Some(Priority::Data))
+                .timeout(TIMEOUT)
+                .await
+                .unwrap();
+            assert!(producer.flush(None).timeout(SLEEP).await.is_err());
+            let (batch, priority) = consumer.pull().await.unwrap();
+            assert_eq!(priority, Priority::Control as usize);
+            consumer.refill(batch, priority);
+            flush.timeout(TIMEOUT).await.unwrap();
+        });
+    }
+
     #[test]
     #[ignore]
     fn tx_pipeline_thr() {
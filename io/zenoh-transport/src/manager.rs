@@ -32,12 +32,13 @@ use std::sync::Arc;
 use std::sync::RwLock;
 use std::time::Duration;
 use zenoh_cfg_properties::{config::*, Properties};
-use zenoh_config::{Config, QueueConf, QueueSizeConf};
+use zenoh_config::{Config, DscpConf, QueueConf, QueueSizeConf, SoPriorityConf};
 use zenoh_core::Result as ZResult;
-use zenoh_core::{bail, zparse};
+use zenoh_core::{bail, zerror, zparse};
 use zenoh_crypto::{BlockCipher, PseudoRng};
 use zenoh_link::NewLinkChannelSender;
 use zenoh_protocol_core::{EndPoint, Locator, Priority};
+use zenoh_sync::Executor;
 
 /// # Examples
 /// ```
@@ -98,6 +99,8 @@ pub struct TransportManagerConfig {
     pub batch_size: u16,
     pub queue_size: [usize; Priority::NUM],
     pub queue_backoff: Duration,
+    pub dscp: [Option<u8>; Priority::NUM],
+    pub so_priority: [Option<u8>; Priority::NUM],
     pub defrag_buff_size: usize,
     pub link_rx_buffer_size: usize,
     pub unicast: TransportManagerConfigUnicast,
@@ -105,6 +108,7 @@ pub struct TransportManagerConfig {
     pub endpoint: HashMap<String, Properties>,
     pub handler: Arc<dyn TransportEventHandler>,
     pub tx_threads: usize,
+    pub executor: Executor,
 }
 
 pub struct TransportManagerState {
@@ -125,12 +129,15 @@ pub struct TransportManagerBuilder {
     batch_size: u16,
     queue_size: QueueSizeConf,
     queue_backoff: Duration,
+    dscp: DscpConf,
+    so_priority: SoPriorityConf,
     defrag_buff_size: usize,
     link_rx_buffer_size: usize,
     unicast: TransportManagerBuilderUnicast,
     multicast: TransportManagerBuilderMulticast,
     endpoint: HashMap<String, Properties>,
     tx_threads: usize,
+    executor: Executor,
 }
 
 impl TransportManagerBuilder {
@@ -164,6 +171,16 @@ impl TransportManagerBuilder {
         self
     }
 
+    pub fn dscp(mut self, dscp: DscpConf) -> Self {
+        self.dscp = dscp;
+        self
+    }
+
+    pub fn so_priority(mut self, so_priority: SoPriorityConf) -> Self {
+        self.so_priority = so_priority;
+        self
+    }
+
     pub fn defrag_buff_size(mut self, defrag_buff_size: usize) -> Self {
         self.defrag_buff_size = defrag_buff_size;
         self
@@ -194,6 +211,13 @@ impl TransportManagerBuilder {
         self
     }
 
+    /// The executor running the tasks of the transports, but the TX tasks of the links
+    /// which run on the `tx_threads` (the executor of the calling context by default).
+    pub fn executor(mut self, executor: Executor) -> Self {
+        self.executor = executor;
+        self
+    }
+
     pub async fn from_config(mut self, config: &Config) -> ZResult<TransportManagerBuilder> {
         self = self.zid(*config.id());
         if let Some(v) = config.mode() {
@@ -213,6 +237,8 @@ impl TransportManagerBuilder {
         self = self.link_rx_buffer_size(config.transport().link().rx().buffer_size().unwrap());
         self = self.queue_size(config.transport().link().tx().queue().size().clone());
         self = self.tx_threads(config.transport().link().tx().threads().unwrap());
+        self = self.dscp(config.transport().qos().dscp().clone());
+        self = self.so_priority(config.transport().qos().so_priority().clone());
 
         let (c, errors) = zenoh_link::LinkConfigurator::default()
             .configurations(config)
@@ -254,6 +280,26 @@ impl TransportManagerBuilder {
         queue_size[Priority::DataLow as usize] = *self.queue_size.data_low();
         queue_size[Priority::Background as usize] = *self.queue_size.background();
 
+        let mut dscp = [None; Priority::NUM];
+        dscp[Priority::Control as usize] = *self.dscp.control();
+        dscp[Priority::RealTime as usize] = *self.dscp.real_time();
+        dscp[Priority::InteractiveHigh as usize] = *self.dscp.interactive_high();
+        dscp[Priority::InteractiveLow as usize] = *self.dscp.interactive_low();
+        dscp[Priority::DataHigh as usize] = *self.dscp.data_high();
+        dscp[Priority::Data as usize] = *self.dscp.data();
+        dscp[Priority::DataLow as usize] = *self.dscp.data_low();
+        dscp[Priority::Background as usize] = *self.dscp.background();
+
+        let mut so_priority = [None; Priority::NUM];
+        so_priority[Priority::Control as usize] = *self.so_priority.control();
+        so_priority[Priority::RealTime as usize] = *self.so_priority.real_time();
+        so_priority[Priority::InteractiveHigh as usize] = *self.so_priority.interactive_high();
+        so_priority[Priority::InteractiveLow as usize] = *self.so_priority.interactive_low();
+        so_priority[Priority::DataHigh as usize] = *self.so_priority.data_high();
+        so_priority[Priority::Data as usize] = *self.so_priority.data();
+        so_priority[Priority::DataLow as usize] = *self.so_priority.data_low();
+        so_priority[Priority::Background as usize] = *self.so_priority.background();
+
         let config = TransportManagerConfig {
             version: self.version,
             zid: self.zid,
@@ -262,6 +308,8 @@ impl TransportManagerBuilder {
             batch_size: self.batch_size,
             queue_size,
             queue_backoff: self.queue_backoff,
+            dscp,
+            so_priority,
             defrag_buff_size: self.defrag_buff_size,
             link_rx_buffer_size: self.link_rx_buffer_size,
             unicast: unicast.config,
@@ -269,6 +317,7 @@ impl TransportManagerBuilder {
             endpoint: self.endpoint,
             handler,
             tx_threads: self.tx_threads,
+            executor: self.executor,
         };
 
         let state = TransportManagerState {
@@ -294,12 +343,15 @@ impl Default for TransportManagerBuilder {
             batch_size: BATCH_SIZE,
             queue_size: queue.size,
             queue_backoff: Duration::from_nanos(backoff),
+            dscp: DscpConf::default(),
+            so_priority: SoPriorityConf::default(),
             defrag_buff_size: zparse!(ZN_DEFRAG_BUFF_SIZE_DEFAULT).unwrap(),
             link_rx_buffer_size: zparse!(ZN_LINK_RX_BUFF_SIZE_DEFAULT).unwrap(),
             endpoint: HashMap::new(),
             unicast: TransportManagerBuilderUnicast::default(),
             multicast: TransportManagerBuilderMulticast::default(),
             tx_threads: 1,
+            executor: Executor::current(),
         }
     }
 }
@@ -372,7 +424,7 @@ impl TransportManager {
         };
 
         // @TODO: this should be moved into the unicast module
-        async_std::task::spawn({
+        this.config.executor.spawn({
             let this = this.clone();
             async move {
                 while let Ok(link) = new_unicast_link_receiver.recv_async().await {
@@ -408,8 +460,9 @@ impl TransportManager {
             .is_multicast(&endpoint.locator)
             .await?
         {
-            // @TODO: multicast
-            unimplemented!();
+            let locator = endpoint.locator.clone();
+            self.open_transport_multicast(endpoint).await?;
+            Ok(locator)
         } else {
             self.add_listener_unicast(endpoint).await
         }
@@ -421,8 +474,16 @@ impl TransportManager {
             .is_multicast(&endpoint.locator)
             .await?
         {
-            // @TODO: multicast
-            unimplemented!();
+            let transport = self
+                .get_transports_multicast()
+                .into_iter()
+                .find(|t| {
+                    t.get_link()
+                        .map(|l| l.dst == endpoint.locator)
+                        .unwrap_or(false)
+                })
+                .ok_or_else(|| zerror!("No multicast transport bound to {}", endpoint))?;
+            transport.close().await
         } else {
             self.del_listener_unicast(endpoint).await
         }
@@ -457,8 +518,10 @@ impl TransportManager {
             .is_multicast(&endpoint.locator)
             .await?
         {
-            // @TODO: multicast
-            unimplemented!();
+            bail!(
+                "Can not open a unicast transport on a multicast locator: {}. Use open_transport_multicast instead.",
+                endpoint
+            )
         } else {
             self.open_transport_unicast(endpoint).await
         }
@@ -16,12 +16,12 @@ use super::transport::TransportMulticastInner;
 #[cfg(feature = "stats")]
 use super::TransportMulticastStatsAtomic;
 use crate::common::batch::SerializationBatch;
+use crate::common::marking::LinkMarking;
 use crate::common::pipeline::{
     TransmissionPipelineConf, TransmissionPipelineConsumer, TransmissionPipelineProducer,
 };
 use async_std::prelude::FutureExt;
 use async_std::task;
-use async_std::task::JoinHandle;
 use std::convert::TryInto;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
@@ -34,7 +34,7 @@ use zenoh_core::{zerror, zlock};
 use zenoh_link::{LinkMulticast, Locator};
 use zenoh_protocol::proto::{MessageReader, TransportMessage};
 use zenoh_protocol_core::{ConduitSn, ConduitSnList, Priority, WhatAmI, ZInt, ZenohId};
-use zenoh_sync::Signal;
+use zenoh_sync::{JoinHandle, Signal};
 
 pub(super) struct TransportLinkMulticastConfig {
     pub(super) version: u8,
@@ -43,6 +43,7 @@ pub(super) struct TransportLinkMulticastConfig {
     pub(super) lease: Duration,
     pub(super) keep_alive: usize,
     pub(super) join_interval: Duration,
+    pub(super) max_rate: ZInt,
     pub(super) sn_resolution: ZInt,
     pub(super) batch_size: u16,
 }
@@ -102,15 +103,24 @@ impl TransportLinkMulticast {
             let (producer, consumer) = TransmissionPipeline::make(tpc, &conduit_tx);
             self.pipeline = Some(producer);
 
+            // The marks of the packets of each conduit
+            let marking = LinkMarking::new(
+                &self.transport.manager.config.dscp,
+                &self.transport.manager.config.so_priority,
+                conduit_tx.len(),
+            );
+
             // Spawn the TX task
             let c_link = self.link.clone();
             let c_transport = self.transport.clone();
-            let handle = task::spawn(async move {
+            let executor = &self.transport.manager.config.executor;
+            let handle = executor.spawn(async move {
                 let res = tx_task(
                     consumer,
                     c_link.clone(),
                     config,
                     initial_sns,
+                    marking,
                     #[cfg(feature = "stats")]
                     c_transport.stats.clone(),
                 )
@@ -119,7 +129,8 @@ impl TransportLinkMulticast {
                     log::debug!("{}", e);
                     // Spawn a task to avoid a deadlock waiting for this same task
                     // to finish in the close() joining its handle
-                    task::spawn(async move { c_transport.delete().await });
+                    let executor = c_transport.manager.config.executor.clone();
+                    executor.spawn(async move { c_transport.delete().await });
                 }
             });
             self.handle_tx = Some(Arc::new(handle));
@@ -140,7 +151,8 @@ impl TransportLinkMulticast {
             let c_signal = self.signal_rx.clone();
             let c_rx_buffer_size = self.transport.manager.config.link_rx_buffer_size;
 
-            let handle = task::spawn(async move {
+            let executor = &self.transport.manager.config.executor;
+            let handle = executor.spawn(async move {
                 // Start the consume task
                 let res = rx_task(
                     c_link.clone(),
@@ -154,7 +166,8 @@ impl TransportLinkMulticast {
                     log::debug!("{}", e);
                     // Spawn a task to avoid a deadlock waiting for this same task
                     // to finish in the close() joining its handle
-                    task::spawn(async move { c_transport.delete().await });
+                    let executor = c_transport.manager.config.executor.clone();
+                    executor.spawn(async move { c_transport.delete().await });
                 }
             });
             self.handle_rx = Some(Arc::new(handle));
@@ -193,6 +206,7 @@ async fn tx_task(
     link: LinkMulticast,
     config: TransportLinkMulticastConfig,
     mut next_sns: Vec<ConduitSn>,
+    mut marking: LinkMarking,
     #[cfg(feature = "stats")] stats: Arc<TransportMulticastStatsAtomic>,
 ) -> ZResult<()> {
     enum Action {
@@ -222,16 +236,46 @@ async fn tx_task(
         Action::Join
     }
 
+    // Pace the transmission when a maximum rate is configured. Multicast receivers
+    // can not push back on the sender, hence the sender needs to throttle itself.
+    async fn pace(next_tx: &mut Instant, len: usize, max_rate: ZInt) {
+        if max_rate == 0 {
+            return;
+        }
+        let now = Instant::now();
+        if *next_tx > now {
+            task::sleep(*next_tx - now).await;
+        } else {
+            *next_tx = now;
+        }
+        *next_tx += Duration::from_nanos((len as u64).saturating_mul(1_000_000_000) / max_rate);
+    }
+
     let keep_alive = config.join_interval / config.keep_alive as u32;
     let mut last_join = Instant::now() - config.join_interval;
+    let mut next_tx = Instant::now();
     loop {
         match pull(&mut pipeline, keep_alive)
             .race(join(last_join, config.join_interval))
             .await
         {
             Action::Pull((batch, priority)) => {
+                // Mark the outgoing packets with the DSCP and SO_PRIORITY of the priority
+                let marks = marking.next(priority);
+                if let Some(dscp) = marks.dscp {
+                    if let Err(e) = link.set_dscp(dscp) {
+                        log::debug!("{}", e);
+                    }
+                }
+                if let Some(so_priority) = marks.so_priority {
+                    if let Err(e) = link.set_so_priority(so_priority) {
+                        log::debug!("{}", e);
+                    }
+                }
+
                 // Send the buffer on the link
                 let bytes = batch.as_bytes();
+                pace(&mut next_tx, bytes.len(), config.max_rate).await;
                 link.write_all(bytes).await?;
                 // Keep track of next SNs
                 if let Some(sn) = batch.sn.reliable {
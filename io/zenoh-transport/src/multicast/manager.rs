@@ -23,12 +23,14 @@ use zenoh_core::{zerror, zlock, zparse};
 use zenoh_link::*;
 use zenoh_protocol::proto::tmsg;
 use zenoh_protocol_core::locators::LocatorProtocol;
+use zenoh_protocol_core::ZInt;
 
 pub struct TransportManagerConfigMulticast {
     pub lease: Duration,
     pub keep_alive: usize,
     pub join_interval: Duration,
     pub max_sessions: usize,
+    pub max_rate: ZInt,
     pub is_qos: bool,
 }
 
@@ -37,6 +39,7 @@ pub struct TransportManagerBuilderMulticast {
     keep_alive: usize,
     join_interval: Duration,
     max_sessions: usize,
+    max_rate: ZInt,
     is_qos: bool,
 }
 
@@ -73,6 +76,11 @@ impl TransportManagerBuilderMulticast {
         self
     }
 
+    pub fn max_rate(mut self, max_rate: ZInt) -> Self {
+        self.max_rate = max_rate;
+        self
+    }
+
     pub fn qos(mut self, is_qos: bool) -> Self {
         self.is_qos = is_qos;
         self
@@ -90,6 +98,7 @@ impl TransportManagerBuilderMulticast {
             config.transport().multicast().join_interval().unwrap(),
         ));
         self = self.max_sessions(config.transport().multicast().max_sessions().unwrap());
+        self = self.max_rate(config.transport().multicast().max_rate().unwrap());
         self = self.qos(*config.transport().qos().enabled());
 
         Ok(self)
@@ -101,6 +110,7 @@ impl TransportManagerBuilderMulticast {
             keep_alive: self.keep_alive,
             join_interval: self.join_interval,
             max_sessions: self.max_sessions,
+            max_rate: self.max_rate,
             is_qos: self.is_qos,
         };
 
@@ -122,6 +132,7 @@ impl Default for TransportManagerBuilderMulticast {
             keep_alive: zparse!(ZN_LINK_KEEP_ALIVE_DEFAULT).unwrap(),
             join_interval: Duration::from_millis(0),
             max_sessions: 0,
+            max_rate: 0,
             is_qos: false,
         };
         async_std::task::block_on(tmb.from_config(&Config::default())).unwrap()
@@ -22,7 +22,7 @@ use super::common;
 #[cfg(feature = "stats")]
 use super::common::stats::stats_struct;
 use super::protocol;
-use super::protocol::core::ZInt;
+use super::protocol::core::{Priority, ZInt};
 use super::protocol::proto::{tmsg, ZenohMessage};
 use crate::{TransportMulticastEventHandler, TransportPeer};
 pub use manager::*;
@@ -140,6 +140,15 @@ impl TransportMulticast {
         Ok(())
     }
 
+    /// Waits until the messages scheduled so far with `priority`, or with any priority if `None`,
+    /// have been written on the link, the higher priorities first.
+    #[inline(always)]
+    pub async fn flush(&self, priority: Option<Priority>) -> ZResult<()> {
+        let transport = self.get_transport()?;
+        transport.flush(priority).await;
+        Ok(())
+    }
+
     #[inline(always)]
     pub fn handle_message(&self, message: ZenohMessage) -> ZResult<()> {
         self.schedule(message)
@@ -12,6 +12,7 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 use super::common::conduit::{TransportConduitRx, TransportConduitTx};
+use super::common::pipeline::flush_pipelines;
 use super::link::{TransportLinkMulticast, TransportLinkMulticastConfig};
 #[cfg(feature = "stats")]
 use super::TransportMulticastStatsAtomic;
@@ -180,6 +181,17 @@ impl TransportMulticastInner {
             cb.closing();
         }
 
+        // Remove all the peers of the multicast group
+        let mut peers = zwrite!(self.peers)
+            .drain()
+            .map(|(_, p)| p)
+            .collect::<Vec<TransportMulticastPeer>>();
+        for peer in peers.drain(..) {
+            peer.handle.clone().defuse();
+            peer.handler.closing();
+            peer.handler.closed();
+        }
+
         // Delete the transport on the manager
         let _ = self.manager.del_transport_multicast(&self.locator);
 
@@ -197,6 +209,13 @@ impl TransportMulticastInner {
         Ok(())
     }
 
+    pub(crate) async fn flush(&self, priority: Option<Priority>) {
+        let pipeline = zread!(self.link).as_ref().and_then(|l| l.pipeline.clone());
+        if let Some(pipeline) = pipeline {
+            flush_pipelines(&[pipeline], priority).await;
+        }
+    }
+
     pub(crate) async fn close(&self, reason: u8) -> ZResult<()> {
         log::trace!(
             "Closing multicast transport of peer {}: {}",
@@ -231,23 +250,24 @@ impl TransportMulticastInner {
     /*************************************/
     /*        SCHEDULE AND SEND TX       */
     /*************************************/
-    /// Schedule a Zenoh message on the transmission queue    
-    #[cfg(feature = "shared-memory")]
+    /// Schedule a Zenoh message on the transmission queue
     pub(crate) fn schedule(&self, mut message: ZenohMessage) {
+        // Multicast transports do not negotiate the optional DataInfo fields
+        // with their peers: none of them is sent
+        message.retain_data_info_extensions(0);
+
         // Multicast transports do not support SHM for the time being
-        let res = message.map_to_shmbuf(self.manager.shmr.clone());
-        if let Err(e) = res {
-            log::trace!("Failed SHM conversion: {}", e);
-            return;
+        #[cfg(feature = "shared-memory")]
+        {
+            let res = message.map_to_shmbuf(self.manager.shmr.clone());
+            if let Err(e) = res {
+                log::trace!("Failed SHM conversion: {}", e);
+                return;
+            }
         }
         self.schedule_first_fit(message);
     }
 
-    #[cfg(not(feature = "shared-memory"))]
-    pub(crate) fn schedule(&self, message: ZenohMessage) {
-        self.schedule_first_fit(message);
-    }
-
     /*************************************/
     /*               LINK                */
     /*************************************/
@@ -263,6 +283,7 @@ impl TransportMulticastInner {
                     lease: self.manager.config.multicast.lease,
                     keep_alive: self.manager.config.multicast.keep_alive,
                     join_interval: self.manager.config.multicast.join_interval,
+                    max_rate: self.manager.config.multicast.max_rate,
                     sn_resolution: self.manager.config.sn_resolution,
                     batch_size,
                 };
@@ -14,24 +14,43 @@
 use super::protocol::proto::{
     Data, Declaration, Declare, LinkStateList, Pull, Query, Unit, ZenohBody, ZenohMessage,
 };
-use super::Primitives;
+use super::{intercept, MessageInterceptor, Primitives};
 use crate::TransportPeerEventHandler;
 use std::any::Any;
+use std::sync::Arc;
 use zenoh_core::{bail, Result as ZResult};
 use zenoh_link::Link;
 
 pub struct DeMux<P: Primitives> {
     primitives: P,
+    interceptor: Option<Arc<dyn MessageInterceptor>>,
 }
 
 impl<P: Primitives> DeMux<P> {
     pub fn new(primitives: P) -> DeMux<P> {
-        DeMux { primitives }
+        DeMux {
+            primitives,
+            interceptor: None,
+        }
+    }
+
+    pub fn with_interceptor(
+        primitives: P,
+        interceptor: Option<Arc<dyn MessageInterceptor>>,
+    ) -> DeMux<P> {
+        DeMux {
+            primitives,
+            interceptor,
+        }
     }
 }
 
 impl<P: 'static + Primitives> TransportPeerEventHandler for DeMux<P> {
     fn handle_message(&self, msg: ZenohMessage) -> ZResult<()> {
+        let msg = match intercept(&self.interceptor, msg) {
+            Some(msg) => msg,
+            None => return Ok(()),
+        };
         match msg.body {
             ZenohBody::Declare(Declare { declarations, .. }) => {
                 for declaration in declarations {
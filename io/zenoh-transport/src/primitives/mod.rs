@@ -19,12 +19,33 @@ use super::protocol::core::{
     Channel, CongestionControl, QueryTarget, QueryableInfo, SubInfo, WireExpr, ZInt, ZenohId,
 };
 use super::protocol::io::ZBuf;
-use super::protocol::proto::{DataInfo, RoutingContext};
+use super::protocol::proto::{DataInfo, RoutingContext, ZenohMessage};
 pub use demux::*;
 pub use mux::*;
 use protocol::proto::QueryBody;
+use std::sync::Arc;
 use zenoh_protocol_core::ConsolidationMode;
 
+/// An interceptor of the [`ZenohMessage`]s sent through a [`Mux`] or received through a [`DeMux`].
+/// It can observe, modify or drop the messages.
+pub trait MessageInterceptor: Send + Sync {
+    /// Returns the message to forward, or `None` if the message must be dropped.
+    fn intercept(&self, msg: ZenohMessage) -> Option<ZenohMessage>;
+}
+
+/// Applies the given interceptor, if any, to the message.
+/// Returns the message to forward, or `None` if the message must be dropped.
+#[inline]
+pub fn intercept(
+    interceptor: &Option<Arc<dyn MessageInterceptor>>,
+    msg: ZenohMessage,
+) -> Option<ZenohMessage> {
+    match interceptor {
+        Some(interceptor) => interceptor.intercept(msg),
+        None => Some(msg),
+    }
+}
+
 pub trait Primitives: Send + Sync {
     fn decl_resource(&self, expr_id: ZInt, key_expr: &WireExpr);
     fn forget_resource(&self, expr_id: ZInt);
@@ -14,7 +14,7 @@ use zenoh_protocol_core::ConsolidationMode;
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
-use super::super::TransportUnicast;
+use super::super::{TransportMulticast, TransportUnicast};
 use super::protocol::core::{
     Channel, CongestionControl, QueryTarget, QueryableInfo, SubInfo, WireExpr, ZInt, ZenohId,
 };
@@ -24,36 +24,78 @@ use super::protocol::proto::{
     ForgetSubscriber, Publisher, Queryable, ReplierInfo, ReplyContext, Resource, RoutingContext,
     Subscriber, ZenohMessage,
 };
-use super::Primitives;
+use super::{intercept, MessageInterceptor, Primitives};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use zenoh_core::{zlock, Result as ZResult};
 
-pub struct Mux {
-    handler: TransportUnicast,
+/// The transport a [`Mux`] sends the messages through.
+pub trait MuxHandler: Send + Sync {
+    fn handle_message(&self, msg: ZenohMessage) -> ZResult<()>;
 }
 
-impl Mux {
-    pub fn new(handler: TransportUnicast) -> Mux {
-        Mux { handler }
+impl MuxHandler for TransportUnicast {
+    #[inline]
+    fn handle_message(&self, msg: ZenohMessage) -> ZResult<()> {
+        TransportUnicast::handle_message(self, msg)
     }
 }
 
-impl Primitives for Mux {
+impl MuxHandler for TransportMulticast {
+    #[inline]
+    fn handle_message(&self, msg: ZenohMessage) -> ZResult<()> {
+        TransportMulticast::handle_message(self, msg)
+    }
+}
+
+pub struct Mux<T: MuxHandler = TransportUnicast> {
+    handler: T,
+    interceptor: Option<Arc<dyn MessageInterceptor>>,
+}
+
+/// The [`Mux`] sending the messages on a whole multicast group.
+pub type McastMux = Mux<TransportMulticast>;
+
+impl<T: MuxHandler> Mux<T> {
+    pub fn new(handler: T) -> Mux<T> {
+        Mux {
+            handler,
+            interceptor: None,
+        }
+    }
+
+    pub fn with_interceptor(
+        handler: T,
+        interceptor: Option<Arc<dyn MessageInterceptor>>,
+    ) -> Mux<T> {
+        Mux {
+            handler,
+            interceptor,
+        }
+    }
+
+    #[inline]
+    fn send(&self, msg: ZenohMessage) {
+        if let Some(msg) = intercept(&self.interceptor, msg) {
+            let _ = self.handler.handle_message(msg);
+        }
+    }
+}
+
+impl<T: MuxHandler> Primitives for Mux<T> {
     fn decl_resource(&self, expr_id: ZInt, key_expr: &WireExpr) {
         let d = Declaration::Resource(Resource {
             expr_id,
             key: key_expr.to_owned(),
         });
         let decls = vec![d];
-        let _ = self
-            .handler
-            .handle_message(ZenohMessage::make_declare(decls, None, None));
+        self.send(ZenohMessage::make_declare(decls, None, None));
     }
 
     fn forget_resource(&self, expr_id: ZInt) {
         let d = Declaration::ForgetResource(ForgetResource { expr_id });
         let decls = vec![d];
-        let _ = self
-            .handler
-            .handle_message(ZenohMessage::make_declare(decls, None, None));
+        self.send(ZenohMessage::make_declare(decls, None, None));
     }
 
     fn decl_subscriber(
@@ -67,9 +109,7 @@ impl Primitives for Mux {
             info: sub_info.clone(),
         });
         let decls = vec![d];
-        let _ =
-            self.handler
-                .handle_message(ZenohMessage::make_declare(decls, routing_context, None));
+        self.send(ZenohMessage::make_declare(decls, routing_context, None));
     }
 
     fn forget_subscriber(&self, key_expr: &WireExpr, routing_context: Option<RoutingContext>) {
@@ -77,9 +117,7 @@ impl Primitives for Mux {
             key: key_expr.to_owned(),
         });
         let decls = vec![d];
-        let _ =
-            self.handler
-                .handle_message(ZenohMessage::make_declare(decls, routing_context, None));
+        self.send(ZenohMessage::make_declare(decls, routing_context, None));
     }
 
     fn decl_publisher(&self, key_expr: &WireExpr, routing_context: Option<RoutingContext>) {
@@ -87,9 +125,7 @@ impl Primitives for Mux {
             key: key_expr.to_owned(),
         });
         let decls = vec![d];
-        let _ =
-            self.handler
-                .handle_message(ZenohMessage::make_declare(decls, routing_context, None));
+        self.send(ZenohMessage::make_declare(decls, routing_context, None));
     }
 
     fn forget_publisher(&self, key_expr: &WireExpr, routing_context: Option<RoutingContext>) {
@@ -97,9 +133,7 @@ impl Primitives for Mux {
             key: key_expr.to_owned(),
         });
         let decls = vec![d];
-        let _ =
-            self.handler
-                .handle_message(ZenohMessage::make_declare(decls, routing_context, None));
+        self.send(ZenohMessage::make_declare(decls, routing_context, None));
     }
 
     fn decl_queryable(
@@ -113,9 +147,7 @@ impl Primitives for Mux {
             info: qabl_info.clone(),
         });
         let decls = vec![d];
-        let _ =
-            self.handler
-                .handle_message(ZenohMessage::make_declare(decls, routing_context, None));
+        self.send(ZenohMessage::make_declare(decls, routing_context, None));
     }
 
     fn forget_queryable(&self, key_expr: &WireExpr, routing_context: Option<RoutingContext>) {
@@ -123,9 +155,7 @@ impl Primitives for Mux {
             key: key_expr.to_owned(),
         });
         let decls = vec![d];
-        let _ =
-            self.handler
-                .handle_message(ZenohMessage::make_declare(decls, routing_context, None));
+        self.send(ZenohMessage::make_declare(decls, routing_context, None));
     }
 
     fn send_data(
@@ -137,7 +167,7 @@ impl Primitives for Mux {
         data_info: Option<DataInfo>,
         routing_context: Option<RoutingContext>,
     ) {
-        let _ = self.handler.handle_message(ZenohMessage::make_data(
+        self.send(ZenohMessage::make_data(
             key_expr.to_owned(),
             payload,
             channel,
@@ -164,7 +194,7 @@ impl Primitives for Mux {
         } else {
             Some(target)
         };
-        let _ = self.handler.handle_message(ZenohMessage::make_query(
+        self.send(ZenohMessage::make_query(
             key_expr.to_owned(),
             parameters.to_owned(),
             qid,
@@ -184,7 +214,7 @@ impl Primitives for Mux {
         data_info: Option<DataInfo>,
         payload: ZBuf,
     ) {
-        let _ = self.handler.handle_message(ZenohMessage::make_data(
+        self.send(ZenohMessage::make_data(
             key_expr.to_owned(),
             payload,
             zmsg::default_channel::REPLY,
@@ -197,7 +227,7 @@ impl Primitives for Mux {
     }
 
     fn send_reply_final(&self, qid: ZInt) {
-        let _ = self.handler.handle_message(ZenohMessage::make_unit(
+        self.send(ZenohMessage::make_unit(
             zmsg::default_channel::REPLY,
             zmsg::default_congestion_control::REPLY,
             Some(ReplyContext::new(qid, None)),
@@ -212,7 +242,7 @@ impl Primitives for Mux {
         pull_id: ZInt,
         max_samples: &Option<ZInt>,
     ) {
-        let _ = self.handler.handle_message(ZenohMessage::make_pull(
+        self.send(ZenohMessage::make_pull(
             is_final,
             key_expr.to_owned(),
             pull_id,
@@ -225,3 +255,194 @@ impl Primitives for Mux {
         // self.handler.closing().await;
     }
 }
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum McastDeclarationKind {
+    Subscriber,
+    Publisher,
+}
+
+/// The declarations sent on a multicast group, shared by the [`McastPeerMux`]es of its peers.
+/// Each declaration is counted once per peer it has been declared to.
+#[derive(Clone, Default)]
+pub struct McastDeclarations(
+    Arc<Mutex<HashMap<(McastDeclarationKind, String, Option<ZInt>), usize>>>,
+);
+
+/// The [`Primitives`] of a remote peer learned through a multicast group.
+///
+/// Multicast messages carry no destination: whatever is sent to one peer is received by the
+/// whole group. Hence only the subscriber and publisher declarations are forwarded, once for
+/// the whole group and with complete key expressions since the expression ids are per peer.
+/// Data is sent once on the group by its [`McastMux`], while queries, replies and pulls are
+/// not routed through multicast groups: the queries and pulls received from multicast peers
+/// are dropped on ingress, so they never expect a reply through this mux.
+pub struct McastPeerMux {
+    mux: McastMux,
+    declarations: McastDeclarations,
+    mappings: Mutex<HashMap<ZInt, String>>,
+}
+
+impl McastPeerMux {
+    pub fn new(handler: TransportMulticast, declarations: McastDeclarations) -> McastPeerMux {
+        McastPeerMux {
+            mux: McastMux::new(handler),
+            declarations,
+            mappings: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn key(&self, key_expr: &WireExpr) -> Option<String> {
+        if key_expr.scope == 0 {
+            return Some(key_expr.suffix.to_string());
+        }
+        match zlock!(self.mappings).get(&key_expr.scope) {
+            Some(prefix) => Some(format!("{}{}", prefix, key_expr.suffix)),
+            None => {
+                log::trace!("Unknown scope {} for multicast peer", key_expr.scope);
+                None
+            }
+        }
+    }
+
+    // Returns the complete key expression to declare on the group, if not already declared.
+    fn declare(
+        &self,
+        kind: McastDeclarationKind,
+        key_expr: &WireExpr,
+        routing_context: Option<RoutingContext>,
+    ) -> Option<WireExpr<'static>> {
+        let key = self.key(key_expr)?;
+        let mut declarations = zlock!(self.declarations.0);
+        let count = declarations
+            .entry((kind, key.clone(), routing_context.map(|c| c.tree_id)))
+            .or_insert(0);
+        *count += 1;
+        (*count == 1).then(|| WireExpr::from(key))
+    }
+
+    // Returns the complete key expression to forget on the group, if no longer declared.
+    fn forget(
+        &self,
+        kind: McastDeclarationKind,
+        key_expr: &WireExpr,
+        routing_context: Option<RoutingContext>,
+    ) -> Option<WireExpr<'static>> {
+        let key = self.key(key_expr)?;
+        let mut declarations = zlock!(self.declarations.0);
+        let id = (kind, key, routing_context.map(|c| c.tree_id));
+        let count = declarations.get_mut(&id)?;
+        *count -= 1;
+        if *count > 0 {
+            return None;
+        }
+        let (_, key, _) = declarations.remove_entry(&id)?.0;
+        Some(WireExpr::from(key))
+    }
+}
+
+impl Primitives for McastPeerMux {
+    fn decl_resource(&self, expr_id: ZInt, key_expr: &WireExpr) {
+        if let Some(key) = self.key(key_expr) {
+            zlock!(self.mappings).insert(expr_id, key);
+        }
+    }
+
+    fn forget_resource(&self, expr_id: ZInt) {
+        zlock!(self.mappings).remove(&expr_id);
+    }
+
+    fn decl_subscriber(
+        &self,
+        key_expr: &WireExpr,
+        sub_info: &SubInfo,
+        routing_context: Option<RoutingContext>,
+    ) {
+        if let Some(key_expr) =
+            self.declare(McastDeclarationKind::Subscriber, key_expr, routing_context)
+        {
+            self.mux
+                .decl_subscriber(&key_expr, sub_info, routing_context);
+        }
+    }
+
+    fn forget_subscriber(&self, key_expr: &WireExpr, routing_context: Option<RoutingContext>) {
+        if let Some(key_expr) =
+            self.forget(McastDeclarationKind::Subscriber, key_expr, routing_context)
+        {
+            self.mux.forget_subscriber(&key_expr, routing_context);
+        }
+    }
+
+    fn decl_publisher(&self, key_expr: &WireExpr, routing_context: Option<RoutingContext>) {
+        if let Some(key_expr) =
+            self.declare(McastDeclarationKind::Publisher, key_expr, routing_context)
+        {
+            self.mux.decl_publisher(&key_expr, routing_context);
+        }
+    }
+
+    fn forget_publisher(&self, key_expr: &WireExpr, routing_context: Option<RoutingContext>) {
+        if let Some(key_expr) =
+            self.forget(McastDeclarationKind::Publisher, key_expr, routing_context)
+        {
+            self.mux.forget_publisher(&key_expr, routing_context);
+        }
+    }
+
+    fn decl_queryable(
+        &self,
+        _key_expr: &WireExpr,
+        _qabl_info: &QueryableInfo,
+        _routing_context: Option<RoutingContext>,
+    ) {
+    }
+
+    fn forget_queryable(&self, _key_expr: &WireExpr, _routing_context: Option<RoutingContext>) {}
+
+    fn send_data(
+        &self,
+        _key_expr: &WireExpr,
+        _payload: ZBuf,
+        _channel: Channel,
+        _cogestion_control: CongestionControl,
+        _data_info: Option<DataInfo>,
+        _routing_context: Option<RoutingContext>,
+    ) {
+    }
+
+    fn send_query(
+        &self,
+        _key_expr: &WireExpr,
+        _parameters: &str,
+        _qid: ZInt,
+        _target: QueryTarget,
+        _consolidation: ConsolidationMode,
+        _body: Option<QueryBody>,
+        _routing_context: Option<RoutingContext>,
+    ) {
+    }
+
+    fn send_reply_data(
+        &self,
+        _qid: ZInt,
+        _replier_id: ZenohId,
+        _key_expr: WireExpr,
+        _data_info: Option<DataInfo>,
+        _payload: ZBuf,
+    ) {
+    }
+
+    fn send_reply_final(&self, _qid: ZInt) {}
+
+    fn send_pull(
+        &self,
+        _is_final: bool,
+        _key_expr: &WireExpr,
+        _pull_id: ZInt,
+        _max_samples: &Option<ZInt>,
+    ) {
+    }
+
+    fn send_close(&self) {}
+}
@@ -12,7 +12,10 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 use crate::unicast::establishment::authenticator::AuthenticatedPeerLink;
-use crate::unicast::establishment::{attachment_from_properties, Cookie, EstablishmentProperties};
+use crate::unicast::establishment::{
+    attachment_from_properties, data_info_extensions_from_properties,
+    data_info_extensions_property, Cookie, EstablishmentProperties,
+};
 use crate::TransportManager;
 use rand::Rng;
 use zenoh_core::{zasynclock, zasyncread};
@@ -53,11 +56,15 @@ pub(super) async fn send(
         zid: input.zid,
         sn_resolution: agreed_sn_resolution,
         is_qos: input.is_qos,
+        data_info_extensions: data_info_extensions_from_properties(&mut input.init_syn_properties),
         nonce: zasynclock!(manager.prng).gen_range(0..agreed_sn_resolution),
     };
 
     // Build the attachment from the authenticators
     let mut ps_attachment = EstablishmentProperties::new();
+    ps_attachment
+        .insert(data_info_extensions_property())
+        .map_err(|e| (e, Some(tmsg::close_reason::UNSUPPORTED)))?;
     let mut ps_cookie = EstablishmentProperties::new();
     for pa in zasyncread!(manager.state.unicast.peer_authenticator).iter() {
         let (mut att, mut cke) = pa
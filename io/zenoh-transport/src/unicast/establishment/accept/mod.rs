@@ -59,6 +59,8 @@ pub(crate) async fn accept_link(
         sn_resolution: output.cookie.sn_resolution,
         is_shm: output.is_shm,
         is_qos: output.cookie.is_qos,
+        data_info_extensions: output.cookie.data_info_extensions,
+        auth_ids: output.auth_ids,
     };
     let transport = step!(transport_init(manager, input)
         .await
@@ -90,7 +92,11 @@ pub(crate) async fn accept_link(
     step!(step!(transport
         .get_inner()
         .map_err(|e| (e, Some(tmsg::close_reason::INVALID))))
-    .add_link(link.clone(), LinkUnicastDirection::Inbound)
+    .add_link(
+        link.clone(),
+        LinkUnicastDirection::Inbound,
+        output.link_priority,
+    )
     .map_err(|e| (e, Some(tmsg::close_reason::MAX_LINKS))));
 
     // Sync the RX sequence number
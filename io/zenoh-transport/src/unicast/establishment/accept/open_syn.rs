@@ -11,9 +11,9 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
-use super::super::authenticator::AuthenticatedPeerLink;
+use super::super::authenticator::{AuthId, AuthenticatedPeerLink};
 use super::super::{attachment_from_properties, properties_from_attachment};
-use super::super::{Cookie, EstablishmentProperties};
+use super::super::{Cookie, EstablishmentProperties, LINK_PRIORITY_PROPERTY};
 use super::AResult;
 use crate::TransportManager;
 use std::time::Duration;
@@ -33,6 +33,8 @@ pub(super) struct Output {
     pub(super) initial_sn: ZInt,
     pub(super) lease: Duration,
     pub(super) is_shm: bool,
+    pub(super) auth_ids: Vec<AuthId>,
+    pub(super) link_priority: u8,
     pub(super) open_ack_attachment: Option<Attachment>,
 }
 #[allow(unused_mut)]
@@ -145,11 +147,32 @@ pub(super) async fn recv(
         }
     }
 
+    // Retrieve the identities authenticated by the peer authenticators
+    let mut auth_ids = vec![];
+    for pa in zasyncread!(manager.state.unicast.peer_authenticator).iter() {
+        if let Some(auth_id) = pa.get_auth_id(&cookie.zid).await {
+            auth_ids.push(auth_id);
+        }
+    }
+
+    let link_priority = match open_syn_properties.remove(LINK_PRIORITY_PROPERTY) {
+        Some(p) => match p.value.as_slice() {
+            [priority] => *priority,
+            _ => {
+                let e = zerror!("Rejecting OpenSyn on: {}. Invalid link priority.", link);
+                return Err((e.into(), Some(tmsg::close_reason::INVALID)));
+            }
+        },
+        None => 0,
+    };
+
     let output = Output {
         cookie,
         initial_sn: open_syn.initial_sn,
         lease: open_syn.lease,
         is_shm,
+        auth_ids,
+        link_priority,
         open_ack_attachment: attachment_from_properties(&ps_attachment).ok(),
     };
     Ok(output)
@@ -122,6 +122,24 @@ impl LinkUnicastAuthenticatorTrait for DummyLinkUnicastAuthenticator {
 /*************************************/
 /*              PEER                 */
 /*************************************/
+/// The identity of a remote peer authenticated during the transport establishment.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum AuthId {
+    /// The user authenticated by the user-password authenticator.
+    Username(String),
+    /// The PKCS#1 PEM-encoded RSA public key authenticated by the public-key authenticator.
+    PublicKey(String),
+}
+
+impl fmt::Display for AuthId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuthId::Username(user) => write!(f, "user:{}", user),
+            AuthId::PublicKey(key) => write!(f, "pubkey:{}", key.trim()),
+        }
+    }
+}
+
 #[derive(PartialEq, Eq, Hash)]
 #[repr(u8)]
 pub enum PeerAuthenticatorId {
@@ -313,6 +331,15 @@ pub trait PeerAuthenticatorTrait: Send + Sync {
     /// * `peerd_id` - The [`ZenohId`][ZenohId] of the transport being closed.
     ///
     async fn handle_close(&self, peer_id: &ZenohId);
+
+    /// Return the identity this authenticator has authenticated for a peer, if any.
+    ///
+    /// # Arguments
+    /// * `peer_id` - The [`ZenohId`][ZenohId] of the authenticated peer.
+    ///
+    async fn get_auth_id(&self, _peer_id: &ZenohId) -> Option<AuthId> {
+        None
+    }
 }
 
 /*************************************/
@@ -12,14 +12,15 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 use super::{
-    AuthenticatedPeerLink, PeerAuthenticator, PeerAuthenticatorId, PeerAuthenticatorTrait,
+    AuthId, AuthenticatedPeerLink, PeerAuthenticator, PeerAuthenticatorId, PeerAuthenticatorTrait,
 };
 use super::{WBuf, ZBuf, ZInt, ZenohId};
 use crate::unicast::establishment::Cookie;
+use async_std::fs;
 use async_std::sync::Mutex;
 use async_trait::async_trait;
-use rand::SeedableRng;
-use rsa::pkcs1::{DecodeRsaPrivateKey, DecodeRsaPublicKey};
+use rand::{Rng, SeedableRng};
+use rsa::pkcs1::{DecodeRsaPrivateKey, DecodeRsaPublicKey, EncodeRsaPublicKey, LineEnding};
 use rsa::{BigUint, PaddingScheme, PublicKey, PublicKeyParts, RsaPrivateKey, RsaPublicKey};
 use std::collections::HashMap;
 use std::path::Path;
@@ -34,7 +35,8 @@ use zenoh_crypto::PseudoRng;
 use zenoh_protocol::io::{WBufCodec, ZBufCodec};
 
 const WBUF_SIZE: usize = 64;
-const MULTILINK_VERSION: ZInt = 1;
+// Version 2 adds the challenge proving that the accepting peer owns its public key
+const MULTILINK_VERSION: ZInt = 2;
 
 /// # Attachment decorator
 ///
@@ -96,14 +98,31 @@ struct InitAckProperty {
 /// +-+-+-+---------+
 /// ~ ciphered nonce~
 /// +---------------+
+/// ~ciphered chllng~
+/// +---------------+
 struct OpenSynProperty {
     nonce_encrypted_with_bob_pubkey: Vec<u8>,
+    challenge_encrypted_with_bob_pubkey: Vec<u8>,
+}
+
+/*************************************/
+/*             OpenAck               */
+/*************************************/
+///  7 6 5 4 3 2 1 0
+/// +-+-+-+-+-+-+-+-+
+/// |0 0 0|  ATTCH  |
+/// +-+-+-+---------+
+/// ~   challenge   ~
+/// +---------------+
+struct OpenAckProperty {
+    challenge: ZInt,
 }
 
 trait WPubKey {
     fn write_init_syn_property_multilink(&mut self, init_syn_property: &InitSynProperty) -> bool;
     fn write_init_ack_property_multilink(&mut self, init_ack_property: &InitAckProperty) -> bool;
     fn write_open_syn_property_multilink(&mut self, open_syn_property: &OpenSynProperty) -> bool;
+    fn write_open_ack_property_multilink(&mut self, open_ack_property: &OpenAckProperty) -> bool;
     fn write_rsa_pub_key(&mut self, pub_key: &RsaPublicKey) -> bool;
 }
 impl WPubKey for WBuf {
@@ -121,6 +140,14 @@ impl WPubKey for WBuf {
     }
     fn write_open_syn_property_multilink(&mut self, open_syn_property: &OpenSynProperty) -> bool {
         self.write_bytes_array(open_syn_property.nonce_encrypted_with_bob_pubkey.as_slice())
+            && self.write_bytes_array(
+                open_syn_property
+                    .challenge_encrypted_with_bob_pubkey
+                    .as_slice(),
+            )
+    }
+    fn write_open_ack_property_multilink(&mut self, open_ack_property: &OpenAckProperty) -> bool {
+        self.write_zint(open_ack_property.challenge)
     }
     fn write_rsa_pub_key(&mut self, pub_key: &RsaPublicKey) -> bool {
         self.write_bytes_array(pub_key.n().to_bytes_le().as_slice())
@@ -132,6 +159,7 @@ trait ZPubKey {
     fn read_init_syn_property_multilink(&mut self) -> Option<InitSynProperty>;
     fn read_init_ack_property_multilink(&mut self) -> Option<InitAckProperty>;
     fn read_open_syn_property_multilink(&mut self) -> Option<OpenSynProperty>;
+    fn read_open_ack_property_multilink(&mut self) -> Option<OpenAckProperty>;
     fn read_rsa_pub_key(&mut self) -> Option<RsaPublicKey>;
 }
 impl ZPubKey for ZBufReader<'_> {
@@ -154,10 +182,16 @@ impl ZPubKey for ZBufReader<'_> {
     }
     fn read_open_syn_property_multilink(&mut self) -> Option<OpenSynProperty> {
         let nonce_encrypted_with_bob_pubkey = self.read_bytes_array()?;
+        let challenge_encrypted_with_bob_pubkey = self.read_bytes_array()?;
         Some(OpenSynProperty {
             nonce_encrypted_with_bob_pubkey,
+            challenge_encrypted_with_bob_pubkey,
         })
     }
+    fn read_open_ack_property_multilink(&mut self) -> Option<OpenAckProperty> {
+        let challenge = self.read_zint()?;
+        Some(OpenAckProperty { challenge })
+    }
     fn read_rsa_pub_key(&mut self) -> Option<RsaPublicKey> {
         let n = BigUint::from_bytes_le(self.read_bytes_array()?.as_slice());
         let e = BigUint::from_bytes_le(self.read_bytes_array()?.as_slice());
@@ -173,6 +207,11 @@ struct InnerState {
     prng: PseudoRng,
     known_keys: Option<Vec<RsaPublicKey>>,
     authenticated: HashMap<ZenohId, Option<RsaPublicKey>>,
+    // The public keys of the peers this authenticator opened links to
+    opened: HashMap<ZenohId, RsaPublicKey>,
+    // The public keys claimed by the peers this authenticator is opening links to, along with
+    // the challenges they must decrypt to prove that they own them
+    challenged: HashMap<(ZenohId, ZInt), RsaPublicKey>,
 }
 
 pub struct PubKeyAuthenticator {
@@ -190,6 +229,8 @@ impl PubKeyAuthenticator {
                 prng: PseudoRng::from_entropy(),
                 known_keys: None,
                 authenticated: HashMap::new(),
+                opened: HashMap::new(),
+                challenged: HashMap::new(),
             }),
         }
     }
@@ -207,6 +248,8 @@ impl PubKeyAuthenticator {
                 prng,
                 known_keys: None,
                 authenticated: HashMap::new(),
+                opened: HashMap::new(),
+                challenged: HashMap::new(),
             }),
         };
         Ok(pka)
@@ -241,7 +284,29 @@ impl PubKeyAuthenticator {
     pub async fn from_config(config: &Config) -> ZResult<Option<PubKeyAuthenticator>> {
         let c = config.transport().auth().pubkey();
 
-        // @TODO: support PubKey keys import
+        let pka = match Self::keys_from_config(config)? {
+            Some((pub_key, pri_key)) => Self::new(pub_key, pri_key),
+            None => return Ok(None),
+        };
+
+        // Load the public keys of the peers allowed to connect, if any
+        if let Some(file) = c.known_keys_file() {
+            let content = fs::read_to_string(file)
+                .await
+                .map_err(|e| zerror!("Invalid known keys file: {}", e))?;
+            for pem in split_pem(&content) {
+                let key = RsaPublicKey::from_pkcs1_pem(&pem)
+                    .map_err(|e| zerror!("Rsa Known Key: {}", e))?;
+                pka.add_key(key).await?;
+            }
+            log::debug!("Known public keys have been configured");
+        }
+
+        Ok(Some(pka))
+    }
+
+    fn keys_from_config(config: &Config) -> ZResult<Option<(RsaPublicKey, RsaPrivateKey)>> {
+        let c = config.transport().auth().pubkey();
 
         // First, check if PEM keys are provided
         match (c.public_key_pem(), c.private_key_pem()) {
@@ -250,7 +315,7 @@ impl PubKeyAuthenticator {
                     .map_err(|e| zerror!("Rsa Public Key: {}", e))?;
                 let pri_key = RsaPrivateKey::from_pkcs1_pem(private)
                     .map_err(|e| zerror!("Rsa Private Key: {}", e))?;
-                return Ok(Some(Self::new(pub_key, pri_key)));
+                return Ok(Some((pub_key, pri_key)));
             }
             (Some(_), None) => {
                 bail!("Missing Rsa Private Key: PEM")
@@ -270,7 +335,7 @@ impl PubKeyAuthenticator {
                 let path = Path::new(private);
                 let pri_key = RsaPrivateKey::read_pkcs1_pem_file(path)
                     .map_err(|e| zerror!("Rsa Private Key: {}", e))?;
-                return Ok(Some(Self::new(pub_key, pri_key)));
+                return Ok(Some((pub_key, pri_key)));
             }
             (Some(_), None) => {
                 bail!("Missing Rsa Private Key: file")
@@ -285,6 +350,20 @@ impl PubKeyAuthenticator {
     }
 }
 
+// Split a content made of concatenated PEM blocks
+fn split_pem(content: &str) -> Vec<String> {
+    let mut pems = vec![];
+    let mut current = String::new();
+    for line in content.lines().map(|l| l.trim()).filter(|l| !l.is_empty()) {
+        current.push_str(line);
+        current.push('\n');
+        if line.starts_with("-----END") {
+            pems.push(std::mem::take(&mut current));
+        }
+    }
+    pems
+}
+
 #[async_trait]
 impl PeerAuthenticatorTrait for PubKeyAuthenticator {
     fn id(&self) -> PeerAuthenticatorId {
@@ -414,6 +493,10 @@ impl PeerAuthenticatorTrait for PubKeyAuthenticator {
             // The connecting zenoh peer does not want to do multilink
             None => {
                 let guard = zasynclock!(self.state);
+                if guard.known_keys.is_some() {
+                    // Only the peers with a known public key are authorized
+                    bail!("Missing pub key on link: {}", link);
+                }
                 if guard.authenticated.get(&cookie.zid).is_some() {
                     // The peer is already present but no multilink intereset is declared.
                     // Rejecting for inconsistent declaration.
@@ -429,7 +512,7 @@ impl PeerAuthenticatorTrait for PubKeyAuthenticator {
     async fn handle_init_ack(
         &self,
         link: &AuthenticatedPeerLink,
-        _peer_id: &ZenohId,
+        peer_id: &ZenohId,
         _sn_resolution: ZInt,
         property: Option<Vec<u8>>,
     ) -> ZResult<Option<Vec<u8>>> {
@@ -453,15 +536,37 @@ impl PeerAuthenticatorTrait for PubKeyAuthenticator {
 
         // Create the OpenSyn attachment
         let mut guard = zasynclock!(self.state);
+        if let Some(bpk) = guard.opened.get(peer_id) {
+            if bpk != &init_ack_property.bob_pubkey {
+                bail!("Invalid multilink pub key on link: {}", link);
+            }
+        }
         let nonce_encrypted_with_bob_pubkey = init_ack_property.bob_pubkey.encrypt(
             &mut guard.prng,
             PaddingScheme::PKCS1v15Encrypt,
             &nonce[..],
         )?;
+
+        // The peer proves that it owns its public key by sending back the challenge in
+        // the OpenAck: its key is only stored once the challenge has been checked
+        let challenge: ZInt = guard.prng.gen();
+        let mut wbuf = WBuf::new(WBUF_SIZE, false);
+        if !wbuf.write_zint(challenge) {
+            bail!("Failed to serialize OpenSyn on link: {}", link);
+        }
+        let challenge_encrypted_with_bob_pubkey = init_ack_property.bob_pubkey.encrypt(
+            &mut guard.prng,
+            PaddingScheme::PKCS1v15Encrypt,
+            &wbuf.contiguous(),
+        )?;
+        guard
+            .challenged
+            .insert((*peer_id, challenge), init_ack_property.bob_pubkey);
         drop(guard);
 
         let open_syn_property = OpenSynProperty {
             nonce_encrypted_with_bob_pubkey,
+            challenge_encrypted_with_bob_pubkey,
         };
 
         // Encode the OpenSyn property
@@ -506,6 +611,21 @@ impl PeerAuthenticatorTrait for PubKeyAuthenticator {
                     bail!("Received invalid nonce on link: {}", link);
                 }
 
+                // Prove that we own our public key by sending back the challenge
+                let challenge_bytes: ZBuf = self
+                    .pri_key
+                    .decrypt(
+                        PaddingScheme::PKCS1v15Encrypt,
+                        open_syn_property
+                            .challenge_encrypted_with_bob_pubkey
+                            .as_slice(),
+                    )?
+                    .into();
+                let challenge = challenge_bytes
+                    .reader()
+                    .read_zint()
+                    .ok_or_else(|| zerror!("Received invalid OpenSyn on link: {}", link))?;
+
                 let zbuf: ZBuf = cke.into();
                 let alice_pubkey = zbuf
                     .reader()
@@ -531,6 +651,15 @@ impl PeerAuthenticatorTrait for PubKeyAuthenticator {
                         guard.authenticated.insert(cookie.zid, Some(alice_pubkey));
                     }
                 }
+                drop(guard);
+
+                // Encode the OpenAck property
+                let mut wbuf = WBuf::new(WBUF_SIZE, false);
+                let res = wbuf.write_open_ack_property_multilink(&OpenAckProperty { challenge });
+                if !res {
+                    bail!("Failed to serialize OpenAck on link: {}", link);
+                }
+                return Ok(Some(wbuf.contiguous().into_owned()));
             }
             (None, None) => {
                 // No multilink
@@ -552,21 +681,73 @@ impl PeerAuthenticatorTrait for PubKeyAuthenticator {
 
     async fn handle_open_ack(
         &self,
-        _link: &AuthenticatedPeerLink,
-        _property: Option<Vec<u8>>,
+        link: &AuthenticatedPeerLink,
+        property: Option<Vec<u8>>,
     ) -> ZResult<Option<Vec<u8>>> {
+        let peer_id = match link.peer_id.as_ref() {
+            Some(peer_id) => peer_id,
+            None => return Ok(None),
+        };
+        let mut guard = zasynclock!(self.state);
+        let challenge = match property {
+            Some(pk) => {
+                let zbuf: ZBuf = pk.into();
+                zbuf.reader()
+                    .read_open_ack_property_multilink()
+                    .ok_or_else(|| zerror!("Received invalid OpenAck on link: {}", link))?
+                    .challenge
+            }
+            None => {
+                // A peer that sent its public key must prove that it owns it
+                if guard.challenged.keys().any(|(zid, _)| zid == peer_id) {
+                    bail!("Missing pub key proof on link: {}", link);
+                }
+                return Ok(None);
+            }
+        };
+        let bob_pubkey = guard
+            .challenged
+            .remove(&(*peer_id, challenge))
+            .ok_or_else(|| zerror!("Received invalid pub key proof on link: {}", link))?;
+        match guard.opened.get(peer_id) {
+            Some(bpk) => {
+                if bpk != &bob_pubkey {
+                    bail!("Invalid multilink pub key on link: {}", link);
+                }
+            }
+            None => {
+                guard.opened.insert(*peer_id, bob_pubkey);
+            }
+        }
         Ok(None)
     }
 
     async fn handle_link_err(&self, link: &AuthenticatedPeerLink) {
         // Need to check if it authenticated and remove it if this is the last link
         if let Some(zid) = link.peer_id.as_ref() {
-            zasynclock!(self.state).authenticated.remove(zid);
+            let mut guard = zasynclock!(self.state);
+            guard.authenticated.remove(zid);
+            guard.opened.remove(zid);
+            guard.challenged.retain(|(peer_id, _), _| peer_id != zid);
         }
     }
 
     async fn handle_close(&self, peer_id: &ZenohId) {
-        zasynclock!(self.state).authenticated.remove(peer_id);
+        let mut guard = zasynclock!(self.state);
+        guard.authenticated.remove(peer_id);
+        guard.opened.remove(peer_id);
+        guard.challenged.retain(|(zid, _), _| zid != peer_id);
+    }
+
+    async fn get_auth_id(&self, peer_id: &ZenohId) -> Option<AuthId> {
+        let guard = zasynclock!(self.state);
+        guard
+            .authenticated
+            .get(peer_id)
+            .and_then(|key| key.as_ref())
+            .or_else(|| guard.opened.get(peer_id))
+            .and_then(|key| key.to_pkcs1_pem(LineEnding::LF).ok())
+            .map(AuthId::PublicKey)
     }
 }
 
@@ -12,7 +12,7 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 use super::{
-    AuthenticatedPeerLink, PeerAuthenticator, PeerAuthenticatorId, PeerAuthenticatorTrait,
+    AuthId, AuthenticatedPeerLink, PeerAuthenticator, PeerAuthenticatorId, PeerAuthenticatorTrait,
 };
 use super::{Locator, WBuf, ZBuf, ZInt, ZenohId};
 use crate::unicast::establishment::Cookie;
@@ -392,6 +392,12 @@ impl PeerAuthenticatorTrait for UserPasswordAuthenticator {
     async fn handle_close(&self, peer_id: &ZenohId) {
         zasynclock!(self.authenticated).remove(peer_id);
     }
+
+    async fn get_auth_id(&self, peer_id: &ZenohId) -> Option<AuthId> {
+        zasynclock!(self.authenticated).get(peer_id).map(|auth| {
+            AuthId::Username(String::from_utf8_lossy(&auth.credentials.user).into_owned())
+        })
+    }
 }
 
 //noinspection ALL
@@ -18,9 +18,9 @@ pub(crate) mod open;
 use super::super::TransportManager;
 use super::protocol::core::{Property, WhatAmI, ZInt, ZenohId};
 use super::protocol::io::{WBuf, ZBuf};
-use super::protocol::proto::{Attachment, TransportMessage};
+use super::protocol::proto::{zmsg, Attachment, TransportMessage};
 use super::{TransportConfigUnicast, TransportPeer, TransportUnicast};
-use authenticator::AuthenticatedPeerLink;
+use authenticator::{AuthId, AuthenticatedPeerLink};
 use rand::Rng;
 use std::ops::{Deref, DerefMut};
 use std::time::Duration;
@@ -35,6 +35,17 @@ use zenoh_protocol::io::{WBufCodec, ZBufCodec};
 
 const WBUF_SIZE: usize = 64;
 
+// Key of the OpenSyn attachment property carrying the failover priority of the link, so that
+// the accepting side orders its links as the opening side does. It is out of the range of the
+// peer authenticator ids: peers not supporting it simply ignore it, and a missing property
+// means a priority of 0.
+pub(super) const LINK_PRIORITY_PROPERTY: ZInt = 0x40;
+
+// Key of the InitSyn and InitAck attachment property advertising the optional DataInfo fields
+// (among zmsg::data::info::EXTENSIONS) a peer is able to decode. Peers not supporting it ignore
+// it, and a missing property means that the peer decodes none of them.
+pub(super) const DATA_INFO_EXTENSIONS_PROPERTY: ZInt = 0x41;
+
 /*************************************/
 /*            PROPERTIES             */
 /*************************************/
@@ -87,6 +98,23 @@ pub(super) fn attachment_from_properties(ps: &EstablishmentProperties) -> ZResul
     Ok(attachment)
 }
 
+pub(super) fn data_info_extensions_property() -> Property {
+    let mut wbuf = WBuf::new(WBUF_SIZE, false);
+    wbuf.write_zint(zmsg::data::info::EXTENSIONS);
+    Property {
+        key: DATA_INFO_EXTENSIONS_PROPERTY,
+        value: ZBuf::from(wbuf).contiguous().into_owned(),
+    }
+}
+
+// Returns the optional DataInfo fields advertised by the peer that are supported on this side too
+pub(super) fn data_info_extensions_from_properties(ps: &mut EstablishmentProperties) -> ZInt {
+    ps.remove(DATA_INFO_EXTENSIONS_PROPERTY)
+        .and_then(|p| ZBuf::from(p.value).reader().read_zint())
+        .map(|options| options & zmsg::data::info::EXTENSIONS)
+        .unwrap_or(0)
+}
+
 pub(super) fn properties_from_attachment(att: Attachment) -> ZResult<EstablishmentProperties> {
     let ps = att
         .buffer
@@ -104,6 +132,7 @@ pub struct Cookie {
     zid: ZenohId,
     sn_resolution: ZInt,
     is_qos: bool,
+    data_info_extensions: ZInt,
     nonce: ZInt,
 }
 pub type CookieHash = Vec<u8>;
@@ -129,6 +158,7 @@ impl Cookie {
         zwrite!(wbuf.write_zid(&self.zid));
         zwrite!(wbuf.write_zint(self.sn_resolution));
         zwrite!(wbuf.write_byte(u8::from(self.is_qos)).is_some());
+        zwrite!(wbuf.write_zint(self.data_info_extensions));
         zwrite!(wbuf.write_zint(self.nonce));
         zwrite!(wbuf.write_properties(properties.as_slice()));
 
@@ -157,6 +187,7 @@ impl Cookie {
         let zid = zread!(reader.read_zid());
         let sn_resolution = zread!(reader.read_zint());
         let is_qos = zread!(reader.read_byte()) == 1;
+        let data_info_extensions = zread!(reader.read_zint());
         let nonce = zread!(reader.read_zint());
 
         let mut ps = zread!(reader.read_properties());
@@ -170,6 +201,7 @@ impl Cookie {
             zid,
             sn_resolution,
             is_qos,
+            data_info_extensions,
             nonce,
         };
         Ok((cookie, properties))
@@ -209,6 +241,8 @@ pub(super) struct InputInit {
     pub(super) sn_resolution: ZInt,
     pub(super) is_shm: bool,
     pub(super) is_qos: bool,
+    pub(super) data_info_extensions: ZInt,
+    pub(super) auth_ids: Vec<AuthId>,
 }
 async fn transport_init(
     manager: &TransportManager,
@@ -223,7 +257,9 @@ async fn transport_init(
         sn_resolution: input.sn_resolution,
         is_shm: input.is_shm,
         is_qos: input.is_qos,
+        data_info_extensions: input.data_info_extensions,
         initial_sn_tx,
+        auth_ids: input.auth_ids,
     };
 
     manager.init_transport_unicast(config)
@@ -12,16 +12,18 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 use crate::unicast::establishment::open::OResult;
-use crate::unicast::establishment::{attachment_from_properties, properties_from_attachment};
 use crate::unicast::establishment::{
     authenticator::AuthenticatedPeerLink, EstablishmentProperties,
 };
+use crate::unicast::establishment::{
+    data_info_extensions_from_properties, properties_from_attachment,
+};
 use crate::TransportManager;
 use zenoh_core::{zasyncread, zerror};
 use zenoh_link::LinkUnicast;
 use zenoh_protocol::core::{Property, WhatAmI, ZInt, ZenohId};
 use zenoh_protocol::io::ZSlice;
-use zenoh_protocol::proto::{tmsg, Attachment, Close, TransportBody};
+use zenoh_protocol::proto::{tmsg, Close, TransportBody};
 
 #[cfg(feature = "shared-memory")]
 use crate::unicast::establishment::authenticator::PeerAuthenticatorId;
@@ -35,8 +37,9 @@ pub(super) struct Output {
     pub(super) sn_resolution: ZInt,
     pub(super) is_qos: bool,
     pub(super) is_shm: bool,
+    pub(super) data_info_extensions: ZInt,
     pub(super) cookie: ZSlice,
-    pub(super) open_syn_attachment: Option<Attachment>,
+    pub(super) open_syn_properties: EstablishmentProperties,
 }
 pub(super) async fn recv(
     link: &LinkUnicast,
@@ -114,6 +117,7 @@ pub(super) async fn recv(
         }
         None => EstablishmentProperties::new(),
     };
+    let data_info_extensions = data_info_extensions_from_properties(&mut init_ack_properties);
 
     #[allow(unused_mut)]
     let mut is_shm = false;
@@ -165,8 +169,9 @@ pub(super) async fn recv(
         sn_resolution,
         is_qos: init_ack.is_qos,
         is_shm,
+        data_info_extensions,
         cookie: init_ack.cookie,
-        open_syn_attachment: attachment_from_properties(&ps_attachment).ok(),
+        open_syn_properties: ps_attachment,
     };
     Ok(output)
 }
@@ -13,7 +13,9 @@
 //
 use super::OResult;
 use crate::unicast::establishment::authenticator::AuthenticatedPeerLink;
-use crate::unicast::establishment::{attachment_from_properties, EstablishmentProperties};
+use crate::unicast::establishment::{
+    attachment_from_properties, data_info_extensions_property, EstablishmentProperties,
+};
 use crate::TransportManager;
 use zenoh_core::zasyncread;
 use zenoh_link::LinkUnicast;
@@ -31,6 +33,9 @@ pub(super) async fn send(
     auth_link: &mut AuthenticatedPeerLink,
 ) -> OResult<Output> {
     let mut ps_attachment = EstablishmentProperties::new();
+    ps_attachment
+        .insert(data_info_extensions_property())
+        .map_err(|e| (e, Some(tmsg::close_reason::UNSUPPORTED)))?;
     for pa in zasyncread!(manager.state.unicast.peer_authenticator).iter() {
         let mut att = pa
             .get_init_syn_properties(auth_link, &manager.config.zid)
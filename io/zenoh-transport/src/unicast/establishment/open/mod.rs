@@ -17,10 +17,14 @@ mod open_ack;
 mod open_syn;
 
 use super::authenticator::AuthenticatedPeerLink;
-use crate::unicast::establishment::{close_link, transport_finalize, InputFinalize, InputInit};
+use crate::unicast::establishment::{
+    attachment_from_properties, close_link, transport_finalize, InputFinalize, InputInit,
+    LINK_PRIORITY_PROPERTY,
+};
 use crate::{TransportManager, TransportUnicast};
-use zenoh_core::Result as ZResult;
+use zenoh_core::{zasyncread, Result as ZResult};
 use zenoh_link::{LinkUnicast, LinkUnicastDirection};
+use zenoh_protocol::core::Property;
 use zenoh_protocol::proto::tmsg;
 
 type OError = (zenoh_core::Error, Option<u8>);
@@ -30,6 +34,7 @@ pub(crate) async fn open_link(
     link: &LinkUnicast,
     manager: &TransportManager,
     auth_link: &mut AuthenticatedPeerLink,
+    priority: u8,
 ) -> ZResult<TransportUnicast> {
     // INIT handshake
     macro_rules! step {
@@ -45,7 +50,16 @@ pub(crate) async fn open_link(
     }
 
     let output = step!(init_syn::send(link, manager, auth_link).await);
-    let output = step!(init_ack::recv(link, manager, auth_link, output).await);
+    let mut output = step!(init_ack::recv(link, manager, auth_link, output).await);
+    if priority != 0 {
+        step!(output
+            .open_syn_properties
+            .insert(Property {
+                key: LINK_PRIORITY_PROPERTY,
+                value: vec![priority],
+            })
+            .map_err(|e| (e, Some(tmsg::close_reason::UNSUPPORTED))));
+    }
 
     // Initialize the transport
     macro_rules! step {
@@ -61,12 +75,22 @@ pub(crate) async fn open_link(
     }
 
     let zid = output.zid;
+    // Retrieve the identities of the peer known to the peer authenticators. The transport
+    // only gets this link once the OpenAck has confirmed them.
+    let mut auth_ids = vec![];
+    for pa in zasyncread!(manager.state.unicast.peer_authenticator).iter() {
+        if let Some(auth_id) = pa.get_auth_id(&zid).await {
+            auth_ids.push(auth_id);
+        }
+    }
     let input = InputInit {
         zid,
         whatami: output.whatami,
         sn_resolution: output.sn_resolution,
         is_shm: output.is_shm,
         is_qos: output.is_qos,
+        data_info_extensions: output.data_info_extensions,
+        auth_ids,
     };
     let transport = step!(super::transport_init(manager, input).await);
 
@@ -96,7 +120,7 @@ pub(crate) async fn open_link(
     let input = open_syn::Input {
         cookie: output.cookie,
         initial_sn,
-        attachment: output.open_syn_attachment,
+        attachment: attachment_from_properties(&output.open_syn_properties).ok(),
     };
     let output = step!(open_syn::send(link, manager, auth_link, input).await);
     let output = step!(open_ack::recv(link, manager, auth_link, output).await);
@@ -106,7 +130,7 @@ pub(crate) async fn open_link(
     step!(step!(transport
         .get_inner()
         .map_err(|e| (e, Some(tmsg::close_reason::INVALID))))
-    .add_link(link.clone(), LinkUnicastDirection::Outbound)
+    .add_link(link.clone(), LinkUnicastDirection::Outbound, priority)
     .map_err(|e| (e, Some(tmsg::close_reason::MAX_LINKS))));
 
     // Sync the RX sequence number
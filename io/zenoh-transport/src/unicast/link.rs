@@ -11,20 +11,20 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
+use super::common::batch::SerializationBatch;
 use super::common::conduit::TransportConduitTx;
 use super::protocol::io::{ZBuf, ZSlice};
 use super::protocol::proto::TransportMessage;
 use super::transport::TransportUnicastInner;
 #[cfg(feature = "stats")]
 use super::TransportUnicastStatsAtomic;
+use crate::common::marking::LinkMarking;
 use crate::common::pipeline::{
     TransmissionPipeline, TransmissionPipelineConf, TransmissionPipelineConsumer,
     TransmissionPipelineProducer,
 };
 use crate::TransportExecutor;
 use async_std::prelude::FutureExt;
-use async_std::task;
-use async_std::task::JoinHandle;
 use std::sync::Arc;
 use std::time::Duration;
 use zenoh_buffers::buffer::InsertBuffer;
@@ -34,7 +34,7 @@ use zenoh_core::Result as ZResult;
 use zenoh_core::{bail, zerror};
 use zenoh_link::{LinkUnicast, LinkUnicastDirection};
 use zenoh_protocol::proto::MessageReader;
-use zenoh_sync::Signal;
+use zenoh_sync::{JoinHandle, Signal};
 
 #[derive(Clone)]
 pub(super) struct TransportLinkUnicast {
@@ -42,6 +42,8 @@ pub(super) struct TransportLinkUnicast {
     pub(super) direction: LinkUnicastDirection,
     // The underlying link
     pub(super) link: LinkUnicast,
+    // The failover priority of the link, the lower the preferred
+    pub(super) priority: u8,
     // The transmission pipeline
     pub(super) pipeline: Option<TransmissionPipelineProducer>,
     // The transport this link is associated to
@@ -57,11 +59,13 @@ impl TransportLinkUnicast {
         transport: TransportUnicastInner,
         link: LinkUnicast,
         direction: LinkUnicastDirection,
+        priority: u8,
     ) -> TransportLinkUnicast {
         TransportLinkUnicast {
             direction,
             transport,
             link,
+            priority,
             pipeline: None,
             handle_tx: None,
             signal_rx: Signal::new(),
@@ -89,23 +93,37 @@ impl TransportLinkUnicast {
             let (producer, consumer) = TransmissionPipeline::make(config, conduit_tx);
             self.pipeline = Some(producer);
 
+            // The marks of the packets of each conduit
+            let marking = LinkMarking::new(
+                &self.transport.config.manager.config.dscp,
+                &self.transport.config.manager.config.so_priority,
+                conduit_tx.len(),
+            );
+
             // Spawn the TX task
             let c_link = self.link.clone();
             let c_transport = self.transport.clone();
             let handle = executor.spawn(async move {
+                let mut consumer = consumer;
+                let mut unsent = None;
                 let res = tx_task(
-                    consumer,
+                    &mut consumer,
+                    &mut unsent,
                     c_link.clone(),
                     keep_alive,
+                    marking,
                     #[cfg(feature = "stats")]
                     c_transport.stats.clone(),
                 )
                 .await;
                 if let Err(e) = res {
                     log::debug!("{}", e);
+                    let failed = FailedTx { consumer, unsent };
                     // Spawn a task to avoid a deadlock waiting for this same task
                     // to finish in the close() joining its handle
-                    task::spawn(async move { c_transport.del_link(&c_link).await });
+                    let executor = c_transport.config.manager.config.executor.clone();
+                    executor
+                        .spawn(async move { c_transport.del_failed_link(&c_link, failed).await });
                 }
             });
             self.handle_tx = Some(Arc::new(handle));
@@ -126,7 +144,8 @@ impl TransportLinkUnicast {
             let c_signal = self.signal_rx.clone();
             let c_rx_buffer_size = self.transport.config.manager.config.link_rx_buffer_size;
 
-            let handle = task::spawn(async move {
+            let executor = &self.transport.config.manager.config.executor;
+            let handle = executor.spawn(async move {
                 // Start the consume task
                 let res = rx_task(
                     c_link.clone(),
@@ -141,7 +160,8 @@ impl TransportLinkUnicast {
                     log::debug!("{}", e);
                     // Spawn a task to avoid a deadlock waiting for this same task
                     // to finish in the close() joining its handle
-                    task::spawn(async move { c_transport.del_link(&c_link).await });
+                    let executor = c_transport.config.manager.config.executor.clone();
+                    executor.spawn(async move { c_transport.del_link(&c_link).await });
                 }
             });
             self.handle_rx = Some(Arc::new(handle));
@@ -172,22 +192,85 @@ impl TransportLinkUnicast {
     }
 }
 
+// The transmission state of a link whose TX task failed
+pub(super) struct FailedTx {
+    consumer: TransmissionPipelineConsumer,
+    // The batch that was being written when the link failed
+    unsent: Option<(SerializationBatch, usize)>,
+}
+
+impl FailedTx {
+    // Re-enqueues the batches containing reliable frames that were not sent on the failed
+    // link on the first remaining link, which is the one the reliable messages are now
+    // scheduled on. Since the sequence numbers are shared by all the links of a transport,
+    // the receiver accepts these batches as long as they precede the new ones.
+    pub(super) fn failover(mut self, links: &[TransportLinkUnicast]) {
+        let pipeline = match links
+            .iter()
+            .filter(|l| l.link.is_reliable())
+            .chain(links.iter())
+            .find_map(|l| l.pipeline.as_ref())
+        {
+            Some(pipeline) => pipeline,
+            None => return,
+        };
+
+        let mut batches = self
+            .unsent
+            .take()
+            .into_iter()
+            .chain(self.consumer.drain())
+            .filter(|(b, _)| !b.is_empty() && b.sn.reliable.is_some())
+            .collect::<Vec<_>>();
+        // The pending batches of each priority keep their order
+        batches.sort_by_key(|(_, priority)| *priority);
+        for (batch, priority) in batches {
+            if !pipeline.push_batch(batch, priority) {
+                log::warn!(
+                    "Reliable batch of priority {} lost in the failover of a link",
+                    priority
+                );
+            }
+        }
+    }
+}
+
 /*************************************/
 /*              TASKS                */
 /*************************************/
 async fn tx_task(
-    mut pipeline: TransmissionPipelineConsumer,
+    pipeline: &mut TransmissionPipelineConsumer,
+    unsent: &mut Option<(SerializationBatch, usize)>,
     link: LinkUnicast,
     keep_alive: Duration,
+    mut marking: LinkMarking,
     #[cfg(feature = "stats")] stats: Arc<TransportUnicastStatsAtomic>,
 ) -> ZResult<()> {
     loop {
         match pipeline.pull().timeout(keep_alive).await {
             Ok(res) => match res {
                 Some((batch, priority)) => {
+                    // Mark the outgoing packets with the DSCP and SO_PRIORITY of the priority
+                    let marks = marking.next(priority);
+                    if let Some(dscp) = marks.dscp {
+                        if let Err(e) = link.set_dscp(dscp) {
+                            log::debug!("{}", e);
+                        }
+                    }
+                    if let Some(so_priority) = marks.so_priority {
+                        if let Err(e) = link.set_so_priority(so_priority) {
+                            log::debug!("{}", e);
+                        }
+                    }
+
                     // Send the buffer on the link
                     let bytes = batch.as_bytes();
-                    link.write_all(bytes).await?;
+                    link.set_priority(priority);
+                    if let Err(e) = link.write_all(bytes).await {
+                        // Keep the batch to fail it over on the remaining links
+                        *unsent = Some((batch, priority));
+                        return Err(e);
+                    }
 
                     #[cfg(feature = "stats")]
                     {
@@ -218,7 +301,8 @@ async fn tx_task(
 
     // Drain the transmission pipeline and write remaining bytes on the wire
     let mut batches = pipeline.drain();
-    for (b, _) in batches.drain(..) {
+    for (b, priority) in batches.drain(..) {
+        link.set_priority(priority);
         link.write_all(b.as_bytes())
             .timeout(keep_alive)
             .await
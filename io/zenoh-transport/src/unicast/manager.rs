@@ -20,7 +20,6 @@ use crate::unicast::{
 use crate::TransportManager;
 use async_std::prelude::FutureExt;
 use async_std::sync::{Mutex as AsyncMutex, RwLock as AsyncRwLock};
-use async_std::task;
 use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
@@ -33,6 +32,19 @@ use zenoh_link::*;
 use zenoh_protocol::proto::tmsg;
 use zenoh_protocol_core::locators::LocatorProtocol;
 
+/// Endpoint configuration key setting the failover priority of a link (default: 0),
+/// e.g. `tcp/192.168.1.1:7447#link_priority=1`. When several links are established with
+/// the same peer, messages are sent on the link with the lowest priority while the others
+/// are kept as backups. If the active link goes down, the transport seamlessly fails over
+/// to the next one without closing the session. The priority is sent to the accepting side
+/// during the link establishment, so that both sides prefer the same link.
+///
+/// When a link fails while sending, the batches of reliable messages still queued on it are
+/// re-queued on the next link, per priority and in their original order, as long as both
+/// links frame the batches the same way (e.g. two TCP links). The batches of best effort
+/// messages are dropped.
+pub const LINK_PRIORITY: &str = "link_priority";
+
 /*************************************/
 /*         TRANSPORT CONFIG          */
 /*************************************/
@@ -424,6 +436,8 @@ impl TransportManager {
                     initial_sn_tx: config.initial_sn_tx,
                     is_shm: config.is_shm,
                     is_qos: config.is_qos,
+                    data_info_extensions: config.data_info_extensions,
+                    auth_ids: config.auth_ids,
                 };
                 let a_t = Arc::new(TransportUnicastInner::make(stc)?);
 
@@ -468,6 +482,17 @@ impl TransportManager {
             endpoint.extend_configuration(config.iter().map(|(k, v)| (k.clone(), v.clone())));
         };
 
+        let priority = match endpoint
+            .config
+            .as_ref()
+            .and_then(|config| config.get(LINK_PRIORITY))
+        {
+            Some(priority) => priority.parse::<u8>().map_err(|e| {
+                zerror!("Invalid {} for endpoint {}: {}", LINK_PRIORITY, endpoint, e)
+            })?,
+            None => 0,
+        };
+
         // Create a new link associated by calling the Link Manager
         let link = manager.new_link(endpoint).await?;
         // Open the link
@@ -476,7 +501,7 @@ impl TransportManager {
             dst: link.get_src().to_owned(),
             peer_id: None,
         };
-        super::establishment::open::open_link(&link, self, &mut auth_link).await
+        super::establishment::open::open_link(&link, self, &mut auth_link, priority).await
     }
 
     pub fn get_transport_unicast(&self, peer: &ZenohId) -> Option<TransportUnicast> {
@@ -558,7 +583,7 @@ impl TransportManager {
 
         // Spawn a task to accept the link
         let c_manager = self.clone();
-        task::spawn(async move {
+        self.config.executor.spawn(async move {
             let mut auth_link = AuthenticatedPeerLink {
                 src: link.get_src().to_owned(),
                 dst: link.get_dst().to_owned(),
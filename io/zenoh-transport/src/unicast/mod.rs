@@ -22,9 +22,10 @@ use super::common;
 #[cfg(feature = "stats")]
 use super::common::stats::stats_struct;
 use super::protocol;
-use super::protocol::core::{WhatAmI, ZInt, ZenohId};
+use super::protocol::core::{Priority, WhatAmI, ZInt, ZenohId};
 use super::protocol::proto::{tmsg, ZenohMessage};
 use super::{TransportPeer, TransportPeerEventHandler};
+use establishment::authenticator::AuthId;
 pub use manager::*;
 use std::fmt;
 use std::sync::{Arc, Weak};
@@ -77,7 +78,7 @@ stats_struct! {
 /*************************************/
 /*        TRANSPORT UNICAST          */
 /*************************************/
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 pub(crate) struct TransportConfigUnicast {
     pub(crate) peer: ZenohId,
     pub(crate) whatami: WhatAmI,
@@ -85,6 +86,8 @@ pub(crate) struct TransportConfigUnicast {
     pub(crate) initial_sn_tx: ZInt,
     pub(crate) is_shm: bool,
     pub(crate) is_qos: bool,
+    pub(crate) data_info_extensions: ZInt,
+    pub(crate) auth_ids: Vec<AuthId>,
 }
 
 /// [`TransportUnicast`] is the transport handler returned
@@ -130,6 +133,23 @@ impl TransportUnicast {
         Ok(transport.is_qos())
     }
 
+    /// Return the optional [`DataInfo`](zenoh_protocol::proto::DataInfo) fields, among
+    /// [`EXTENSIONS`](zenoh_protocol::proto::zmsg::data::info::EXTENSIONS), that the
+    /// remote peer advertised during the transport establishment.
+    /// The other ones are removed from the data sent on this transport.
+    #[inline(always)]
+    pub fn get_data_info_extensions(&self) -> ZResult<ZInt> {
+        let transport = self.get_inner()?;
+        Ok(transport.get_data_info_extensions())
+    }
+
+    /// Return the identities of the remote peer authenticated during the transport establishment.
+    #[inline(always)]
+    pub fn get_auth_ids(&self) -> ZResult<Vec<AuthId>> {
+        let transport = self.get_inner()?;
+        Ok(transport.get_auth_ids())
+    }
+
     #[inline(always)]
     pub fn get_callback(&self) -> ZResult<Option<Arc<dyn TransportPeerEventHandler>>> {
         let transport = self.get_inner()?;
@@ -192,6 +212,15 @@ impl TransportUnicast {
         }
     }
 
+    /// Waits until the messages scheduled so far with `priority`, or with any priority if `None`,
+    /// have been written on the links, the higher priorities first.
+    #[inline(always)]
+    pub async fn flush(&self, priority: Option<Priority>) -> ZResult<()> {
+        let transport = self.get_inner()?;
+        transport.flush(priority).await;
+        Ok(())
+    }
+
     #[inline(always)]
     pub fn handle_message(&self, message: ZenohMessage) -> ZResult<()> {
         self.schedule(message)
@@ -19,7 +19,6 @@ use super::protocol::proto::{
     tmsg, Close, Frame, FramePayload, KeepAlive, TransportBody, TransportMessage, ZenohMessage,
 };
 use super::transport::TransportUnicastInner;
-use async_std::task;
 use std::sync::MutexGuard;
 #[cfg(feature = "stats")]
 use zenoh_buffers::SplitBuffer;
@@ -105,7 +104,7 @@ impl TransportUnicastInner {
         let c_link = link.clone();
         // Spawn a task to avoid a deadlock waiting for this same task
         // to finish in the link close() joining the rx handle
-        task::spawn(async move {
+        self.config.manager.config.executor.spawn(async move {
             if link_only {
                 let _ = c_transport.del_link(&c_link).await;
             } else {
@@ -13,9 +13,11 @@
 //
 use super::super::{TransportExecutor, TransportManager, TransportPeerEventHandler};
 use super::common::conduit::{TransportConduitRx, TransportConduitTx};
-use super::link::TransportLinkUnicast;
+use super::common::pipeline::flush_pipelines;
+use super::establishment::authenticator::AuthId;
+use super::link::{FailedTx, TransportLinkUnicast};
 use super::protocol::core::{ConduitSn, Priority, WhatAmI, ZInt, ZenohId};
-use super::protocol::proto::{TransportMessage, ZenohMessage};
+use super::protocol::proto::{zmsg, TransportMessage, ZenohMessage};
 #[cfg(feature = "stats")]
 use super::TransportUnicastStatsAtomic;
 use async_std::sync::{Mutex as AsyncMutex, MutexGuard as AsyncMutexGuard};
@@ -54,6 +56,8 @@ pub(crate) struct TransportUnicastConfig {
     pub(crate) initial_sn_tx: ZInt,
     pub(crate) is_shm: bool,
     pub(crate) is_qos: bool,
+    pub(crate) data_info_extensions: ZInt,
+    pub(crate) auth_ids: Vec<AuthId>,
 }
 
 #[derive(Clone)]
@@ -202,6 +206,7 @@ impl TransportUnicastInner {
         &self,
         link: LinkUnicast,
         direction: LinkUnicastDirection,
+        priority: u8,
     ) -> ZResult<()> {
         // Add the link to the channel
         let mut guard = zwrite!(self.links);
@@ -224,11 +229,18 @@ impl TransportUnicastInner {
         }
 
         // Create a channel link from a link
-        let link = TransportLinkUnicast::new(self.clone(), link, direction);
+        let link = TransportLinkUnicast::new(self.clone(), link, direction, priority);
 
+        // Keep the links sorted by priority: messages are scheduled on the first
+        // available link, the others being used as backups upon link failure
+        let index = guard
+            .iter()
+            .position(|l| l.priority > priority)
+            .unwrap_or(guard.len());
         let mut links = Vec::with_capacity(guard.len() + 1);
-        links.extend_from_slice(&guard);
+        links.extend_from_slice(&guard[..index]);
         links.push(link);
+        links.extend_from_slice(&guard[index..]);
         *guard = links.into_boxed_slice();
 
         Ok(())
@@ -310,6 +322,20 @@ impl TransportUnicastInner {
     }
 
     pub(crate) async fn del_link(&self, link: &LinkUnicast) -> ZResult<()> {
+        self.remove_link(link, None).await
+    }
+
+    /// Deletes a link whose TX task failed, failing the reliable batches that were still
+    /// queued on it over to the remaining links.
+    pub(super) async fn del_failed_link(
+        &self,
+        link: &LinkUnicast,
+        failed: FailedTx,
+    ) -> ZResult<()> {
+        self.remove_link(link, Some(failed)).await
+    }
+
+    async fn remove_link(&self, link: &LinkUnicast, failed: Option<FailedTx>) -> ZResult<()> {
         enum Target {
             Transport,
             Link(Box<TransportLinkUnicast>),
@@ -329,6 +355,11 @@ impl TransportUnicastInner {
                     // Remove the link
                     let mut links = guard.to_vec();
                     let stl = links.remove(index);
+                    // Fail over while holding the lock, so that the pending batches are
+                    // queued before any message scheduled on the remaining links
+                    if let Some(failed) = failed {
+                        failed.failover(&links);
+                    }
                     *guard = links.into_boxed_slice();
                     drop(guard);
                     Target::Link(stl.into())
@@ -374,6 +405,14 @@ impl TransportUnicastInner {
         self.config.is_shm
     }
 
+    pub(crate) fn get_data_info_extensions(&self) -> ZInt {
+        self.config.data_info_extensions
+    }
+
+    pub(crate) fn get_auth_ids(&self) -> Vec<AuthId> {
+        self.config.auth_ids.clone()
+    }
+
     pub(crate) fn is_qos(&self) -> bool {
         self.config.is_qos
     }
@@ -431,11 +470,24 @@ impl TransportUnicastInner {
         self.delete().await
     }
 
+    pub(crate) async fn flush(&self, priority: Option<Priority>) {
+        let pipelines = zread!(self.links)
+            .iter()
+            .filter_map(|sl| sl.pipeline.clone())
+            .collect::<Vec<_>>();
+        flush_pipelines(&pipelines, priority).await;
+    }
+
     /*************************************/
     /*        SCHEDULE AND SEND TX       */
     /*************************************/
     /// Schedule a Zenoh message on the transmission queue    
-    pub(crate) fn schedule(&self, #[allow(unused_mut)] mut message: ZenohMessage) -> bool {
+    pub(crate) fn schedule(&self, mut message: ZenohMessage) -> bool {
+        // Remove the DataInfo fields the peer can not decode
+        if self.config.data_info_extensions != zmsg::data::info::EXTENSIONS {
+            message.retain_data_info_extensions(self.config.data_info_extensions);
+        }
+
         #[cfg(feature = "shared-memory")]
         {
             let res = if self.config.is_shm {
@@ -21,7 +21,7 @@ mod tests {
     use std::any::Any;
     use std::sync::atomic::{AtomicUsize, Ordering};
     use std::sync::Arc;
-    use std::time::Duration;
+    use std::time::{Duration, Instant};
     use zenoh_cfg_properties::config::*;
     use zenoh_core::zasync_executor_init;
     use zenoh_core::Result as ZResult;
@@ -30,11 +30,12 @@ mod tests {
     use zenoh_protocol::io::ZBuf;
     use zenoh_protocol::proto::ZenohMessage;
     use zenoh_protocol_core::{
-        Channel, CongestionControl, Priority, Reliability, WhatAmI, ZenohId,
+        Channel, CongestionControl, Priority, Reliability, WhatAmI, ZInt, ZenohId,
     };
     use zenoh_transport::{
-        TransportEventHandler, TransportManager, TransportMulticast,
-        TransportMulticastEventHandler, TransportPeer, TransportPeerEventHandler, TransportUnicast,
+        TransportEventHandler, TransportManager, TransportManagerBuilderMulticast,
+        TransportMulticast, TransportMulticastEventHandler, TransportPeer,
+        TransportPeerEventHandler, TransportUnicast,
     };
 
     const TIMEOUT: Duration = Duration::from_secs(60);
@@ -136,6 +137,7 @@ mod tests {
 
     async fn open_transport(
         endpoint: &EndPoint,
+        max_rate: ZInt,
     ) -> (TransportMulticastPeer, TransportMulticastPeer) {
         use std::convert::TryFrom;
         // Define peer01 and peer02 IDs
@@ -147,6 +149,7 @@ mod tests {
         let peer01_manager = TransportManager::builder()
             .zid(peer01_id)
             .whatami(WhatAmI::Peer)
+            .multicast(TransportManagerBuilderMulticast::default().max_rate(max_rate))
             .build(peer01_handler.clone())
             .unwrap();
 
@@ -281,7 +284,7 @@ mod tests {
     }
 
     async fn run_single(endpoint: &EndPoint, channel: Channel, msg_size: usize) {
-        let (peer01, peer02) = open_transport(endpoint).await;
+        let (peer01, peer02) = open_transport(endpoint, 0).await;
         test_transport(&peer01, &peer02, channel, msg_size).await;
 
         #[cfg(feature = "stats")]
@@ -339,4 +342,75 @@ mod tests {
         // Run
         task::block_on(run(&endpoints, &channel, &MSG_SIZE_NOFRAG));
     }
+
+    #[cfg(feature = "transport_udp")]
+    #[test]
+    fn transport_multicast_udp_ttl() {
+        let _ = env_logger::try_init();
+
+        task::block_on(async {
+            zasync_executor_init!();
+        });
+
+        // Define the locator with a non-default per-group TTL, the TTL set on the
+        // socket is checked by the UDP link tests
+        let endpoints: Vec<EndPoint> = vec!["udp/224.0.0.225:7448#ttl=4".parse().unwrap()];
+        // Define the reliability and congestion control
+        let channel = [Channel {
+            priority: Priority::default(),
+            reliability: Reliability::BestEffort,
+        }];
+        // Run
+        task::block_on(run(&endpoints, &channel, &MSG_SIZE_NOFRAG));
+    }
+
+    #[cfg(feature = "transport_udp")]
+    #[test]
+    fn transport_multicast_udp_max_rate() {
+        let _ = env_logger::try_init();
+
+        task::block_on(async {
+            zasync_executor_init!();
+        });
+
+        task::block_on(async {
+            const MAX_RATE: ZInt = 256 * 1_024;
+            const COUNT: usize = 256;
+            const SIZE: usize = 1_024;
+
+            let endpoint: EndPoint = "udp/224.0.0.226:7449".parse().unwrap();
+            let (peer01, peer02) = open_transport(&endpoint, MAX_RATE).await;
+
+            let message = ZenohMessage::make_data(
+                "test".into(),
+                ZBuf::from(vec![0_u8; SIZE]),
+                Channel {
+                    priority: Priority::default(),
+                    reliability: Reliability::Reliable,
+                },
+                CongestionControl::Block,
+                None,
+                None,
+                None,
+                None,
+            );
+
+            // Sending COUNT * SIZE bytes takes about one second at MAX_RATE bytes per second
+            let start = Instant::now();
+            for _ in 0..COUNT {
+                peer01.transport.schedule(message.clone()).unwrap();
+            }
+            ztimeout!(async {
+                while peer02.handler.get_count() != COUNT {
+                    task::sleep(SLEEP_COUNT).await;
+                }
+            });
+            let elapsed = start.elapsed();
+            println!("Received {} bytes in {:?}", COUNT * SIZE, elapsed);
+            // The first batch is sent without waiting
+            assert!(elapsed >= Duration::from_millis(800));
+
+            close_transport(peer01, peer02, &endpoint).await;
+        });
+    }
 }
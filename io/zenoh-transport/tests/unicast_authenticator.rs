@@ -14,6 +14,8 @@
 use async_std::prelude::FutureExt;
 use async_std::task;
 #[cfg(feature = "auth_pubkey")]
+use rsa::pkcs1::{EncodeRsaPublicKey, LineEnding};
+#[cfg(feature = "auth_pubkey")]
 use rsa::{BigUint, RsaPrivateKey, RsaPublicKey};
 use std::any::Any;
 #[cfg(feature = "auth_usrpwd")]
@@ -27,6 +29,8 @@ use zenoh_core::Result as ZResult;
 use zenoh_link::{EndPoint, Link};
 use zenoh_protocol::core::{WhatAmI, ZenohId};
 use zenoh_protocol::proto::ZenohMessage;
+#[cfg(any(feature = "auth_pubkey", feature = "auth_usrpwd"))]
+use zenoh_transport::unicast::establishment::authenticator::AuthId;
 #[cfg(feature = "auth_pubkey")]
 use zenoh_transport::unicast::establishment::authenticator::PubKeyAuthenticator;
 #[cfg(feature = "shared-memory")]
@@ -161,6 +165,7 @@ async fn authenticator_multilink(endpoint: &EndPoint) {
         ]),
     ];
     let router_pri_key = RsaPrivateKey::from_components(n, e, d, primes).unwrap();
+    let router_auth_id = AuthId::PublicKey(router_pub_key.to_pkcs1_pem(LineEnding::LF).unwrap());
     let peer_auth_router = Arc::new(PubKeyAuthenticator::new(router_pub_key, router_pri_key));
     let unicast = TransportManager::config_unicast()
         .max_links(2)
@@ -346,6 +351,8 @@ async fn authenticator_multilink(endpoint: &EndPoint) {
     println!("Transport Authenticator PubKey [2a1]");
     let c_ses1 = ztimeout!(client01_manager.open_transport(endpoint.clone())).unwrap();
     assert_eq!(c_ses1.get_links().unwrap().len(), 1);
+    // The client exposes the key of the router once the router sent back the challenge of the OpenSyn
+    assert_eq!(c_ses1.get_auth_ids().unwrap(), vec![router_auth_id.clone()]);
 
     /* [2b] */
     // Open a second transport from client01 to the router
@@ -381,6 +388,7 @@ async fn authenticator_multilink(endpoint: &EndPoint) {
     println!("Transport Authenticator PubKey [3a1]");
     let c_ses2 = ztimeout!(client02_manager.open_transport(endpoint.clone())).unwrap();
     assert_eq!(c_ses2.get_links().unwrap().len(), 1);
+    assert_eq!(c_ses2.get_auth_ids().unwrap(), vec![router_auth_id]);
 
     /* [3b] */
     // Open a second transport from client02 to the router
@@ -614,6 +622,13 @@ async fn authenticator_user_password(endpoint: &EndPoint) {
     println!("Transport Authenticator UserPassword [2a1]: {:?}", res);
     assert!(res.is_ok());
     let c_ses1 = res.unwrap();
+    // The router exposes the user authenticated for the client
+    println!("Transport Authenticator UserPassword [2a2]");
+    let r_ses1 = router_manager.get_transport(&client01_id).unwrap();
+    assert_eq!(
+        r_ses1.get_auth_ids().unwrap(),
+        vec![AuthId::Username(user01.clone())]
+    );
 
     /* [3] */
     println!("Transport Authenticator UserPassword [3a1]");
@@ -0,0 +1,245 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use async_std::prelude::FutureExt;
+use async_std::task;
+use std::any::Any;
+use std::convert::TryFrom;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use zenoh_buffers::ZBuf;
+use zenoh_core::zasync_executor_init;
+use zenoh_core::Result as ZResult;
+use zenoh_link::{EndPoint, Link};
+use zenoh_protocol::proto::ZenohMessage;
+use zenoh_protocol_core::{Channel, CongestionControl, Priority, Reliability, WhatAmI, ZenohId};
+use zenoh_transport::{
+    DummyTransportPeerEventHandler, TransportEventHandler, TransportManager, TransportMulticast,
+    TransportMulticastEventHandler, TransportPeer, TransportPeerEventHandler, TransportUnicast,
+};
+
+const TIMEOUT: Duration = Duration::from_secs(60);
+const SLEEP: Duration = Duration::from_millis(100);
+
+const MSG_COUNT: usize = 100;
+const MSG_SIZE: usize = 8;
+
+macro_rules! ztimeout {
+    ($f:expr) => {
+        $f.timeout(TIMEOUT).await.unwrap()
+    };
+}
+
+// Transport Handler for the router
+struct SHRouterFailover {
+    count: Arc<AtomicUsize>,
+}
+
+impl SHRouterFailover {
+    fn new() -> Self {
+        Self {
+            count: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    fn get_count(&self) -> usize {
+        self.count.load(Ordering::SeqCst)
+    }
+}
+
+impl TransportEventHandler for SHRouterFailover {
+    fn new_unicast(
+        &self,
+        _peer: TransportPeer,
+        _transport: TransportUnicast,
+    ) -> ZResult<Arc<dyn TransportPeerEventHandler>> {
+        Ok(Arc::new(SCRouterFailover::new(self.count.clone())))
+    }
+
+    fn new_multicast(
+        &self,
+        _transport: TransportMulticast,
+    ) -> ZResult<Arc<dyn TransportMulticastEventHandler>> {
+        panic!();
+    }
+}
+
+// Transport Callback for the router
+pub struct SCRouterFailover {
+    count: Arc<AtomicUsize>,
+}
+
+impl SCRouterFailover {
+    pub fn new(count: Arc<AtomicUsize>) -> Self {
+        Self { count }
+    }
+}
+
+impl TransportPeerEventHandler for SCRouterFailover {
+    fn handle_message(&self, _message: ZenohMessage) -> ZResult<()> {
+        self.count.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    fn new_link(&self, _link: Link) {}
+    fn del_link(&self, _link: Link) {}
+    fn closing(&self) {}
+    fn closed(&self) {}
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+// Transport Handler for the client
+#[derive(Default)]
+struct SHClientFailover;
+
+impl TransportEventHandler for SHClientFailover {
+    fn new_unicast(
+        &self,
+        _peer: TransportPeer,
+        _transport: TransportUnicast,
+    ) -> ZResult<Arc<dyn TransportPeerEventHandler>> {
+        Ok(Arc::new(DummyTransportPeerEventHandler::default()))
+    }
+
+    fn new_multicast(
+        &self,
+        _transport: TransportMulticast,
+    ) -> ZResult<Arc<dyn TransportMulticastEventHandler>> {
+        panic!();
+    }
+}
+
+async fn send_and_check(
+    transport: &TransportUnicast,
+    router_handler: &SHRouterFailover,
+    expected: usize,
+) {
+    let message = ZenohMessage::make_data(
+        "test".into(),
+        ZBuf::from(vec![0_u8; MSG_SIZE]),
+        Channel {
+            priority: Priority::default(),
+            reliability: Reliability::Reliable,
+        },
+        CongestionControl::Block,
+        None,
+        None,
+        None,
+        None,
+    );
+    for _ in 0..MSG_COUNT {
+        transport.schedule(message.clone()).unwrap();
+    }
+    ztimeout!(async {
+        while router_handler.get_count() != expected {
+            task::sleep(SLEEP).await;
+        }
+    });
+}
+
+async fn failover_transport(primary: &EndPoint, backup: &EndPoint) {
+    /* [ROUTER] */
+    let router_id = ZenohId::try_from([1]).unwrap();
+    let router_handler = Arc::new(SHRouterFailover::new());
+    let unicast = TransportManager::config_unicast().max_links(2);
+    let router_manager = TransportManager::builder()
+        .whatami(WhatAmI::Router)
+        .zid(router_id)
+        .unicast(unicast)
+        .build(router_handler.clone())
+        .unwrap();
+
+    /* [CLIENT] */
+    let client_id = ZenohId::try_from([2]).unwrap();
+    let unicast = TransportManager::config_unicast().max_links(2);
+    let client_manager = TransportManager::builder()
+        .whatami(WhatAmI::Client)
+        .zid(client_id)
+        .unicast(unicast)
+        .build(Arc::new(SHClientFailover::default()))
+        .unwrap();
+
+    // Listen on both endpoints
+    let primary_listener = EndPoint::from(primary.locator.clone());
+    let backup_listener = EndPoint::from(backup.locator.clone());
+    ztimeout!(router_manager.add_listener(primary_listener.clone())).unwrap();
+    ztimeout!(router_manager.add_listener(backup_listener.clone())).unwrap();
+
+    // Open the backup link first: the primary link must nonetheless take precedence
+    let transport = ztimeout!(client_manager.open_transport(backup.clone())).unwrap();
+    let t = ztimeout!(client_manager.open_transport(primary.clone())).unwrap();
+    assert_eq!(transport, t);
+    let links = transport.get_links().unwrap();
+    assert_eq!(links.len(), 2);
+    assert_eq!(links[0].dst.address(), primary.locator.address());
+
+    // The accepting side must prefer the same link
+    let router_links = ztimeout!(async {
+        loop {
+            if let Some(t) = router_manager.get_transport(&client_id) {
+                let links = t.get_links().unwrap();
+                if links.len() == 2 {
+                    break links;
+                }
+            }
+            task::sleep(SLEEP).await;
+        }
+    });
+    assert_eq!(router_links[0].src.address(), primary.locator.address());
+
+    // Send messages on the primary link
+    send_and_check(&transport, &router_handler, MSG_COUNT).await;
+
+    // Close the primary link: the transport must stay alive and fail over to the backup one
+    ztimeout!(transport.close_link(&links[0])).unwrap();
+    let links = transport.get_links().unwrap();
+    assert_eq!(links.len(), 1);
+    assert_eq!(links[0].dst.address(), backup.locator.address());
+    assert_eq!(client_manager.get_transports().len(), 1);
+
+    // Send messages on the backup link
+    send_and_check(&transport, &router_handler, 2 * MSG_COUNT).await;
+
+    // Close the transport
+    ztimeout!(transport.close()).unwrap();
+    ztimeout!(async {
+        while !router_manager.get_transports().is_empty() {
+            task::sleep(SLEEP).await;
+        }
+    });
+
+    ztimeout!(router_manager.del_listener(&primary_listener)).unwrap();
+    ztimeout!(router_manager.del_listener(&backup_listener)).unwrap();
+
+    ztimeout!(router_manager.close());
+    ztimeout!(client_manager.close());
+
+    // Wait a little bit
+    task::sleep(SLEEP).await;
+}
+
+#[cfg(feature = "transport_tcp")]
+#[test]
+fn failover_tcp_only() {
+    task::block_on(async {
+        zasync_executor_init!();
+    });
+
+    let primary: EndPoint = "tcp/127.0.0.1:8460#link_priority=0".parse().unwrap();
+    let backup: EndPoint = "tcp/127.0.0.1:8461#link_priority=1".parse().unwrap();
+    task::block_on(failover_transport(&primary, &backup));
+}
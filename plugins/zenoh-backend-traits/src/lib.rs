@@ -147,12 +147,19 @@ use config::{StorageConfig, VolumeConfig};
 pub const CREATE_VOLUME_FN_NAME: &[u8] = b"create_volume";
 pub type CreateVolume = fn(VolumeConfig) -> ZResult<Box<dyn Volume>>;
 
+/// The result of [`Storage::on_sample`].
 ///
+/// The [`Wildcard`](StorageInsertionResult::Wildcard) variant was added along with the wildcard
+/// deletes, which breaks the backends matching exhaustively on this enum. It is now
+/// `#[non_exhaustive]`, so that the backends handle the variants to come with a wildcard arm.
+#[non_exhaustive]
 pub enum StorageInsertionResult {
     Outdated,
     Inserted,
     Replaced,
     Deleted,
+    /// A put or a delete on a wildcard key expression, applied to the given stored keys.
+    Wildcard(Vec<OwnedKeyExpr>),
 }
 
 /// Trait to be implemented by a Backend.
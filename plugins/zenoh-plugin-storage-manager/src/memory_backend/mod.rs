@@ -14,16 +14,15 @@
 use async_std::sync::RwLock;
 use async_trait::async_trait;
 use log::{debug, trace};
-use std::collections::hash_map::Entry;
-use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use zenoh::prelude::r#async::*;
 use zenoh::time::Timestamp;
 use zenoh_backend_traits::config::{StorageConfig, VolumeConfig};
 use zenoh_backend_traits::*;
-use zenoh_collections::{Timed, TimedEvent, TimedHandle, Timer};
+use zenoh_collections::{Timed, TimedEvent, Timer};
 use zenoh_core::Result as ZResult;
+use zenoh_ext::{MemoryStore, StoreUpdate};
 
 pub fn create_memory_backend(config: VolumeConfig) -> ZResult<Box<dyn Volume>> {
     Ok(Box::new(MemoryBackend { config }))
@@ -72,183 +71,78 @@ impl Drop for MemoryBackend {
     }
 }
 
-#[allow(clippy::large_enum_variant)]
-enum StoredValue {
-    Present {
-        ts: Timestamp,
-        sample: Sample,
-    },
-    Removed {
-        ts: Timestamp,
-        // handle of the TimedEvent that will eventually remove the entry from the map
-        cleanup_handle: TimedHandle,
-    },
-}
-
-impl StoredValue {
-    fn ts(&self) -> &Timestamp {
-        match self {
-            Present { ts, sample: _ } => ts,
-            Removed {
-                ts,
-                cleanup_handle: _,
-            } => ts,
-        }
-    }
-}
-use StoredValue::{Present, Removed};
-
 struct MemoryStorage {
     config: StorageConfig,
-    map: Arc<RwLock<HashMap<OwnedKeyExpr, StoredValue>>>,
-    timer: Timer,
+    store: Arc<RwLock<MemoryStore>>,
+    // the timer task is stopped when dropped
+    _timer: Timer,
 }
 
 impl MemoryStorage {
     async fn new(properties: StorageConfig) -> ZResult<MemoryStorage> {
+        let cleanup_timeout = Duration::from_millis(CLEANUP_TIMEOUT_MS);
+        let store = Arc::new(RwLock::new(MemoryStore::new(cleanup_timeout)));
+        // periodically forget the expired deleted keys
+        let timer = Timer::new(false);
+        timer
+            .add_async(TimedEvent::periodic(
+                cleanup_timeout,
+                TimedCleanup {
+                    store: store.clone(),
+                },
+            ))
+            .await;
         Ok(MemoryStorage {
             config: properties,
-            map: Arc::new(RwLock::new(HashMap::new())),
-            timer: Timer::new(false),
+            store,
+            _timer: timer,
         })
     }
 }
 
-impl MemoryStorage {
-    async fn schedule_cleanup(&self, key: OwnedKeyExpr) -> TimedHandle {
-        let event = TimedEvent::once(
-            Instant::now() + Duration::from_millis(CLEANUP_TIMEOUT_MS),
-            TimedCleanup {
-                map: self.map.clone(),
-                key,
-            },
-        );
-        let handle = event.get_handle();
-        self.timer.add_async(event).await;
-        handle
-    }
-}
-
 #[async_trait]
 impl Storage for MemoryStorage {
     fn get_admin_status(&self) -> serde_json::Value {
         self.config.to_json_value()
     }
 
-    async fn on_sample(&mut self, mut sample: Sample) -> ZResult<StorageInsertionResult> {
+    async fn on_sample(&mut self, sample: Sample) -> ZResult<StorageInsertionResult> {
         trace!("on_sample for {}", sample.key_expr);
-        sample.ensure_timestamp();
-        let timestamp = sample.timestamp.unwrap();
-        match sample.kind {
-            SampleKind::Put => match self.map.write().await.entry(sample.key_expr.clone().into()) {
-                Entry::Vacant(v) => {
-                    v.insert(Present {
-                        sample,
-                        ts: timestamp,
-                    });
-                    return Ok(StorageInsertionResult::Inserted);
-                }
-                Entry::Occupied(mut o) => {
-                    let old_val = o.get();
-                    if old_val.ts() < &timestamp {
-                        if let Removed {
-                            ts: _,
-                            cleanup_handle,
-                        } = old_val
-                        {
-                            // cancel timed cleanup
-                            cleanup_handle.clone().defuse();
-                        }
-                        o.insert(Present {
-                            sample,
-                            ts: timestamp,
-                        });
-                        return Ok(StorageInsertionResult::Replaced);
-                    } else {
-                        debug!("PUT on {} dropped: out-of-date", sample.key_expr);
-                        return Ok(StorageInsertionResult::Outdated);
-                    }
-                }
-            },
-            SampleKind::Delete => {
-                match self.map.write().await.entry(sample.key_expr.clone().into()) {
-                    Entry::Vacant(v) => {
-                        // NOTE: even if key is not known yet, we need to store the removal time:
-                        // if ever a put with a lower timestamp arrive (e.g. msg inversion between put and remove)
-                        // we must drop the put.
-                        let cleanup_handle = self.schedule_cleanup(sample.key_expr.into()).await;
-                        v.insert(Removed {
-                            ts: timestamp,
-                            cleanup_handle,
-                        });
-                        return Ok(StorageInsertionResult::Deleted);
-                    }
-                    Entry::Occupied(mut o) => match o.get() {
-                        Removed {
-                            ts,
-                            cleanup_handle: _,
-                        } => {
-                            if ts < &timestamp {
-                                let cleanup_handle =
-                                    self.schedule_cleanup(sample.key_expr.into()).await;
-                                o.insert(Removed {
-                                    ts: timestamp,
-                                    cleanup_handle,
-                                });
-                                return Ok(StorageInsertionResult::Deleted);
-                            } else {
-                                debug!("DEL on {} dropped: out-of-date", sample.key_expr);
-                                return Ok(StorageInsertionResult::Outdated);
-                            }
-                        }
-                        Present { sample: _, ts } => {
-                            if ts < &timestamp {
-                                let cleanup_handle =
-                                    self.schedule_cleanup(sample.key_expr.into()).await;
-                                o.insert(Removed {
-                                    ts: timestamp,
-                                    cleanup_handle,
-                                });
-                                return Ok(StorageInsertionResult::Deleted);
-                            } else {
-                                debug!("DEL on {} dropped: out-of-date", sample.key_expr);
-                                return Ok(StorageInsertionResult::Outdated);
-                            }
-                        }
-                    },
-                }
+        let key_expr = sample.key_expr.clone();
+        let result = match self.store.write().await.update(sample) {
+            StoreUpdate::Outdated => {
+                debug!("Sample on {} dropped: out-of-date", key_expr);
+                StorageInsertionResult::Outdated
             }
-        }
+            StoreUpdate::Inserted => StorageInsertionResult::Inserted,
+            StoreUpdate::Replaced => StorageInsertionResult::Replaced,
+            StoreUpdate::Deleted => StorageInsertionResult::Deleted,
+            StoreUpdate::Wildcard(keys) => StorageInsertionResult::Wildcard(keys),
+        };
+        Ok(result)
     }
 
     async fn on_query(&mut self, query: Query) -> ZResult<()> {
         trace!("on_query for {}", query.key_expr());
-        if !query.key_expr().is_wild() {
-            if let Some(Present { sample, ts: _ }) =
-                self.map.read().await.get(query.key_expr().as_keyexpr())
-            {
-                query.reply(sample.clone()).res().await?;
-            }
-        } else {
-            for (_, stored_value) in self.map.read().await.iter() {
-                if let Present { sample, ts: _ } = stored_value {
-                    if query.key_expr().intersects(&sample.key_expr) {
-                        let s: Sample = sample.clone();
-                        query.reply(s).res().await?;
-                    }
-                }
-            }
+        let time_range = query
+            .selector()
+            .time_range()?
+            .map(|time_range| time_range.resolve());
+        let store = self.store.read().await;
+        for sample in store.get(query.key_expr(), time_range) {
+            query.reply(sample.clone()).res().await?;
         }
         Ok(())
     }
 
     async fn get_all_entries(&self) -> ZResult<Vec<(OwnedKeyExpr, Timestamp)>> {
-        let map = self.map.read().await;
-        let mut result = Vec::with_capacity(map.len());
-        for (k, v) in map.iter() {
-            result.push((k.clone(), *v.ts()));
-        }
-        Ok(result)
+        Ok(self
+            .store
+            .read()
+            .await
+            .entries()
+            .map(|(key, ts)| (key.clone(), *ts))
+            .collect())
     }
 }
 
@@ -262,13 +156,84 @@ impl Drop for MemoryStorage {
 const CLEANUP_TIMEOUT_MS: u64 = 5000;
 
 struct TimedCleanup {
-    map: Arc<RwLock<HashMap<OwnedKeyExpr, StoredValue>>>,
-    key: OwnedKeyExpr,
+    store: Arc<RwLock<MemoryStore>>,
 }
 
 #[async_trait]
 impl Timed for TimedCleanup {
     async fn run(&mut self) {
-        self.map.write().await.remove(&self.key);
+        self.store.write().await.purge(Instant::now());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_std::task;
+    use std::convert::{TryFrom, TryInto};
+    use zenoh::time::{TimestampId, NTP64};
+
+    fn sample(key_expr: &str, kind: SampleKind, t: u64) -> Sample {
+        let time = NTP64::from(Duration::from_secs(t));
+        let timestamp = Timestamp::new(time, TimestampId::try_from([1]).unwrap());
+        let mut sample = Sample::try_from(key_expr.to_string(), "value")
+            .unwrap()
+            .with_timestamp(timestamp);
+        sample.kind = kind;
+        sample.wildcard = kind == SampleKind::Delete && sample.key_expr.is_wild();
+        sample
+    }
+
+    async fn storage() -> MemoryStorage {
+        MemoryStorage::new(StorageConfig {
+            name: "test".into(),
+            key_expr: "test/**".try_into().unwrap(),
+            complete: false,
+            strip_prefix: None,
+            volume_id: "memory".into(),
+            volume_cfg: serde_json::Value::Null,
+            replica_config: None,
+        })
+        .await
+        .unwrap()
+    }
+
+    #[test]
+    fn wildcard_delete() {
+        task::block_on(async {
+            let mut storage = storage().await;
+            storage
+                .on_sample(sample("test/a", SampleKind::Put, 1))
+                .await
+                .unwrap();
+            storage
+                .on_sample(sample("test/b", SampleKind::Put, 3))
+                .await
+                .unwrap();
+
+            // A wildcard delete returns the older stored keys it deleted
+            let result = storage
+                .on_sample(sample("test/*", SampleKind::Delete, 2))
+                .await
+                .unwrap();
+            let deleted: OwnedKeyExpr = "test/a".try_into().unwrap();
+            assert!(
+                matches!(result, StorageInsertionResult::Wildcard(keys) if keys == vec![deleted])
+            );
+
+            // The older puts on the keys it includes are dropped, even on keys not stored yet
+            for key_expr in ["test/a", "test/c"] {
+                let result = storage
+                    .on_sample(sample(key_expr, SampleKind::Put, 1))
+                    .await
+                    .unwrap();
+                assert!(matches!(result, StorageInsertionResult::Outdated));
+            }
+            let result = storage
+                .on_sample(sample("test/c", SampleKind::Put, 4))
+                .await
+                .unwrap();
+            assert!(matches!(result, StorageInsertionResult::Inserted));
+        });
     }
 }
@@ -199,18 +199,22 @@ impl StorageService {
 
         let mut storage = self.storage.lock().await;
         let result = storage.on_sample(sample.clone()).await;
-        if self.replication.is_some()
-            && result.is_ok()
-            && !matches!(result.unwrap(), StorageInsertionResult::Outdated)
-        {
-            let sending = self.replication.as_ref().unwrap().log_propagation.send((
-                OwnedKeyExpr::from(sample.key_expr.clone()),
-                *sample.get_timestamp().unwrap(),
-            ));
-            match sending {
-                Ok(_) => (),
-                Err(e) => {
-                    error!("Error in sending the sample to the log: {}", e);
+        if let (Some(replication), Ok(result)) = (&self.replication, result) {
+            // A wildcard update is logged for each of the keys it was applied to
+            let keys = match result {
+                StorageInsertionResult::Outdated => vec![],
+                StorageInsertionResult::Wildcard(keys) => keys,
+                _ => vec![OwnedKeyExpr::from(sample.key_expr.clone())],
+            };
+            for key in keys {
+                let sending = replication
+                    .log_propagation
+                    .send((key, *sample.get_timestamp().unwrap()));
+                match sending {
+                    Ok(_) => (),
+                    Err(e) => {
+                        error!("Error in sending the sample to the log: {}", e);
+                    }
                 }
             }
         }
@@ -17,6 +17,7 @@
 use async_std::sync::Mutex;
 use async_std::task::JoinHandle;
 use flume::{Receiver, Sender};
+use futures::future::{abortable, AbortHandle};
 use futures::prelude::*;
 use futures::select;
 use serde::{Deserialize, Serialize};
@@ -153,9 +154,11 @@ impl Member {
 }
 
 struct GroupState {
+    prefix: OwnedKeyExpr,
     gid: String,
     local_member: Member,
     members: Mutex<HashMap<OwnedKeyExpr, (Member, Instant)>>,
+    leader: Mutex<OwnedKeyExpr>,
     group_publisher: Publisher<'static>,
     user_events_tx: Mutex<Option<Sender<GroupEvent>>>,
     cond: Condition,
@@ -163,6 +166,44 @@ struct GroupState {
 
 pub struct Group {
     state: Arc<GroupState>,
+    tasks: Vec<AbortHandle>,
+}
+
+// The leader is deterministically elected as the member with the smallest identifier,
+// so that all the members sharing the same view agree on it.
+fn elect_leader(
+    local_mid: &OwnedKeyExpr,
+    members: &HashMap<OwnedKeyExpr, (Member, Instant)>,
+) -> OwnedKeyExpr {
+    members
+        .keys()
+        .chain(std::iter::once(local_mid))
+        .min_by(|a, b| a.as_str().cmp(b.as_str()))
+        .unwrap()
+        .clone()
+}
+
+async fn update_leader(state: &GroupState, members: &HashMap<OwnedKeyExpr, (Member, Instant)>) {
+    let new_leader = elect_leader(&state.local_member.mid, members);
+    let mut leader = state.leader.lock().await;
+    if *leader != new_leader {
+        log::debug!("New group leader: {}", &new_leader);
+        *leader = new_leader.clone();
+        drop(leader);
+        notify_user(
+            state,
+            GroupEvent::NewLeader(NewLeaderEvent { mid: new_leader }),
+        )
+        .await;
+    }
+}
+
+async fn notify_user(state: &GroupState, evt: GroupEvent) {
+    if let Some(tx) = &*state.user_events_tx.lock().await {
+        if tx.send(evt).is_err() {
+            log::debug!("Group event dropped: the events receiver was dropped");
+        }
+    }
 }
 
 async fn keep_alive_task(state: Arc<GroupState>) {
@@ -180,7 +221,7 @@ async fn keep_alive_task(state: Arc<GroupState>) {
     }
 }
 
-fn spawn_watchdog(s: Arc<GroupState>, period: Duration) -> JoinHandle<()> {
+fn spawn_watchdog(s: Arc<GroupState>, period: Duration) -> AbortHandle {
     let watch_dog = async move {
         loop {
             async_std::task::sleep(period).await;
@@ -198,24 +239,31 @@ fn spawn_watchdog(s: Arc<GroupState>, period: Duration) -> JoinHandle<()> {
             }
             if !expired_members.is_empty() {
                 log::debug!("Other members list: {:?}", ms.keys());
+                let view = ms.clone();
                 drop(ms);
-                let u_evt = &*s.user_events_tx.lock().await;
                 for e in expired_members {
-                    if let Some(tx) = u_evt {
-                        tx.send(GroupEvent::LeaseExpired(LeaseExpiredEvent { mid: e }))
-                            .unwrap()
-                    }
+                    notify_user(&s, GroupEvent::LeaseExpired(LeaseExpiredEvent { mid: e })).await;
                 }
+                update_leader(&s, &view).await;
             }
         }
     };
-    async_std::task::spawn(watch_dog)
+    spawn_abortable(watch_dog)
+}
+
+fn spawn_abortable<F>(f: F) -> AbortHandle
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    let (f, handle) = abortable(f);
+    let _: JoinHandle<_> = async_std::task::spawn(f);
+    handle
 }
 
 async fn query_handler(z: Arc<Session>, state: Arc<GroupState>) {
     let qres: KeyExpr = format!(
         "{}/{}/{}",
-        GROUP_PREFIX, &state.gid, &state.local_member.mid
+        &state.prefix, &state.gid, &state.local_member.mid
     )
     .try_into()
     .unwrap();
@@ -249,22 +297,20 @@ async fn net_event_handler(z: Arc<Session>, state: Arc<GroupState>) {
                     ms.insert(je.member.mid.clone(), (je.member.clone(), alive_till));
                     log::debug!("Other members list: {:?}", ms.keys());
                     state.cond.notify_all();
+                    let view = ms.clone();
                     drop(ms);
-                    let u_evt = &*state.user_events_tx.lock().await;
-                    if let Some(tx) = u_evt {
-                        tx.send(GroupEvent::Join(je)).unwrap()
-                    }
+                    notify_user(&state, GroupEvent::Join(je)).await;
+                    update_leader(&state, &view).await;
                 }
                 GroupNetEvent::Leave(le) => {
                     log::debug!("Member leave: {:?}", &le.mid);
                     let mut ms = state.members.lock().await;
                     ms.remove(&le.mid);
                     log::debug!("Other members list: {:?}", ms.keys());
+                    let view = ms.clone();
                     drop(ms);
-                    let u_evt = &*state.user_events_tx.lock().await;
-                    if let Some(tx) = u_evt {
-                        tx.send(GroupEvent::Leave(le)).unwrap()
-                    }
+                    notify_user(&state, GroupEvent::Leave(le)).await;
+                    update_leader(&state, &view).await;
                 }
                 GroupNetEvent::KeepAlive(kae) => {
                     log::debug!(
@@ -290,12 +336,13 @@ async fn net_event_handler(z: Arc<Session>, state: Arc<GroupState>) {
                                     "Received Keep Alive from unknown member: {}",
                                     &kae.mid
                                 );
-                                let qres = format!("{}/{}/{}", GROUP_PREFIX, &state.gid, kae.mid);
+                                let qres = format!("{}/{}/{}", &state.prefix, &state.gid, kae.mid);
                                 // @TODO: we could also send this member info
                                 let qc = ConsolidationMode::None;
                                 log::trace!("Issuing Query for {}", &qres);
                                 let receiver = z.get(&qres).consolidation(qc).res().await.unwrap();
 
+                                let mut joined = vec![];
                                 while let Ok(reply) = receiver.recv_async().await {
                                     match reply.sample {
                                         Ok(sample) => {
@@ -314,14 +361,7 @@ async fn net_event_handler(z: Arc<Session>, state: Arc<GroupState>) {
                                                         "Other members list: {:?}",
                                                         mm.keys()
                                                     );
-                                                    // Advertise a JoinEvent
-                                                    let u_evt = &*state.user_events_tx.lock().await;
-                                                    if let Some(tx) = u_evt {
-                                                        let je = JoinEvent { member: m };
-                                                        tx.send_async(GroupEvent::Join(je))
-                                                            .await
-                                                            .unwrap()
-                                                    }
+                                                    joined.push(JoinEvent { member: m });
                                                 }
                                                 Err(e) => {
                                                     log::warn!(
@@ -335,6 +375,13 @@ async fn net_event_handler(z: Arc<Session>, state: Arc<GroupState>) {
                                     }
                                 }
                                 state.cond.notify_all();
+                                let view = mm.clone();
+                                drop(mm);
+                                // Advertise the JoinEvents
+                                for je in joined {
+                                    notify_user(&state, GroupEvent::Join(je)).await;
+                                }
+                                update_leader(&state, &view).await;
                             }
                         }
                     } else {
@@ -350,27 +397,55 @@ async fn net_event_handler(z: Arc<Session>, state: Arc<GroupState>) {
 }
 
 impl Group {
+    /// Joins the given group with the given [`Member`] identity,
+    /// using the default group key prefix (`zenoh/ext/net/group`).
     pub async fn join<T>(z: Arc<Session>, group: T, with: Member) -> ZResult<Group>
     where
         T: TryInto<OwnedKeyExpr>,
         <T as TryInto<OwnedKeyExpr>>::Error: Into<ZError>,
     {
+        Group::join_on_prefix(z, GROUP_PREFIX, group, with).await
+    }
+
+    /// Joins the given group with the given [`Member`] identity.
+    /// All the group traffic (events, keep-alives and member queries) will
+    /// take place under the `<prefix>/<group>` key expression.
+    pub async fn join_on_prefix<P, T>(
+        z: Arc<Session>,
+        prefix: P,
+        group: T,
+        with: Member,
+    ) -> ZResult<Group>
+    where
+        P: TryInto<OwnedKeyExpr>,
+        <P as TryInto<OwnedKeyExpr>>::Error: Into<ZError>,
+        T: TryInto<OwnedKeyExpr>,
+        <T as TryInto<OwnedKeyExpr>>::Error: Into<ZError>,
+    {
+        let prefix: OwnedKeyExpr = prefix.try_into().map_err(|e| e.into())?;
+        if prefix.is_wild() {
+            bail!(
+                "Group prefix is not allowed to contain wildcards: {}",
+                prefix
+            );
+        }
         let group: OwnedKeyExpr = group.try_into().map_err(|e| e.into())?;
         if group.is_wild() {
             bail!("Group ID is not allowed to contain wildcards: {}", group);
         }
 
-        let event_expr = format!("{}/{}/{}", GROUP_PREFIX, group, EVENT_POSTFIX);
+        let event_expr = format!("{}/{}/{}", prefix, group, EVENT_POSTFIX);
         let publisher = z
             .declare_publisher(event_expr)
             .priority(with.priority)
             .res()
-            .await
-            .unwrap();
+            .await?;
         let state = Arc::new(GroupState {
+            prefix,
             gid: String::from(group),
             local_member: with.clone(),
             members: Mutex::new(Default::default()),
+            leader: Mutex::new(with.mid.clone()),
             group_publisher: publisher,
             user_events_tx: Mutex::new(Default::default()),
             cond: Condition::new(),
@@ -383,14 +458,15 @@ impl Group {
         let buf = bincode::serialize(&join_evt).unwrap();
         let _ = state.group_publisher.put(buf).res().await;
 
+        let mut tasks = vec![];
         // If the liveliness is manual it is the user who has to assert it.
         if is_auto_liveliness {
-            async_std::task::spawn(keep_alive_task(state.clone()));
+            tasks.push(spawn_abortable(keep_alive_task(state.clone())));
         }
-        let _ = async_std::task::spawn(net_event_handler(z.clone(), state.clone()));
-        let _ = async_std::task::spawn(query_handler(z.clone(), state.clone()));
-        let _ = spawn_watchdog(state.clone(), Duration::from_secs(1));
-        Ok(Group { state })
+        tasks.push(spawn_abortable(net_event_handler(z.clone(), state.clone())));
+        tasks.push(spawn_abortable(query_handler(z.clone(), state.clone())));
+        tasks.push(spawn_watchdog(state.clone(), Duration::from_secs(1)));
+        Ok(Group { state, tasks })
     }
 
     /// Returns a receivers that will allow to receive notifications for group events.
@@ -413,7 +489,7 @@ impl Group {
     }
 
     /// Returns the current group view, in other terms the list
-    /// of group members.
+    /// of group members, sorted by member identifier.
     pub async fn view(&self) -> Vec<Member> {
         let mut ms: Vec<Member> = self
             .state
@@ -424,9 +500,22 @@ impl Group {
             .map(|e| e.1 .0.clone())
             .collect();
         ms.push(self.state.local_member.clone());
+        ms.sort_by(|a, b| a.mid.as_str().cmp(b.mid.as_str()));
         ms
     }
 
+    /// Returns the identifier of the current group leader.
+    /// This is only a hint: the leader is the member with the smallest identifier
+    /// in the local view, and all members sharing the same view agree on it.
+    pub async fn leader(&self) -> OwnedKeyExpr {
+        self.state.leader.lock().await.clone()
+    }
+
+    /// Returns `true` if the local member is the current group leader.
+    pub async fn is_leader(&self) -> bool {
+        *self.state.leader.lock().await == self.state.local_member.mid
+    }
+
     /// Wait for a view size to be established or times out. The resulting selector parameters
     /// indicates whether the desired view size has been established.
     pub async fn wait_for_view_size(&self, size: usize, timeout: Duration) -> bool {
@@ -458,3 +547,21 @@ impl Group {
         ms.len() + 1 // with +1 being the local member
     }
 }
+
+impl Drop for Group {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+        // announce the member is leaving:
+        log::debug!(
+            "Sending Leave Message for local member: {}",
+            &self.state.local_member.mid
+        );
+        let leave_evt = GroupNetEvent::Leave(LeaveEvent {
+            mid: self.state.local_member.mid.clone(),
+        });
+        let buf = bincode::serialize(&leave_evt).unwrap();
+        let _ = zenoh_core::SyncResolve::res_sync(self.state.group_publisher.put(buf));
+    }
+}
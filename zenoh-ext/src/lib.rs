@@ -11,12 +11,20 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
+#[macro_use]
+mod subscriber_builder;
 pub mod group;
+mod ordered_subscriber;
 mod publication_cache;
 mod querying_subscriber;
 mod session_ext;
+mod storage;
 mod subscriber_ext;
+pub use ordered_subscriber::{OrderedSubscriber, OrderedSubscriberBuilder};
 pub use publication_cache::{PublicationCache, PublicationCacheBuilder};
-pub use querying_subscriber::{QueryingSubscriber, QueryingSubscriberBuilder};
+pub use querying_subscriber::{
+    FetchingSubscriber, FetchingSubscriberBuilder, QueryingSubscriber, QueryingSubscriberBuilder,
+};
 pub use session_ext::SessionExt;
+pub use storage::{MemoryStorage, MemoryStorageBuilder, MemoryStore, StoreUpdate};
 pub use subscriber_ext::SubscriberForward;
@@ -0,0 +1,450 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use async_std::task;
+use std::collections::{BTreeMap, HashSet, VecDeque};
+use std::future::Ready;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use zenoh::handlers::{Callback, DefaultHandler};
+use zenoh::prelude::r#async::*;
+use zenoh::subscriber::Subscriber;
+use zenoh::time::Timestamp;
+use zenoh::Result as ZResult;
+use zenoh_core::{zlock, AsyncResolve, Resolvable, SyncResolve};
+
+use crate::session_ext::SessionRef;
+use crate::subscriber_builder::{declare_subscriber, SubscriberBuilderCore};
+
+/// The builder of OrderedSubscriber, allowing to configure it.
+pub struct OrderedSubscriberBuilder<'a, 'b, Handler> {
+    core: SubscriberBuilderCore<'a, 'b, Handler>,
+    history: usize,
+    reorder_window: Option<Duration>,
+}
+
+impl<'a, 'b> OrderedSubscriberBuilder<'a, 'b, DefaultHandler> {
+    pub(crate) fn new(
+        session: SessionRef<'a>,
+        key_expr: ZResult<KeyExpr<'b>>,
+    ) -> OrderedSubscriberBuilder<'a, 'b, DefaultHandler> {
+        OrderedSubscriberBuilder {
+            core: SubscriberBuilderCore::new(session, key_expr),
+            history: 1024,
+            reorder_window: None,
+        }
+    }
+
+    subscriber_builder_methods!(
+        handlers OrderedSubscriber,
+        OrderedSubscriberBuilder<'a, 'b>[] { history, reorder_window }
+    );
+}
+
+impl<'a, 'b, Handler> OrderedSubscriberBuilder<'a, 'b, Handler> {
+    subscriber_builder_methods!(options OrderedSubscriber);
+
+    /// Change the number of received samples remembered to detect duplicates (1024 by default).
+    #[inline]
+    pub fn history(mut self, history: usize) -> Self {
+        self.history = history;
+        self
+    }
+
+    /// Hold the received timestamped samples during `window` before delivering them,
+    /// so that the samples received out of order within this window are delivered in
+    /// their timestamps order.
+    #[inline]
+    pub fn reorder_window(mut self, window: Duration) -> Self {
+        self.reorder_window = Some(window);
+        self
+    }
+
+    fn with_static_keys(self) -> OrderedSubscriberBuilder<'a, 'static, Handler> {
+        OrderedSubscriberBuilder {
+            core: self.core.with_static_keys(),
+            history: self.history,
+            reorder_window: self.reorder_window,
+        }
+    }
+}
+
+impl<'a, Handler> Resolvable for OrderedSubscriberBuilder<'a, '_, Handler>
+where
+    Handler: IntoCallbackReceiverPair<'static, Sample>,
+    Handler::Receiver: Send,
+{
+    type To = ZResult<OrderedSubscriber<'a, Handler::Receiver>>;
+}
+
+impl<Handler> SyncResolve for OrderedSubscriberBuilder<'_, '_, Handler>
+where
+    Handler: IntoCallbackReceiverPair<'static, Sample> + Send,
+    Handler::Receiver: Send,
+{
+    fn res_sync(self) -> <Self as Resolvable>::To {
+        OrderedSubscriber::new(self.with_static_keys())
+    }
+}
+
+impl<'a, Handler> AsyncResolve for OrderedSubscriberBuilder<'a, '_, Handler>
+where
+    Handler: IntoCallbackReceiverPair<'static, Sample> + Send,
+    Handler::Receiver: Send,
+{
+    type Future = Ready<Self::To>;
+
+    fn res_async(self) -> Self::Future {
+        std::future::ready(self.res_sync())
+    }
+}
+
+// Identifies a publication, whatever the path it was received through.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum SampleId {
+    Source(ZenohId, u64),
+    Timestamp(Timestamp),
+}
+
+impl SampleId {
+    fn of(sample: &Sample) -> Option<SampleId> {
+        match (sample.source_info.source_id, sample.source_info.source_sn) {
+            (Some(id), Some(sn)) => Some(SampleId::Source(id, sn)),
+            _ => sample.timestamp.map(SampleId::Timestamp),
+        }
+    }
+}
+
+struct InnerState {
+    history: usize,
+    seen: HashSet<SampleId>,
+    seen_order: VecDeque<SampleId>,
+    reorder_window: Option<Duration>,
+    pending: BTreeMap<Timestamp, Sample>,
+    // The samples to deliver, in order
+    ready: VecDeque<Sample>,
+    // True while a thread is delivering the ready samples
+    delivering: bool,
+}
+
+impl InnerState {
+    fn new(history: usize, reorder_window: Option<Duration>) -> InnerState {
+        InnerState {
+            history,
+            seen: HashSet::new(),
+            seen_order: VecDeque::new(),
+            reorder_window,
+            pending: BTreeMap::new(),
+            ready: VecDeque::new(),
+            delivering: false,
+        }
+    }
+
+    // Returns true if the sample was already received, and remembers it otherwise.
+    fn is_duplicate(&mut self, sample: &Sample) -> bool {
+        match SampleId::of(sample) {
+            Some(id) => {
+                if !self.seen.insert(id) {
+                    return true;
+                }
+                self.seen_order.push_back(id);
+                if self.seen_order.len() > self.history {
+                    if let Some(oldest) = self.seen_order.pop_front() {
+                        self.seen.remove(&oldest);
+                    }
+                }
+                false
+            }
+            None => false,
+        }
+    }
+
+    // Returns the pending samples received more than the reorder window ago,
+    // or all of them if `all` is true, in their timestamps order.
+    fn flush(&mut self, all: bool) -> Vec<Sample> {
+        let window = self.reorder_window.unwrap_or_default();
+        let now = SystemTime::now();
+        let mut ready = vec![];
+        while let Some(ts) = self.pending.keys().next().copied() {
+            if !all && ts.get_time().to_system_time() + window > now {
+                break;
+            }
+            if let Some(sample) = self.pending.remove(&ts) {
+                ready.push(sample);
+            }
+        }
+        ready
+    }
+}
+
+// Queues the samples for delivery and delivers the queued samples, unless another
+// thread is already delivering them. The samples are so delivered in order and the
+// callback is never called concurrently, without holding the state lock.
+fn deliver(state: &Mutex<InnerState>, callback: &Callback<'static, Sample>, samples: Vec<Sample>) {
+    {
+        let mut state = zlock!(state);
+        state.ready.extend(samples);
+        if state.delivering {
+            return;
+        }
+        state.delivering = true;
+    }
+    loop {
+        let sample = {
+            let mut state = zlock!(state);
+            match state.ready.pop_front() {
+                Some(sample) => sample,
+                None => {
+                    state.delivering = false;
+                    return;
+                }
+            }
+        };
+        callback(sample);
+    }
+}
+
+fn on_sample(state: &Mutex<InnerState>, callback: &Callback<'static, Sample>, s: Sample) {
+    let ready = {
+        let state = &mut zlock!(state);
+        if state.is_duplicate(&s) {
+            log::trace!("Drop duplicated sample on {}", s.key_expr);
+            return;
+        }
+        match (state.reorder_window, s.timestamp) {
+            (Some(_), Some(ts)) => {
+                state.pending.insert(ts, s);
+                state.flush(false)
+            }
+            _ => vec![s],
+        }
+    };
+    deliver(state, callback, ready);
+}
+
+/// A subscriber that drops the duplicated samples and optionally reorders them.
+///
+/// A sample is identified by its [`SourceInfo`](zenoh::sample::SourceInfo) if present,
+/// or by its timestamp otherwise. A sample having the same identity as one of the
+/// latest received samples is considered as a duplicate (e.g. received through several
+/// routers in a mesh) and is dropped. Samples with neither are always delivered.
+///
+/// If a reorder window is configured, the timestamped samples are delivered in their
+/// timestamps order once the window elapsed.
+///
+/// Publications usually carry no source info: timestamping must be enabled
+/// (`timestamping/enabled` in the configuration) for the samples to be deduplicated
+/// and reordered. A warning is logged on declaration if it's disabled on the session.
+pub struct OrderedSubscriber<'a, Receiver> {
+    subscriber: Subscriber<'a, ()>,
+    _stoptx: Option<flume::Sender<()>>,
+    receiver: Receiver,
+}
+
+impl<Receiver> std::ops::Deref for OrderedSubscriber<'_, Receiver> {
+    type Target = Receiver;
+    fn deref(&self) -> &Self::Target {
+        &self.receiver
+    }
+}
+
+impl<Receiver> std::ops::DerefMut for OrderedSubscriber<'_, Receiver> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.receiver
+    }
+}
+
+impl<'a, Receiver> OrderedSubscriber<'a, Receiver> {
+    fn new<Handler>(conf: OrderedSubscriberBuilder<'a, 'a, Handler>) -> ZResult<Self>
+    where
+        Handler: IntoCallbackReceiverPair<'static, Sample, Receiver = Receiver> + Send,
+    {
+        let state = Arc::new(Mutex::new(InnerState::new(
+            conf.history,
+            conf.reorder_window,
+        )));
+        let (callback, receiver) = conf.core.handler.into_cb_receiver_pair();
+
+        let sub_callback = {
+            let state = state.clone();
+            let callback = callback.clone();
+            move |s: Sample| on_sample(&state, &callback, s)
+        };
+
+        let key_expr = conf.core.key_expr?;
+        if conf.core.session.hlc().is_none() {
+            log::warn!(
+                "Timestamping is disabled: the OrderedSubscriber on {} will only deduplicate and \
+                reorder the samples timestamped by their publisher or a router",
+                key_expr
+            );
+        }
+        let subscriber = declare_subscriber(
+            &conf.core.session,
+            &key_expr,
+            conf.core.reliability,
+            conf.core.origin,
+            sub_callback,
+        )?;
+
+        // periodically deliver the samples that waited long enough for reordering
+        let stoptx = conf.reorder_window.map(|window| {
+            let (stoptx, stoprx) = flume::bounded::<()>(1);
+            task::spawn(async move {
+                let period = window.max(Duration::from_millis(1));
+                while async_std::future::timeout(period, stoprx.recv_async())
+                    .await
+                    .is_err()
+                {
+                    let ready = zlock!(state).flush(false);
+                    deliver(&state, &callback, ready);
+                }
+                // When stoptx is dropped, deliver the remaining samples
+                let ready = zlock!(state).flush(true);
+                deliver(&state, &callback, ready);
+            });
+            stoptx
+        });
+
+        Ok(OrderedSubscriber {
+            subscriber,
+            _stoptx: stoptx,
+            receiver,
+        })
+    }
+
+    /// Close this OrderedSubscriber
+    #[inline]
+    pub fn close(self) -> impl Resolve<ZResult<()>> + 'a {
+        self.subscriber.undeclare()
+    }
+
+    pub fn key_expr(&self) -> &KeyExpr<'static> {
+        self.subscriber.key_expr()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryFrom;
+    use std::time::UNIX_EPOCH;
+    use zenoh::time::{TimestampId, NTP64};
+
+    fn sample(value: &str, time: SystemTime) -> Sample {
+        let time = NTP64::from(time.duration_since(UNIX_EPOCH).unwrap());
+        let id = TimestampId::try_from([1]).unwrap();
+        Sample::try_from("test/ordered", value)
+            .unwrap()
+            .with_timestamp(Timestamp::new(time, id))
+    }
+
+    #[test]
+    fn duplicates() {
+        let mut state = InnerState::new(2, None);
+        let now = SystemTime::now();
+        let s1 = sample("1", now);
+        let s2 = sample("2", now + Duration::from_millis(1));
+        let s3 = sample("3", now + Duration::from_millis(2));
+        assert!(!state.is_duplicate(&s1));
+        assert!(!state.is_duplicate(&s2));
+        assert!(state.is_duplicate(&s1));
+        assert!(state.is_duplicate(&s2));
+        // Only the latest `history` samples are remembered
+        assert!(!state.is_duplicate(&s3));
+        assert!(!state.is_duplicate(&s1));
+
+        // Samples without identity are never duplicates
+        let anonymous = Sample::try_from("test/ordered", "4").unwrap();
+        assert!(!state.is_duplicate(&anonymous));
+        assert!(!state.is_duplicate(&anonymous));
+    }
+
+    #[test]
+    fn reorder() {
+        let mut state = InnerState::new(16, Some(Duration::from_secs(3600)));
+        let now = SystemTime::now();
+        for (value, delay) in [("2", 2), ("1", 1), ("3", 3)] {
+            let s = sample(
+                value,
+                now - Duration::from_secs(7200) + Duration::from_secs(delay),
+            );
+            state.pending.insert(s.timestamp.unwrap(), s);
+        }
+        let s = sample("4", now);
+        state.pending.insert(s.timestamp.unwrap(), s);
+
+        // The samples older than the window are delivered in their timestamps order
+        let values = |samples: Vec<Sample>| {
+            samples
+                .into_iter()
+                .map(|s| String::try_from(s.value).unwrap())
+                .collect::<Vec<String>>()
+        };
+        assert_eq!(values(state.flush(false)), vec!["1", "2", "3"]);
+        assert!(state.flush(false).is_empty());
+        assert_eq!(values(state.flush(true)), vec!["4"]);
+        assert!(state.pending.is_empty());
+    }
+
+    #[test]
+    fn concurrent_delivery() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        let state = Arc::new(Mutex::new(InnerState::new(1024, None)));
+        let delivered = Arc::new(Mutex::new(vec![]));
+        let in_callback = Arc::new(AtomicBool::new(false));
+        let callback: Callback<'static, Sample> = {
+            let delivered = delivered.clone();
+            Arc::new(move |s: Sample| {
+                assert!(!in_callback.swap(true, Ordering::SeqCst));
+                std::thread::sleep(Duration::from_micros(100));
+                delivered
+                    .lock()
+                    .unwrap()
+                    .push(String::try_from(s.value).unwrap());
+                in_callback.store(false, Ordering::SeqCst);
+            })
+        };
+
+        // Samples received concurrently are delivered one at a time and in order
+        let now = SystemTime::now();
+        let threads = (0..4)
+            .map(|t| {
+                let state = state.clone();
+                let callback = callback.clone();
+                std::thread::spawn(move || {
+                    for i in 0..100 {
+                        let time = now + Duration::from_millis(t * 1000 + i);
+                        on_sample(&state, &callback, sample(&format!("{}:{}", t, i), time));
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        let delivered = delivered.lock().unwrap();
+        assert_eq!(delivered.len(), 400);
+        for t in 0..4 {
+            let values = delivered
+                .iter()
+                .filter(|value| value.starts_with(&format!("{}:", t)))
+                .cloned()
+                .collect::<Vec<_>>();
+            let expected = (0..100).map(|i| format!("{}:{}", t, i)).collect::<Vec<_>>();
+            assert_eq!(values, expected);
+        }
+    }
+}
@@ -17,27 +17,24 @@ use std::future::Ready;
 use std::mem::swap;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
-use zenoh::handlers::{locked, DefaultHandler};
+use zenoh::handlers::DefaultHandler;
 use zenoh::prelude::r#async::*;
 use zenoh::query::{QueryConsolidation, QueryTarget, ReplyKeyExpr};
-use zenoh::subscriber::{Reliability, Subscriber};
+use zenoh::subscriber::Subscriber;
 use zenoh::time::Timestamp;
 use zenoh::Result as ZResult;
 use zenoh_core::{zlock, AsyncResolve, Resolvable, SyncResolve};
 
 use crate::session_ext::SessionRef;
+use crate::subscriber_builder::{declare_subscriber, SubscriberBuilderCore};
 
 /// The builder of QueryingSubscriber, allowing to configure it.
 pub struct QueryingSubscriberBuilder<'a, 'b, Handler> {
-    session: SessionRef<'a>,
-    key_expr: ZResult<KeyExpr<'b>>,
-    reliability: Reliability,
-    origin: Locality,
+    core: SubscriberBuilderCore<'a, 'b, Handler>,
     query_selector: Option<ZResult<Selector<'b>>>,
     query_target: QueryTarget,
     query_consolidation: QueryConsolidation,
     query_timeout: Duration,
-    handler: Handler,
 }
 
 impl<'a, 'b> QueryingSubscriberBuilder<'a, 'b, DefaultHandler> {
@@ -53,126 +50,26 @@ impl<'a, 'b> QueryingSubscriberBuilder<'a, 'b, DefaultHandler> {
         let query_consolidation = QueryConsolidation::from(zenoh::query::ConsolidationMode::None);
 
         QueryingSubscriberBuilder {
-            session,
-            key_expr,
-            reliability: Reliability::default(),
-            origin: Locality::default(),
+            core: SubscriberBuilderCore::new(session, key_expr),
             query_selector: None,
             query_target,
             query_consolidation,
             query_timeout: Duration::from_secs(10),
-            handler: DefaultHandler,
         }
     }
 
-    /// Add callback to QueryingSubscriber.
-    #[inline]
-    pub fn callback<Callback>(
-        self,
-        callback: Callback,
-    ) -> QueryingSubscriberBuilder<'a, 'b, Callback>
-    where
-        Callback: Fn(Sample) + Send + Sync + 'static,
-    {
-        let QueryingSubscriberBuilder {
-            session,
-            key_expr,
-            reliability,
-            origin,
-            query_selector,
-            query_target,
-            query_consolidation,
-            query_timeout,
-            handler: _,
-        } = self;
-        QueryingSubscriberBuilder {
-            session,
-            key_expr,
-            reliability,
-            origin,
+    subscriber_builder_methods!(
+        handlers QueryingSubscriber,
+        QueryingSubscriberBuilder<'a, 'b>[] {
             query_selector,
             query_target,
             query_consolidation,
-            query_timeout,
-            handler: callback,
+            query_timeout
         }
-    }
-
-    /// Add callback to `QueryingSubscriber`.
-    ///
-    /// Using this guarantees that your callback will never be called concurrently.
-    /// If your callback is also accepted by the [`callback`](QueryingSubscriberBuilder::callback) method, we suggest you use it instead of `callback_mut`
-    #[inline]
-    pub fn callback_mut<CallbackMut>(
-        self,
-        callback: CallbackMut,
-    ) -> QueryingSubscriberBuilder<'a, 'b, impl Fn(Sample) + Send + Sync + 'static>
-    where
-        CallbackMut: FnMut(Sample) + Send + Sync + 'static,
-    {
-        self.callback(locked(callback))
-    }
-
-    /// Make the built QueryingSubscriber a [`QueryingSubscriber`](QueryingSubscriber).
-    #[inline]
-    pub fn with<Handler>(self, handler: Handler) -> QueryingSubscriberBuilder<'a, 'b, Handler>
-    where
-        Handler: zenoh::prelude::IntoCallbackReceiverPair<'static, Sample>,
-    {
-        let QueryingSubscriberBuilder {
-            session,
-            key_expr,
-            reliability,
-            origin,
-            query_selector,
-            query_target,
-            query_consolidation,
-            query_timeout,
-            handler: _,
-        } = self;
-        QueryingSubscriberBuilder {
-            session,
-            key_expr,
-            reliability,
-            origin,
-            query_selector,
-            query_target,
-            query_consolidation,
-            query_timeout,
-            handler,
-        }
-    }
+    );
 }
 impl<'a, 'b, Handler> QueryingSubscriberBuilder<'a, 'b, Handler> {
-    /// Change the subscription reliability.
-    #[inline]
-    pub fn reliability(mut self, reliability: Reliability) -> Self {
-        self.reliability = reliability;
-        self
-    }
-
-    /// Change the subscription reliability to Reliable.
-    #[inline]
-    pub fn reliable(mut self) -> Self {
-        self.reliability = Reliability::Reliable;
-        self
-    }
-
-    /// Change the subscription reliability to BestEffort.
-    #[inline]
-    pub fn best_effort(mut self) -> Self {
-        self.reliability = Reliability::BestEffort;
-        self
-    }
-
-    /// Restrict the matching publications that will be receive by this [`Subscriber`]
-    /// to the ones that have the given [`Locality`](zenoh::prelude::Locality).
-    #[zenoh_core::unstable]
-    #[inline]
-    pub fn allowed_origin(mut self, origin: Locality) -> Self {
-        self.origin = origin;
-        self
-    }
+    subscriber_builder_methods!(options QueryingSubscriber);
 
     /// Change the selector to be used for queries.
     #[inline]
@@ -211,15 +108,11 @@ impl<'a, 'b, Handler> QueryingSubscriberBuilder<'a, 'b, Handler> {
 
     fn with_static_keys(self) -> QueryingSubscriberBuilder<'a, 'static, Handler> {
         QueryingSubscriberBuilder {
-            session: self.session,
-            key_expr: self.key_expr.map(|s| s.into_owned()),
-            reliability: self.reliability,
-            origin: self.origin,
+            core: self.core.with_static_keys(),
             query_selector: self.query_selector.map(|s| s.map(|s| s.into_owned())),
             query_target: self.query_target,
             query_consolidation: self.query_consolidation,
             query_timeout: self.query_timeout,
-            handler: self.handler,
         }
     }
 }
@@ -347,7 +240,7 @@ impl<'a, Receiver> QueryingSubscriber<'a, Receiver> {
             pending_queries: 0,
             merge_queue: MergeQueue::new(),
         }));
-        let (callback, receiver) = conf.handler.into_cb_receiver_pair();
+        let (callback, receiver) = conf.core.handler.into_cb_receiver_pair();
 
         let sub_callback = {
             let state = state.clone();
@@ -366,7 +259,7 @@ impl<'a, Receiver> QueryingSubscriber<'a, Receiver> {
             }
         };
 
-        let key_expr = conf.key_expr?;
+        let key_expr = conf.core.key_expr?;
         let (key_selector, parameters) = match conf.query_selector {
             Some(Ok(s)) => s,
             Some(Err(e)) => return Err(e),
@@ -375,23 +268,16 @@ impl<'a, Receiver> QueryingSubscriber<'a, Receiver> {
         .split();
 
         // declare subscriber at first
-        let subscriber = match conf.session.clone() {
-            SessionRef::Borrow(session) => session
-                .declare_subscriber(&key_expr)
-                .callback(sub_callback)
-                .reliability(conf.reliability)
-                .allowed_origin(conf.origin)
-                .res_sync()?,
-            SessionRef::Shared(session) => session
-                .declare_subscriber(&key_expr)
-                .callback(sub_callback)
-                .reliability(conf.reliability)
-                .allowed_origin(conf.origin)
-                .res_sync()?,
-        };
+        let subscriber = declare_subscriber(
+            &conf.core.session,
+            &key_expr,
+            conf.core.reliability,
+            conf.core.origin,
+            sub_callback,
+        )?;
 
         let mut query_subscriber = QueryingSubscriber {
-            session: conf.session,
+            session: conf.core.session,
             query_key_expr: key_selector,
             query_parameters: parameters.into_owned(),
             query_target: conf.query_target,
@@ -518,3 +404,272 @@ impl Drop for RepliesHandler {
         }
     }
 }
+
+/// The builder of a [`FetchingSubscriber`], allowing to configure it.
+pub struct FetchingSubscriberBuilder<'a, 'b, Handler, Fetch, TryIntoSample>
+where
+    TryIntoSample: TryInto<Sample>,
+    <TryIntoSample as TryInto<Sample>>::Error: Into<zenoh_core::Error>,
+    Fetch: FnOnce(Box<dyn Fn(TryIntoSample) + Send + Sync>) -> ZResult<()>,
+{
+    core: SubscriberBuilderCore<'a, 'b, Handler>,
+    fetch: Fetch,
+    phantom: std::marker::PhantomData<TryIntoSample>,
+}
+
+impl<'a, 'b, Fetch, TryIntoSample>
+    FetchingSubscriberBuilder<'a, 'b, DefaultHandler, Fetch, TryIntoSample>
+where
+    TryIntoSample: TryInto<Sample>,
+    <TryIntoSample as TryInto<Sample>>::Error: Into<zenoh_core::Error>,
+    Fetch: FnOnce(Box<dyn Fn(TryIntoSample) + Send + Sync>) -> ZResult<()>,
+{
+    pub(crate) fn new(
+        session: SessionRef<'a>,
+        key_expr: ZResult<KeyExpr<'b>>,
+        fetch: Fetch,
+    ) -> Self {
+        FetchingSubscriberBuilder {
+            core: SubscriberBuilderCore::new(session, key_expr),
+            fetch,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    subscriber_builder_methods!(
+        handlers FetchingSubscriber,
+        FetchingSubscriberBuilder<'a, 'b>[Fetch, TryIntoSample] { fetch, phantom }
+    );
+}
+
+impl<'a, 'b, Handler, Fetch, TryIntoSample>
+    FetchingSubscriberBuilder<'a, 'b, Handler, Fetch, TryIntoSample>
+where
+    TryIntoSample: TryInto<Sample>,
+    <TryIntoSample as TryInto<Sample>>::Error: Into<zenoh_core::Error>,
+    Fetch: FnOnce(Box<dyn Fn(TryIntoSample) + Send + Sync>) -> ZResult<()>,
+{
+    subscriber_builder_methods!(options FetchingSubscriber);
+
+    fn with_static_keys(
+        self,
+    ) -> FetchingSubscriberBuilder<'a, 'static, Handler, Fetch, TryIntoSample> {
+        FetchingSubscriberBuilder {
+            core: self.core.with_static_keys(),
+            fetch: self.fetch,
+            phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<'a, Handler, Fetch, TryIntoSample> Resolvable
+    for FetchingSubscriberBuilder<'a, '_, Handler, Fetch, TryIntoSample>
+where
+    Handler: IntoCallbackReceiverPair<'static, Sample>,
+    Handler::Receiver: Send,
+    TryIntoSample: TryInto<Sample>,
+    <TryIntoSample as TryInto<Sample>>::Error: Into<zenoh_core::Error>,
+    Fetch: FnOnce(Box<dyn Fn(TryIntoSample) + Send + Sync>) -> ZResult<()>,
+{
+    type To = ZResult<FetchingSubscriber<'a, Handler::Receiver>>;
+}
+
+impl<Handler, Fetch, TryIntoSample> SyncResolve
+    for FetchingSubscriberBuilder<'_, '_, Handler, Fetch, TryIntoSample>
+where
+    Handler: IntoCallbackReceiverPair<'static, Sample> + Send,
+    Handler::Receiver: Send,
+    TryIntoSample: TryInto<Sample> + Send + Sync,
+    <TryIntoSample as TryInto<Sample>>::Error: Into<zenoh_core::Error>,
+    Fetch: FnOnce(Box<dyn Fn(TryIntoSample) + Send + Sync>) -> ZResult<()> + Send + Sync,
+{
+    fn res_sync(self) -> <Self as Resolvable>::To {
+        FetchingSubscriber::new(self.with_static_keys())
+    }
+}
+
+impl<'a, Handler, Fetch, TryIntoSample> AsyncResolve
+    for FetchingSubscriberBuilder<'a, '_, Handler, Fetch, TryIntoSample>
+where
+    Handler: IntoCallbackReceiverPair<'static, Sample> + Send,
+    Handler::Receiver: Send,
+    TryIntoSample: TryInto<Sample> + Send + Sync,
+    <TryIntoSample as TryInto<Sample>>::Error: Into<zenoh_core::Error>,
+    Fetch: FnOnce(Box<dyn Fn(TryIntoSample) + Send + Sync>) -> ZResult<()> + Send + Sync,
+{
+    type Future = Ready<Self::To>;
+
+    fn res_async(self) -> Self::Future {
+        std::future::ready(self.res_sync())
+    }
+}
+
+/// A Subscriber that will run the given user defined `fetch` function at startup.
+///
+/// The user defined `fetch` function should fetch some samples and return them through the callback function
+/// (e.g. with one or multiple calls to [`Session::get`](zenoh::Session::get)).
+/// Those samples will be merged with the received publications, ordered by timestamp
+/// and deduplicated, then made available in the receiver.
+/// Later on, new fetches can be performed again, calling [`FetchingSubscriber::fetch()`](super::FetchingSubscriber::fetch()).
+///
+/// A typical usage of the `FetchingSubscriber` is to retrieve publications that were made in the past,
+/// but stored in some zenoh Storage or [`PublicationCache`](super::PublicationCache).
+pub struct FetchingSubscriber<'a, Receiver> {
+    subscriber: Subscriber<'a, ()>,
+    callback: Arc<dyn Fn(Sample) + Send + Sync + 'static>,
+    state: Arc<Mutex<InnerState>>,
+    receiver: Receiver,
+}
+
+impl<Receiver> std::ops::Deref for FetchingSubscriber<'_, Receiver> {
+    type Target = Receiver;
+    fn deref(&self) -> &Self::Target {
+        &self.receiver
+    }
+}
+
+impl<Receiver> std::ops::DerefMut for FetchingSubscriber<'_, Receiver> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.receiver
+    }
+}
+
+impl<'a, Receiver> FetchingSubscriber<'a, Receiver> {
+    fn new<Handler, Fetch, TryIntoSample>(
+        conf: FetchingSubscriberBuilder<'a, 'a, Handler, Fetch, TryIntoSample>,
+    ) -> ZResult<Self>
+    where
+        Handler: IntoCallbackReceiverPair<'static, Sample, Receiver = Receiver> + Send,
+        TryIntoSample: TryInto<Sample> + Send + Sync,
+        <TryIntoSample as TryInto<Sample>>::Error: Into<zenoh_core::Error>,
+        Fetch: FnOnce(Box<dyn Fn(TryIntoSample) + Send + Sync>) -> ZResult<()> + Send + Sync,
+    {
+        let state = Arc::new(Mutex::new(InnerState {
+            pending_queries: 0,
+            merge_queue: MergeQueue::new(),
+        }));
+        let (callback, receiver) = conf.core.handler.into_cb_receiver_pair();
+
+        let sub_callback = {
+            let state = state.clone();
+            let callback = callback.clone();
+            move |mut s| {
+                let state = &mut zlock!(state);
+                if state.pending_queries == 0 {
+                    callback(s);
+                } else {
+                    log::trace!("Sample received while fetch in progress: push it to merge_queue");
+                    // ensure the sample has a timestamp, thus it will always be sorted into the MergeQueue
+                    // after any timestamped Sample possibly coming from a fetch reply.
+                    s.ensure_timestamp();
+                    state.merge_queue.push(s);
+                }
+            }
+        };
+
+        let key_expr = conf.core.key_expr?;
+
+        // declare subscriber at first
+        let subscriber = declare_subscriber(
+            &conf.core.session,
+            &key_expr,
+            conf.core.reliability,
+            conf.core.origin,
+            sub_callback,
+        )?;
+
+        let fetch_subscriber = FetchingSubscriber {
+            subscriber,
+            callback,
+            state,
+            receiver,
+        };
+
+        // run fetch
+        fetch_subscriber.run_fetch(conf.fetch)?;
+
+        Ok(fetch_subscriber)
+    }
+
+    /// Close this FetchingSubscriber
+    #[inline]
+    pub fn close(self) -> impl Resolve<ZResult<()>> + 'a {
+        self.subscriber.undeclare()
+    }
+
+    /// Return the key expression of this FetchingSubscriber
+    #[inline]
+    pub fn key_expr(&self) -> &KeyExpr<'static> {
+        self.subscriber.key_expr()
+    }
+
+    /// Perform an additional `fetch`.
+    ///
+    /// The provided `fetch` function should fetch some samples and return them through the callback function
+    /// (e.g. with one or multiple calls to [`Session::get`](zenoh::Session::get)).
+    /// Those samples will be merged with the received publications and made available in the receiver.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # async_std::task::block_on(async {
+    /// use zenoh::prelude::r#async::*;
+    /// use zenoh_ext::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap().into_arc();
+    /// let mut subscriber = session
+    ///     .declare_fetching_subscriber("key/expr", |cb| {
+    ///         use zenoh::prelude::sync::SyncResolve;
+    ///         session
+    ///             .get("key/expr")
+    ///             .callback(cb)
+    ///             .res_sync()
+    ///     })
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    ///
+    /// subscriber
+    ///     .fetch(|cb| {
+    ///         use zenoh::prelude::sync::SyncResolve;
+    ///         session
+    ///             .get("key/expr")
+    ///             .callback(cb)
+    ///             .res_sync()
+    ///     })
+    ///     .unwrap();
+    /// # })
+    /// ```
+    #[inline]
+    pub fn fetch<Fetch, TryIntoSample>(&mut self, fetch: Fetch) -> ZResult<()>
+    where
+        TryIntoSample: TryInto<Sample> + Send + Sync,
+        <TryIntoSample as TryInto<Sample>>::Error: Into<zenoh_core::Error>,
+        Fetch: FnOnce(Box<dyn Fn(TryIntoSample) + Send + Sync>) -> ZResult<()>,
+    {
+        self.run_fetch(fetch)
+    }
+
+    fn run_fetch<Fetch, TryIntoSample>(&self, fetch: Fetch) -> ZResult<()>
+    where
+        TryIntoSample: TryInto<Sample> + Send + Sync,
+        <TryIntoSample as TryInto<Sample>>::Error: Into<zenoh_core::Error>,
+        Fetch: FnOnce(Box<dyn Fn(TryIntoSample) + Send + Sync>) -> ZResult<()>,
+    {
+        zlock!(self.state).pending_queries += 1;
+        // pending fetches will be decremented in RepliesHandler drop()
+        let handler = RepliesHandler {
+            state: self.state.clone(),
+            callback: self.callback.clone(),
+        };
+
+        log::debug!("Fetch data for FetchingSubscriber");
+        fetch(Box::new(move |s: TryIntoSample| match s.try_into() {
+            Ok(s) => {
+                let mut state = zlock!(handler.state);
+                log::trace!("Fetched sample received: push it to merge_queue");
+                state.merge_queue.push(s);
+            }
+            Err(e) => log::debug!("Received error fetching data: {}", e.into()),
+        }))
+    }
+}
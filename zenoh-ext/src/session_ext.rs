@@ -11,14 +11,18 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
-use super::{PublicationCacheBuilder, QueryingSubscriberBuilder};
+use super::{
+    FetchingSubscriberBuilder, MemoryStorageBuilder, OrderedSubscriberBuilder,
+    PublicationCacheBuilder, QueryingSubscriberBuilder,
+};
 use std::convert::TryInto;
 use std::fmt;
 use std::ops::Deref;
 use std::sync::Arc;
 use zenoh::handlers::DefaultHandler;
-use zenoh::prelude::KeyExpr;
+use zenoh::prelude::{KeyExpr, Sample};
 use zenoh::Session;
+use zenoh_core::Result as ZResult;
 
 #[derive(Clone)]
 pub(crate) enum SessionRef<'a> {
@@ -51,7 +55,7 @@ pub trait SessionExt {
     /// Create a [QueryingSubscriber](super::QueryingSubscriber) with the given key expression.
     ///
     /// This operation returns a [`QueryingSubscriberBuilder`](QueryingSubscriberBuilder) that can be used to finely configure the subscriber.  
-    /// As soon as built (calling `.wait()` or `.await` on the `QueryingSubscriberBuilder`), the `QueryingSubscriber`
+    /// As soon as built (calling `.res()` or `.await` on the `QueryingSubscriberBuilder`), the `QueryingSubscriber`
     /// will issue a query on a given key expression (by default it uses the same key expression than it subscribes to).
     /// The results of the query will be merged with the received publications and made available in the receiver.
     /// Later on, new queries can be issued again, calling [`QueryingSubscriber::query()`](super::QueryingSubscriber::query()) or
@@ -86,6 +90,55 @@ pub trait SessionExt {
         TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
         <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_core::Error>;
 
+    /// Create a [FetchingSubscriber](super::FetchingSubscriber) with the given key expression.
+    ///
+    /// This operation returns a [`FetchingSubscriberBuilder`](FetchingSubscriberBuilder) that can be used to finely configure the subscriber.
+    /// As soon as built (calling `.res()` or `.await` on the `FetchingSubscriberBuilder`), the `FetchingSubscriber`
+    /// will run the given `fetch` function. The user defined `fetch` function should fetch some samples and return them
+    /// through the callback function. Those samples will be merged with the received publications and made available in the receiver.
+    /// Later on, new fetches can be performed again, calling [`FetchingSubscriber::fetch()`](super::FetchingSubscriber::fetch()).
+    ///
+    /// A typical usage of the `FetchingSubscriber` is to retrieve publications that were made in the past, but stored in some zenoh Storage.
+    ///
+    /// # Arguments
+    /// * `sub_key_expr` - The key expression to subscribe on
+    /// * `fetch` - The function that will fetch the samples to be merged with live publications
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # async_std::task::block_on(async {
+    /// use zenoh::prelude::r#async::*;
+    /// use zenoh_ext::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap().into_arc();
+    /// let subscriber = session
+    ///     .declare_fetching_subscriber("key/expr", |cb| {
+    ///         use zenoh::prelude::sync::SyncResolve;
+    ///         session
+    ///             .get("key/expr")
+    ///             .callback(cb)
+    ///             .res_sync()
+    ///     })
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// while let Ok(sample) = subscriber.recv_async().await {
+    ///     println!("Received : {:?}", sample);
+    /// }
+    /// # })
+    /// ```
+    fn declare_fetching_subscriber<'a, 'b, TryIntoKeyExpr, Fetch, TryIntoSample>(
+        &'a self,
+        sub_key_expr: TryIntoKeyExpr,
+        fetch: Fetch,
+    ) -> FetchingSubscriberBuilder<'a, 'b, DefaultHandler, Fetch, TryIntoSample>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_core::Error>,
+        TryIntoSample: TryInto<Sample>,
+        <TryIntoSample as TryInto<Sample>>::Error: Into<zenoh_core::Error>,
+        Fetch: FnOnce(Box<dyn Fn(TryIntoSample) + Send + Sync>) -> ZResult<()>;
+
     fn declare_publication_cache<'a, 'b, 'c, TryIntoKeyExpr>(
         &'a self,
         pub_key_expr: TryIntoKeyExpr,
@@ -93,6 +146,73 @@ pub trait SessionExt {
     where
         TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
         <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_core::Error>;
+
+    /// Create a [MemoryStorage](super::MemoryStorage) on the given key expression.
+    ///
+    /// The `MemoryStorage` keeps in memory the latest value of each key published on the key expression
+    /// (applying puts and deletes, including wildcard ones, in timestamp order) and answers the queries
+    /// with them, honoring the `_time` selector parameter. It acts as a "last value cache"
+    /// without the need of deploying the storage plugin.
+    ///
+    /// # Arguments
+    /// * `key_expr` - The key expression to store
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # async_std::task::block_on(async {
+    /// use zenoh::prelude::r#async::*;
+    /// use zenoh_ext::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let storage = session.declare_memory_storage("key/expr/**")
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// # })
+    /// ```
+    fn declare_memory_storage<'a, 'b, TryIntoKeyExpr>(
+        &'a self,
+        key_expr: TryIntoKeyExpr,
+    ) -> MemoryStorageBuilder<'a, 'b>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_core::Error>;
+
+    /// Create an [OrderedSubscriber](super::OrderedSubscriber) with the given key expression.
+    ///
+    /// The `OrderedSubscriber` drops the samples already received (e.g. through several routers
+    /// in a mesh), identifying them by their source info or timestamp. Optionally, configuring a
+    /// [`reorder_window`](OrderedSubscriberBuilder::reorder_window) delivers the samples received
+    /// slightly out of order in their timestamps order.
+    ///
+    /// # Arguments
+    /// * `sub_key_expr` - The key expression to subscribe on
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # async_std::task::block_on(async {
+    /// use std::time::Duration;
+    /// use zenoh::prelude::r#async::*;
+    /// use zenoh_ext::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let subscriber = session.declare_ordered_subscriber("key/expr")
+    ///     .reorder_window(Duration::from_millis(100))
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// while let Ok(sample) = subscriber.recv_async().await {
+    ///     println!("Received : {:?}", sample);
+    /// }
+    /// # })
+    /// ```
+    fn declare_ordered_subscriber<'a, 'b, TryIntoKeyExpr>(
+        &'a self,
+        sub_key_expr: TryIntoKeyExpr,
+    ) -> OrderedSubscriberBuilder<'a, 'b, DefaultHandler>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_core::Error>;
 }
 
 impl SessionExt for Session {
@@ -110,6 +230,25 @@ impl SessionExt for Session {
         )
     }
 
+    fn declare_fetching_subscriber<'a, 'b, TryIntoKeyExpr, Fetch, TryIntoSample>(
+        &'a self,
+        sub_key_expr: TryIntoKeyExpr,
+        fetch: Fetch,
+    ) -> FetchingSubscriberBuilder<'a, 'b, DefaultHandler, Fetch, TryIntoSample>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_core::Error>,
+        TryIntoSample: TryInto<Sample>,
+        <TryIntoSample as TryInto<Sample>>::Error: Into<zenoh_core::Error>,
+        Fetch: FnOnce(Box<dyn Fn(TryIntoSample) + Send + Sync>) -> ZResult<()>,
+    {
+        FetchingSubscriberBuilder::new(
+            SessionRef::Borrow(self),
+            sub_key_expr.try_into().map_err(Into::into),
+            fetch,
+        )
+    }
+
     fn declare_publication_cache<'a, 'b, 'c, TryIntoKeyExpr>(
         &'a self,
         pub_key_expr: TryIntoKeyExpr,
@@ -120,6 +259,31 @@ impl SessionExt for Session {
     {
         PublicationCacheBuilder::new(self, pub_key_expr.try_into().map_err(Into::into))
     }
+
+    fn declare_memory_storage<'a, 'b, TryIntoKeyExpr>(
+        &'a self,
+        key_expr: TryIntoKeyExpr,
+    ) -> MemoryStorageBuilder<'a, 'b>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_core::Error>,
+    {
+        MemoryStorageBuilder::new(self, key_expr.try_into().map_err(Into::into))
+    }
+
+    fn declare_ordered_subscriber<'a, 'b, TryIntoKeyExpr>(
+        &'a self,
+        sub_key_expr: TryIntoKeyExpr,
+    ) -> OrderedSubscriberBuilder<'a, 'b, DefaultHandler>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_core::Error>,
+    {
+        OrderedSubscriberBuilder::new(
+            SessionRef::Borrow(self),
+            sub_key_expr.try_into().map_err(Into::into),
+        )
+    }
 }
 
 impl SessionExt for Arc<Session> {
@@ -137,6 +301,25 @@ impl SessionExt for Arc<Session> {
         )
     }
 
+    fn declare_fetching_subscriber<'a, 'b, TryIntoKeyExpr, Fetch, TryIntoSample>(
+        &'a self,
+        sub_key_expr: TryIntoKeyExpr,
+        fetch: Fetch,
+    ) -> FetchingSubscriberBuilder<'a, 'b, DefaultHandler, Fetch, TryIntoSample>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_core::Error>,
+        TryIntoSample: TryInto<Sample>,
+        <TryIntoSample as TryInto<Sample>>::Error: Into<zenoh_core::Error>,
+        Fetch: FnOnce(Box<dyn Fn(TryIntoSample) + Send + Sync>) -> ZResult<()>,
+    {
+        FetchingSubscriberBuilder::new(
+            SessionRef::Shared(self.clone()),
+            sub_key_expr.try_into().map_err(Into::into),
+            fetch,
+        )
+    }
+
     fn declare_publication_cache<'a, 'b, 'c, TryIntoKeyExpr>(
         &'a self,
         pub_key_expr: TryIntoKeyExpr,
@@ -147,4 +330,29 @@ impl SessionExt for Arc<Session> {
     {
         PublicationCacheBuilder::new(self, pub_key_expr.try_into().map_err(Into::into))
     }
+
+    fn declare_memory_storage<'a, 'b, TryIntoKeyExpr>(
+        &'a self,
+        key_expr: TryIntoKeyExpr,
+    ) -> MemoryStorageBuilder<'a, 'b>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_core::Error>,
+    {
+        MemoryStorageBuilder::new(self, key_expr.try_into().map_err(Into::into))
+    }
+
+    fn declare_ordered_subscriber<'a, 'b, TryIntoKeyExpr>(
+        &'a self,
+        sub_key_expr: TryIntoKeyExpr,
+    ) -> OrderedSubscriberBuilder<'a, 'b, DefaultHandler>
+    where
+        TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
+        <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_core::Error>,
+    {
+        OrderedSubscriberBuilder::new(
+            SessionRef::Shared(self.clone()),
+            sub_key_expr.try_into().map_err(Into::into),
+        )
+    }
 }
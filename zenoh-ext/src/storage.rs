@@ -0,0 +1,502 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use async_std::channel::{bounded, Sender};
+use async_std::task;
+use futures::select;
+use futures::{FutureExt, StreamExt};
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::future::Ready;
+use std::time::{Duration, Instant, SystemTime};
+use zenoh::prelude::r#async::*;
+use zenoh::queryable::{Query, Queryable};
+use zenoh::selector::TimeRange;
+use zenoh::subscriber::FlumeSubscriber;
+use zenoh::time::Timestamp;
+use zenoh::Session;
+use zenoh_core::{AsyncResolve, Resolvable, Result as ZResult, SyncResolve};
+use zenoh_util::core::ResolveFuture;
+
+/// The builder of MemoryStorage, allowing to configure it.
+pub struct MemoryStorageBuilder<'a, 'b> {
+    session: &'a Session,
+    key_expr: ZResult<KeyExpr<'b>>,
+    complete: bool,
+    deleted_lifetime: Duration,
+}
+
+impl<'a, 'b> MemoryStorageBuilder<'a, 'b> {
+    pub(crate) fn new(
+        session: &'a Session,
+        key_expr: ZResult<KeyExpr<'b>>,
+    ) -> MemoryStorageBuilder<'a, 'b> {
+        MemoryStorageBuilder {
+            session,
+            key_expr,
+            complete: true,
+            deleted_lifetime: Duration::from_secs(5),
+        }
+    }
+
+    /// Change the completeness of the storage's queryable (`true` by default).
+    ///
+    /// A complete storage holds all the values published on its key expression,
+    /// and is thus sufficient to answer the queries it includes.
+    #[inline]
+    pub fn complete(mut self, complete: bool) -> Self {
+        self.complete = complete;
+        self
+    }
+
+    /// Change how long a deleted key is remembered (5 seconds by default).
+    ///
+    /// During this time, the puts on this key that are older than its deletion
+    /// (e.g. received out of order) are ignored.
+    #[inline]
+    pub fn deleted_lifetime(mut self, lifetime: Duration) -> Self {
+        self.deleted_lifetime = lifetime;
+        self
+    }
+}
+
+impl<'a> Resolvable for MemoryStorageBuilder<'a, '_> {
+    type To = ZResult<MemoryStorage<'a>>;
+}
+
+impl SyncResolve for MemoryStorageBuilder<'_, '_> {
+    fn res_sync(self) -> <Self as Resolvable>::To {
+        MemoryStorage::new(self)
+    }
+}
+
+impl<'a> AsyncResolve for MemoryStorageBuilder<'a, '_> {
+    type Future = Ready<Self::To>;
+
+    fn res_async(self) -> Self::Future {
+        std::future::ready(self.res_sync())
+    }
+}
+
+/// A storage keeping in memory the latest value of each key published on its key expression.
+///
+/// Puts and deletes, including the ones on wildcard key expressions, are applied following
+/// their timestamps: a sample older than the stored value of a key is ignored.
+/// Queries are answered with the latest values of the matching keys, filtered by the
+/// time range of the `_time` parameter of the selector if present.
+pub struct MemoryStorage<'a> {
+    subscriber: FlumeSubscriber<'a>,
+    _queryable: Queryable<'a, flume::Receiver<Query>>,
+    _stoptx: Sender<bool>,
+}
+
+impl<'a> MemoryStorage<'a> {
+    fn new(conf: MemoryStorageBuilder<'a, '_>) -> ZResult<MemoryStorage<'a>> {
+        let key_expr = conf.key_expr?;
+        log::debug!(
+            "Create MemoryStorage on {} with complete={}",
+            &key_expr,
+            conf.complete
+        );
+
+        // declare the subscriber that will receive the updates
+        let subscriber = conf.session.declare_subscriber(&key_expr).res_sync()?;
+
+        // declare the queryable that will answer to queries on the storage
+        let queryable = conf
+            .session
+            .declare_queryable(&key_expr)
+            .complete(conf.complete)
+            .res_sync()?;
+
+        // take local ownership of stuff to be moved into task
+        let sub_recv = subscriber.receiver.clone();
+        let quer_recv = queryable.receiver.clone();
+
+        let deleted_lifetime = conf.deleted_lifetime;
+        let purge_period = deleted_lifetime.max(Duration::from_millis(1));
+        let (stoptx, mut stoprx) = bounded::<bool>(1);
+        task::spawn(async move {
+            let mut store = MemoryStore::new(deleted_lifetime);
+            let mut next_purge = Instant::now() + purge_period;
+
+            loop {
+                let purge_delay = next_purge.saturating_duration_since(Instant::now());
+                select!(
+                    // on update received by the subscriber, apply it
+                    sample = sub_recv.recv_async() => {
+                        if let Ok(sample) = sample {
+                            store.update(sample);
+                        }
+                    },
+
+                    // on query, reply with the matching stored values
+                    query = quer_recv.recv_async() => {
+                        if let Ok(query) = query {
+                            let selector = query.selector();
+                            match selector.time_range() {
+                                Ok(time_range) => {
+                                    let time_range = time_range.map(|time_range| time_range.resolve());
+                                    for sample in store.get(&selector.key_expr, time_range) {
+                                        if let Err(e) = query.reply(Ok(sample.clone())).res_async().await {
+                                            log::warn!("Error replying to query: {}", e);
+                                        }
+                                    }
+                                }
+                                Err(e) => log::warn!("Invalid time range in query {}: {}", selector, e),
+                            }
+                        }
+                    },
+
+                    // periodically forget the expired deleted keys
+                    _ = task::sleep(purge_delay).fuse() => {
+                        let now = Instant::now();
+                        store.purge(now);
+                        next_purge = now + purge_period;
+                    },
+
+                    // When stoptx is dropped, stop the task
+                    _ = stoprx.next().fuse() => {
+                        return
+                    }
+                );
+            }
+        });
+
+        Ok(MemoryStorage {
+            subscriber,
+            _queryable: queryable,
+            _stoptx: stoptx,
+        })
+    }
+
+    /// Close this MemoryStorage
+    #[inline]
+    pub fn close(self) -> impl Resolve<ZResult<()>> + 'a {
+        ResolveFuture::new(async move {
+            let MemoryStorage {
+                _queryable,
+                subscriber,
+                _stoptx,
+            } = self;
+            _queryable.undeclare().res_async().await?;
+            subscriber.undeclare().res_async().await?;
+            drop(_stoptx);
+            Ok(())
+        })
+    }
+
+    pub fn key_expr(&self) -> &KeyExpr<'static> {
+        self.subscriber.key_expr()
+    }
+}
+
+enum StoredEntry {
+    Present(Sample),
+    // The deleted keys are kept with the timestamp of their deletion, so that older
+    // puts received afterwards are ignored, until they expire at the given instant.
+    Deleted(Timestamp, Instant),
+}
+
+impl StoredEntry {
+    fn timestamp(&self) -> &Timestamp {
+        match self {
+            // The stored samples always have a timestamp
+            StoredEntry::Present(sample) => sample.timestamp.as_ref().unwrap(),
+            StoredEntry::Deleted(timestamp, _) => timestamp,
+        }
+    }
+}
+
+/// The outcome of a [`MemoryStore::update`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StoreUpdate {
+    /// The sample is older than the stored state of its key and was ignored.
+    Outdated,
+    /// A put on a key that was not stored.
+    Inserted,
+    /// A put on a stored or deleted key.
+    Replaced,
+    /// A delete on a key.
+    Deleted,
+    /// A put or a delete on a wildcard key expression, applied to the given stored keys.
+    Wildcard(Vec<OwnedKeyExpr>),
+}
+
+/// The latest value of each key, as kept by a [`MemoryStorage`].
+///
+/// The updates are applied following their timestamps. The deletions, including the ones
+/// on wildcard key expressions, are remembered during `deleted_lifetime` so that the older
+/// puts received afterwards on the keys they include are ignored, even on keys not stored yet.
+pub struct MemoryStore {
+    entries: HashMap<OwnedKeyExpr, StoredEntry>,
+    wildcard_deletes: HashMap<OwnedKeyExpr, (Timestamp, Instant)>,
+    deleted_lifetime: Duration,
+}
+
+impl MemoryStore {
+    pub fn new(deleted_lifetime: Duration) -> MemoryStore {
+        MemoryStore {
+            entries: HashMap::new(),
+            wildcard_deletes: HashMap::new(),
+            deleted_lifetime,
+        }
+    }
+
+    /// Apply a sample to the store, adding it a timestamp if it has none.
+    pub fn update(&mut self, mut sample: Sample) -> StoreUpdate {
+        let timestamp = *sample.ensure_timestamp();
+        if sample.key_expr.is_wild() {
+            // A wildcard update applies to all the stored keys it includes
+            let keys: Vec<OwnedKeyExpr> = self
+                .entries
+                .iter()
+                .filter(|(key, entry)| {
+                    sample.key_expr.includes(key) && *entry.timestamp() < timestamp
+                })
+                .map(|(key, _)| key.clone())
+                .collect();
+            for key in keys.iter() {
+                let entry = match sample.kind {
+                    SampleKind::Put => {
+                        let mut sample = sample.clone();
+                        sample.key_expr = key.clone().into();
+                        StoredEntry::Present(sample)
+                    }
+                    SampleKind::Delete => {
+                        StoredEntry::Deleted(timestamp, Instant::now() + self.deleted_lifetime)
+                    }
+                };
+                self.entries.insert(key.clone(), entry);
+            }
+            if sample.kind == SampleKind::Delete {
+                // Remember the wildcard delete for the keys it includes that are not stored yet
+                let expiration = Instant::now() + self.deleted_lifetime;
+                let deleted = self
+                    .wildcard_deletes
+                    .entry(sample.key_expr.into())
+                    .or_insert((timestamp, expiration));
+                if deleted.0 < timestamp {
+                    *deleted = (timestamp, expiration);
+                }
+            }
+            StoreUpdate::Wildcard(keys)
+        } else {
+            let key: OwnedKeyExpr = sample.key_expr.clone().into();
+            let outdated = match self.entries.get(&key) {
+                Some(entry) => *entry.timestamp() >= timestamp,
+                None => self
+                    .wildcard_deletes
+                    .iter()
+                    .any(|(key_expr, (ts, _))| key_expr.includes(&key) && *ts >= timestamp),
+            };
+            if outdated {
+                log::trace!("MemoryStorage: ignore outdated sample on {}", key);
+                return StoreUpdate::Outdated;
+            }
+            let (entry, update) = match sample.kind {
+                SampleKind::Put if self.entries.contains_key(&key) => {
+                    (StoredEntry::Present(sample), StoreUpdate::Replaced)
+                }
+                SampleKind::Put => (StoredEntry::Present(sample), StoreUpdate::Inserted),
+                SampleKind::Delete => (
+                    StoredEntry::Deleted(timestamp, Instant::now() + self.deleted_lifetime),
+                    StoreUpdate::Deleted,
+                ),
+            };
+            self.entries.insert(key, entry);
+            update
+        }
+    }
+
+    /// Forget the deletions that expired at `now`.
+    pub fn purge(&mut self, now: Instant) {
+        self.entries.retain(|_, entry| match entry {
+            StoredEntry::Deleted(_, expiration) => *expiration > now,
+            StoredEntry::Present(_) => true,
+        });
+        self.wildcard_deletes
+            .retain(|_, (_, expiration)| *expiration > now);
+    }
+
+    /// Returns the stored samples intersecting `key_expr`, filtered by `time_range` if any.
+    pub fn get<'a>(
+        &'a self,
+        key_expr: &'a keyexpr,
+        time_range: Option<TimeRange<SystemTime>>,
+    ) -> impl Iterator<Item = &'a Sample> + 'a {
+        self.entries
+            .iter()
+            .filter_map(move |(key, entry)| match entry {
+                StoredEntry::Present(sample) if key_expr.intersects(key) => Some(sample),
+                _ => None,
+            })
+            .filter(move |sample| match &time_range {
+                Some(time_range) => {
+                    time_range.contains(sample.timestamp.unwrap().get_time().to_system_time())
+                }
+                None => true,
+            })
+    }
+
+    /// Returns the stored and deleted keys, with the timestamp of their latest update.
+    pub fn entries(&self) -> impl Iterator<Item = (&OwnedKeyExpr, &Timestamp)> {
+        self.entries
+            .iter()
+            .map(|(key, entry)| (key, entry.timestamp()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryFrom;
+    use zenoh::time::{TimestampId, NTP64};
+
+    fn timestamp(t: u64) -> Timestamp {
+        let time = NTP64::from(Duration::from_secs(t));
+        Timestamp::new(time, TimestampId::try_from([1]).unwrap())
+    }
+
+    fn put(key_expr: &str, value: &str, t: u64) -> Sample {
+        Sample::try_from(key_expr.to_string(), value)
+            .unwrap()
+            .with_timestamp(timestamp(t))
+    }
+
+    fn delete(key_expr: &str, t: u64) -> Sample {
+        let mut sample = put(key_expr, "", t);
+        sample.kind = SampleKind::Delete;
+        sample
+    }
+
+    fn values(store: &MemoryStore, key_expr: &str) -> Vec<(String, String)> {
+        let key_expr = keyexpr::new(key_expr).unwrap();
+        let mut values = store
+            .get(key_expr, None)
+            .map(|s| {
+                (
+                    s.key_expr.to_string(),
+                    String::try_from(s.value.clone()).unwrap(),
+                )
+            })
+            .collect::<Vec<_>>();
+        values.sort();
+        values
+    }
+
+    fn value(key_expr: &str, value: &str) -> (String, String) {
+        (key_expr.to_string(), value.to_string())
+    }
+
+    #[test]
+    fn out_of_order() {
+        let mut store = MemoryStore::new(Duration::from_secs(5));
+        store.update(put("test/a", "2", 2));
+        // An older put is ignored
+        store.update(put("test/a", "1", 1));
+        assert_eq!(values(&store, "test/a"), vec![value("test/a", "2")]);
+
+        // An older delete is ignored
+        store.update(delete("test/a", 1));
+        assert_eq!(values(&store, "test/a"), vec![value("test/a", "2")]);
+
+        // A put older than the deletion of its key is ignored
+        store.update(delete("test/a", 4));
+        store.update(put("test/a", "3", 3));
+        assert!(values(&store, "test/a").is_empty());
+        store.update(put("test/a", "5", 5));
+        assert_eq!(values(&store, "test/a"), vec![value("test/a", "5")]);
+    }
+
+    #[test]
+    fn wildcard() {
+        let mut store = MemoryStore::new(Duration::from_secs(5));
+        store.update(put("test/a", "a1", 1));
+        store.update(put("test/b", "b3", 3));
+        store.update(put("other/c", "c1", 1));
+
+        // A wildcard put only replaces the older included keys
+        store.update(put("test/*", "w2", 2));
+        assert_eq!(
+            values(&store, "**"),
+            vec![
+                value("other/c", "c1"),
+                value("test/a", "w2"),
+                value("test/b", "b3"),
+            ]
+        );
+
+        // A wildcard delete only deletes the older included keys
+        store.update(delete("test/**", 3));
+        assert_eq!(values(&store, "test/*"), vec![value("test/b", "b3")]);
+        store.update(delete("test/**", 4));
+        assert!(values(&store, "test/*").is_empty());
+        assert_eq!(values(&store, "**"), vec![value("other/c", "c1")]);
+
+        // A put older than a wildcard delete including its key is ignored,
+        // even if the key was not stored
+        assert_eq!(store.update(put("test/c", "c3", 3)), StoreUpdate::Outdated);
+        assert_eq!(store.update(put("test/c", "c5", 5)), StoreUpdate::Inserted);
+        assert_eq!(values(&store, "test/*"), vec![value("test/c", "c5")]);
+    }
+
+    #[test]
+    fn wildcard_keys() {
+        let mut store = MemoryStore::new(Duration::from_secs(5));
+        assert_eq!(store.update(put("test/a", "a1", 1)), StoreUpdate::Inserted);
+        assert_eq!(store.update(put("test/b", "b3", 3)), StoreUpdate::Inserted);
+        assert_eq!(store.update(put("test/a", "a2", 2)), StoreUpdate::Replaced);
+
+        // A wildcard update returns the stored keys it was applied to
+        let key = |k: &str| OwnedKeyExpr::try_from(k).unwrap();
+        assert_eq!(
+            store.update(delete("test/*", 3)),
+            StoreUpdate::Wildcard(vec![key("test/a")])
+        );
+        assert_eq!(
+            store.update(delete("other/*", 3)),
+            StoreUpdate::Wildcard(vec![])
+        );
+        assert_eq!(store.update(delete("test/b", 4)), StoreUpdate::Deleted);
+    }
+
+    #[test]
+    fn purge() {
+        let mut store = MemoryStore::new(Duration::from_secs(5));
+        store.update(put("test/a", "a1", 1));
+        store.update(delete("test/a", 2));
+        store.update(put("test/b", "b1", 1));
+
+        // The deleted keys are remembered until they expire
+        store.purge(Instant::now());
+        assert_eq!(store.entries.len(), 2);
+        store.purge(Instant::now() + Duration::from_secs(5));
+        assert_eq!(store.entries.len(), 1);
+        assert_eq!(values(&store, "test/*"), vec![value("test/b", "b1")]);
+
+        // The wildcard deletes are remembered until they expire as well
+        store.update(delete("other/*", 3));
+        store.purge(Instant::now());
+        assert_eq!(store.wildcard_deletes.len(), 1);
+        store.purge(Instant::now() + Duration::from_secs(5));
+        assert!(store.wildcard_deletes.is_empty());
+
+        // Once forgotten, a deleted key accepts older puts again
+        store.update(put("test/a", "a1", 1));
+        assert_eq!(
+            values(&store, "test/*"),
+            vec![value("test/a", "a1"), value("test/b", "b1")]
+        );
+    }
+}
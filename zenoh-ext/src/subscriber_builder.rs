@@ -0,0 +1,186 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use zenoh::handlers::DefaultHandler;
+use zenoh::prelude::r#async::*;
+use zenoh::subscriber::{Reliability, Subscriber};
+use zenoh::Result as ZResult;
+use zenoh_core::SyncResolve;
+
+use crate::session_ext::SessionRef;
+
+/// The options shared by the builders of the subscribers of this crate.
+pub(crate) struct SubscriberBuilderCore<'a, 'b, Handler> {
+    pub(crate) session: SessionRef<'a>,
+    pub(crate) key_expr: ZResult<KeyExpr<'b>>,
+    pub(crate) reliability: Reliability,
+    pub(crate) origin: Locality,
+    pub(crate) handler: Handler,
+}
+
+impl<'a, 'b> SubscriberBuilderCore<'a, 'b, DefaultHandler> {
+    pub(crate) fn new(session: SessionRef<'a>, key_expr: ZResult<KeyExpr<'b>>) -> Self {
+        SubscriberBuilderCore {
+            session,
+            key_expr,
+            reliability: Reliability::default(),
+            origin: Locality::default(),
+            handler: DefaultHandler,
+        }
+    }
+}
+
+impl<'a, 'b, Handler> SubscriberBuilderCore<'a, 'b, Handler> {
+    pub(crate) fn with<H>(self, handler: H) -> SubscriberBuilderCore<'a, 'b, H> {
+        SubscriberBuilderCore {
+            session: self.session,
+            key_expr: self.key_expr,
+            reliability: self.reliability,
+            origin: self.origin,
+            handler,
+        }
+    }
+
+    pub(crate) fn with_static_keys(self) -> SubscriberBuilderCore<'a, 'static, Handler> {
+        SubscriberBuilderCore {
+            session: self.session,
+            key_expr: self.key_expr.map(|s| s.into_owned()),
+            reliability: self.reliability,
+            origin: self.origin,
+            handler: self.handler,
+        }
+    }
+}
+
+/// Declares the subscriber on which the subscribers of this crate receive the live publications.
+pub(crate) fn declare_subscriber<'a, Callback>(
+    session: &SessionRef<'a>,
+    key_expr: &KeyExpr<'_>,
+    reliability: Reliability,
+    origin: Locality,
+    callback: Callback,
+) -> ZResult<Subscriber<'a, ()>>
+where
+    Callback: Fn(Sample) + Send + Sync + 'static,
+{
+    match session.clone() {
+        SessionRef::Borrow(session) => session
+            .declare_subscriber(key_expr)
+            .callback(callback)
+            .reliability(reliability)
+            .allowed_origin(origin)
+            .res_sync(),
+        SessionRef::Shared(session) => session
+            .declare_subscriber(key_expr)
+            .callback(callback)
+            .reliability(reliability)
+            .allowed_origin(origin)
+            .res_sync(),
+    }
+}
+
+/// Implements the methods shared by the subscriber builders of this crate, which hold
+/// a [`SubscriberBuilderCore`] in their `core` field:
+/// - `handlers`: `callback`, `callback_mut` and `with`, given the builder type with its
+///   lifetimes, the generic parameters following the handler and the other fields.
+/// - `options`: `reliability`, `reliable`, `best_effort` and `allowed_origin`.
+macro_rules! subscriber_builder_methods {
+    (handlers $subscriber:ident, $builder:ident<$($lt:lifetime),*>[$($param:ident),*] { $($field:ident),* }) => {
+        #[doc = concat!("Add callback to ", stringify!($subscriber), ".")]
+        #[inline]
+        pub fn callback<Callback>(
+            self,
+            callback: Callback,
+        ) -> $builder<$($lt,)* Callback $(, $param)*>
+        where
+            Callback: Fn(zenoh::prelude::Sample) + Send + Sync + 'static,
+        {
+            self.with(callback)
+        }
+
+        #[doc = concat!("Add callback to `", stringify!($subscriber), "`.")]
+        ///
+        /// Using this guarantees that your callback will never be called concurrently.
+        #[doc = concat!(
+            "If your callback is also accepted by the [`callback`](",
+            stringify!($builder),
+            "::callback) method, we suggest you use it instead of `callback_mut`"
+        )]
+        #[inline]
+        pub fn callback_mut<CallbackMut>(
+            self,
+            callback: CallbackMut,
+        ) -> $builder<$($lt,)* impl Fn(zenoh::prelude::Sample) + Send + Sync + 'static $(, $param)*>
+        where
+            CallbackMut: FnMut(zenoh::prelude::Sample) + Send + Sync + 'static,
+        {
+            self.callback(zenoh::handlers::locked(callback))
+        }
+
+        #[doc = concat!(
+            "Make the built ",
+            stringify!($subscriber),
+            " a [`",
+            stringify!($subscriber),
+            "`](",
+            stringify!($subscriber),
+            ")."
+        )]
+        #[inline]
+        pub fn with<Handler>(self, handler: Handler) -> $builder<$($lt,)* Handler $(, $param)*>
+        where
+            Handler: zenoh::prelude::IntoCallbackReceiverPair<'static, zenoh::prelude::Sample>,
+        {
+            let $builder { core, $($field),* } = self;
+            $builder {
+                core: core.with(handler),
+                $($field),*
+            }
+        }
+    };
+    (options $subscriber:ident) => {
+        /// Change the subscription reliability.
+        #[inline]
+        pub fn reliability(mut self, reliability: zenoh::subscriber::Reliability) -> Self {
+            self.core.reliability = reliability;
+            self
+        }
+
+        /// Change the subscription reliability to Reliable.
+        #[inline]
+        pub fn reliable(mut self) -> Self {
+            self.core.reliability = zenoh::subscriber::Reliability::Reliable;
+            self
+        }
+
+        /// Change the subscription reliability to BestEffort.
+        #[inline]
+        pub fn best_effort(mut self) -> Self {
+            self.core.reliability = zenoh::subscriber::Reliability::BestEffort;
+            self
+        }
+
+        #[doc = concat!(
+            "Restrict the matching publications that will be receive by this [`",
+            stringify!($subscriber),
+            "`]"
+        )]
+        /// to the ones that have the given [`Locality`](zenoh::prelude::Locality).
+        #[zenoh_core::unstable]
+        #[inline]
+        pub fn allowed_origin(mut self, origin: zenoh::prelude::Locality) -> Self {
+            self.core.origin = origin;
+            self
+        }
+    };
+}
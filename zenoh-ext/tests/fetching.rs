@@ -0,0 +1,95 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use async_std::prelude::FutureExt;
+use async_std::task;
+use std::convert::TryFrom;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use zenoh::prelude::r#async::*;
+use zenoh::time::{Timestamp, TimestampId, NTP64};
+use zenoh_core::zasync_executor_init;
+use zenoh_ext::*;
+
+const TIMEOUT: Duration = Duration::from_secs(60);
+
+macro_rules! ztimeout {
+    ($f:expr) => {
+        $f.timeout(TIMEOUT).await.unwrap()
+    };
+}
+
+type FetchCallback = Box<dyn Fn(Sample) + Send + Sync>;
+
+// A fetched sample, timestamped long before the publications
+fn fetched(key_expr: &str, value: &str, t: u64) -> Sample {
+    let time = NTP64::from(Duration::from_secs(t));
+    Sample::try_from(key_expr, value)
+        .unwrap()
+        .with_timestamp(Timestamp::new(time, TimestampId::try_from([1]).unwrap()))
+}
+
+#[test]
+fn fetching_subscriber_merge() {
+    task::block_on(async {
+        zasync_executor_init!();
+        let session = ztimeout!(zenoh::open(zenoh::test::config(&[], &[])).res_async()).unwrap();
+
+        let old1 = fetched("test/fetching/a", "old1", 1);
+        let old2 = fetched("test/fetching/b", "old2", 2);
+
+        // Keep the fetch callback to run the fetch after some publications
+        let pending: Arc<Mutex<Option<FetchCallback>>> = Arc::new(Mutex::new(None));
+        let c_pending = pending.clone();
+        let mut subscriber = ztimeout!(session
+            .declare_fetching_subscriber("test/fetching/**", move |cb| {
+                *c_pending.lock().unwrap() = Some(cb);
+                Ok(())
+            })
+            .res_async())
+        .unwrap();
+
+        ztimeout!(session.put("test/fetching/c", "live1").res_async()).unwrap();
+        assert!(subscriber.try_recv().is_err());
+
+        let cb = pending.lock().unwrap().take().unwrap();
+        cb(old2);
+        cb(old1);
+        assert!(subscriber.try_recv().is_err());
+        // The fetch ends when its callback is dropped
+        drop(cb);
+
+        for value in &["old1", "old2", "live1"] {
+            let sample = ztimeout!(subscriber.recv_async()).unwrap();
+            assert_eq!(sample.value.to_string(), *value);
+        }
+
+        // Once no fetch is pending, publications are delivered immediately
+        ztimeout!(session.put("test/fetching/c", "live2").res_async()).unwrap();
+        let sample = ztimeout!(subscriber.recv_async()).unwrap();
+        assert_eq!(sample.value.to_string(), "live2");
+
+        // A new fetch merges the fetched samples the same way
+        subscriber
+            .fetch(|cb| {
+                cb(fetched("test/fetching/d", "old3", 3));
+                Ok(())
+            })
+            .unwrap();
+        let sample = ztimeout!(subscriber.recv_async()).unwrap();
+        assert_eq!(sample.value.to_string(), "old3");
+
+        ztimeout!(subscriber.close().res_async()).unwrap();
+        ztimeout!(session.close().res_async()).unwrap();
+    });
+}
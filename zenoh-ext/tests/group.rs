@@ -0,0 +1,95 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use async_std::prelude::FutureExt;
+use async_std::task;
+use std::sync::Arc;
+use std::time::Duration;
+use zenoh::prelude::r#async::*;
+use zenoh::Session;
+use zenoh_core::zasync_executor_init;
+use zenoh_ext::group::*;
+
+const TIMEOUT: Duration = Duration::from_secs(60);
+const LEASE: Duration = Duration::from_secs(2);
+
+macro_rules! ztimeout {
+    ($f:expr) => {
+        $f.timeout(TIMEOUT).await.unwrap()
+    };
+}
+
+async fn open_session(listen: &[&str], connect: &[&str]) -> Arc<Session> {
+    let config = zenoh::test::config(listen, connect);
+    ztimeout!(zenoh::open(config).res_async())
+        .unwrap()
+        .into_arc()
+}
+
+async fn join(session: &Arc<Session>, mid: &str) -> Group {
+    let member = Member::new(mid).unwrap().lease(LEASE);
+    Group::join(session.clone(), "test", member).await.unwrap()
+}
+
+async fn wait_for_leader(group: &Group, mid: &str) {
+    ztimeout!(async {
+        while group.leader().await.as_str() != mid {
+            task::sleep(Duration::from_millis(10)).await;
+        }
+    });
+}
+
+#[test]
+fn group_membership() {
+    task::block_on(async {
+        zasync_executor_init!();
+        let s1 = open_session(&["test-group-1"], &[]).await;
+        let s2 = open_session(&["test-group-2"], &["test-group-1"]).await;
+        let s3 = open_session(&[], &["test-group-1", "test-group-2"]).await;
+
+        let g1 = join(&s1, "member1").await;
+        let g2 = join(&s2, "member2").await;
+        assert!(g1.wait_for_view_size(2, TIMEOUT).await);
+        assert!(g2.wait_for_view_size(2, TIMEOUT).await);
+        // The leader is the member with the smallest identifier
+        wait_for_leader(&g1, "member1").await;
+        wait_for_leader(&g2, "member1").await;
+        assert!(g1.is_leader().await);
+        assert!(!g2.is_leader().await);
+
+        // The group keeps working once the events receiver is dropped
+        drop(g1.subscribe().await);
+        let g3 = join(&s3, "member3").await;
+        assert!(g1.wait_for_view_size(3, TIMEOUT).await);
+        assert!(g2.wait_for_view_size(3, TIMEOUT).await);
+        assert!(g3.wait_for_view_size(3, TIMEOUT).await);
+
+        // The remaining members are notified of the leave and elect a new leader
+        let events = g2.subscribe().await;
+        drop(g1);
+        let (mut left, mut new_leader) = (false, false);
+        while !(left && new_leader) {
+            match ztimeout!(events.recv_async()).unwrap() {
+                GroupEvent::Leave(le) => left |= le.mid.as_str() == "member1",
+                GroupEvent::NewLeader(nle) => {
+                    assert_eq!(nle.mid.as_str(), "member2");
+                    new_leader = true;
+                }
+                _ => {}
+            }
+        }
+        assert!(g2.is_leader().await);
+        wait_for_leader(&g3, "member2").await;
+        assert_eq!(g2.size().await, 2);
+    });
+}
@@ -0,0 +1,192 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! End-to-end encryption of the payloads.
+//!
+//! The payloads published on the key expressions having some key material are encrypted
+//! by the publishing session and decrypted by the receiving sessions, the routers
+//! forwarding them as is. The key expression is authenticated along with the payload.
+//!
+//! An encrypted payload starts with a header made of a magic number, the format version
+//! and the identifier of the secret it was encrypted with, so that the sessions that
+//! don't know this secret drop it instead of delivering the ciphertext.
+use crate::config::EncryptionConf;
+use crate::key_expr::{keyexpr, OwnedKeyExpr};
+use crate::SampleKind;
+use rand::SeedableRng;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use zenoh_buffers::{SplitBuffer, ZBuf};
+use zenoh_core::{bail, zerror, zlock, Result as ZResult};
+use zenoh_crypto::{AuthCipher, PseudoRng};
+
+const MAGIC: &[u8; 4] = b"zENC";
+const VERSION: u8 = 1;
+const HEADER_SIZE: usize = MAGIC.len() + 1 + AuthCipher::ID_SIZE;
+
+/// A callback returning the secret used to encrypt the payloads published
+/// on the given key expression, if any.
+pub(crate) type KeyProvider = Arc<dyn Fn(&keyexpr) -> Option<Vec<u8>> + Send + Sync>;
+
+pub(crate) struct PayloadEncryption {
+    keys: Vec<(Vec<OwnedKeyExpr>, Arc<AuthCipher>)>,
+    provider: Option<KeyProvider>,
+    // The ciphers of the secrets returned by the provider
+    provided: Mutex<HashMap<Vec<u8>, Arc<AuthCipher>>>,
+    prng: Mutex<PseudoRng>,
+}
+
+impl PayloadEncryption {
+    /// Returns `None` if neither the configuration nor the `provider` define any key material.
+    pub(crate) fn new(
+        conf: &EncryptionConf,
+        provider: Option<KeyProvider>,
+    ) -> ZResult<Option<PayloadEncryption>> {
+        let mut keys = Vec::with_capacity(conf.keys().len());
+        for key in conf.keys() {
+            let secret = match (&key.secret, &key.secret_file) {
+                (Some(secret), None) => secret.as_bytes().to_vec(),
+                (None, Some(file)) => std::fs::read(file)
+                    .map_err(|e| zerror!("Invalid encryption secret file {}: {}", file, e))?,
+                _ => {
+                    return Err(zerror!(
+                    "Encryption key for {:?} must have exactly one of `secret` or `secret_file`",
+                    key.key_exprs
+                )
+                    .into())
+                }
+            };
+            keys.push((key.key_exprs.clone(), Arc::new(AuthCipher::new(&secret))));
+        }
+        if keys.is_empty() && provider.is_none() {
+            return Ok(None);
+        }
+        Ok(Some(PayloadEncryption {
+            keys,
+            provider,
+            provided: Mutex::new(HashMap::new()),
+            prng: Mutex::new(PseudoRng::from_entropy()),
+        }))
+    }
+
+    fn cipher(&self, key_expr: &keyexpr) -> Option<Arc<AuthCipher>> {
+        if let Some(secret) = self
+            .provider
+            .as_ref()
+            .and_then(|provider| provider(key_expr))
+        {
+            let mut provided = zlock!(self.provided);
+            let cipher = provided
+                .entry(secret)
+                .or_insert_with_key(|secret| Arc::new(AuthCipher::new(secret)));
+            return Some(cipher.clone());
+        }
+        self.keys
+            .iter()
+            .find(|(kes, _)| kes.iter().any(|ke| ke.includes(key_expr)))
+            .map(|(_, cipher)| cipher.clone())
+    }
+
+    /// Returns the encrypted payload, or `None` if no key applies to `key_expr`.
+    pub(crate) fn encrypt(&self, key_expr: &keyexpr, payload: &ZBuf) -> Option<ZResult<ZBuf>> {
+        let cipher = self.cipher(key_expr)?;
+        let encrypted = cipher.encrypt(
+            key_expr.as_str().as_bytes(),
+            &payload.contiguous(),
+            &mut zlock!(self.prng),
+        );
+        Some(encrypted.map(|encrypted| {
+            let mut bytes = Vec::with_capacity(HEADER_SIZE + encrypted.len());
+            bytes.extend_from_slice(MAGIC);
+            bytes.push(VERSION);
+            bytes.extend_from_slice(cipher.id());
+            bytes.extend_from_slice(&encrypted);
+            bytes.into()
+        }))
+    }
+
+    /// Returns the decrypted payload, or `None` if no key applies to `key_expr`.
+    pub(crate) fn decrypt(&self, key_expr: &keyexpr, payload: &ZBuf) -> Option<ZResult<ZBuf>> {
+        let cipher = self.cipher(key_expr)?;
+        Some(decrypt(&cipher, key_expr, &payload.contiguous()))
+    }
+}
+
+// Only reads the first bytes of the payload, without making it contiguous
+fn is_encrypted(payload: &ZBuf) -> bool {
+    payload
+        .slices()
+        .flatten()
+        .take(MAGIC.len())
+        .eq(MAGIC.iter())
+}
+
+fn decrypt(cipher: &AuthCipher, key_expr: &keyexpr, payload: &[u8]) -> ZResult<ZBuf> {
+    if !payload.starts_with(MAGIC) {
+        bail!("Payload is not encrypted");
+    }
+    if payload.len() < HEADER_SIZE {
+        bail!("Invalid encrypted payload length: {}", payload.len());
+    }
+    let (header, encrypted) = payload.split_at(HEADER_SIZE);
+    let version = header[MAGIC.len()];
+    if version != VERSION {
+        bail!("Unsupported encrypted payload version: {}", version);
+    }
+    if &header[MAGIC.len() + 1..] != cipher.id() {
+        bail!("Payload is encrypted with another secret");
+    }
+    cipher
+        .decrypt(key_expr.as_str().as_bytes(), encrypted)
+        .map(ZBuf::from)
+}
+
+/// Returns the payload of a sample of the given `kind` to send to the network,
+/// encrypted if some key applies to `key_expr`.
+pub(crate) fn outgoing_payload(
+    encryption: Option<&PayloadEncryption>,
+    key_expr: &keyexpr,
+    kind: SampleKind,
+    payload: &ZBuf,
+) -> ZResult<ZBuf> {
+    match encryption {
+        Some(encryption) if kind != SampleKind::Delete => encryption
+            .encrypt(key_expr, payload)
+            .unwrap_or_else(|| Ok(payload.clone())),
+        _ => Ok(payload.clone()),
+    }
+}
+
+/// Returns the payload of a sample of the given `kind` received from the network,
+/// decrypted if some key applies to `key_expr`.
+///
+/// Fails if the payload is encrypted but no key applies, or if it's not encrypted
+/// with the key that applies.
+pub(crate) fn incoming_payload(
+    encryption: Option<&PayloadEncryption>,
+    key_expr: &keyexpr,
+    kind: SampleKind,
+    payload: ZBuf,
+) -> ZResult<ZBuf> {
+    if kind == SampleKind::Delete {
+        return Ok(payload);
+    }
+    match encryption.and_then(|encryption| encryption.decrypt(key_expr, &payload)) {
+        Some(decrypted) => decrypted,
+        None if is_encrypted(&payload) => {
+            bail!("No key to decrypt the encrypted payload")
+        }
+        None => Ok(payload),
+    }
+}
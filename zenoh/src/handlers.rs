@@ -80,6 +80,73 @@ impl<T: Send + Sync + 'static> IntoCallbackReceiverPair<'static, T>
     }
 }
 
+/// The policy applied by a [`BoundedQueue`] when receiving data while full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Drop the oldest data of the queue to make room for the received one.
+    DropOldest,
+    /// Drop the received data.
+    DropNewest,
+    /// Block the delivery until room is made in the queue.
+    Block,
+}
+
+/// A handler delivering the received data through a [`flume`] channel of the given capacity,
+/// applying the given [`OverflowPolicy`] when the channel is full.
+#[derive(Debug, Clone, Copy)]
+pub struct BoundedQueue {
+    capacity: usize,
+    policy: OverflowPolicy,
+}
+
+impl BoundedQueue {
+    pub fn new(capacity: usize, policy: OverflowPolicy) -> Self {
+        BoundedQueue { capacity, policy }
+    }
+}
+
+impl<T: Send + 'static> IntoCallbackReceiverPair<'static, T> for BoundedQueue {
+    type Receiver = flume::Receiver<T>;
+    fn into_cb_receiver_pair(self) -> (Callback<'static, T>, Self::Receiver) {
+        let (sender, receiver) = flume::bounded(self.capacity);
+        match self.policy {
+            OverflowPolicy::Block => (sender, receiver).into_cb_receiver_pair(),
+            OverflowPolicy::DropNewest => (
+                Dyn::new(move |t| match sender.try_send(t) {
+                    Ok(()) => (),
+                    Err(flume::TrySendError::Full(_)) => {
+                        log::trace!("Queue full: drop received data")
+                    }
+                    Err(e) => log::error!("{}", e),
+                }),
+                receiver,
+            ),
+            OverflowPolicy::DropOldest => {
+                let queue = receiver.clone();
+                (
+                    Dyn::new(move |mut t| loop {
+                        match sender.try_send(t) {
+                            Ok(()) => break,
+                            Err(flume::TrySendError::Full(back)) => {
+                                log::trace!("Queue full: drop oldest data");
+                                if queue.try_recv().is_err() {
+                                    break;
+                                }
+                                t = back;
+                            }
+                            Err(e) => {
+                                log::error!("{}", e);
+                                break;
+                            }
+                        }
+                    }),
+                    receiver,
+                )
+            }
+        }
+    }
+}
+
 /// A function that can transform a [`FnMut`]`(T)` to
 /// a [`Fn`]`(T)` with the help of a [`Mutex`](std::sync::Mutex).
 pub fn locked<T>(fnmut: impl FnMut(T)) -> impl Fn(T) {
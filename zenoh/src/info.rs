@@ -13,10 +13,17 @@
 //
 
 //! Tools to access information about the current zenoh [`Session`](crate::Session).
+use crate::prelude::KeyExpr;
+#[zenoh_core::unstable]
+use crate::prelude::Locality;
+use crate::publication::{CongestionControl, Priority};
+use crate::scouting::Hello;
+use crate::subscriber::{Reliability, SubMode};
 use crate::SessionRef;
 use std::future::Ready;
 use zenoh_config::{WhatAmI, ZenohId};
 use zenoh_core::{AsyncResolve, Resolvable, SyncResolve};
+pub use zenoh_transport::unicast::establishment::authenticator::AuthId;
 
 /// A builder retuned by [`SessionInfo::zid()`](SessionInfo::zid) that allows
 /// to access the [`ZenohId`] of the current zenoh [`Session`](crate::Session).
@@ -148,6 +155,84 @@ impl<'a> AsyncResolve for PeersZidBuilder<'a> {
     }
 }
 
+/// A builder returned by [`SessionInfo::auth_ids()`](SessionInfo::auth_ids) that allows
+/// to access the identities authenticated for the zenoh nodes this process is currently connected to.
+///
+/// # Examples
+/// ```
+/// # async_std::task::block_on(async {
+/// use zenoh::prelude::r#async::*;
+///
+/// let session = zenoh::open(config::peer()).res().await.unwrap();
+/// let mut auth_ids = session.info().auth_ids().res().await;
+/// while let Some((zid, auth_id)) = auth_ids.next() {}
+/// # })
+/// ```
+pub struct AuthIdsBuilder<'a> {
+    pub(crate) session: SessionRef<'a>,
+}
+
+impl<'a> Resolvable for AuthIdsBuilder<'a> {
+    type To = Box<dyn Iterator<Item = (ZenohId, AuthId)> + Send + Sync>;
+}
+
+impl<'a> SyncResolve for AuthIdsBuilder<'a> {
+    fn res_sync(self) -> <Self as Resolvable>::To {
+        Box::new(
+            self.session
+                .runtime
+                .manager()
+                .get_transports()
+                .into_iter()
+                .filter_map(|s| Some((s.get_zid().ok()?, s.get_auth_ids().ok()?)))
+                .flat_map(|(zid, auth_ids)| auth_ids.into_iter().map(move |id| (zid, id))),
+        )
+    }
+}
+
+impl<'a> AsyncResolve for AuthIdsBuilder<'a> {
+    type Future = Ready<Self::To>;
+
+    fn res_async(self) -> Self::Future {
+        std::future::ready(self.res_sync())
+    }
+}
+
+/// A builder returned by [`SessionInfo::discovered_nodes()`](SessionInfo::discovered_nodes) that allows
+/// to access the zenoh nodes discovered through gossip scouting this process is not connected to.
+///
+/// # Examples
+/// ```
+/// # async_std::task::block_on(async {
+/// use zenoh::prelude::r#async::*;
+///
+/// let session = zenoh::open(config::peer()).res().await.unwrap();
+/// let mut nodes = session.info().discovered_nodes().res().await;
+/// while let Some(hello) = nodes.next() {}
+/// # })
+/// ```
+pub struct DiscoveredNodesBuilder<'a> {
+    pub(crate) session: SessionRef<'a>,
+}
+
+impl<'a> Resolvable for DiscoveredNodesBuilder<'a> {
+    type To = Box<dyn Iterator<Item = Hello> + Send + Sync>;
+}
+
+impl<'a> SyncResolve for DiscoveredNodesBuilder<'a> {
+    fn res_sync(self) -> <Self as Resolvable>::To {
+        Box::new(self.session.runtime.router.discovered_nodes().into_iter())
+    }
+}
+
+impl<'a> AsyncResolve for DiscoveredNodesBuilder<'a> {
+    type Future = Ready<Self::To>;
+
+    fn res_async(self) -> Self::Future {
+        std::future::ready(self.res_sync())
+    }
+}
+
 /// Struct returned by [`Session::info()`](crate::Session::info) which allows
 /// to access informations about the current zenoh [`Session`](crate::Session).
 ///
@@ -219,4 +304,87 @@ impl SessionInfo<'_> {
             session: self.session.clone(),
         }
     }
+
+    /// Return the identities authenticated during the establishment of the transports
+    /// with the zenoh nodes this process is currently connected to,
+    /// along with the [`ZenohId`] of the authenticated nodes.
+    ///
+    /// # Examples
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let mut auth_ids = session.info().auth_ids().res().await;
+    /// while let Some((zid, auth_id)) = auth_ids.next() {}
+    /// # })
+    /// ```
+    pub fn auth_ids(&self) -> AuthIdsBuilder<'_> {
+        AuthIdsBuilder {
+            session: self.session.clone(),
+        }
+    }
+
+    /// Return the zenoh nodes discovered through gossip scouting
+    /// that this process is currently not connected to.
+    ///
+    /// Whether discovered nodes are automatically connected to depends on the
+    /// `scouting/gossip/autoconnect` configuration, that can be changed at runtime
+    /// through [`Session::config()`](crate::Session::config).
+    ///
+    /// # Examples
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let mut nodes = session.info().discovered_nodes().res().await;
+    /// while let Some(hello) = nodes.next() {
+    ///     println!("{}", hello);
+    /// }
+    /// # })
+    /// ```
+    pub fn discovered_nodes(&self) -> DiscoveredNodesBuilder<'_> {
+        DiscoveredNodesBuilder {
+            session: self.session.clone(),
+        }
+    }
+}
+
+/// A subscriber currently declared on a [`Session`](crate::Session).
+#[derive(Debug, Clone)]
+pub struct SubscriberDeclaration {
+    pub key_expr: KeyExpr<'static>,
+    pub reliability: Reliability,
+    pub mode: SubMode,
+    #[cfg(feature = "unstable")]
+    pub origin: Locality,
+}
+
+/// A queryable currently declared on a [`Session`](crate::Session).
+#[derive(Debug, Clone)]
+pub struct QueryableDeclaration {
+    pub key_expr: KeyExpr<'static>,
+    pub complete: bool,
+    #[cfg(feature = "unstable")]
+    pub origin: Locality,
+}
+
+/// A publisher currently declared on a [`Session`](crate::Session).
+#[derive(Debug, Clone)]
+pub struct PublisherDeclaration {
+    pub key_expr: KeyExpr<'static>,
+    pub congestion_control: CongestionControl,
+    pub priority: Priority,
+    #[cfg(feature = "unstable")]
+    pub destination: Locality,
+}
+
+/// The entities currently declared on a [`Session`](crate::Session),
+/// in their declaration order, as returned by [`Session::declarations()`](crate::Session::declarations).
+#[derive(Debug, Clone, Default)]
+pub struct Declarations {
+    pub subscribers: Vec<SubscriberDeclaration>,
+    pub queryables: Vec<QueryableDeclaration>,
+    pub publishers: Vec<PublisherDeclaration>,
 }
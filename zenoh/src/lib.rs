@@ -74,12 +74,27 @@
 //!     }
 //! }
 //! ```
+//!
+//! # Executors
+//! Zenoh runs on the async-std executor. With the `tokio` feature, a session opened from
+//! within a Tokio runtime runs all its tasks on this runtime: the runtime and routing tasks,
+//! the transports, the links and their timers, and hence the callbacks, with the exception
+//! of the links transmission tasks which run on the `transport/link/tx/threads` dedicated
+//! threads. So does a scout started from within a Tokio runtime.
+//! Once the Tokio runtime has been shut down, the tasks move to async-std.
+//!
+//! # OpenTelemetry
+//! With the `opentelemetry` feature, the puts, deletes and queries carry the current
+//! OpenTelemetry context, injected by the global text map propagator, next to their attachment.
+//! The receivers extract it with [`Sample::trace_context`](crate::sample::Sample::trace_context)
+//! and [`Query::trace_context`](crate::queryable::Query::trace_context), so that the traces
+//! follow the samples and the queries across the processes.
 #[macro_use]
 extern crate zenoh_core;
 
 use git_version::git_version;
 use handlers::DefaultHandler;
-#[zenoh_core::unstable]
+use net::routing::interceptor::InterceptorFactory;
 use net::runtime::Runtime;
 use prelude::config::whatami::WhatAmIMatcher;
 use prelude::*;
@@ -96,13 +111,78 @@ pub use zenoh_core::Result;
 const GIT_VERSION: &str = git_version!(prefix = "v", cargo_prefix = "v");
 
 mod admin;
+mod encryption;
 #[macro_use]
 mod session;
 pub use session::*;
+mod telemetry;
 
 pub mod key_expr;
 pub(crate) mod net;
 pub use net::runtime;
+
+/// Interception of the messages exchanged on the transports.
+///
+/// An [`InterceptorFactoryTrait`](interceptor::InterceptorFactoryTrait) registered with
+/// [`OpenBuilder::with_interceptor`] or [`RuntimeBuilder::with_interceptor`](runtime::RuntimeBuilder::with_interceptor)
+/// builds the ingress and egress [`InterceptorTrait`](interceptor::InterceptorTrait)s of each new transport,
+/// which can observe, modify or drop the messages (data, queries, declarations...) flowing through it.
+///
+/// # Examples
+/// ```no_run
+/// # async_std::task::block_on(async {
+/// use zenoh::interceptor::*;
+/// use zenoh::prelude::r#async::*;
+///
+/// struct Audit;
+///
+/// impl InterceptorTrait for Audit {
+///     fn intercept(&self, msg: ZenohMessage, mappings: &KeyExprMappings) -> Option<ZenohMessage> {
+///         if let ZenohBody::Data(data) = &msg.body {
+///             println!("Data on {:?}: {} bytes", mappings.resolve(&data.key), data.payload.len());
+///         }
+///         Some(msg)
+///     }
+/// }
+///
+/// struct AuditFactory;
+///
+/// impl InterceptorFactoryTrait for AuditFactory {
+///     fn new_transport_unicast(
+///         &self,
+///         _transport: &TransportUnicast,
+///     ) -> (Option<Interceptor>, Option<Interceptor>) {
+///         (Some(Box::new(Audit)), Some(Box::new(Audit)))
+///     }
+///     fn new_transport_multicast(&self, _transport: &TransportMulticast) -> Option<Interceptor> {
+///         Some(Box::new(Audit))
+///     }
+///     fn new_peer_multicast(
+///         &self,
+///         _transport: &TransportMulticast,
+///         _peer: &TransportPeer,
+///     ) -> Option<Interceptor> {
+///         Some(Box::new(Audit))
+///     }
+/// }
+///
+/// let session = zenoh::open(config::peer())
+///     .with_interceptor(AuditFactory)
+///     .res()
+///     .await
+///     .unwrap();
+/// # })
+/// ```
+#[zenoh_core::unstable]
+pub mod interceptor {
+    pub use crate::net::routing::interceptor::{
+        Interceptor, InterceptorFactory, InterceptorFactoryTrait, InterceptorTrait, KeyExprMappings,
+    };
+    pub use zenoh_protocol::proto::{
+        Data, DataInfo, Declaration, Declare, Query, ZenohBody, ZenohMessage,
+    };
+    pub use zenoh_transport::{TransportMulticast, TransportPeer, TransportUnicast};
+}
 pub mod selector;
 #[deprecated = "This module is now a separate crate. Use the crate directly for shorter compile-times"]
 pub use zenoh_config as config;
@@ -115,6 +195,8 @@ pub mod query;
 pub mod queryable;
 pub mod sample;
 pub mod subscriber;
+#[cfg(feature = "test")]
+pub mod test;
 pub mod value;
 
 /// A collection of useful buffers used by zenoh internally and exposed to the user to facilitate
@@ -195,6 +277,8 @@ where
     ScoutBuilder {
         what: what.into(),
         config: config.try_into().map_err(|e| e.into()),
+        timeout: None,
+        interface: None,
         handler: DefaultHandler,
     }
 }
@@ -231,7 +315,11 @@ where
     TryIntoConfig: std::convert::TryInto<crate::config::Config> + Send + 'static,
     <TryIntoConfig as std::convert::TryInto<crate::config::Config>>::Error: std::fmt::Debug,
 {
-    OpenBuilder { config }
+    OpenBuilder {
+        config,
+        interceptors: vec![],
+        key_provider: None,
+    }
 }
 
 /// A builder returned by [`open`] used to open a zenoh [`Session`].
@@ -251,6 +339,61 @@ where
     <TryIntoConfig as std::convert::TryInto<crate::config::Config>>::Error: std::fmt::Debug,
 {
     config: TryIntoConfig,
+    interceptors: Vec<InterceptorFactory>,
+    key_provider: Option<encryption::KeyProvider>,
+}
+
+impl<TryIntoConfig> OpenBuilder<TryIntoConfig>
+where
+    TryIntoConfig: std::convert::TryInto<crate::config::Config> + Send + 'static,
+    <TryIntoConfig as std::convert::TryInto<crate::config::Config>>::Error: std::fmt::Debug,
+{
+    /// Add an interceptor of the messages exchanged on the transports of the [`Session`].
+    ///
+    /// See the [`interceptor`](crate::interceptor) module.
+    #[zenoh_core::unstable]
+    #[inline]
+    pub fn with_interceptor<F>(mut self, factory: F) -> Self
+    where
+        F: net::routing::interceptor::InterceptorFactoryTrait + 'static,
+    {
+        self.interceptors.push(Box::new(factory));
+        self
+    }
+
+    /// Encrypt end-to-end the payloads published on the key expressions for which
+    /// the given callback returns a secret, in addition to the keys configured in the
+    /// `encryption` section of the configuration.
+    ///
+    /// The payloads are encrypted by the publishers and decrypted by the subscribers and
+    /// queriers of the sessions knowing the same secret, the routers forwarding them as is.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # async_std::task::block_on(async {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer())
+    ///     .with_encryption(|key_expr| {
+    ///         key_expr
+    ///             .as_str()
+    ///             .starts_with("demo/secret/")
+    ///             .then(|| b"my secret".to_vec())
+    ///     })
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// # })
+    /// ```
+    #[zenoh_core::unstable]
+    #[inline]
+    pub fn with_encryption<F>(mut self, key_provider: F) -> Self
+    where
+        F: Fn(&key_expr::keyexpr) -> Option<Vec<u8>> + Send + Sync + 'static,
+    {
+        self.key_provider = Some(std::sync::Arc::new(key_provider));
+        self
+    }
 }
 
 impl<TryIntoConfig> Resolvable for OpenBuilder<TryIntoConfig>
@@ -271,7 +414,7 @@ where
             .config
             .try_into()
             .map_err(|e| zerror!("Invalid Zenoh configuration {:?}", &e))?;
-        Session::new(config).res_sync()
+        Session::new(config, self.interceptors, self.key_provider).res_sync()
     }
 }
 
@@ -287,10 +430,27 @@ where
     }
 }
 
-/// Initialize a Session with an existing Runtime.
-/// This operation is used by the plugins to share the same Runtime as the router.
-#[doc(hidden)]
-#[zenoh_core::unstable]
+/// Initialize a zenoh [`Session`] on top of an existing [`Runtime`].
+///
+/// The returned Session shares the transports and the routing tables of the given Runtime:
+/// it communicates with the other Sessions and plugins of this Runtime without any network hop.
+/// This is how plugins and applications embedding a router talk to the local infrastructure.
+/// Closing or dropping this Session leaves the Runtime running.
+///
+/// # Arguments
+///
+/// * `runtime` - The [`Runtime`] to initialize the Session on
+///
+/// # Examples
+/// ```no_run
+/// # async_std::task::block_on(async {
+/// use zenoh::prelude::r#async::*;
+/// use zenoh::runtime::Runtime;
+///
+/// let runtime = Runtime::new(config::default()).res().await.unwrap();
+/// let session = zenoh::init(runtime.clone()).res().await.unwrap();
+/// # })
+/// ```
 pub fn init(runtime: Runtime) -> InitBuilder {
     InitBuilder {
         runtime,
@@ -300,22 +460,22 @@ pub fn init(runtime: Runtime) -> InitBuilder {
 }
 
 /// A builder returned by [`init`] and used to initialize a Session with an existing Runtime.
-#[doc(hidden)]
-#[zenoh_core::unstable]
+#[must_use = "Resolvables do nothing unless you resolve them using the `res` method from either `SyncResolve` or `AsyncResolve`"]
 pub struct InitBuilder {
     runtime: Runtime,
     aggregated_subscribers: Vec<OwnedKeyExpr>,
     aggregated_publishers: Vec<OwnedKeyExpr>,
 }
 
-#[zenoh_core::unstable]
 impl InitBuilder {
+    /// Set the key expressions whose subscriptions are aggregated by this Session.
     #[inline]
     pub fn aggregated_subscribers(mut self, exprs: Vec<OwnedKeyExpr>) -> Self {
         self.aggregated_subscribers = exprs;
         self
     }
 
+    /// Set the key expressions whose publications are aggregated by this Session.
     #[inline]
     pub fn aggregated_publishers(mut self, exprs: Vec<OwnedKeyExpr>) -> Self {
         self.aggregated_publishers = exprs;
@@ -323,24 +483,25 @@ impl InitBuilder {
     }
 }
 
-#[zenoh_core::unstable]
 impl Resolvable for InitBuilder {
     type To = ZResult<Session>;
 }
 
-#[zenoh_core::unstable]
 impl SyncResolve for InitBuilder {
     fn res_sync(self) -> <Self as Resolvable>::To {
+        let encryption =
+            encryption::PayloadEncryption::new(self.runtime.config.lock().encryption(), None)?
+                .map(std::sync::Arc::new);
         Ok(Session::init(
             self.runtime,
             self.aggregated_subscribers,
             self.aggregated_publishers,
+            encryption,
         )
         .res_sync())
     }
 }
 
-#[zenoh_core::unstable]
 impl AsyncResolve for InitBuilder {
     type Future = Ready<Self::To>;
 
@@ -30,14 +30,19 @@ pub struct FaceState {
     pub(super) whatami: WhatAmI,
     pub(super) primitives: Arc<dyn Primitives + Send + Sync>,
     pub(super) link_id: usize,
+    // id of the face of the multicast group this face was learned through
+    pub(super) mcast_group: Option<usize>,
+    // whether the remote end acknowledges the data carrying an ack_id
+    pub(super) acks: bool,
     pub(super) local_mappings: HashMap<ZInt, Arc<Resource>>,
     pub(super) remote_mappings: HashMap<ZInt, Arc<Resource>>,
-    pub(super) local_subs: HashSet<Arc<Resource>>,
+    pub(super) local_subs: HashMap<Arc<Resource>, SubInfo>,
     pub(super) remote_subs: HashSet<Arc<Resource>>,
     pub(super) local_qabls: HashMap<Arc<Resource>, QueryableInfo>,
     pub(super) remote_qabls: HashSet<Arc<Resource>>,
     pub(super) next_qid: ZInt,
     pub(super) pending_queries: HashMap<ZInt, Arc<Query>>,
+    pub(super) pending_acks: HashMap<ZInt, Arc<PendingAck>>,
 }
 
 impl FaceState {
@@ -47,6 +52,8 @@ impl FaceState {
         whatami: WhatAmI,
         primitives: Arc<dyn Primitives + Send + Sync>,
         link_id: usize,
+        mcast_group: Option<usize>,
+        acks: bool,
     ) -> Arc<FaceState> {
         Arc::new(FaceState {
             id,
@@ -54,14 +61,17 @@ impl FaceState {
             whatami,
             primitives,
             link_id,
+            mcast_group,
+            acks,
             local_mappings: HashMap::new(),
             remote_mappings: HashMap::new(),
-            local_subs: HashSet::new(),
+            local_subs: HashMap::new(),
             remote_subs: HashSet::new(),
             local_qabls: HashMap::new(),
             remote_qabls: HashSet::new(),
             next_qid: 0,
             pending_queries: HashMap::new(),
+            pending_acks: HashMap::new(),
         })
     }
 
@@ -337,19 +347,33 @@ impl Primitives for Face {
         payload: ZBuf,
         channel: Channel,
         congestion_control: CongestionControl,
-        data_info: Option<DataInfo>,
+        mut data_info: Option<DataInfo>,
         routing_context: Option<RoutingContext>,
     ) {
-        full_reentrant_route_data(
-            &self.tables,
-            &self.state,
-            key_expr,
-            channel,
-            congestion_control,
-            data_info,
-            payload,
-            routing_context,
-        );
+        match data_info.as_mut().and_then(|info| info.ack_id.take()) {
+            // The acknowledgments can not be sent back over a multicast group
+            Some(ack_id) if self.state.mcast_group.is_none() => full_reentrant_route_acked_data(
+                &self.tables,
+                &self.state,
+                key_expr,
+                channel,
+                congestion_control,
+                data_info,
+                payload,
+                routing_context,
+                ack_id,
+            ),
+            _ => full_reentrant_route_data(
+                &self.tables,
+                &self.state,
+                key_expr,
+                channel,
+                congestion_control,
+                data_info,
+                payload,
+                routing_context,
+            ),
+        }
     }
 
     fn send_query(
@@ -362,6 +386,16 @@ impl Primitives for Face {
         body: Option<QueryBody>,
         routing_context: Option<RoutingContext>,
     ) {
+        // The replies can't be routed back to a multicast peer
+        if self.state.mcast_group.is_some() {
+            log::debug!(
+                "Drop query {} on {} received from multicast peer {}",
+                qid,
+                key_expr,
+                self.state
+            );
+            return;
+        }
         route_query(
             &self.tables,
             &self.state,
@@ -405,6 +439,15 @@ impl Primitives for Face {
         pull_id: ZInt,
         max_samples: &Option<ZInt>,
     ) {
+        // The pulled data can't be routed back to a multicast peer
+        if self.state.mcast_group.is_some() {
+            log::debug!(
+                "Drop pull on {} received from multicast peer {}",
+                key_expr,
+                self.state
+            );
+            return;
+        }
         pull_data(
             &self.tables,
             &self.state.clone(),
@@ -0,0 +1,271 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use super::{
+    link_on_interfaces, Interceptor, InterceptorFactory, InterceptorFactoryTrait, InterceptorTrait,
+    KeyExprMappings,
+};
+use std::sync::Arc;
+use zenoh_config::{AclAction, AclConfig, AclConfigRule, InterceptorFlow, Permission};
+use zenoh_core::Result as ZResult;
+use zenoh_link::Link;
+use zenoh_protocol::proto::{Declaration, ZenohBody, ZenohMessage};
+use zenoh_protocol_core::key_expr::keyexpr;
+use zenoh_protocol_core::{WireExpr, ZenohId};
+use zenoh_transport::unicast::establishment::authenticator::AuthId;
+use zenoh_transport::{TransportMulticast, TransportPeer, TransportUnicast};
+
+pub(super) fn acl_interceptor_factories(config: &AclConfig) -> ZResult<Vec<InterceptorFactory>> {
+    let mut res: Vec<InterceptorFactory> = vec![];
+    if *config.enabled() {
+        res.push(Box::new(AclInterceptorFactory {
+            default_permission: *config.default_permission(),
+            rules: config.rules().iter().cloned().map(Arc::new).collect(),
+        }));
+    }
+    Ok(res)
+}
+
+struct AclInterceptorFactory {
+    default_permission: Permission,
+    rules: Vec<Arc<AclConfigRule>>,
+}
+
+impl AclInterceptorFactory {
+    fn new_interceptor(
+        &self,
+        zid: Option<ZenohId>,
+        links: &[Link],
+        auth_ids: &[AuthId],
+        flow: InterceptorFlow,
+    ) -> Option<Interceptor> {
+        let rules: Vec<Arc<AclConfigRule>> = self
+            .rules
+            .iter()
+            .filter(|rule| applies_to(rule, zid, links, auth_ids, flow))
+            .cloned()
+            .collect();
+        if rules.is_empty() && self.default_permission == Permission::Allow {
+            None
+        } else {
+            Some(Box::new(AclInterceptor {
+                default_permission: self.default_permission,
+                rules,
+            }))
+        }
+    }
+}
+
+/// Returns true if the given rule applies to the messages of the given flow
+/// exchanged with the peer of the given id and identities over the given links.
+fn applies_to(
+    rule: &AclConfigRule,
+    zid: Option<ZenohId>,
+    links: &[Link],
+    auth_ids: &[AuthId],
+    flow: InterceptorFlow,
+) -> bool {
+    if let Some(flows) = &rule.flows {
+        if !flows.contains(&flow) {
+            return false;
+        }
+    }
+    if let Some(zids) = &rule.zids {
+        match zid {
+            Some(zid) if zids.contains(&zid) => (),
+            _ => return false,
+        }
+    }
+    if let Some(interfaces) = &rule.interfaces {
+        if !links
+            .iter()
+            .any(|link| link_on_interfaces(link, interfaces))
+        {
+            return false;
+        }
+    }
+    if let Some(names) = &rule.cert_common_names {
+        if !links.iter().any(|link| {
+            link.cert_common_name
+                .as_ref()
+                .map(|cn| names.contains(cn))
+                .unwrap_or(false)
+        }) {
+            return false;
+        }
+    }
+    if let Some(usernames) = &rule.usernames {
+        if !auth_ids.iter().any(|id| match id {
+            AuthId::Username(user) => usernames.contains(user),
+            _ => false,
+        }) {
+            return false;
+        }
+    }
+    if let Some(keys) = &rule.public_keys {
+        if !auth_ids.iter().any(|id| match id {
+            AuthId::PublicKey(key) => keys.iter().any(|k| k.trim() == key.trim()),
+            _ => false,
+        }) {
+            return false;
+        }
+    }
+    true
+}
+
+impl InterceptorFactoryTrait for AclInterceptorFactory {
+    fn new_transport_unicast(
+        &self,
+        transport: &TransportUnicast,
+    ) -> (Option<Interceptor>, Option<Interceptor>) {
+        match (
+            transport.get_zid(),
+            transport.get_links(),
+            transport.get_auth_ids(),
+        ) {
+            (Ok(zid), Ok(links), Ok(auth_ids)) => (
+                self.new_interceptor(Some(zid), &links, &auth_ids, InterceptorFlow::Ingress),
+                self.new_interceptor(Some(zid), &links, &auth_ids, InterceptorFlow::Egress),
+            ),
+            _ => {
+                log::error!("Access control: unable to retrieve the transport identity");
+                (
+                    Some(Box::new(AclInterceptor::deny_all())),
+                    Some(Box::new(AclInterceptor::deny_all())),
+                )
+            }
+        }
+    }
+
+    fn new_transport_multicast(&self, transport: &TransportMulticast) -> Option<Interceptor> {
+        match transport.get_link() {
+            Ok(link) => self.new_interceptor(None, &[link], &[], InterceptorFlow::Egress),
+            Err(_) => Some(Box::new(AclInterceptor::deny_all())),
+        }
+    }
+
+    fn new_peer_multicast(
+        &self,
+        transport: &TransportMulticast,
+        peer: &TransportPeer,
+    ) -> Option<Interceptor> {
+        match transport.get_link() {
+            Ok(link) => {
+                self.new_interceptor(Some(peer.zid), &[link], &[], InterceptorFlow::Ingress)
+            }
+            Err(_) => Some(Box::new(AclInterceptor::deny_all())),
+        }
+    }
+}
+
+struct AclInterceptor {
+    default_permission: Permission,
+    rules: Vec<Arc<AclConfigRule>>,
+}
+
+impl AclInterceptor {
+    fn deny_all() -> Self {
+        AclInterceptor {
+            default_permission: Permission::Deny,
+            rules: vec![],
+        }
+    }
+
+    fn permission(&self, action: AclAction, key_expr: &keyexpr) -> Permission {
+        let mut allowed = false;
+        for rule in self
+            .rules
+            .iter()
+            .filter(|rule| rule.actions.contains(&action))
+        {
+            match rule.permission {
+                // Deny rules take precedence over allow rules
+                Permission::Deny => {
+                    if rule.key_exprs.iter().any(|ke| ke.intersects(key_expr)) {
+                        return Permission::Deny;
+                    }
+                }
+                Permission::Allow => {
+                    if rule.key_exprs.iter().any(|ke| ke.includes(key_expr)) {
+                        allowed = true;
+                    }
+                }
+            }
+        }
+        if allowed {
+            Permission::Allow
+        } else {
+            self.default_permission
+        }
+    }
+
+    fn is_allowed(&self, action: AclAction, key: &WireExpr, mappings: &KeyExprMappings) -> bool {
+        let permission = match mappings.resolve(key) {
+            Some(key_expr) => self.permission(action, &key_expr),
+            None => self.default_permission,
+        };
+        if permission == Permission::Deny {
+            log::debug!("Access control: {:?} on {} denied", action, key);
+            false
+        } else {
+            true
+        }
+    }
+}
+
+impl InterceptorTrait for AclInterceptor {
+    fn intercept(&self, msg: ZenohMessage, mappings: &KeyExprMappings) -> Option<ZenohMessage> {
+        let mut msg = msg;
+        match &mut msg.body {
+            ZenohBody::Data(data) => {
+                // The replies carry the data retrieved by a get
+                let action = match data.reply_context {
+                    Some(_) => AclAction::Get,
+                    None => AclAction::Put,
+                };
+                if !self.is_allowed(action, &data.key, mappings) {
+                    return None;
+                }
+            }
+            ZenohBody::Query(query) => {
+                if !self.is_allowed(AclAction::Get, &query.key, mappings) {
+                    return None;
+                }
+            }
+            ZenohBody::Pull(pull) => {
+                // The pulls retrieve the data of a pull subscriber
+                if !self.is_allowed(AclAction::DeclareSubscriber, &pull.key, mappings) {
+                    return None;
+                }
+            }
+            ZenohBody::Declare(declare) => {
+                declare
+                    .declarations
+                    .retain(|declaration| match declaration {
+                        Declaration::Subscriber(sub) => {
+                            self.is_allowed(AclAction::DeclareSubscriber, &sub.key, mappings)
+                        }
+                        Declaration::Queryable(qabl) => {
+                            self.is_allowed(AclAction::DeclareQueryable, &qabl.key, mappings)
+                        }
+                        _ => true,
+                    });
+                if declare.declarations.is_empty() {
+                    return None;
+                }
+            }
+            _ => (),
+        }
+        Some(msg)
+    }
+}
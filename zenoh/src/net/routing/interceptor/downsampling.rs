@@ -0,0 +1,196 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use super::{
+    link_on_interfaces, Interceptor, InterceptorFactory, InterceptorFactoryTrait, InterceptorTrait,
+    KeyExprMappings,
+};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use zenoh_config::{DownsamplingItemConf, InterceptorFlow};
+use zenoh_core::{bail, Result as ZResult};
+use zenoh_link::Link;
+use zenoh_protocol::proto::{ZenohBody, ZenohMessage};
+use zenoh_protocol_core::key_expr::OwnedKeyExpr;
+use zenoh_transport::{TransportMulticast, TransportPeer, TransportUnicast};
+
+pub(super) fn downsampling_interceptor_factories(
+    config: &[DownsamplingItemConf],
+) -> ZResult<Vec<InterceptorFactory>> {
+    let mut res: Vec<InterceptorFactory> = vec![];
+    for item in config {
+        for rule in &item.rules {
+            if !(rule.freq > 0.0 && rule.freq.is_finite()) {
+                bail!(
+                    "Invalid downsampling frequency {} for {}: it must be strictly positive",
+                    rule.freq,
+                    rule.key_expr
+                );
+            }
+        }
+        res.push(Box::new(DownsamplingInterceptorFactory {
+            interfaces: item.interfaces.clone(),
+            flow: item.flow,
+            rules: item
+                .rules
+                .iter()
+                .map(|rule| {
+                    (
+                        rule.key_expr.clone(),
+                        Duration::from_secs_f64(1.0 / rule.freq),
+                    )
+                })
+                .collect(),
+        }));
+    }
+    Ok(res)
+}
+
+struct DownsamplingInterceptorFactory {
+    interfaces: Option<Vec<String>>,
+    flow: InterceptorFlow,
+    rules: Vec<(OwnedKeyExpr, Duration)>,
+}
+
+impl DownsamplingInterceptorFactory {
+    fn applies_to(&self, links: &[Link]) -> bool {
+        match &self.interfaces {
+            Some(interfaces) => links
+                .iter()
+                .any(|link| link_on_interfaces(link, interfaces)),
+            None => true,
+        }
+    }
+
+    fn new_interceptor(&self) -> Interceptor {
+        Box::new(DownsamplingInterceptor {
+            rules: self
+                .rules
+                .iter()
+                .map(|(key_expr, threshold)| DownsamplingRule {
+                    key_expr: key_expr.clone(),
+                    threshold: *threshold,
+                    state: Mutex::new(DownsamplingState {
+                        latest: HashMap::new(),
+                        swept: Instant::now(),
+                    }),
+                })
+                .collect(),
+        })
+    }
+}
+
+impl InterceptorFactoryTrait for DownsamplingInterceptorFactory {
+    fn new_transport_unicast(
+        &self,
+        transport: &TransportUnicast,
+    ) -> (Option<Interceptor>, Option<Interceptor>) {
+        match transport.get_links() {
+            Ok(links) if self.applies_to(&links) => match self.flow {
+                InterceptorFlow::Ingress => (Some(self.new_interceptor()), None),
+                InterceptorFlow::Egress => (None, Some(self.new_interceptor())),
+            },
+            _ => (None, None),
+        }
+    }
+
+    fn new_transport_multicast(&self, transport: &TransportMulticast) -> Option<Interceptor> {
+        match transport.get_link() {
+            Ok(link) if self.flow == InterceptorFlow::Egress && self.applies_to(&[link]) => {
+                Some(self.new_interceptor())
+            }
+            _ => None,
+        }
+    }
+
+    fn new_peer_multicast(
+        &self,
+        transport: &TransportMulticast,
+        _peer: &TransportPeer,
+    ) -> Option<Interceptor> {
+        match transport.get_link() {
+            Ok(link) if self.flow == InterceptorFlow::Ingress && self.applies_to(&[link]) => {
+                Some(self.new_interceptor())
+            }
+            _ => None,
+        }
+    }
+}
+
+struct DownsamplingRule {
+    key_expr: OwnedKeyExpr,
+    threshold: Duration,
+    state: Mutex<DownsamplingState>,
+}
+
+struct DownsamplingState {
+    // The time of the latest message forwarded on each key matching the rule
+    latest: HashMap<OwnedKeyExpr, Instant>,
+    // The time of the latest eviction of the expired entries of `latest`
+    swept: Instant,
+}
+
+impl DownsamplingState {
+    // Returns whether a message received now on the given key must be forwarded.
+    // An entry older than the threshold no longer drops anything, so the expired entries
+    // are evicted at most once per threshold: the map only holds the keys forwarded
+    // during the last two thresholds, however many keys match the rule over time.
+    fn forward(&mut self, key_expr: &OwnedKeyExpr, now: Instant, threshold: Duration) -> bool {
+        if now.duration_since(self.swept) >= threshold {
+            self.latest
+                .retain(|_, latest| now.duration_since(*latest) < threshold);
+            self.swept = now;
+        }
+        match self.latest.get_mut(key_expr) {
+            Some(latest) if now.duration_since(*latest) < threshold => false,
+            Some(latest) => {
+                *latest = now;
+                true
+            }
+            None => {
+                self.latest.insert(key_expr.clone(), now);
+                true
+            }
+        }
+    }
+}
+
+struct DownsamplingInterceptor {
+    rules: Vec<DownsamplingRule>,
+}
+
+impl InterceptorTrait for DownsamplingInterceptor {
+    fn intercept(&self, msg: ZenohMessage, mappings: &KeyExprMappings) -> Option<ZenohMessage> {
+        if let ZenohBody::Data(data) = &msg.body {
+            // Only publications are downsampled, not query replies
+            if data.reply_context.is_none() {
+                if let Some(key_expr) = mappings.resolve(&data.key) {
+                    // The first matching rule applies
+                    if let Some(rule) = self
+                        .rules
+                        .iter()
+                        .find(|rule| rule.key_expr.includes(&key_expr))
+                    {
+                        let mut state = zlock!(rule.state);
+                        if !state.forward(&key_expr, Instant::now(), rule.threshold) {
+                            log::trace!("Downsampling: drop data on {}", key_expr);
+                            return None;
+                        }
+                    }
+                }
+            }
+        }
+        Some(msg)
+    }
+}
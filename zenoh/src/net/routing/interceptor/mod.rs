@@ -0,0 +1,210 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! ⚠️ WARNING ⚠️
+//!
+//! This module is intended for Zenoh's internal use.
+//!
+//! [Click here for Zenoh's documentation](../zenoh/index.html)
+mod access_control;
+mod downsampling;
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::net::SocketAddr;
+use std::sync::{Arc, RwLock};
+use zenoh_config::Config;
+use zenoh_core::Result as ZResult;
+use zenoh_link::Link;
+use zenoh_protocol::proto::{Declaration, ZenohBody, ZenohMessage};
+use zenoh_protocol_core::key_expr::OwnedKeyExpr;
+use zenoh_protocol_core::{WireExpr, ZInt};
+use zenoh_transport::{MessageInterceptor, TransportMulticast, TransportPeer, TransportUnicast};
+
+/// An interceptor of the messages flowing in one direction of a transport.
+pub trait InterceptorTrait: Send + Sync {
+    /// Returns the message to forward, or `None` if the message must be dropped.
+    /// The `mappings` allow to resolve the key expressions of the message.
+    fn intercept(&self, msg: ZenohMessage, mappings: &KeyExprMappings) -> Option<ZenohMessage>;
+}
+
+pub type Interceptor = Box<dyn InterceptorTrait>;
+
+/// A factory building the [`Interceptor`]s of each new transport.
+pub trait InterceptorFactoryTrait: Send + Sync {
+    /// Returns the (ingress, egress) interceptors of a new unicast transport.
+    fn new_transport_unicast(
+        &self,
+        transport: &TransportUnicast,
+    ) -> (Option<Interceptor>, Option<Interceptor>);
+    /// Returns the egress interceptor of a new multicast transport.
+    fn new_transport_multicast(&self, transport: &TransportMulticast) -> Option<Interceptor>;
+    /// Returns the ingress interceptor of a new peer of a multicast transport.
+    fn new_peer_multicast(
+        &self,
+        transport: &TransportMulticast,
+        peer: &TransportPeer,
+    ) -> Option<Interceptor>;
+}
+
+pub type InterceptorFactory = Box<dyn InterceptorFactoryTrait>;
+
+pub fn interceptor_factories(config: &Config) -> ZResult<Vec<InterceptorFactory>> {
+    let mut res: Vec<InterceptorFactory> = vec![];
+    res.extend(access_control::acl_interceptor_factories(
+        config.access_control(),
+    )?);
+    res.extend(downsampling::downsampling_interceptor_factories(
+        config.downsampling(),
+    )?);
+    Ok(res)
+}
+
+/// Returns true if the source address of the given link belongs to one of the given interfaces.
+pub(crate) fn link_on_interfaces(link: &Link, interfaces: &[String]) -> bool {
+    match link.src.address().parse::<SocketAddr>() {
+        Ok(addr) => interfaces.iter().any(|iface| {
+            zenoh_util::net::get_unicast_addresses_of_interface(iface)
+                .map(|addrs| addrs.contains(&addr.ip()))
+                .unwrap_or(false)
+        }),
+        Err(_) => false,
+    }
+}
+
+/// The key expressions declared in one direction of a transport, indexed by expression id.
+#[derive(Default)]
+pub struct KeyExprMappings {
+    mappings: RwLock<HashMap<ZInt, String>>,
+}
+
+impl KeyExprMappings {
+    fn update(&self, msg: &ZenohMessage) {
+        if let ZenohBody::Declare(declare) = &msg.body {
+            for declaration in &declare.declarations {
+                match declaration {
+                    Declaration::Resource(resource) => {
+                        if let Some(key) = self.resolve_str(&resource.key) {
+                            zwrite!(self.mappings).insert(resource.expr_id, key);
+                        }
+                    }
+                    Declaration::ForgetResource(resource) => {
+                        zwrite!(self.mappings).remove(&resource.expr_id);
+                    }
+                    _ => (),
+                }
+            }
+        }
+    }
+
+    fn resolve_str(&self, key: &WireExpr) -> Option<String> {
+        if key.scope == 0 {
+            Some(key.suffix.to_string())
+        } else {
+            zread!(self.mappings)
+                .get(&key.scope)
+                .map(|prefix| [prefix.as_str(), key.suffix.as_ref()].concat())
+        }
+    }
+
+    /// Resolves the given [`WireExpr`] into a key expression,
+    /// returning `None` if its scope is unknown.
+    pub fn resolve(&self, key: &WireExpr) -> Option<OwnedKeyExpr> {
+        self.resolve_str(key)
+            .and_then(|key| OwnedKeyExpr::try_from(key).ok())
+    }
+}
+
+/// A chain of [`Interceptor`]s applied one after the other to the messages of a transport.
+#[derive(Default)]
+pub(crate) struct InterceptorsChain {
+    interceptors: RwLock<Vec<Interceptor>>,
+    mappings: KeyExprMappings,
+}
+
+impl InterceptorsChain {
+    pub(crate) fn new(interceptors: Vec<Interceptor>) -> Option<Arc<dyn MessageInterceptor>> {
+        if interceptors.is_empty() {
+            None
+        } else {
+            Some(Arc::new(InterceptorsChain {
+                interceptors: RwLock::new(interceptors),
+                mappings: KeyExprMappings::default(),
+            }))
+        }
+    }
+
+    /// Replaces the interceptors of the chain, keeping the key expressions declared so far.
+    fn set(&self, interceptors: Vec<Interceptor>) {
+        *zwrite!(self.interceptors) = interceptors;
+    }
+}
+
+impl MessageInterceptor for InterceptorsChain {
+    fn intercept(&self, msg: ZenohMessage) -> Option<ZenohMessage> {
+        self.mappings.update(&msg);
+        let mut msg = msg;
+        for interceptor in zread!(self.interceptors).iter() {
+            msg = interceptor.intercept(msg, &self.mappings)?;
+        }
+        Some(msg)
+    }
+}
+
+/// The ingress and egress [`InterceptorsChain`]s of a unicast transport.
+///
+/// The factories select the interceptors of a transport according to its links,
+/// so the chains are rebuilt each time a link is added to or removed from the transport.
+pub(crate) struct TransportInterceptors {
+    factories: Arc<Vec<InterceptorFactory>>,
+    ingress: Arc<InterceptorsChain>,
+    egress: Arc<InterceptorsChain>,
+}
+
+impl TransportInterceptors {
+    pub(crate) fn new(
+        factories: Arc<Vec<InterceptorFactory>>,
+        transport: &TransportUnicast,
+    ) -> Option<Self> {
+        if factories.is_empty() {
+            return None;
+        }
+        let interceptors = TransportInterceptors {
+            factories,
+            ingress: Arc::new(InterceptorsChain::default()),
+            egress: Arc::new(InterceptorsChain::default()),
+        };
+        interceptors.update(transport);
+        Some(interceptors)
+    }
+
+    /// Rebuilds the chains from the current links of the transport.
+    pub(crate) fn update(&self, transport: &TransportUnicast) {
+        let (ingress, egress): (Vec<_>, Vec<_>) = self
+            .factories
+            .iter()
+            .map(|itor| itor.new_transport_unicast(transport))
+            .unzip();
+        self.ingress.set(ingress.into_iter().flatten().collect());
+        self.egress.set(egress.into_iter().flatten().collect());
+    }
+
+    pub(crate) fn ingress(&self) -> Arc<dyn MessageInterceptor> {
+        self.ingress.clone()
+    }
+
+    pub(crate) fn egress(&self) -> Arc<dyn MessageInterceptor> {
+        self.egress.clone()
+    }
+}
@@ -11,13 +11,17 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
+use async_trait::async_trait;
 use petgraph::graph::NodeIndex;
 use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
 use std::convert::TryFrom;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::RwLock;
-use zenoh_core::zread;
+use std::sync::{Arc, Weak};
+use std::time::Instant;
+use zenoh_collections::{Timed, TimedEvent};
+use zenoh_core::{zread, zwrite};
 use zenoh_protocol_core::key_expr::OwnedKeyExpr;
 use zenoh_sync::get_mut_unchecked;
 
@@ -30,8 +34,9 @@ use zenoh_protocol_core::{
 
 use super::face::FaceState;
 use super::network::Network;
-use super::resource::{elect_router, PullCaches, Resource, Route, SessionContext};
+use super::resource::{elect_router, PullCache, PullCaches, Resource, Route, SessionContext};
 use super::router::Tables;
+use crate::subscriber::PullBufferPolicy;
 
 #[inline]
 fn send_sourced_subscription_to_net_childs(
@@ -73,7 +78,7 @@ fn propagate_simple_subscription_to(
     full_peer_net: bool,
 ) {
     if src_face.id != dst_face.id
-        && !dst_face.local_subs.contains(res)
+        && !dst_face.local_subs.contains_key(res)
         && match tables.whatami {
             WhatAmI::Router => {
                 if full_peer_net {
@@ -95,7 +100,9 @@ fn propagate_simple_subscription_to(
             _ => src_face.whatami == WhatAmI::Client || dst_face.whatami == WhatAmI::Client,
         }
     {
-        get_mut_unchecked(dst_face).local_subs.insert(res.clone());
+        get_mut_unchecked(dst_face)
+            .local_subs
+            .insert(res.clone(), sub_info.clone());
         let key_expr = Resource::decl_key(res, dst_face);
         dst_face
             .primitives
@@ -304,7 +311,7 @@ fn register_client_subscription(
                         remote_expr_id: None,
                         subs: Some(sub_info.clone()),
                         qabl: None,
-                        last_values: HashMap::new(),
+                        pull_cache: PullCache::default(),
                     }),
                 );
             }
@@ -428,7 +435,7 @@ fn send_forget_sourced_subscription_to_net_childs(
 
 fn propagate_forget_simple_subscription(tables: &mut Tables, res: &Arc<Resource>) {
     for face in tables.faces.values_mut() {
-        if face.local_subs.contains(res) {
+        if face.local_subs.contains_key(res) {
             let key_expr = Resource::get_best_key(res, "", face.id);
             face.primitives.forget_subscriber(&key_expr, None);
 
@@ -449,7 +456,7 @@ fn propagate_forget_simple_subscription_to_peers(tables: &mut Tables, res: &Arc<
             .collect::<Vec<Arc<FaceState>>>()
         {
             if face.whatami == WhatAmI::Peer
-                && face.local_subs.contains(res)
+                && face.local_subs.contains_key(res)
                 && !res.session_ctxs.values().any(|s| {
                     face.zid != s.face.zid
                         && s.subs.is_some()
@@ -657,7 +664,7 @@ pub(crate) fn undeclare_client_subscription(
     }
     if client_subs.len() == 1 && !router_subs && !peer_subs {
         let face = &mut client_subs[0];
-        if face.local_subs.contains(res) {
+        if face.local_subs.contains_key(res) {
             let key_expr = Resource::get_best_key(res, "", face.id);
             face.primitives.forget_subscriber(&key_expr, None);
 
@@ -690,7 +697,9 @@ pub(crate) fn pubsub_new_face(tables: &mut Tables, face: &mut Arc<FaceState>) {
         WhatAmI::Router => {
             if face.whatami == WhatAmI::Client {
                 for sub in &tables.router_subs {
-                    get_mut_unchecked(face).local_subs.insert(sub.clone());
+                    get_mut_unchecked(face)
+                        .local_subs
+                        .insert(sub.clone(), sub_info.clone());
                     let key_expr = Resource::decl_key(sub, face);
                     face.primitives.decl_subscriber(&key_expr, &sub_info, None);
                 }
@@ -705,7 +714,9 @@ pub(crate) fn pubsub_new_face(tables: &mut Tables, face: &mut Arc<FaceState>) {
                                             && tables.failover_brokering(s.face.zid, face.zid)))
                             }))
                     {
-                        get_mut_unchecked(face).local_subs.insert(sub.clone());
+                        get_mut_unchecked(face)
+                            .local_subs
+                            .insert(sub.clone(), sub_info.clone());
                         let key_expr = Resource::decl_key(sub, face);
                         face.primitives.decl_subscriber(&key_expr, &sub_info, None);
                     }
@@ -716,7 +727,9 @@ pub(crate) fn pubsub_new_face(tables: &mut Tables, face: &mut Arc<FaceState>) {
             if tables.full_net(WhatAmI::Peer) {
                 if face.whatami == WhatAmI::Client {
                     for sub in &tables.peer_subs {
-                        get_mut_unchecked(face).local_subs.insert(sub.clone());
+                        get_mut_unchecked(face)
+                            .local_subs
+                            .insert(sub.clone(), sub_info.clone());
                         let key_expr = Resource::decl_key(sub, face);
                         face.primitives.decl_subscriber(&key_expr, &sub_info, None);
                     }
@@ -865,19 +878,21 @@ pub(crate) fn pubsub_linkstate_change(tables: &mut Tables, zid: &ZenohId, links:
                     for dst_face in tables.faces.values_mut() {
                         if dst_face.whatami == WhatAmI::Peer && src_face.zid != dst_face.zid {
                             if !Tables::failover_brokering_to(links, dst_face.zid) {
-                                if dst_face.local_subs.contains(res) {
+                                if dst_face.local_subs.contains_key(res) {
                                     let key_expr = Resource::get_best_key(res, "", dst_face.id);
                                     dst_face.primitives.forget_subscriber(&key_expr, None);
 
                                     get_mut_unchecked(dst_face).local_subs.remove(res);
                                 }
                             } else {
-                                get_mut_unchecked(dst_face).local_subs.insert(res.clone());
-                                let key_expr = Resource::decl_key(res, dst_face);
                                 let sub_info = SubInfo {
                                     reliability: Reliability::Reliable, // TODO
                                     mode: SubMode::Push,
                                 };
+                                get_mut_unchecked(dst_face)
+                                    .local_subs
+                                    .insert(res.clone(), sub_info.clone());
+                                let key_expr = Resource::decl_key(res, dst_face);
                                 dst_face
                                     .primitives
                                     .decl_subscriber(&key_expr, &sub_info, None);
@@ -1041,6 +1056,30 @@ fn compute_data_route(
             }
         }
     }
+    // Subscribers learned through a multicast group are reached with a single send
+    // on the group face instead of one send per remote peer of that group
+    let mut mcast_groups: Vec<(usize, Option<RoutingContext>)> = vec![];
+    route.retain(|_, (outface, _, context)| match outface.mcast_group {
+        Some(group) => {
+            if !mcast_groups.iter().any(|(g, _)| *g == group) {
+                mcast_groups.push((group, *context));
+            }
+            false
+        }
+        None => true,
+    });
+    for (group, context) in mcast_groups {
+        if let Some(mcast_group) = tables.mcast_groups.iter().find(|face| face.id == group) {
+            route.insert(
+                mcast_group.id,
+                (
+                    mcast_group.clone(),
+                    Resource::get_best_key(prefix, suffix, mcast_group.id).to_owned(),
+                    context,
+                ),
+            );
+        }
+    }
     Arc::new(route)
 }
 
@@ -1134,7 +1173,7 @@ pub(crate) fn compute_data_routes(tables: &mut Tables, res: &mut Arc<Resource>)
     }
 }
 
-fn compute_data_routes_from(tables: &mut Tables, res: &mut Arc<Resource>) {
+pub(crate) fn compute_data_routes_from(tables: &mut Tables, res: &mut Arc<Resource>) {
     compute_data_routes(tables, res);
     let res = get_mut_unchecked(res);
     for child in res.childs.values_mut() {
@@ -1313,20 +1352,150 @@ fn get_matching_pulls(
         .unwrap_or_else(|| compute_matching_pulls(tables, prefix, suffix))
 }
 
-macro_rules! cache_data {
-    (
-        $matching_pulls:expr,
-        $prefix:expr,
-        $suffix:expr,
-        $payload:expr,
-        $info:expr
-    ) => {
-        for context in $matching_pulls.iter() {
-            get_mut_unchecked(&mut context.clone())
-                .last_values
-                .insert($prefix.expr() + $suffix, ($info.clone(), $payload.clone()));
+// Caches the data for the matching pull subscriptions, and returns the
+// directions of the ones that still have credits to be delivered right away.
+fn cache_data(
+    tables: &Tables,
+    matching_pulls: &PullCaches,
+    prefix: &Arc<Resource>,
+    suffix: &str,
+    payload: &ZBuf,
+    info: &Option<DataInfo>,
+) -> Vec<(Arc<FaceState>, WireExpr<'static>, Reliability)> {
+    let mut pulled = vec![];
+    let lock = zlock!(tables.pull_caches_lock);
+    for context in matching_pulls.iter() {
+        let ctx = get_mut_unchecked(&mut context.clone());
+        let name = prefix.expr() + suffix;
+        if ctx.pull_cache.credits > 0 {
+            ctx.pull_cache.credits -= 1;
+            let reliability = ctx
+                .subs
+                .as_ref()
+                .map(|subinfo| subinfo.reliability)
+                .unwrap_or_default();
+            pulled.push((
+                ctx.face.clone(),
+                Resource::get_best_key(&tables.root_res, &name, ctx.face.id).to_owned(),
+                reliability,
+            ));
+        } else {
+            ctx.pull_cache.insert(name, info.clone(), payload.clone());
         }
-    };
+    }
+    drop(lock);
+    pulled
+}
+
+// Data received from a multicast group is never sent back on that same group
+#[inline]
+fn same_mcast_group(face: &FaceState, outface: &FaceState) -> bool {
+    face.mcast_group.is_some() && face.mcast_group == outface.mcast_group
+}
+
+/// The acknowledgment expected by a face for some data it sent, pending until
+/// all the remote faces the data was routed to acknowledged it.
+pub(crate) struct PendingAck {
+    src_face: Arc<FaceState>,
+    src_ack_id: ZInt,
+    // Set when one of the remote faces closed or timed out before acknowledging the data
+    failed: AtomicBool,
+}
+
+#[derive(Clone)]
+struct AckCleanup {
+    tables: Arc<RwLock<Tables>>,
+    face: Weak<FaceState>,
+    ack_id: ZInt,
+}
+
+#[async_trait]
+impl Timed for AckCleanup {
+    async fn run(&mut self) {
+        if let Some(mut face) = self.face.upgrade() {
+            let tables_lock = zwrite!(self.tables);
+            if let Some(ack) = get_mut_unchecked(&mut face)
+                .pending_acks
+                .remove(&self.ack_id)
+            {
+                drop(tables_lock);
+                log::warn!(
+                    "Didn't receive acknowledgment {}:{} from {}: Timeout!",
+                    ack.src_face,
+                    self.ack_id,
+                    face
+                );
+                ack.failed.store(true, Ordering::Relaxed);
+                finalize_pending_ack(ack);
+            }
+        }
+    }
+}
+
+#[inline]
+fn insert_pending_ack(
+    tables_ref: &Arc<RwLock<Tables>>,
+    outface: &Arc<FaceState>,
+    ack: &Arc<PendingAck>,
+) -> ZInt {
+    let tables = zwrite!(tables_ref);
+    let outface_mut = get_mut_unchecked(&mut outface.clone());
+    outface_mut.next_qid += 1;
+    let ack_id = outface_mut.next_qid;
+    outface_mut.pending_acks.insert(ack_id, ack.clone());
+    tables.timer.add(TimedEvent::once(
+        Instant::now() + tables.queries_default_timeout,
+        AckCleanup {
+            tables: tables_ref.clone(),
+            face: Arc::downgrade(outface),
+            ack_id,
+        },
+    ));
+    ack_id
+}
+
+// Request an acknowledgment from the remote faces only: local faces
+// deliver the data synchronously. Multicast groups and the peers not
+// supporting acknowledgments can not acknowledge: they are not waited for.
+#[inline]
+fn with_ack(
+    outface: &Arc<FaceState>,
+    info: Option<DataInfo>,
+    ack: Option<(&Arc<RwLock<Tables>>, &Arc<PendingAck>)>,
+) -> Option<DataInfo> {
+    match ack {
+        Some((tables_ref, ack)) if outface.zid != ack.src_face.zid && outface.acks => {
+            let mut info = info.unwrap_or_default();
+            info.ack_id = Some(insert_pending_ack(tables_ref, outface, ack));
+            Some(info)
+        }
+        _ => info,
+    }
+}
+
+pub(crate) fn finalize_pending_ack(ack: Arc<PendingAck>) {
+    if let Ok(ack) = Arc::try_unwrap(ack) {
+        if ack.failed.into_inner() {
+            log::debug!(
+                "Drop acknowledgment {}:{}: a destination closed",
+                ack.src_face,
+                ack.src_ack_id
+            );
+        } else {
+            log::trace!(
+                "Propagate acknowledgment {}:{}",
+                ack.src_face,
+                ack.src_ack_id
+            );
+            ack.src_face.primitives.send_reply_final(ack.src_ack_id);
+        }
+    }
+}
+
+pub(crate) fn fail_pending_acks(_tables: &mut Tables, face: &mut Arc<FaceState>) {
+    for (_, ack) in get_mut_unchecked(face).pending_acks.drain() {
+        ack.failed.store(true, Ordering::Relaxed);
+    }
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -1339,6 +1508,84 @@ pub fn full_reentrant_route_data(
     info: Option<DataInfo>,
     payload: ZBuf,
     routing_context: Option<RoutingContext>,
+) {
+    route_data(
+        tables_ref,
+        face,
+        expr,
+        channel,
+        congestion_control,
+        info,
+        payload,
+        routing_context,
+        None,
+    )
+}
+
+/// Route some data for which the sending face requested an acknowledgment.
+///
+/// Data received from a remote node is acknowledged right away, this node being
+/// its first hop. Data sent by a local session is acknowledged once all the remote
+/// faces it was routed to acknowledged it.
+#[allow(clippy::too_many_arguments)]
+pub fn full_reentrant_route_acked_data(
+    tables_ref: &Arc<RwLock<Tables>>,
+    face: &Arc<FaceState>,
+    expr: &WireExpr,
+    channel: Channel,
+    congestion_control: CongestionControl,
+    info: Option<DataInfo>,
+    payload: ZBuf,
+    routing_context: Option<RoutingContext>,
+    ack_id: ZInt,
+) {
+    let is_local = face.zid == zread!(tables_ref).zid;
+    if is_local {
+        let ack = Arc::new(PendingAck {
+            src_face: face.clone(),
+            src_ack_id: ack_id,
+            failed: AtomicBool::new(false),
+        });
+        route_data(
+            tables_ref,
+            face,
+            expr,
+            channel,
+            congestion_control,
+            info,
+            payload,
+            routing_context,
+            Some((tables_ref, &ack)),
+        );
+        finalize_pending_ack(ack);
+    } else {
+        route_data(
+            tables_ref,
+            face,
+            expr,
+            channel,
+            congestion_control,
+            info,
+            payload,
+            routing_context,
+            None,
+        );
+        log::trace!("Acknowledge data {}:{}", face, ack_id);
+        face.primitives.send_reply_final(ack_id);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn route_data(
+    tables_ref: &RwLock<Tables>,
+    face: &FaceState,
+    expr: &WireExpr,
+    channel: Channel,
+    congestion_control: CongestionControl,
+    info: Option<DataInfo>,
+    payload: ZBuf,
+    routing_context: Option<RoutingContext>,
+    ack: Option<(&Arc<RwLock<Tables>>, &Arc<PendingAck>)>,
 ) {
     let tables = zread!(tables_ref);
     match tables.get_mapping(face, &expr.scope).cloned() {
@@ -1367,6 +1614,7 @@ pub fn full_reentrant_route_data(
                 if route.len() == 1 && matching_pulls.len() == 0 {
                     let (outface, key_expr, context) = route.values().next().unwrap();
                     if face.id != outface.id
+                        && !same_mcast_group(face, outface)
                         && (face.whatami != WhatAmI::Peer
                             || outface.whatami != WhatAmI::Peer
                             || peers_full_net
@@ -1378,22 +1626,23 @@ pub fn full_reentrant_route_data(
                             payload,
                             channel, // @TODO: Need to check the active subscriptions to determine the right reliability value
                             congestion_control,
-                            data_info,
+                            with_ack(outface, data_info, ack),
                             *context,
                         )
                     }
                 } else {
-                    if !matching_pulls.is_empty() {
-                        let lock = zlock!(tables.pull_caches_lock);
-                        cache_data!(
-                            matching_pulls,
-                            prefix,
+                    let pulled = if !matching_pulls.is_empty() {
+                        cache_data(
+                            &tables,
+                            &matching_pulls,
+                            &prefix,
                             expr.suffix.as_ref(),
-                            payload,
-                            data_info
-                        );
-                        drop(lock);
-                    }
+                            &payload,
+                            &data_info,
+                        )
+                    } else {
+                        vec![]
+                    };
 
                     if face.whatami == WhatAmI::Peer && !peers_full_net {
                         let router_peers_failover_brokering =
@@ -1406,6 +1655,7 @@ pub fn full_reentrant_route_data(
                         drop(tables);
                         for (outface, key_expr, context) in route.values() {
                             if face.id != outface.id
+                                && !same_mcast_group(face, outface)
                                 && (outface.whatami != WhatAmI::Peer
                                     || (router_peers_failover_brokering
                                         && Tables::failover_brokering_to(
@@ -1418,7 +1668,7 @@ pub fn full_reentrant_route_data(
                                     payload.clone(),
                                     channel, // @TODO: Need to check the active subscriptions to determine the right reliability value
                                     congestion_control,
-                                    data_info.clone(),
+                                    with_ack(outface, data_info.clone(), ack),
                                     *context,
                                 )
                             }
@@ -1426,18 +1676,31 @@ pub fn full_reentrant_route_data(
                     } else {
                         drop(tables);
                         for (outface, key_expr, context) in route.values() {
-                            if face.id != outface.id {
+                            if face.id != outface.id && !same_mcast_group(face, outface) {
                                 outface.primitives.send_data(
                                     key_expr,
                                     payload.clone(),
                                     channel, // @TODO: Need to check the active subscriptions to determine the right reliability value
                                     congestion_control,
-                                    data_info.clone(),
+                                    with_ack(outface, data_info.clone(), ack),
                                     *context,
                                 )
                             }
                         }
                     }
+                    for (outface, key_expr, reliability) in pulled {
+                        outface.primitives.send_data(
+                            &key_expr,
+                            payload.clone(),
+                            Channel {
+                                priority: channel.priority,
+                                reliability,
+                            },
+                            congestion_control,
+                            with_ack(&outface, data_info.clone(), ack),
+                            None,
+                        )
+                    }
                 }
             }
         }
@@ -1453,7 +1716,7 @@ pub fn pull_data(
     _is_final: bool,
     expr: &WireExpr,
     _pull_id: ZInt,
-    _max_samples: &Option<ZInt>,
+    max_samples: &Option<ZInt>,
 ) {
     let tables = zread!(tables_ref);
     match tables.get_mapping(face, &expr.scope) {
@@ -1465,9 +1728,17 @@ pub fn pull_data(
                         Some(subinfo) => {
                             let reliability = subinfo.reliability;
                             let lock = zlock!(tables.pull_caches_lock);
-                            let route = get_mut_unchecked(ctx)
-                                .last_values
-                                .drain()
+                            let pull_cache = &mut get_mut_unchecked(ctx).pull_cache;
+                            let pulled = pull_cache.take(*max_samples);
+                            // The samples pulled but not available yet will be delivered
+                            // as soon as they are received. A new pull replaces the credits
+                            // left by the previous one.
+                            pull_cache.credits = match max_samples {
+                                Some(max_samples) => max_samples - pulled.len() as ZInt,
+                                None => 0,
+                            };
+                            let route = pulled
+                                .into_iter()
                                 .map(|(name, sample)| {
                                     (
                                         Resource::get_best_key(&tables.root_res, &name, face.id)
@@ -1519,3 +1790,35 @@ pub fn pull_data(
         }
     };
 }
+
+pub(crate) fn set_pull_buffer_policy(
+    tables_ref: &RwLock<Tables>,
+    face: &Arc<FaceState>,
+    expr: &WireExpr,
+    policy: PullBufferPolicy,
+) {
+    let tables = zread!(tables_ref);
+    match tables
+        .get_mapping(face, &expr.scope)
+        .and_then(|prefix| Resource::get_resource(prefix, expr.suffix.as_ref()))
+    {
+        Some(mut res) => match get_mut_unchecked(&mut res).session_ctxs.get_mut(&face.id) {
+            Some(ctx) => {
+                let _lock = zlock!(tables.pull_caches_lock);
+                get_mut_unchecked(ctx).pull_cache.set_policy(policy);
+            }
+            None => {
+                log::error!(
+                    "Set pull buffer policy for unknown subscription {}!",
+                    res.expr()
+                );
+            }
+        },
+        None => {
+            log::error!(
+                "Set pull buffer policy for unknown subscription {}!",
+                expr.suffix
+            );
+        }
+    }
+}
@@ -33,8 +33,10 @@ use crate::prelude::KeyExpr;
 
 use super::face::FaceState;
 use super::network::Network;
+use super::pubsub::finalize_pending_ack;
 use super::resource::{
-    elect_router, QueryRoute, QueryTargetQabl, QueryTargetQablSet, Resource, SessionContext,
+    elect_router, PullCache, QueryRoute, QueryTargetQabl, QueryTargetQablSet, Resource,
+    SessionContext,
 };
 use super::router::Tables;
 
@@ -453,7 +455,7 @@ fn register_client_queryable(
                 remote_expr_id: None,
                 subs: None,
                 qabl: None,
-                last_values: HashMap::new(),
+                pull_cache: PullCache::default(),
             })
         }))
         .qabl = Some(qabl_info.clone());
@@ -986,11 +988,11 @@ pub(crate) fn queries_linkstate_change(tables: &mut Tables, zid: &ZenohId, links
                                     get_mut_unchecked(&mut dst_face).local_qabls.remove(res);
                                 }
                             } else {
+                                let info = local_qabl_info(tables, res, &dst_face);
                                 get_mut_unchecked(&mut dst_face)
-                                    .local_subs
-                                    .insert(res.clone());
+                                    .local_qabls
+                                    .insert(res.clone(), info.clone());
                                 let key_expr = Resource::decl_key(res, &mut dst_face);
-                                let info = local_qabl_info(tables, res, &dst_face);
                                 dst_face.primitives.decl_queryable(&key_expr, &info, None);
                             }
                         }
@@ -1190,10 +1192,16 @@ fn compute_query_route(
 
         if tables.whatami != WhatAmI::Router || master || source_type == WhatAmI::Router {
             for (sid, context) in &mres.session_ctxs {
-                if match tables.whatami {
-                    WhatAmI::Router => context.face.whatami != WhatAmI::Router,
-                    _ => source_type == WhatAmI::Client || context.face.whatami == WhatAmI::Client,
-                } {
+                // Queries are not routed through multicast groups: they carry no destination
+                if context.face.mcast_group.is_none()
+                    && match tables.whatami {
+                        WhatAmI::Router => context.face.whatami != WhatAmI::Router,
+                        _ => {
+                            source_type == WhatAmI::Client
+                                || context.face.whatami == WhatAmI::Client
+                        }
+                    }
+                {
                     let key_expr = Resource::get_best_key(prefix, suffix, *sid);
                     if let Some(qabl_info) = context.qabl.as_ref() {
                         route.push(QueryTargetQabl {
@@ -1804,12 +1812,19 @@ pub(crate) fn route_send_reply_final(
             );
             finalize_pending_query(query);
         }
-        None => log::warn!(
-            "Route final reply {}:{} from {}: Query nof found!",
-            face,
-            qid,
-            face
-        ),
+        None => match get_mut_unchecked(face).pending_acks.remove(&qid) {
+            Some(ack) => {
+                drop(tables_lock);
+                log::trace!("Received acknowledgment {} from {}", qid, face);
+                finalize_pending_ack(ack);
+            }
+            None => log::warn!(
+                "Route final reply {}:{} from {}: Query nof found!",
+                face,
+                qid,
+                face
+            ),
+        },
     }
 }
 
@@ -13,8 +13,9 @@
 //
 use super::face::FaceState;
 use super::router::Tables;
+use crate::subscriber::PullBufferPolicy;
 use std::collections::hash_map::DefaultHasher;
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::convert::TryInto;
 use std::hash::{Hash, Hasher};
 use std::sync::{Arc, Weak};
@@ -44,7 +45,80 @@ pub(super) struct SessionContext {
     pub(super) remote_expr_id: Option<ZInt>,
     pub(super) subs: Option<SubInfo>,
     pub(super) qabl: Option<QueryableInfo>,
-    pub(super) last_values: HashMap<String, (Option<DataInfo>, ZBuf)>,
+    pub(super) pull_cache: PullCache,
+}
+
+pub(super) type PulledData = (String, (Option<DataInfo>, ZBuf));
+
+/// The data buffered for a pull subscription until it's pulled.
+#[derive(Default)]
+pub(super) struct PullCache {
+    policy: PullBufferPolicy,
+    // The number of samples pulled but not yet delivered
+    pub(super) credits: ZInt,
+    last_values: HashMap<String, (Option<DataInfo>, ZBuf)>,
+    values: VecDeque<PulledData>,
+}
+
+impl PullCache {
+    pub(super) fn set_policy(&mut self, policy: PullBufferPolicy) {
+        self.policy = policy;
+        let buffered = self
+            .last_values
+            .drain()
+            .chain(self.values.drain(..))
+            .collect::<Vec<PulledData>>();
+        for (name, (info, payload)) in buffered {
+            self.insert(name, info, payload);
+        }
+    }
+
+    pub(super) fn insert(&mut self, name: String, info: Option<DataInfo>, payload: ZBuf) {
+        match self.policy {
+            PullBufferPolicy::KeepLast => {
+                self.last_values.insert(name, (info, payload));
+            }
+            PullBufferPolicy::DropOldest(capacity) => {
+                if capacity > 0 {
+                    if self.values.len() >= capacity {
+                        self.values.pop_front();
+                    }
+                    self.values.push_back((name, (info, payload)));
+                }
+            }
+            PullBufferPolicy::DropNewest(capacity) => {
+                if self.values.len() < capacity {
+                    self.values.push_back((name, (info, payload)));
+                }
+            }
+        }
+    }
+
+    pub(super) fn take(&mut self, max_samples: Option<ZInt>) -> Vec<PulledData> {
+        let max = max_samples.map_or(usize::MAX, |max| max as usize);
+        match self.policy {
+            PullBufferPolicy::KeepLast => {
+                if max >= self.last_values.len() {
+                    self.last_values.drain().collect()
+                } else {
+                    let names = self
+                        .last_values
+                        .keys()
+                        .take(max)
+                        .cloned()
+                        .collect::<Vec<String>>();
+                    names
+                        .iter()
+                        .filter_map(|name| self.last_values.remove_entry(name))
+                        .collect()
+                }
+            }
+            PullBufferPolicy::DropOldest(_) | PullBufferPolicy::DropNewest(_) => {
+                let n = max.min(self.values.len());
+                self.values.drain(..n).collect()
+            }
+        }
+    }
 }
 
 pub(super) struct ResourceContext {
@@ -395,7 +469,7 @@ impl Resource {
                             remote_expr_id: None,
                             subs: None,
                             qabl: None,
-                            last_values: HashMap::new(),
+                            pull_cache: PullCache::default(),
                         })
                     });
 
@@ -606,7 +680,7 @@ pub fn register_expr(
                             remote_expr_id: Some(expr_id),
                             subs: None,
                             qabl: None,
-                            last_values: HashMap::new(),
+                            pull_cache: PullCache::default(),
                         })
                     })
                     .clone();
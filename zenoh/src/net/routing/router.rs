@@ -12,27 +12,31 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 use super::face::{Face, FaceState};
+use super::interceptor::{InterceptorFactory, InterceptorsChain, TransportInterceptors};
 use super::network::{shared_nodes, Network};
 pub use super::pubsub::*;
 pub use super::queries::*;
 pub use super::resource::*;
 use super::runtime::Runtime;
-use async_std::task::JoinHandle;
+use petgraph::graph::NodeIndex;
 use std::any::Any;
 use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Weak};
 use std::sync::{Mutex, RwLock};
 use std::time::Duration;
 use uhlc::HLC;
+use zenoh_collections::Timer;
 use zenoh_config::whatami::WhatAmIMatcher;
-use zenoh_link::Link;
-use zenoh_protocol::proto::{ZenohBody, ZenohMessage};
-use zenoh_protocol_core::{WhatAmI, ZInt, ZenohId};
-use zenoh_transport::{DeMux, Mux, Primitives, TransportPeerEventHandler, TransportUnicast};
-// use zenoh_collections::Timer;
 use zenoh_core::zconfigurable;
 use zenoh_core::Result as ZResult;
-use zenoh_sync::get_mut_unchecked;
+use zenoh_link::Link;
+use zenoh_protocol::proto::{zmsg, Hello, ZenohBody, ZenohMessage};
+use zenoh_protocol_core::{WhatAmI, ZInt, ZenohId};
+use zenoh_sync::{get_mut_unchecked, Executor, JoinHandle};
+use zenoh_transport::{
+    DeMux, McastDeclarations, McastMux, McastPeerMux, MessageInterceptor, Mux, Primitives,
+    TransportMulticast, TransportPeer, TransportPeerEventHandler, TransportUnicast,
+};
 
 zconfigurable! {
     static ref TREES_COMPUTATION_DELAY: u64 = 100;
@@ -46,10 +50,11 @@ pub struct Tables {
     pub(crate) hlc: Option<Arc<HLC>>,
     pub(crate) drop_future_timestamp: bool,
     pub(crate) router_peers_failover_brokering: bool,
-    // pub(crate) timer: Timer,
-    // pub(crate) queries_default_timeout: Duration,
+    pub(crate) timer: Timer,
+    pub(crate) queries_default_timeout: Duration,
     pub(crate) root_res: Arc<Resource>,
     pub(crate) faces: HashMap<usize, Arc<FaceState>>,
+    pub(crate) mcast_groups: Vec<Arc<FaceState>>,
     pub(crate) pull_caches_lock: Mutex<()>,
     pub(crate) router_subs: HashSet<Arc<Resource>>,
     pub(crate) peer_subs: HashSet<Arc<Resource>>,
@@ -69,7 +74,7 @@ impl Tables {
         hlc: Option<Arc<HLC>>,
         drop_future_timestamp: bool,
         router_peers_failover_brokering: bool,
-        _queries_default_timeout: Duration,
+        queries_default_timeout: Duration,
     ) -> Self {
         Tables {
             zid,
@@ -78,10 +83,11 @@ impl Tables {
             hlc,
             drop_future_timestamp,
             router_peers_failover_brokering,
-            // timer: Timer::new(true),
-            // queries_default_timeout,
+            timer: Timer::new(true),
+            queries_default_timeout,
             root_res: Resource::root(),
             faces: HashMap::new(),
+            mcast_groups: vec![],
             pull_caches_lock: Mutex::new(()),
             router_subs: HashSet::new(),
             peer_subs: HashSet::new(),
@@ -170,13 +176,25 @@ impl Tables {
         whatami: WhatAmI,
         primitives: Arc<dyn Primitives + Send + Sync>,
         link_id: usize,
+        mcast_group: Option<usize>,
+        acks: bool,
     ) -> Weak<FaceState> {
         let fid = self.face_counter;
         self.face_counter += 1;
         let mut newface = self
             .faces
             .entry(fid)
-            .or_insert_with(|| FaceState::new(fid, zid, whatami, primitives.clone(), link_id))
+            .or_insert_with(|| {
+                FaceState::new(
+                    fid,
+                    zid,
+                    whatami,
+                    primitives.clone(),
+                    link_id,
+                    mcast_group,
+                    acks,
+                )
+            })
             .clone();
         log::debug!("New {}", newface);
 
@@ -192,7 +210,61 @@ impl Tables {
         whatami: WhatAmI,
         primitives: Arc<dyn Primitives + Send + Sync>,
     ) -> Weak<FaceState> {
-        self.open_net_face(zid, whatami, primitives, 0)
+        self.open_net_face(zid, whatami, primitives, 0, None, true)
+    }
+
+    /// Open the face of a multicast group. It is never declared to the routing tables:
+    /// it is only used to send data once on the group instead of once per remote peer
+    /// that subscribed through that group.
+    pub fn open_mcast_group(
+        &mut self,
+        primitives: Arc<dyn Primitives + Send + Sync>,
+    ) -> Weak<FaceState> {
+        let fid = self.face_counter;
+        self.face_counter += 1;
+        let group = FaceState::new(
+            fid,
+            self.zid,
+            WhatAmI::Peer,
+            primitives,
+            0,
+            Some(fid),
+            false,
+        );
+        log::debug!("New multicast group {}", group);
+        self.mcast_groups.push(group.clone());
+
+        let mut root_res = self.root_res.clone();
+        compute_data_routes_from(self, &mut root_res);
+        Arc::downgrade(&group)
+    }
+
+    /// Open the face of a remote peer learned through the multicast group `group`.
+    pub fn open_mcast_face(
+        &mut self,
+        zid: ZenohId,
+        whatami: WhatAmI,
+        primitives: Arc<dyn Primitives + Send + Sync>,
+        group: usize,
+    ) -> Weak<FaceState> {
+        self.open_net_face(zid, whatami, primitives, 0, Some(group), false)
+    }
+
+    /// Close the face of a multicast group and the faces of the remote peers learned through it.
+    pub fn close_mcast_group(&mut self, group: usize) {
+        self.mcast_groups.retain(|face| face.id != group);
+        let peers = self
+            .faces
+            .values()
+            .filter(|face| face.mcast_group == Some(group))
+            .map(Arc::downgrade)
+            .collect::<Vec<Weak<FaceState>>>();
+        for face in peers {
+            self.close_face(&face);
+        }
+
+        let mut root_res = self.root_res.clone();
+        compute_data_routes_from(self, &mut root_res);
     }
 
     pub fn close_face(&mut self, face: &Weak<FaceState>) {
@@ -200,6 +272,7 @@ impl Tables {
             Some(mut face) => {
                 log::debug!("Close {}", face);
                 finalize_pending_queries(self, &mut face);
+                fail_pending_acks(self, &mut face);
 
                 let mut face_clone = face.clone();
                 let face = get_mut_unchecked(&mut face);
@@ -229,6 +302,41 @@ impl Tables {
         }
     }
 
+    /// Re-send to a face the declarations previously sent to it. Used when a link of the
+    /// transport of the face went down while the transport survives on its other links:
+    /// the declarations still queued on the failed link may have been lost.
+    pub(crate) fn redeclare(&mut self, face: &Arc<FaceState>) {
+        log::debug!("Redeclare on {}", face);
+        for (expr_id, res) in &face.local_mappings {
+            face.primitives.decl_resource(*expr_id, &res.expr().into());
+        }
+        for (res, sub_info) in &face.local_subs {
+            let key_expr = Resource::get_best_key(res, "", face.id);
+            face.primitives.decl_subscriber(&key_expr, sub_info, None);
+        }
+        for (res, info) in &face.local_qabls {
+            let key_expr = Resource::get_best_key(res, "", face.id);
+            face.primitives.decl_queryable(&key_expr, info, None);
+        }
+
+        // The declarations sourced by the other nodes are sent on the trees the face is a child of
+        for net_type in [WhatAmI::Router, WhatAmI::Peer] {
+            if !self.full_net(net_type) {
+                continue;
+            }
+            let net = self.get_net(net_type).unwrap();
+            if let Some(idx) = net.get_idx(&face.zid) {
+                let childs = net
+                    .trees
+                    .iter()
+                    .map(|tree| tree.childs.iter().filter(|c| **c == idx).cloned().collect())
+                    .collect::<Vec<Vec<NodeIndex>>>();
+                pubsub_tree_change(self, &childs, net_type);
+                queries_tree_change(self, &childs, net_type);
+            }
+        }
+    }
+
     fn compute_routes(&mut self, res: &mut Arc<Resource>) {
         compute_data_routes(self, res);
         compute_query_routes(self, res);
@@ -257,7 +365,7 @@ impl Tables {
         if (net_type == WhatAmI::Router && self.routers_trees_task.is_none())
             || (net_type == WhatAmI::Peer && self.peers_trees_task.is_none())
         {
-            let task = Some(async_std::task::spawn(async move {
+            let task = Some(Executor::current().spawn(async move {
                 async_std::task::sleep(std::time::Duration::from_millis(*TREES_COMPUTATION_DELAY))
                     .await;
                 let mut tables = zwrite!(tables_ref);
@@ -289,6 +397,7 @@ impl Tables {
 pub struct Router {
     whatami: WhatAmI,
     pub tables: Arc<RwLock<Tables>>,
+    interceptors: Arc<Vec<InterceptorFactory>>,
 }
 
 impl Router {
@@ -299,6 +408,7 @@ impl Router {
         drop_future_timestamp: bool,
         router_peers_failover_brokering: bool,
         queries_default_timeout: Duration,
+        interceptors: Vec<InterceptorFactory>,
     ) -> Self {
         Router {
             whatami,
@@ -310,7 +420,90 @@ impl Router {
                 router_peers_failover_brokering,
                 queries_default_timeout,
             ))),
+            interceptors: Arc::new(interceptors),
+        }
+    }
+
+    pub(crate) fn update_gossip(
+        &self,
+        runtime: &Runtime,
+        gossip: bool,
+        gossip_multihop: bool,
+        autoconnect: WhatAmIMatcher,
+    ) {
+        // Get the transports first to not call the transport manager with the tables locked
+        let transports = runtime.manager().get_transports();
+        let mut tables = zwrite!(self.tables);
+        let tables = &mut *tables;
+        if gossip && tables.peers_net.is_none() && self.whatami != WhatAmI::Client {
+            let mut net = Network::new(
+                "[Peers network]".to_string(),
+                tables.zid,
+                runtime.clone(),
+                false,
+                tables.router_peers_failover_brokering,
+                gossip,
+                gossip_multihop,
+                autoconnect,
+            );
+            // Add the links the peers network would have been given if gossip
+            // had been enabled at startup
+            for transport in transports {
+                match (self.whatami, transport.get_whatami()) {
+                    (WhatAmI::Router, Ok(WhatAmI::Peer))
+                    | (WhatAmI::Peer, Ok(WhatAmI::Router))
+                    | (WhatAmI::Peer, Ok(WhatAmI::Peer)) => {
+                        net.add_link(transport);
+                    }
+                    _ => (),
+                }
+            }
+            tables.peers_net = Some(net);
+        }
+        let mut nets = tables
+            .routers_net
+            .iter_mut()
+            .chain(tables.peers_net.iter_mut())
+            .peekable();
+        if gossip && nets.peek().is_none() {
+            log::warn!(
+                "Gossip scouting can't be enabled at runtime on a client if it was disabled at startup"
+            );
+        }
+        for net in nets {
+            log::debug!(
+                "{} Update gossip: enabled={}, multihop={}, autoconnect={}",
+                net.name,
+                gossip,
+                gossip_multihop,
+                autoconnect
+            );
+            net.gossip = gossip;
+            net.gossip_multihop = gossip_multihop;
+            net.autoconnect = autoconnect;
+        }
+    }
+
+    // Returns the nodes discovered through gossip or linkstate that this
+    // node is not connected to.
+    pub(crate) fn discovered_nodes(&self) -> Vec<Hello> {
+        let tables = zread!(self.tables);
+        let mut nodes: HashMap<ZenohId, Hello> = HashMap::new();
+        for net in tables.routers_net.iter().chain(tables.peers_net.iter()) {
+            for (idx, node) in net.graph.node_indices().map(|idx| (idx, &net.graph[idx])) {
+                if idx != net.idx
+                    && node.locators.is_some()
+                    && net.runtime.manager().get_transport(&node.zid).is_none()
+                {
+                    nodes.entry(node.zid).or_insert_with(|| Hello {
+                        zid: Some(node.zid),
+                        whatami: node.whatami,
+                        locators: node.locators.clone(),
+                    });
+                }
+            }
         }
+        nodes.into_values().collect()
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -403,6 +596,13 @@ impl Router {
             );
         }
 
+        let interceptors = TransportInterceptors::new(self.interceptors.clone(), &transport);
+        let egress = interceptors.as_ref().map(|itors| itors.egress());
+        let acks = transport
+            .get_data_info_extensions()
+            .map(|ext| ext & zmsg::data::info::ACK != 0)
+            .unwrap_or(false);
+
         let handler = Arc::new(LinkStateInterceptor::new(
             transport.clone(),
             self.tables.clone(),
@@ -412,12 +612,15 @@ impl Router {
                     .open_net_face(
                         transport.get_zid().unwrap(),
                         whatami,
-                        Arc::new(Mux::new(transport)),
+                        Arc::new(Mux::with_interceptor(transport, egress)),
                         link_id,
+                        None,
+                        acks,
                     )
                     .upgrade()
                     .unwrap(),
             },
+            interceptors,
         ));
 
         match (self.whatami, whatami) {
@@ -435,6 +638,55 @@ impl Router {
         }
         Ok(handler)
     }
+
+    pub fn new_transport_multicast(&self, transport: TransportMulticast) -> ZResult<usize> {
+        let egress = InterceptorsChain::new(
+            self.interceptors
+                .iter()
+                .filter_map(|itor| itor.new_transport_multicast(&transport))
+                .collect(),
+        );
+        let group = zwrite!(self.tables)
+            .open_mcast_group(Arc::new(McastMux::with_interceptor(transport, egress)))
+            .upgrade()
+            .unwrap();
+        Ok(group.id)
+    }
+
+    pub fn del_transport_multicast(&self, group: usize) {
+        zwrite!(self.tables).close_mcast_group(group);
+    }
+
+    pub fn new_peer_multicast(
+        &self,
+        transport: TransportMulticast,
+        group: usize,
+        declarations: McastDeclarations,
+        peer: TransportPeer,
+    ) -> ZResult<Arc<DeMux<Face>>> {
+        let ingress = InterceptorsChain::new(
+            self.interceptors
+                .iter()
+                .filter_map(|itor| itor.new_peer_multicast(&transport, &peer))
+                .collect(),
+        );
+        let face = zwrite!(self.tables)
+            .open_mcast_face(
+                peer.zid,
+                peer.whatami,
+                Arc::new(McastPeerMux::new(transport, declarations)),
+                group,
+            )
+            .upgrade()
+            .unwrap();
+        Ok(Arc::new(DeMux::with_interceptor(
+            Face {
+                tables: self.tables.clone(),
+                state: face,
+            },
+            ingress,
+        )))
+    }
 }
 
 pub struct LinkStateInterceptor {
@@ -442,15 +694,32 @@ pub struct LinkStateInterceptor {
     pub(crate) tables: Arc<RwLock<Tables>>,
     pub(crate) face: Face,
     pub(crate) demux: DeMux<Face>,
+    pub(crate) ingress: Option<Arc<dyn MessageInterceptor>>,
+    interceptors: Option<TransportInterceptors>,
 }
 
 impl LinkStateInterceptor {
-    fn new(transport: TransportUnicast, tables: Arc<RwLock<Tables>>, face: Face) -> Self {
+    fn new(
+        transport: TransportUnicast,
+        tables: Arc<RwLock<Tables>>,
+        face: Face,
+        interceptors: Option<TransportInterceptors>,
+    ) -> Self {
         LinkStateInterceptor {
             transport,
             tables,
             face: face.clone(),
             demux: DeMux::new(face),
+            ingress: interceptors.as_ref().map(|itors| itors.ingress()),
+            interceptors,
+        }
+    }
+
+    // The interceptors depend on the links of the transport (e.g. on their interfaces
+    // or certificates), so they are checked again each time these links change
+    fn update_interceptors(&self) {
+        if let Some(interceptors) = &self.interceptors {
+            interceptors.update(&self.transport);
         }
     }
 }
@@ -543,9 +812,20 @@ impl TransportPeerEventHandler for LinkStateInterceptor {
         }
     }
 
-    fn new_link(&self, _link: Link) {}
+    fn new_link(&self, _link: Link) {
+        self.update_interceptors();
+    }
 
-    fn del_link(&self, _link: Link) {}
+    fn del_link(&self, link: Link) {
+        self.update_interceptors();
+        // The transport survives if the link was not the last one, but the
+        // declarations queued on the removed link may have been lost
+        if let Ok(links) = self.transport.get_links() {
+            if !links.is_empty() && !links.iter().any(|l| l.src == link.src && l.dst == link.dst) {
+                zwrite!(self.tables).redeclare(&self.face.state);
+            }
+        }
+    }
 
     fn closing(&self) {
         self.demux.closing();
@@ -15,7 +15,6 @@ use super::Runtime;
 use crate::plugins::sealed as plugins;
 use crate::prelude::KeyExpr;
 use crate::prelude::SampleKind;
-use async_std::task;
 use futures::future::{BoxFuture, FutureExt};
 use log::{error, trace};
 use serde_json::json;
@@ -113,7 +112,7 @@ impl AdminSpace {
         });
 
         let cfg_rx = admin.context.runtime.config.subscribe();
-        task::spawn({
+        runtime.executor.spawn({
             let admin = admin.clone();
             async move {
                 while let Ok(change) = cfg_rx.recv_async().await {
@@ -388,7 +387,7 @@ impl Primitives for AdminSpace {
                     key_expr
                 );
                 // router is not re-entrant
-                task::spawn(async move {
+                self.context.runtime.executor.spawn(async move {
                     primitives.send_reply_final(qid);
                 });
                 return;
@@ -400,7 +399,7 @@ impl Primitives for AdminSpace {
             Err(e) => {
                 log::error!("Unknown KeyExpr!! ({})", e);
                 // router is not re-entrant
-                task::spawn(async move {
+                self.context.runtime.executor.spawn(async move {
                     primitives.send_reply_final(qid);
                 });
                 return;
@@ -422,7 +421,7 @@ impl Primitives for AdminSpace {
         let parameters = parameters.to_owned();
 
         // router is not re-entrant
-        task::spawn(async move {
+        self.context.runtime.executor.spawn(async move {
             let handler_tasks = futures::future::join_all(matching_handlers.into_iter().map(
                 |(key, handler)| async {
                     let handler = handler;
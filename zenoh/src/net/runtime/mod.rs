@@ -21,12 +21,16 @@ mod adminspace;
 pub mod orchestrator;
 
 use super::routing;
+use super::routing::interceptor::{
+    interceptor_factories, InterceptorFactory, InterceptorFactoryTrait,
+};
 use super::routing::pubsub::full_reentrant_route_data;
 use super::routing::router::{LinkStateInterceptor, Router};
-use crate::config::{unwrap_or_default, Config, ModeDependent, Notifier};
+use crate::config::{unwrap_or_default, Config, ModeDependent, Notifier, PluginLoad};
+use crate::plugins::sealed::PluginsManager;
 use crate::GIT_VERSION;
 pub use adminspace::AdminSpace;
-use async_std::task::JoinHandle;
+use futures::future::{BoxFuture, FutureExt as _};
 use futures::stream::StreamExt;
 use futures::Future;
 use std::any::Any;
@@ -35,19 +39,167 @@ use std::time::Duration;
 use stop_token::future::FutureExt;
 use stop_token::{StopSource, TimedOutError};
 use uhlc::{HLCBuilder, HLC};
-use zenoh_core::bail;
 use zenoh_core::Result as ZResult;
+use zenoh_core::{bail, AsyncResolve, Resolvable, SyncResolve};
 use zenoh_link::{EndPoint, Link};
 use zenoh_protocol;
 use zenoh_protocol::core::{whatami::WhatAmIMatcher, Locator, WhatAmI, ZenohId};
 use zenoh_protocol::proto::{ZenohBody, ZenohMessage};
-use zenoh_sync::get_mut_unchecked;
+use zenoh_sync::{get_mut_unchecked, Executor, JoinHandle};
 use zenoh_transport;
 use zenoh_transport::{
-    TransportEventHandler, TransportManager, TransportMulticast, TransportMulticastEventHandler,
-    TransportPeer, TransportPeerEventHandler, TransportUnicast,
+    McastDeclarations, TransportEventHandler, TransportManager, TransportMulticast,
+    TransportMulticastEventHandler, TransportPeer, TransportPeerEventHandler, TransportUnicast,
 };
 
+/// A builder returned by [`Runtime::new`] used to start a [`Runtime`].
+#[must_use = "Resolvables do nothing unless you resolve them using the `res` method from either `SyncResolve` or `AsyncResolve`"]
+pub struct RuntimeBuilder {
+    config: Config,
+    plugins: Option<PluginsManager>,
+    version: String,
+    interceptors: Vec<InterceptorFactory>,
+}
+
+impl RuntimeBuilder {
+    /// Use the given [`PluginsManager`] to run plugins in the [`Runtime`].
+    ///
+    /// The plugins requested in the `plugins` section of the configuration are loaded into
+    /// this manager, in addition to the ones it already holds (e.g. static plugins).
+    /// Once the runtime is started, all the plugins are started and exposed on the admin space.
+    /// The loading of a plugin marked as `__required__` failing makes the runtime fail to start.
+    /// The plugins failing to start, required or not, are only logged: the runtime starts
+    /// without them and they are not exposed on the admin space.
+    #[inline]
+    pub fn with_plugins(mut self, plugins: PluginsManager) -> Self {
+        self.plugins = Some(plugins);
+        self
+    }
+
+    /// Set the version reported on the admin space (defaults to the zenoh version).
+    #[inline]
+    pub fn with_version(mut self, version: String) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Add an interceptor of the messages exchanged on the transports of the [`Runtime`].
+    ///
+    /// The given factory is called for each new transport to build its ingress and egress
+    /// interceptors, which are applied after the ones configured in the configuration
+    /// (e.g. access control and downsampling).
+    #[zenoh_core::unstable]
+    #[inline]
+    pub fn with_interceptor<F>(mut self, factory: F) -> Self
+    where
+        F: InterceptorFactoryTrait + 'static,
+    {
+        self.interceptors.push(Box::new(factory));
+        self
+    }
+
+    async fn start(self) -> ZResult<Runtime> {
+        let RuntimeBuilder {
+            config,
+            mut plugins,
+            version,
+            interceptors,
+        } = self;
+
+        if let Some(plugins) = plugins.as_mut() {
+            load_plugins(plugins, &config)?;
+        }
+
+        let mut runtime = Runtime::init(config, interceptors).await?;
+        runtime.start().await?;
+
+        if let Some(mut plugins) = plugins {
+            start_plugins(&mut plugins, &runtime);
+            AdminSpace::start(&runtime, plugins, version).await;
+        }
+        Ok(runtime)
+    }
+}
+
+impl Resolvable for RuntimeBuilder {
+    type To = ZResult<Runtime>;
+}
+
+impl SyncResolve for RuntimeBuilder {
+    fn res_sync(self) -> <Self as Resolvable>::To {
+        async_std::task::block_on(self.start())
+    }
+}
+
+impl AsyncResolve for RuntimeBuilder {
+    type Future = BoxFuture<'static, <Self as Resolvable>::To>;
+
+    fn res_async(self) -> Self::Future {
+        self.start().boxed()
+    }
+}
+
+/// Loads in the given [`PluginsManager`] the plugins requested in the given [`Config`].
+fn load_plugins(plugins: &mut PluginsManager, config: &Config) -> ZResult<()> {
+    for PluginLoad {
+        name,
+        paths,
+        required,
+    } in config.plugins().load_requests()
+    {
+        if let Err(e) = match paths {
+            None => plugins.load_plugin_by_name(name),
+            Some(paths) => plugins.load_plugin_by_paths(name, &paths),
+        } {
+            if required {
+                bail!("Plugin load failure: {}", e)
+            } else {
+                log::error!("Plugin load failure: {}", e)
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Starts all the plugins of the given [`PluginsManager`] on the given [`Runtime`]
+/// and registers their configuration validators.
+fn start_plugins(plugins: &mut PluginsManager, runtime: &Runtime) {
+    for (name, path, start_result) in plugins.start_all(runtime) {
+        match start_result {
+            Ok(Some(_)) => log::info!("Successfully started plugin {} from {:?}", name, path),
+            Ok(None) => log::warn!(
+                "Plugin {} from {:?} wasn't loaded, as an other plugin by the same name is already running",
+                name,
+                path
+            ),
+            Err(e) => {
+                match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| e.to_string())) {
+                    Ok(report) => log::error!(
+                        "Plugin start failure: {}",
+                        if report.is_empty() {
+                            "no details provided"
+                        } else {
+                            report.as_str()
+                        }
+                    ),
+                    Err(_) => log::error!(
+                        "Formatting the error from plugin {} ({:?}) failed, this is likely due to ABI unstability. Make sure your plugin was built with the same version of cargo as zenoh",
+                        name,
+                        path
+                    ),
+                }
+            }
+        }
+    }
+    log::info!("Finished loading plugins");
+
+    let mut config_guard = runtime.config.lock();
+    for (name, (_, plugin)) in plugins.running_plugins() {
+        let hook = plugin.config_checker();
+        config_guard.add_plugin_validator(name, hook)
+    }
+}
+
 pub struct RuntimeState {
     pub zid: ZenohId,
     pub whatami: WhatAmI,
@@ -58,6 +210,8 @@ pub struct RuntimeState {
     pub(crate) locators: std::sync::RwLock<Vec<Locator>>,
     pub hlc: Option<Arc<HLC>>,
     pub(crate) stop_source: std::sync::RwLock<Option<StopSource>>,
+    // The executor of the context the runtime was created in, running all its tasks
+    pub(crate) executor: Executor,
 }
 
 #[derive(Clone)]
@@ -73,16 +227,56 @@ impl std::ops::Deref for Runtime {
     }
 }
 
+// Returns whether gossip scouting is enabled, whether it is multihop and which
+// kinds of nodes discovered through gossip to connect to.
+fn gossip_config(config: &Config, whatami: WhatAmI) -> (bool, bool, WhatAmIMatcher) {
+    let gossip = unwrap_or_default!(config.scouting().gossip().enabled());
+    let gossip_multihop = unwrap_or_default!(config.scouting().gossip().multihop());
+    let autoconnect = if gossip {
+        *unwrap_or_default!(config.scouting().gossip().autoconnect().get(whatami))
+    } else {
+        WhatAmIMatcher::empty()
+    };
+    (gossip, gossip_multihop, autoconnect)
+}
+
 impl Runtime {
-    pub async fn new(config: Config) -> ZResult<Runtime> {
-        let mut runtime = Runtime::init(config).await?;
-        match runtime.start().await {
-            Ok(()) => Ok(runtime),
-            Err(err) => Err(err),
+    /// Returns a [`RuntimeBuilder`] to start a new [`Runtime`] with the given [`Config`].
+    ///
+    /// Resolving the builder starts a bare runtime. Call [`RuntimeBuilder::with_plugins`]
+    /// to also load the plugins declared in the configuration, start them and expose them
+    /// on the admin space, as `zenohd` does.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # #[cfg(feature = "unstable")]
+    /// # async_std::task::block_on(async {
+    /// use zenoh::prelude::r#async::*;
+    /// use zenoh::plugins::PluginsManager;
+    /// use zenoh::runtime::Runtime;
+    ///
+    /// let config = config::default();
+    /// let plugins = PluginsManager::dynamic(config.libloader());
+    /// let runtime = Runtime::new(config)
+    ///     .with_plugins(plugins)
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// # });
+    /// ```
+    pub fn new(config: Config) -> RuntimeBuilder {
+        RuntimeBuilder {
+            config,
+            plugins: None,
+            version: GIT_VERSION.to_string(),
+            interceptors: vec![],
         }
     }
 
-    pub(crate) async fn init(config: Config) -> ZResult<Runtime> {
+    pub(crate) async fn init(
+        config: Config,
+        interceptors: Vec<InterceptorFactory>,
+    ) -> ZResult<Runtime> {
         log::debug!("Zenoh Rust API {}", GIT_VERSION);
         // Make sure to have have enough threads spawned in the async futures executor
         zasync_executor_init!();
@@ -92,18 +286,19 @@ impl Runtime {
         log::info!("Using PID: {}", zid);
 
         let whatami = unwrap_or_default!(config.mode());
-        let hlc = (*unwrap_or_default!(config.timestamping().enabled().get(whatami)))
-            .then(|| Arc::new(HLCBuilder::new().with_id(uhlc::ID::from(&zid)).build()));
+        let hlc = (*unwrap_or_default!(config.timestamping().enabled().get(whatami))).then(|| {
+            #[allow(unused_mut)]
+            let mut hlc = HLCBuilder::new().with_id(uhlc::ID::from(&zid));
+            #[cfg(feature = "test")]
+            if let Some(clock) = crate::test::manual_clock() {
+                hlc = hlc.with_clock(clock);
+            }
+            Arc::new(hlc.build())
+        });
         let drop_future_timestamp =
             unwrap_or_default!(config.timestamping().drop_future_timestamp());
 
-        let gossip = unwrap_or_default!(config.scouting().gossip().enabled());
-        let gossip_multihop = unwrap_or_default!(config.scouting().gossip().multihop());
-        let autoconnect = if gossip {
-            *unwrap_or_default!(config.scouting().gossip().autoconnect().get(whatami))
-        } else {
-            WhatAmIMatcher::empty()
-        };
+        let (gossip, gossip_multihop, autoconnect) = gossip_config(&config, whatami);
 
         let router_link_state = whatami == WhatAmI::Router;
         let peer_link_state = whatami != WhatAmI::Client
@@ -120,17 +315,23 @@ impl Runtime {
             drop_future_timestamp,
             router_peers_failover_brokering,
             queries_default_timeout,
+            interceptor_factories(&config)?
+                .into_iter()
+                .chain(interceptors)
+                .collect(),
         ));
 
         let handler = Arc::new(RuntimeTransportEventHandler {
             runtime: std::sync::RwLock::new(None),
         });
 
+        let executor = Executor::current();
         let transport_manager = TransportManager::builder()
             .from_config(&config)
             .await?
             .whatami(whatami)
             .zid(zid)
+            .executor(executor.clone())
             .build(handler.clone())?;
 
         let config = Notifier::new(config);
@@ -146,6 +347,7 @@ impl Runtime {
                 locators: std::sync::RwLock::new(vec![]),
                 hlc,
                 stop_source: std::sync::RwLock::new(Some(StopSource::new())),
+                executor,
             }),
         };
         *handler.runtime.write().unwrap() = Some(runtime.clone());
@@ -169,6 +371,8 @@ impl Runtime {
                         if let Err(e) = runtime2.update_peers().await {
                             log::error!("Error updating peers : {}", e);
                         }
+                    } else if &*event == "scouting" || event.starts_with("scouting/gossip") {
+                        runtime2.update_gossip();
                     }
                 }
             }
@@ -177,6 +381,14 @@ impl Runtime {
         Ok(runtime)
     }
 
+    // Apply the gossip scouting configuration to the running gossip networks
+    pub(crate) fn update_gossip(&self) {
+        let (gossip, gossip_multihop, autoconnect) =
+            gossip_config(&self.config.lock(), self.whatami);
+        self.router
+            .update_gossip(self, gossip, gossip_multihop, autoconnect);
+    }
+
     #[inline(always)]
     pub fn manager(&self) -> &TransportManager {
         &self.manager
@@ -186,6 +398,11 @@ impl Runtime {
         zwrite!(self.state.transport_handlers).push(handler);
     }
 
+    pub fn del_handler(&self, handler: &Arc<dyn TransportEventHandler>) {
+        zwrite!(self.state.transport_handlers)
+            .retain(|h| Arc::as_ptr(h) as *const u8 != Arc::as_ptr(handler) as *const u8);
+    }
+
     pub async fn close(&self) -> ZResult<()> {
         log::trace!("Runtime::close())");
         drop(self.stop_source.write().unwrap().take());
@@ -210,7 +427,7 @@ impl Runtime {
             .read()
             .unwrap()
             .as_ref()
-            .map(|source| async_std::task::spawn(future.timeout_at(source.token())))
+            .map(|source| self.executor.spawn(future.timeout_at(source.token())))
     }
 }
 
@@ -235,7 +452,7 @@ impl TransportEventHandler for RuntimeTransportEventHandler {
                         .collect();
                 Ok(Arc::new(RuntimeSession {
                     runtime: runtime.clone(),
-                    endpoint: std::sync::RwLock::new(None),
+                    endpoints: std::sync::RwLock::new(vec![]),
                     main_handler: runtime.router.new_transport_unicast(transport).unwrap(),
                     slave_handlers,
                 }))
@@ -246,22 +463,65 @@ impl TransportEventHandler for RuntimeTransportEventHandler {
 
     fn new_multicast(
         &self,
-        _transport: TransportMulticast,
+        transport: TransportMulticast,
     ) -> ZResult<Arc<dyn TransportMulticastEventHandler>> {
-        // @TODO
-        unimplemented!();
+        match zread!(self.runtime).as_ref() {
+            Some(runtime) => {
+                let group = runtime.router.new_transport_multicast(transport.clone())?;
+                Ok(Arc::new(RuntimeMulticastGroup {
+                    runtime: runtime.clone(),
+                    transport,
+                    group,
+                    declarations: McastDeclarations::default(),
+                }))
+            }
+            None => bail!("Runtime not yet ready!"),
+        }
+    }
+}
+
+pub(super) struct RuntimeMulticastGroup {
+    pub(super) runtime: Runtime,
+    pub(super) transport: TransportMulticast,
+    pub(super) group: usize,
+    pub(super) declarations: McastDeclarations,
+}
+
+impl TransportMulticastEventHandler for RuntimeMulticastGroup {
+    fn new_peer(&self, peer: TransportPeer) -> ZResult<Arc<dyn TransportPeerEventHandler>> {
+        Ok(self.runtime.router.new_peer_multicast(
+            self.transport.clone(),
+            self.group,
+            self.declarations.clone(),
+            peer,
+        )?)
+    }
+
+    fn closing(&self) {
+        self.runtime.router.del_transport_multicast(self.group);
+    }
+
+    fn closed(&self) {}
+
+    fn as_any(&self) -> &dyn Any {
+        self
     }
 }
 
 pub(super) struct RuntimeSession {
     pub(super) runtime: Runtime,
-    pub(super) endpoint: std::sync::RwLock<Option<EndPoint>>,
+    pub(super) endpoints: std::sync::RwLock<Vec<EndPoint>>,
     pub(super) main_handler: Arc<LinkStateInterceptor>,
     pub(super) slave_handlers: Vec<Arc<dyn TransportPeerEventHandler>>,
 }
 
 impl TransportPeerEventHandler for RuntimeSession {
-    fn handle_message(&self, mut msg: ZenohMessage) -> ZResult<()> {
+    fn handle_message(&self, msg: ZenohMessage) -> ZResult<()> {
+        let mut msg = match zenoh_transport::intercept(&self.main_handler.ingress, msg) {
+            Some(msg) => msg,
+            None => return Ok(()),
+        };
+
         // critical path shortcut
         if let ZenohBody::Data(data) = msg.body {
             if data.reply_context.is_none() {
@@ -293,6 +553,13 @@ impl TransportPeerEventHandler for RuntimeSession {
     }
 
     fn del_link(&self, link: Link) {
+        // The link is only removed from the transport if it is not the last one,
+        // otherwise the whole session is closing and will be handled in closing()
+        if let Ok(links) = self.main_handler.transport.get_links() {
+            if !links.iter().any(|l| l.src == link.src && l.dst == link.dst) {
+                Runtime::closing_link(self, &link);
+            }
+        }
         self.main_handler.del_link(link.clone());
         for handler in &self.slave_handlers {
             handler.del_link(link.clone());
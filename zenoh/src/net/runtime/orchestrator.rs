@@ -23,7 +23,7 @@ use zenoh_buffers::SplitBuffer;
 use zenoh_config::{unwrap_or_default, EndPoint, ModeDependent};
 use zenoh_core::Result as ZResult;
 use zenoh_core::{bail, zerror};
-use zenoh_link::Locator;
+use zenoh_link::{Link, Locator};
 use zenoh_protocol::io::{WBuf, ZBuf};
 use zenoh_protocol::proto::{Hello, Scout, TransportBody, TransportMessage};
 use zenoh_protocol::proto::{MessageReader, MessageWriter};
@@ -195,7 +195,7 @@ impl Runtime {
                 let this = self.clone();
                 match (listen, autoconnect.is_empty()) {
                     (true, false) => {
-                        async_std::task::spawn(async move {
+                        self.executor.spawn(async move {
                             async_std::prelude::FutureExt::race(
                                 this.responder(&mcast_socket, &sockets),
                                 this.connect_all(&sockets, autoconnect, &addr),
@@ -204,12 +204,12 @@ impl Runtime {
                         });
                     }
                     (true, true) => {
-                        async_std::task::spawn(async move {
+                        self.executor.spawn(async move {
                             this.responder(&mcast_socket, &sockets).await;
                         });
                     }
                     (false, false) => {
-                        async_std::task::spawn(async move {
+                        self.executor.spawn(async move {
                             this.connect_all(&sockets, autoconnect, &addr).await
                         });
                     }
@@ -233,11 +233,9 @@ impl Runtime {
                     .as_any()
                     .downcast_ref::<super::RuntimeSession>()
                 {
-                    if let Some(endpoint) = &*zread!(orch_transport.endpoint) {
-                        !peers.contains(endpoint)
-                    } else {
-                        true
-                    }
+                    !zread!(orch_transport.endpoints)
+                        .iter()
+                        .any(|endpoint| peers.contains(endpoint))
                 } else {
                     false
                 };
@@ -255,9 +253,7 @@ impl Runtime {
                         .as_any()
                         .downcast_ref::<super::RuntimeSession>()
                     {
-                        if let Some(endpoint) = &*zread!(orch_transport.endpoint) {
-                            return *endpoint == peer;
-                        }
+                        return zread!(orch_transport.endpoints).contains(&peer);
                     }
                     false
                 }) {
@@ -444,7 +440,10 @@ impl Runtime {
                         .as_any()
                         .downcast_ref::<super::RuntimeSession>()
                     {
-                        *zwrite!(orch_transport.endpoint) = Some(peer);
+                        let mut endpoints = zwrite!(orch_transport.endpoints);
+                        if !endpoints.contains(&peer) {
+                            endpoints.push(peer);
+                        }
                     }
                     break;
                 }
@@ -751,10 +750,9 @@ impl Runtime {
                 });
             }
             _ => {
-                if let Some(endpoint) = &*zread!(session.endpoint) {
-                    let peers = { session.runtime.config.lock().connect().endpoints().clone() };
-                    if peers.contains(endpoint) {
-                        let endpoint = endpoint.clone();
+                let peers = { session.runtime.config.lock().connect().endpoints().clone() };
+                for endpoint in zwrite!(session.endpoints).drain(..) {
+                    if peers.contains(&endpoint) {
                         let runtime = session.runtime.clone();
                         session
                             .runtime
@@ -764,4 +762,23 @@ impl Runtime {
             }
         }
     }
+
+    pub(super) fn closing_link(session: &RuntimeSession, link: &Link) {
+        // The session survives on its remaining links: reconnect the configured endpoint
+        // of the lost link in background so that it is available again as a backup
+        let mut endpoints = zwrite!(session.endpoints);
+        if let Some(index) = endpoints.iter().position(|endpoint| {
+            endpoint.locator.protocol() == link.dst.protocol()
+                && endpoint.locator.address() == link.dst.address()
+        }) {
+            let endpoint = endpoints.remove(index);
+            let peers = { session.runtime.config.lock().connect().endpoints().clone() };
+            if peers.contains(&endpoint) {
+                let runtime = session.runtime.clone();
+                session
+                    .runtime
+                    .spawn(async move { runtime.peer_connector(endpoint).await });
+            }
+        }
+    }
 }
@@ -11,14 +11,16 @@
 // Contributors:
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
+use crate::net::routing::face::FaceState;
 use crate::net::routing::router::*;
 use crate::prelude::keyexpr;
 use std::convert::{TryFrom, TryInto};
-use std::sync::{Arc, RwLock};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock, Weak};
 use std::time::Duration;
 use uhlc::HLC;
 use zenoh_config::ZN_QUERIES_DEFAULT_TIMEOUT_DEFAULT;
-use zenoh_core::zlock;
+use zenoh_core::{zlock, zwrite};
 use zenoh_protocol::io::ZBuf;
 use zenoh_protocol::proto::{DataInfo, QueryBody, RoutingContext};
 use zenoh_protocol_core::{
@@ -712,3 +714,360 @@ fn client_test() {
     // mapping strategy check
     // assert_eq!(primitives2.get_last_key().unwrap(), KeyExpr::IdWithSuffix(31, "/z2_pub1".to_string()));
 }
+
+struct McastPrimitives {
+    data_count: AtomicUsize,
+}
+
+impl McastPrimitives {
+    fn new() -> McastPrimitives {
+        McastPrimitives {
+            data_count: AtomicUsize::new(0),
+        }
+    }
+
+    fn take_data_count(&self) -> usize {
+        self.data_count.swap(0, Ordering::SeqCst)
+    }
+}
+
+impl Primitives for McastPrimitives {
+    fn decl_resource(&self, _expr_id: ZInt, _key_expr: &WireExpr) {}
+    fn forget_resource(&self, _expr_id: ZInt) {}
+
+    fn decl_publisher(&self, _key_expr: &WireExpr, _routing_context: Option<RoutingContext>) {}
+    fn forget_publisher(&self, _key_expr: &WireExpr, _routing_context: Option<RoutingContext>) {}
+
+    fn decl_subscriber(
+        &self,
+        _key_expr: &WireExpr,
+        _sub_info: &SubInfo,
+        _routing_context: Option<RoutingContext>,
+    ) {
+    }
+    fn forget_subscriber(&self, _key_expr: &WireExpr, _routing_context: Option<RoutingContext>) {}
+
+    fn decl_queryable(
+        &self,
+        _key_expr: &WireExpr,
+        _qabl_info: &QueryableInfo,
+        _routing_context: Option<RoutingContext>,
+    ) {
+    }
+    fn forget_queryable(&self, _key_expr: &WireExpr, _routing_context: Option<RoutingContext>) {}
+
+    fn send_data(
+        &self,
+        _key_expr: &WireExpr,
+        _payload: ZBuf,
+        _channel: Channel,
+        _congestion_control: CongestionControl,
+        _info: Option<DataInfo>,
+        _routing_context: Option<RoutingContext>,
+    ) {
+        self.data_count.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn send_query(
+        &self,
+        _key_expr: &WireExpr,
+        _parameters: &str,
+        _qid: ZInt,
+        _target: QueryTarget,
+        _consolidation: ConsolidationMode,
+        _body: Option<QueryBody>,
+        _routing_context: Option<RoutingContext>,
+    ) {
+    }
+
+    fn send_reply_data(
+        &self,
+        _qid: ZInt,
+        _replier_id: ZenohId,
+        _key_expr: WireExpr,
+        _info: Option<DataInfo>,
+        _payload: ZBuf,
+    ) {
+    }
+    fn send_reply_final(&self, _qid: ZInt) {}
+
+    fn send_pull(
+        &self,
+        _is_final: bool,
+        _key_expr: &WireExpr,
+        _pull_id: ZInt,
+        _max_samples: &Option<ZInt>,
+    ) {
+    }
+
+    fn send_close(&self) {}
+}
+
+#[test]
+fn mcast_test() {
+    let mut tables = RwLock::new(Tables::new(
+        ZenohId::try_from([1]).unwrap(),
+        WhatAmI::Peer,
+        Some(Arc::new(HLC::default())),
+        false,
+        true,
+        Duration::from_millis(ZN_QUERIES_DEFAULT_TIMEOUT_DEFAULT.parse().unwrap()),
+    ));
+
+    let sub_info = SubInfo {
+        reliability: Reliability::Reliable,
+        mode: SubMode::Push,
+    };
+
+    let tables_mutref = tables.get_mut().unwrap();
+    let primitives0 = Arc::new(ClientPrimitives::new());
+    let face0 = tables_mutref.open_face(
+        ZenohId::try_from([1]).unwrap(),
+        WhatAmI::Client,
+        primitives0.clone(),
+    );
+
+    // All the remote peers of a group share the primitives of the group
+    let group_primitives = Arc::new(McastPrimitives::new());
+    let group = tables_mutref
+        .open_mcast_group(group_primitives.clone())
+        .upgrade()
+        .unwrap()
+        .id;
+    let face1 = tables_mutref.open_mcast_face(
+        ZenohId::try_from([2]).unwrap(),
+        WhatAmI::Peer,
+        group_primitives.clone(),
+        group,
+    );
+    let face2 = tables_mutref.open_mcast_face(
+        ZenohId::try_from([3]).unwrap(),
+        WhatAmI::Peer,
+        group_primitives.clone(),
+        group,
+    );
+
+    let route = |tables: &RwLock<Tables>, face: &Weak<FaceState>, key_expr: &str| {
+        full_reentrant_route_data(
+            tables,
+            &face.upgrade().unwrap(),
+            &key_expr.into(),
+            Channel::default(),
+            CongestionControl::default(),
+            None,
+            ZBuf::default(),
+            None,
+        )
+    };
+
+    // No subscriber was learned through the group: nothing is sent on it
+    route(&tables, &face0, "test/mcast/a");
+    assert_eq!(group_primitives.take_data_count(), 0);
+
+    let tables_mutref = tables.get_mut().unwrap();
+    for face in [&face1, &face2] {
+        declare_client_subscription(
+            tables_mutref,
+            &mut face.upgrade().unwrap(),
+            &"test/mcast/**".into(),
+            &sub_info,
+        );
+    }
+    declare_client_subscription(
+        tables_mutref,
+        &mut face0.upgrade().unwrap(),
+        &"test/mcast/**".into(),
+        &sub_info,
+    );
+
+    // Two remote subscribers learned through the group: the data is sent once on it
+    route(&tables, &face0, "test/mcast/a");
+    assert_eq!(group_primitives.take_data_count(), 1);
+
+    // Data received from the group is delivered locally but never sent back on the group
+    primitives0.clear_data();
+    route(&tables, &face1, "test/mcast/b");
+    assert_eq!(group_primitives.take_data_count(), 0);
+    assert_eq!(primitives0.get_last_name().unwrap(), "test/mcast/b");
+
+    // Closing the group closes the faces of its peers and the data is no longer sent on it
+    let tables_mutref = tables.get_mut().unwrap();
+    tables_mutref.close_mcast_group(group);
+    for face in [&face1, &face2] {
+        let id = face.upgrade().map(|face| face.id);
+        assert!(id.map_or(true, |id| !tables_mutref.faces.contains_key(&id)));
+    }
+    route(&tables, &face0, "test/mcast/a");
+    assert_eq!(group_primitives.take_data_count(), 0);
+}
+
+struct AckPrimitives {
+    ack_ids: std::sync::Mutex<Vec<ZInt>>,
+    finals: std::sync::Mutex<Vec<ZInt>>,
+}
+
+impl AckPrimitives {
+    fn new() -> AckPrimitives {
+        AckPrimitives {
+            ack_ids: std::sync::Mutex::new(vec![]),
+            finals: std::sync::Mutex::new(vec![]),
+        }
+    }
+
+    fn take_ack_ids(&self) -> Vec<ZInt> {
+        std::mem::take(&mut *zlock!(self.ack_ids))
+    }
+
+    fn take_finals(&self) -> Vec<ZInt> {
+        std::mem::take(&mut *zlock!(self.finals))
+    }
+}
+
+impl Primitives for AckPrimitives {
+    fn decl_resource(&self, _expr_id: ZInt, _key_expr: &WireExpr) {}
+    fn forget_resource(&self, _expr_id: ZInt) {}
+
+    fn decl_publisher(&self, _key_expr: &WireExpr, _routing_context: Option<RoutingContext>) {}
+    fn forget_publisher(&self, _key_expr: &WireExpr, _routing_context: Option<RoutingContext>) {}
+
+    fn decl_subscriber(
+        &self,
+        _key_expr: &WireExpr,
+        _sub_info: &SubInfo,
+        _routing_context: Option<RoutingContext>,
+    ) {
+    }
+    fn forget_subscriber(&self, _key_expr: &WireExpr, _routing_context: Option<RoutingContext>) {}
+
+    fn decl_queryable(
+        &self,
+        _key_expr: &WireExpr,
+        _qabl_info: &QueryableInfo,
+        _routing_context: Option<RoutingContext>,
+    ) {
+    }
+    fn forget_queryable(&self, _key_expr: &WireExpr, _routing_context: Option<RoutingContext>) {}
+
+    fn send_data(
+        &self,
+        _key_expr: &WireExpr,
+        _payload: ZBuf,
+        _channel: Channel,
+        _congestion_control: CongestionControl,
+        info: Option<DataInfo>,
+        _routing_context: Option<RoutingContext>,
+    ) {
+        if let Some(ack_id) = info.and_then(|info| info.ack_id) {
+            zlock!(self.ack_ids).push(ack_id);
+        }
+    }
+
+    fn send_query(
+        &self,
+        _key_expr: &WireExpr,
+        _parameters: &str,
+        _qid: ZInt,
+        _target: QueryTarget,
+        _consolidation: ConsolidationMode,
+        _body: Option<QueryBody>,
+        _routing_context: Option<RoutingContext>,
+    ) {
+    }
+
+    fn send_reply_data(
+        &self,
+        _qid: ZInt,
+        _replier_id: ZenohId,
+        _key_expr: WireExpr,
+        _info: Option<DataInfo>,
+        _payload: ZBuf,
+    ) {
+    }
+    fn send_reply_final(&self, qid: ZInt) {
+        zlock!(self.finals).push(qid);
+    }
+
+    fn send_pull(
+        &self,
+        _is_final: bool,
+        _key_expr: &WireExpr,
+        _pull_id: ZInt,
+        _max_samples: &Option<ZInt>,
+    ) {
+    }
+
+    fn send_close(&self) {}
+}
+
+#[test]
+fn ack_timeout_test() {
+    let tables = Arc::new(RwLock::new(Tables::new(
+        ZenohId::try_from([1]).unwrap(),
+        WhatAmI::Peer,
+        Some(Arc::new(HLC::default())),
+        false,
+        true,
+        Duration::from_millis(100),
+    )));
+
+    let sub_info = SubInfo {
+        reliability: Reliability::Reliable,
+        mode: SubMode::Push,
+    };
+
+    // A local session publishing acked data and a remote subscriber
+    let local_primitives = Arc::new(AckPrimitives::new());
+    let remote_primitives = Arc::new(AckPrimitives::new());
+    let (local_face, remote_face) = {
+        let mut tables_mutref = zwrite!(tables);
+        let local_face = tables_mutref.open_face(
+            ZenohId::try_from([1]).unwrap(),
+            WhatAmI::Client,
+            local_primitives.clone(),
+        );
+        let remote_face = tables_mutref.open_face(
+            ZenohId::try_from([2]).unwrap(),
+            WhatAmI::Client,
+            remote_primitives.clone(),
+        );
+        declare_client_subscription(
+            &mut tables_mutref,
+            &mut remote_face.upgrade().unwrap(),
+            &"test/ack/**".into(),
+            &sub_info,
+        );
+        (
+            local_face.upgrade().unwrap(),
+            remote_face.upgrade().unwrap(),
+        )
+    };
+
+    let route = |ack_id: ZInt| {
+        full_reentrant_route_acked_data(
+            &tables,
+            &local_face,
+            &"test/ack/a".into(),
+            Channel::default(),
+            CongestionControl::default(),
+            None,
+            ZBuf::default(),
+            None,
+            ack_id,
+        )
+    };
+
+    // The acknowledgment of the remote face is propagated to the local session
+    route(1);
+    let ack_ids = remote_primitives.take_ack_ids();
+    assert_eq!(ack_ids.len(), 1);
+    route_send_reply_final(&tables, &mut remote_face.clone(), ack_ids[0]);
+    assert_eq!(local_primitives.take_finals(), vec![1]);
+
+    // An acknowledgment not received within the queries timeout is dropped
+    route(2);
+    let ack_ids = remote_primitives.take_ack_ids();
+    assert_eq!(ack_ids.len(), 1);
+    std::thread::sleep(Duration::from_millis(500));
+    route_send_reply_final(&tables, &mut remote_face.clone(), ack_ids[0]);
+    assert!(local_primitives.take_finals().is_empty());
+}
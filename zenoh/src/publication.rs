@@ -14,24 +14,74 @@
 
 //! Publishing primitives.
 
+use crate::encryption::outgoing_payload;
+use crate::info::PublisherDeclaration;
 use crate::net::transport::Primitives;
 use crate::prelude::*;
+use crate::session::SessionState;
 use crate::subscriber::Reliability;
 use crate::Encoding;
+use crate::Session;
 use crate::SessionRef;
 use crate::Undeclarable;
+use futures::future::{BoxFuture, Either, FutureExt as _};
+use std::fmt;
 use std::future::Ready;
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+use zenoh_buffers::buffer::InsertBuffer;
+use zenoh_buffers::{ZBuf, ZSlice};
+use zenoh_config::unwrap_or_default;
 use zenoh_core::zresult::ZResult;
 use zenoh_core::AsyncResolve;
 use zenoh_core::Resolvable;
 use zenoh_core::Resolve;
-use zenoh_core::{zread, SyncResolve};
+use zenoh_core::{zlock, zread, zwrite, SyncResolve};
 use zenoh_protocol::proto::{DataInfo, Options};
 use zenoh_protocol_core::Channel;
+use zenoh_protocol_core::Timestamp;
+use zenoh_protocol_core::ZInt;
+use zenoh_sync::Executor;
 
 /// The kind of congestion control.
 pub use zenoh_protocol_core::CongestionControl;
 
+/// The maximum size of the chunks read by [`PutBuilder::payload_from_reader`].
+pub const PAYLOAD_CHUNK_SIZE: usize = 65_536;
+
+/// A reader set by [`PutBuilder::payload_from_reader`], only read when the put is resolved.
+#[derive(Clone)]
+pub(crate) struct PayloadReader {
+    reader: Arc<Mutex<Box<dyn std::io::Read + Send>>>,
+    len: usize,
+}
+
+impl PayloadReader {
+    fn read(self) -> ZResult<ZBuf> {
+        let mut reader = zlock!(self.reader);
+        let mut payload = ZBuf::default();
+        let mut remaining = self.len;
+        while remaining > 0 {
+            let mut chunk = vec![0; remaining.min(PAYLOAD_CHUNK_SIZE)];
+            reader
+                .read_exact(&mut chunk)
+                .map_err(|e| zerror!("Failed to read the payload: {}", e))?;
+            remaining -= chunk.len();
+            payload.append(ZSlice::from(chunk));
+        }
+        Ok(payload)
+    }
+}
+
+impl fmt::Debug for PayloadReader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PayloadReader")
+            .field("len", &self.len)
+            .finish()
+    }
+}
+
 /// A builder for initializing a [`delete`](crate::Session::delete) operation.
 ///
 /// # Examples
@@ -43,12 +93,88 @@ pub use zenoh_protocol_core::CongestionControl;
 /// let session = zenoh::open(config::peer()).res().await.unwrap();
 /// session
 ///     .delete("key/expression")
+///     .congestion_control(CongestionControl::Block)
 ///     .res()
 ///     .await
 ///     .unwrap();
 /// # })
 /// ```
-pub type DeleteBuilder<'a, 'b> = PutBuilder<'a, 'b>;
+#[derive(Debug, Clone)]
+pub struct DeleteBuilder<'a, 'b> {
+    pub(crate) publisher: PublisherBuilder<'a, 'b>,
+    pub(crate) timestamp: Option<Timestamp>,
+    pub(crate) attachment: Option<ZBuf>,
+}
+
+impl DeleteBuilder<'_, '_> {
+    /// Change the `congestion_control` to apply when routing the deletion.
+    #[inline]
+    pub fn congestion_control(mut self, congestion_control: CongestionControl) -> Self {
+        self.publisher = self.publisher.congestion_control(congestion_control);
+        self
+    }
+
+    /// Change the priority of the deletion.
+    #[inline]
+    pub fn priority(mut self, priority: Priority) -> Self {
+        self.publisher = self.publisher.priority(priority);
+        self
+    }
+
+    /// Set the timestamp of the deletion, instead of the one generated by the session.
+    #[inline]
+    pub fn timestamp(mut self, timestamp: Timestamp) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+
+    /// Restrict the matching subscribers that will receive the deletion
+    /// to the ones that have the given [`Locality`](crate::prelude::Locality).
+    #[zenoh_core::unstable]
+    #[inline]
+    pub fn allowed_destination(mut self, destination: Locality) -> Self {
+        self.publisher = self.publisher.allowed_destination(destination);
+        self
+    }
+
+    /// Attach some data to the deletion, received by the subscribers in [`Sample::attachment`].
+    #[zenoh_core::unstable]
+    #[inline]
+    pub fn attachment<IntoZBuf>(mut self, attachment: IntoZBuf) -> Self
+    where
+        IntoZBuf: Into<ZBuf>,
+    {
+        self.attachment = Some(attachment.into());
+        self
+    }
+}
+
+impl Resolvable for DeleteBuilder<'_, '_> {
+    type To = ZResult<()>;
+}
+
+impl SyncResolve for DeleteBuilder<'_, '_> {
+    #[inline]
+    fn res_sync(self) -> <Self as Resolvable>::To {
+        resolve_put(
+            self.publisher,
+            Value::empty(),
+            SampleKind::Delete,
+            self.timestamp,
+            self.attachment,
+            false,
+        )
+        .map(|_| ())
+    }
+}
+
+impl AsyncResolve for DeleteBuilder<'_, '_> {
+    type Future = Ready<Self::To>;
+
+    fn res_async(self) -> Self::Future {
+        std::future::ready(self.res_sync())
+    }
+}
 
 /// A builder for initializing a [`put`](crate::Session::put) operation.
 ///
@@ -71,8 +197,11 @@ pub type DeleteBuilder<'a, 'b> = PutBuilder<'a, 'b>;
 #[derive(Debug, Clone)]
 pub struct PutBuilder<'a, 'b> {
     pub(crate) publisher: PublisherBuilder<'a, 'b>,
-    pub(crate) value: Value,
+    pub(crate) value: ZResult<Value>,
+    pub(crate) payload_reader: Option<PayloadReader>,
     pub(crate) kind: SampleKind,
+    pub(crate) acked: bool,
+    pub(crate) attachment: Option<ZBuf>,
 }
 
 impl PutBuilder<'_, '_> {
@@ -82,7 +211,42 @@ impl PutBuilder<'_, '_> {
     where
         IntoEncoding: Into<Encoding>,
     {
-        self.value.encoding = encoding.into();
+        if let Ok(value) = &mut self.value {
+            value.encoding = encoding.into();
+        }
+        self
+    }
+
+    /// Read the payload of the written data from the given reader, in chunks of
+    /// at most [`PAYLOAD_CHUNK_SIZE`] bytes, without assembling it in one contiguous buffer.
+    ///
+    /// The reader is only read when the put is resolved, and exactly `len` bytes are read:
+    /// the put fails if the reader fails or ends before.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # async_std::task::block_on(async {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let file = std::fs::File::open("video.mp4").unwrap();
+    /// let len = file.metadata().unwrap().len() as usize;
+    /// session
+    ///     .put("key/expression", Value::empty())
+    ///     .payload_from_reader(file, len)
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// # })
+    /// ```
+    pub fn payload_from_reader<R>(mut self, reader: R, len: usize) -> Self
+    where
+        R: std::io::Read + Send + 'static,
+    {
+        self.payload_reader = Some(PayloadReader {
+            reader: Arc::new(Mutex::new(Box::new(reader))),
+            len,
+        });
         self
     }
     /// Change the `congestion_control` to apply when routing the data.
@@ -108,10 +272,69 @@ impl PutBuilder<'_, '_> {
         self
     }
 
+    #[deprecated(note = "use `Session::delete` to delete a key expression")]
     pub fn kind(mut self, kind: SampleKind) -> Self {
         self.kind = kind;
         self
     }
+
+    /// Attach some data to the written data, received by the subscribers in [`Sample::attachment`].
+    ///
+    /// # Examples
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// session
+    ///     .put("key/expression", "value")
+    ///     .attachment(b"metadata".to_vec())
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// # })
+    /// ```
+    #[zenoh_core::unstable]
+    #[inline]
+    pub fn attachment<IntoZBuf>(mut self, attachment: IntoZBuf) -> Self
+    where
+        IntoZBuf: Into<ZBuf>,
+    {
+        self.attachment = Some(attachment.into());
+        self
+    }
+
+    /// Wait for the written data to be acknowledged before resolving (`false` by default).
+    ///
+    /// When acked, the put resolves once the data has been received by the first hop of
+    /// each of its remote destinations: the router a client is connected to, or the peers
+    /// with matching subscribers. The put fails if the acknowledgment is not received
+    /// within the `queries_default_timeout` of the configuration, e.g. because a
+    /// destination disconnected meanwhile, in which case the data may have been lost.
+    ///
+    /// The destinations reached through a multicast group, or through a peer that does not
+    /// support acknowledgments, can not acknowledge the data: the put does not wait for them.
+    ///
+    /// # Examples
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// session
+    ///     .put("key/expression", "value")
+    ///     .acked(true)
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// # })
+    /// ```
+    #[zenoh_core::unstable]
+    #[inline]
+    pub fn acked(mut self, acked: bool) -> Self {
+        self.acked = acked;
+        self
+    }
 }
 
 impl Resolvable for PutBuilder<'_, '_> {
@@ -124,59 +347,194 @@ impl SyncResolve for PutBuilder<'_, '_> {
         let PutBuilder {
             publisher,
             value,
+            payload_reader,
+            kind,
+            acked,
+            attachment,
+        } = self;
+        let mut value = value?;
+        if let Some(payload_reader) = payload_reader {
+            value.payload = payload_reader.read()?;
+        }
+        match resolve_put(publisher, value, kind, None, attachment, acked)? {
+            Some(ack) => ack.wait(),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<'a, 'b: 'a> AsyncResolve for PutBuilder<'a, 'b> {
+    type Future = Either<Ready<Self::To>, BoxFuture<'a, Self::To>>;
+
+    fn res_async(self) -> Self::Future {
+        let PutBuilder {
+            publisher,
+            value,
+            payload_reader,
             kind,
+            acked,
+            attachment,
         } = self;
-        let key_expr = publisher.key_expr?;
-        log::trace!("write({:?}, [...])", &key_expr);
-        let primitives = zread!(publisher.session.state)
-            .primitives
-            .as_ref()
-            .unwrap()
-            .clone();
-
-        let mut info = DataInfo::new();
-        info.kind = kind;
-        info.encoding = if value.encoding != Encoding::default() {
-            Some(value.encoding)
-        } else {
-            None
+        let mut value = match value {
+            Ok(value) => value,
+            Err(e) => return Either::Left(std::future::ready(Err(e))),
         };
-        info.timestamp = publisher.session.runtime.new_timestamp();
-        let data_info = if info.has_options() { Some(info) } else { None };
-
-        if publisher.destination != Locality::SessionLocal {
-            primitives.send_data(
-                &key_expr.to_wire(&publisher.session),
-                value.payload.clone(),
-                Channel {
-                    priority: publisher.priority.into(),
-                    reliability: Reliability::Reliable, // @TODO: need to check subscriptions to determine the right reliability value
-                },
-                publisher.congestion_control,
-                data_info.clone(),
-                None,
+        if let Some(payload_reader) = payload_reader {
+            // The reader may block: read it out of the async executor
+            return Either::Right(
+                async move {
+                    value.payload = Executor::current()
+                        .spawn_blocking(move || payload_reader.read())
+                        .await?;
+                    match resolve_put(publisher, value, kind, None, attachment, acked)? {
+                        Some(ack) => ack.wait_async().await,
+                        None => Ok(()),
+                    }
+                }
+                .boxed(),
             );
         }
-        if publisher.destination != Locality::Remote {
-            publisher.session.handle_data(
-                true,
-                &key_expr.to_wire(&publisher.session),
-                data_info,
-                value.payload,
-            );
+        match resolve_put(publisher, value, kind, None, attachment, acked) {
+            Ok(Some(ack)) => Either::Right(ack.wait_async().boxed()),
+            Ok(None) => Either::Left(std::future::ready(Ok(()))),
+            Err(e) => Either::Left(std::future::ready(Err(e))),
         }
-        Ok(())
     }
 }
 
-impl AsyncResolve for PutBuilder<'_, '_> {
-    type Future = Ready<Self::To>;
+/// The acknowledgment awaited by an acked put.
+struct PendingPutAck {
+    state: Arc<RwLock<SessionState>>,
+    ack_id: ZInt,
+    receiver: flume::Receiver<()>,
+    timeout: Duration,
+}
 
-    fn res_async(self) -> Self::Future {
-        std::future::ready(self.res_sync())
+impl PendingPutAck {
+    fn wait(self) -> ZResult<()> {
+        match self.receiver.recv_timeout(self.timeout) {
+            Ok(()) => Ok(()),
+            Err(_) => self.fail(),
+        }
+    }
+
+    async fn wait_async(self) -> ZResult<()> {
+        match async_std::future::timeout(self.timeout, self.receiver.recv_async()).await {
+            Ok(Ok(())) => Ok(()),
+            _ => self.fail(),
+        }
+    }
+
+    fn fail(self) -> ZResult<()> {
+        zwrite!(self.state).pending_acks.remove(&self.ack_id);
+        bail!(
+            "Put {} was not acknowledged within {:?}",
+            self.ack_id,
+            self.timeout
+        )
     }
 }
 
+fn resolve_put(
+    publisher: PublisherBuilder<'_, '_>,
+    value: Value,
+    kind: SampleKind,
+    timestamp: Option<Timestamp>,
+    attachment: Option<ZBuf>,
+    acked: bool,
+) -> ZResult<Option<PendingPutAck>> {
+    let key_expr = publisher.key_expr?;
+    send_put(
+        &publisher.session,
+        &key_expr,
+        publisher.priority,
+        publisher.congestion_control,
+        publisher.destination,
+        value,
+        kind,
+        timestamp,
+        attachment,
+        acked,
+    )
+}
+
+/// Sends a put or a delete to the remote and/or the local subscribers, depending on
+/// `destination`. Returns the acknowledgment to wait for if `acked`.
+#[allow(clippy::too_many_arguments)]
+fn send_put(
+    session: &Session,
+    key_expr: &KeyExpr,
+    priority: Priority,
+    congestion_control: CongestionControl,
+    destination: Locality,
+    value: Value,
+    kind: SampleKind,
+    timestamp: Option<Timestamp>,
+    attachment: Option<ZBuf>,
+    acked: bool,
+) -> ZResult<Option<PendingPutAck>> {
+    log::trace!("write({:?}, [...])", key_expr);
+    let (primitives, encryption) = {
+        let state = zread!(session.state);
+        (
+            state.primitives.as_ref().unwrap().clone(),
+            state.encryption.clone(),
+        )
+    };
+
+    let mut info = DataInfo::new();
+    info.kind = kind;
+    info.encoding = if value.encoding != Encoding::default() {
+        Some(value.encoding)
+    } else {
+        None
+    };
+    info.timestamp = timestamp.or_else(|| session.runtime.new_timestamp());
+    info.wildcard = kind == SampleKind::Delete && key_expr.is_wild();
+    info.attachment = crate::telemetry::wrap(crate::telemetry::inject_current(), attachment);
+    let data_info = if info.has_options() { Some(info) } else { None };
+
+    let mut pending_ack = None;
+    if destination != Locality::SessionLocal {
+        let payload = outgoing_payload(encryption.as_deref(), key_expr, kind, &value.payload)?;
+        let mut net_info = data_info.clone();
+        if acked {
+            let (sender, receiver) = flume::bounded(1);
+            let mut state = zwrite!(session.state);
+            let ack_id = state.qid_counter.fetch_add(1, Ordering::SeqCst);
+            state.pending_acks.insert(ack_id, sender);
+            drop(state);
+            net_info.get_or_insert_with(DataInfo::new).ack_id = Some(ack_id);
+            let timeout = Duration::from_millis(unwrap_or_default!(session
+                .runtime
+                .config
+                .lock()
+                .queries_default_timeout()));
+            pending_ack = Some(PendingPutAck {
+                state: session.state.clone(),
+                ack_id,
+                receiver,
+                timeout,
+            });
+        }
+        primitives.send_data(
+            &key_expr.to_wire(session),
+            payload,
+            Channel {
+                priority: priority.into(),
+                reliability: Reliability::Reliable, // @TODO: need to check subscriptions to determine the right reliability value
+            },
+            congestion_control,
+            net_info,
+            None,
+        );
+    }
+    if destination != Locality::Remote {
+        session.handle_data(true, &key_expr.to_wire(session), data_info, value.payload);
+    }
+    Ok(pending_ack)
+}
+
 use futures::Sink;
 use std::convert::TryFrom;
 use std::convert::TryInto;
@@ -216,10 +574,13 @@ use zenoh_core::zresult::Error;
 #[derive(Debug, Clone)]
 pub struct Publisher<'a> {
     pub(crate) session: SessionRef<'a>,
+    pub(crate) id: Id,
     pub(crate) key_expr: KeyExpr<'a>,
     pub(crate) congestion_control: CongestionControl,
     pub(crate) priority: Priority,
     pub(crate) destination: Locality,
+    pub(crate) rate_limiter: Option<Arc<RateLimiter>>,
+    pub(crate) sink_delayed: SinkDelayed,
 }
 
 impl<'a> Publisher<'a> {
@@ -255,6 +616,8 @@ impl<'a> Publisher<'a> {
             publisher: self,
             value,
             kind,
+            timestamp: None,
+            attachment: None,
         }
     }
 
@@ -286,7 +649,12 @@ impl<'a> Publisher<'a> {
     ///
     /// let session = zenoh::open(config::peer()).res().await.unwrap().into_arc();
     /// let publisher = session.declare_publisher("key/expression").res().await.unwrap();
-    /// publisher.put("value").res().await.unwrap();
+    /// publisher
+    ///     .put("value")
+    ///     .encoding(KnownEncoding::TextPlain)
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
     /// # })
     /// ```
     #[inline]
@@ -297,6 +665,31 @@ impl<'a> Publisher<'a> {
         self._write(SampleKind::Put, value.into())
     }
 
+    /// Put data right away, without going through a builder.
+    ///
+    /// This is equivalent to `publisher.put(value).res_sync()`, and is meant for
+    /// hot loops that don't need to set any per-message option.
+    ///
+    /// # Examples
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap().into_arc();
+    /// let publisher = session.declare_publisher("key/expression").res().await.unwrap();
+    /// for i in 0..10 {
+    ///     publisher.put_now(i).unwrap();
+    /// }
+    /// # })
+    /// ```
+    #[inline]
+    pub fn put_now<IntoValue>(&self, value: IntoValue) -> ZResult<()>
+    where
+        IntoValue: Into<Value>,
+    {
+        resolve_publication(self, value.into(), SampleKind::Put, None, None)
+    }
+
     /// Delete data.
     ///
     /// # Examples
@@ -309,8 +702,33 @@ impl<'a> Publisher<'a> {
     /// publisher.delete().res().await.unwrap();
     /// # })
     /// ```
-    pub fn delete(&self) -> Publication {
-        self._write(SampleKind::Delete, Value::empty())
+    pub fn delete(&self) -> PublicationDelete {
+        PublicationDelete {
+            publisher: self,
+            timestamp: None,
+            attachment: None,
+        }
+    }
+
+    /// Flush the data sent with the priority of this [`Publisher`] to the network.
+    ///
+    /// The returned [`Resolve`] resolves once the messages sent up to this call with the
+    /// priority of this Publisher, by this Publisher or not, have been written on the links.
+    /// Use [`Session::flush`](crate::Session::flush) to flush the messages of all the priorities.
+    ///
+    /// # Examples
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap().into_arc();
+    /// let publisher = session.declare_publisher("key/expression").res().await.unwrap();
+    /// publisher.put("value").res().await.unwrap();
+    /// publisher.flush().res().await.unwrap();
+    /// # })
+    /// ```
+    pub fn flush(&self) -> impl Resolve<ZResult<()>> + '_ {
+        self.session.flush_priority(Some(self.priority))
     }
 
     /// Undeclares the [`Publisher`], informing the network that it needn't optimize publications for its key expression anymore.
@@ -359,11 +777,12 @@ impl Resolvable for PublisherUndeclaration<'_> {
 impl SyncResolve for PublisherUndeclaration<'_> {
     fn res_sync(mut self) -> <Self as Resolvable>::To {
         let Publisher {
-            session, key_expr, ..
+            session,
+            id,
+            key_expr,
+            ..
         } = &self.publisher;
-        session
-            .undeclare_publication_intent(key_expr.clone())
-            .res_sync()?;
+        session.undeclare_publisher_inner(*id, key_expr)?;
         self.publisher.key_expr = unsafe { keyexpr::from_str_unchecked("") }.into();
         Ok(())
     }
@@ -382,18 +801,49 @@ impl Drop for Publisher<'_> {
         if !self.key_expr.is_empty() {
             let _ = self
                 .session
-                .undeclare_publication_intent(self.key_expr.clone())
-                .res_sync();
+                .undeclare_publisher_inner(self.id, &self.key_expr);
         }
     }
 }
 
-/// A [`Resolvable`] returned by [`Publisher::put()`](Publisher::put),
-/// [`Publisher::delete()`](Publisher::delete) and [`Publisher::write()`](Publisher::write).
+/// A [`Resolvable`] returned by [`Publisher::put()`](Publisher::put)
+/// and [`Publisher::write()`](Publisher::write).
 pub struct Publication<'a> {
     publisher: &'a Publisher<'a>,
     value: Value,
     kind: SampleKind,
+    timestamp: Option<Timestamp>,
+    attachment: Option<ZBuf>,
+}
+
+impl Publication<'_> {
+    /// Change the encoding of the written data.
+    #[inline]
+    pub fn encoding<IntoEncoding>(mut self, encoding: IntoEncoding) -> Self
+    where
+        IntoEncoding: Into<Encoding>,
+    {
+        self.value.encoding = encoding.into();
+        self
+    }
+
+    /// Set the timestamp of the written data, instead of the one generated by the session.
+    #[inline]
+    pub fn timestamp(mut self, timestamp: Timestamp) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+
+    /// Attach some data to the written data, received by the subscribers in [`Sample::attachment`].
+    #[zenoh_core::unstable]
+    #[inline]
+    pub fn attachment<IntoZBuf>(mut self, attachment: IntoZBuf) -> Self
+    where
+        IntoZBuf: Into<ZBuf>,
+    {
+        self.attachment = Some(attachment.into());
+        self
+    }
 }
 
 impl Resolvable for Publication<'_> {
@@ -401,62 +851,184 @@ impl Resolvable for Publication<'_> {
 }
 
 impl SyncResolve for Publication<'_> {
+    #[inline]
     fn res_sync(self) -> <Self as Resolvable>::To {
-        let Publication {
-            publisher,
-            value,
-            kind,
-        } = self;
-        log::trace!("write({:?}, [...])", publisher.key_expr);
-        let primitives = zread!(publisher.session.state)
-            .primitives
-            .as_ref()
-            .unwrap()
-            .clone();
-
-        let mut info = DataInfo::new();
-        info.kind = kind;
-        info.encoding = if value.encoding != Encoding::default() {
-            Some(value.encoding)
-        } else {
-            None
-        };
-        info.timestamp = publisher.session.runtime.new_timestamp();
-        let data_info = if info.has_options() { Some(info) } else { None };
-
-        if publisher.destination != Locality::SessionLocal {
-            primitives.send_data(
-                &publisher.key_expr.to_wire(&publisher.session),
-                value.payload.clone(),
-                Channel {
-                    priority: publisher.priority.into(),
-                    reliability: Reliability::Reliable, // @TODO: need to check subscriptions to determine the right reliability value
-                },
-                publisher.congestion_control,
-                data_info.clone(),
-                None,
-            );
-        }
-        if publisher.destination != Locality::Remote {
-            publisher.session.handle_data(
-                true,
-                &publisher.key_expr.to_wire(&publisher.session),
-                data_info,
-                value.payload,
-            );
-        }
-        Ok(())
+        resolve_publication(
+            self.publisher,
+            self.value,
+            self.kind,
+            self.timestamp,
+            self.attachment,
+        )
     }
 }
 
-impl AsyncResolve for Publication<'_> {
-    type Future = Ready<Self::To>;
+impl<'a> AsyncResolve for Publication<'a> {
+    type Future = Either<Ready<Self::To>, BoxFuture<'a, Self::To>>;
 
     fn res_async(self) -> Self::Future {
-        std::future::ready(self.res_sync())
+        resolve_publication_async(
+            self.publisher,
+            self.value,
+            self.kind,
+            self.timestamp,
+            self.attachment,
+        )
+    }
+}
+
+/// A [`Resolvable`] returned by [`Publisher::delete()`](Publisher::delete).
+pub struct PublicationDelete<'a> {
+    publisher: &'a Publisher<'a>,
+    timestamp: Option<Timestamp>,
+    attachment: Option<ZBuf>,
+}
+
+impl PublicationDelete<'_> {
+    /// Set the timestamp of the deletion, instead of the one generated by the session.
+    #[inline]
+    pub fn timestamp(mut self, timestamp: Timestamp) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+
+    /// Attach some data to the deletion, received by the subscribers in [`Sample::attachment`].
+    #[zenoh_core::unstable]
+    #[inline]
+    pub fn attachment<IntoZBuf>(mut self, attachment: IntoZBuf) -> Self
+    where
+        IntoZBuf: Into<ZBuf>,
+    {
+        self.attachment = Some(attachment.into());
+        self
+    }
+}
+
+impl Resolvable for PublicationDelete<'_> {
+    type To = ZResult<()>;
+}
+
+impl SyncResolve for PublicationDelete<'_> {
+    #[inline]
+    fn res_sync(self) -> <Self as Resolvable>::To {
+        resolve_publication(
+            self.publisher,
+            Value::empty(),
+            SampleKind::Delete,
+            self.timestamp,
+            self.attachment,
+        )
+    }
+}
+
+impl<'a> AsyncResolve for PublicationDelete<'a> {
+    type Future = Either<Ready<Self::To>, BoxFuture<'a, Self::To>>;
+
+    fn res_async(self) -> Self::Future {
+        resolve_publication_async(
+            self.publisher,
+            Value::empty(),
+            SampleKind::Delete,
+            self.timestamp,
+            self.attachment,
+        )
+    }
+}
+
+/// Returns `None` if the publication of `value` must be dropped by the rate limiter
+/// of `publisher`, or the delay to wait before publishing it.
+#[inline]
+fn rate_limit_publication(publisher: &Publisher, value: &Value) -> ZResult<Option<Duration>> {
+    match &publisher.rate_limiter {
+        Some(rate_limiter) => rate_limiter.admit(&publisher.key_expr, value),
+        None => Ok(Some(Duration::ZERO)),
     }
 }
 
+fn resolve_publication(
+    publisher: &Publisher,
+    value: Value,
+    kind: SampleKind,
+    timestamp: Option<Timestamp>,
+    attachment: Option<ZBuf>,
+) -> ZResult<()> {
+    match rate_limit_publication(publisher, &value)? {
+        Some(delay) => {
+            if !delay.is_zero() {
+                // Sleeping would block the executor along with all its other tasks
+                if in_async_context() {
+                    if let Some(rate_limiter) = &publisher.rate_limiter {
+                        rate_limiter.refund(&value);
+                    }
+                    bail!(
+                        "Rate limit exceeded on {}: delayed publications must be awaited with res_async in an async context",
+                        publisher.key_expr
+                    );
+                }
+                std::thread::sleep(delay);
+            }
+            write_publication(publisher, value, kind, timestamp, attachment)
+        }
+        None => Ok(()),
+    }
+}
+
+// Returns true if called from a task of async-std or (with the `tokio` feature) from a Tokio runtime.
+fn in_async_context() -> bool {
+    #[cfg(feature = "tokio")]
+    if tokio::runtime::Handle::try_current().is_ok() {
+        return true;
+    }
+    async_std::task::try_current().is_some()
+}
+
+/// Publishes `value` without blocking: a publication delayed by the rate limiter
+/// of `publisher` is awaited asynchronously.
+fn resolve_publication_async<'a>(
+    publisher: &'a Publisher<'a>,
+    value: Value,
+    kind: SampleKind,
+    timestamp: Option<Timestamp>,
+    attachment: Option<ZBuf>,
+) -> Either<Ready<ZResult<()>>, BoxFuture<'a, ZResult<()>>> {
+    match rate_limit_publication(publisher, &value) {
+        Ok(Some(delay)) if delay.is_zero() => Either::Left(std::future::ready(write_publication(
+            publisher, value, kind, timestamp, attachment,
+        ))),
+        Ok(Some(delay)) => Either::Right(
+            async move {
+                async_std::task::sleep(delay).await;
+                write_publication(publisher, value, kind, timestamp, attachment)
+            }
+            .boxed(),
+        ),
+        Ok(None) => Either::Left(std::future::ready(Ok(()))),
+        Err(e) => Either::Left(std::future::ready(Err(e))),
+    }
+}
+
+fn write_publication(
+    publisher: &Publisher,
+    value: Value,
+    kind: SampleKind,
+    timestamp: Option<Timestamp>,
+    attachment: Option<ZBuf>,
+) -> ZResult<()> {
+    send_put(
+        &publisher.session,
+        &publisher.key_expr,
+        publisher.priority,
+        publisher.congestion_control,
+        publisher.destination,
+        value,
+        kind,
+        timestamp,
+        attachment,
+        false,
+    )
+    .map(|_| ())
+}
+
 impl<'a, IntoValue> Sink<IntoValue> for Publisher<'a>
 where
     IntoValue: Into<Value>,
@@ -464,23 +1036,72 @@ where
     type Error = Error;
 
     #[inline]
-    fn poll_ready(self: Pin<&mut Self>, _cx: &mut Context) -> Poll<Result<(), Self::Error>> {
-        Poll::Ready(Ok(()))
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        self.get_mut().poll_sink_delayed(cx)
     }
 
     #[inline]
     fn start_send(self: Pin<&mut Self>, item: IntoValue) -> Result<(), Self::Error> {
-        self.put(item.into()).res_sync()
+        let publisher = self.get_mut();
+        let value = item.into();
+        // The bucket is charged right away: a delayed publication is held until its delay
+        // has elapsed, and the sink is not ready again until then
+        match rate_limit_publication(publisher, &value)? {
+            Some(delay) if delay.is_zero() => {
+                write_publication(publisher, value, SampleKind::Put, None, None)
+            }
+            Some(delay) => {
+                let timer = async_std::task::sleep(delay).boxed();
+                publisher.sink_delayed.0 = Some((Mutex::new(timer), value));
+                Ok(())
+            }
+            None => Ok(()),
+        }
     }
 
     #[inline]
-    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context) -> Poll<Result<(), Self::Error>> {
-        Poll::Ready(Ok(()))
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        self.get_mut().poll_sink_delayed(cx)
     }
 
     #[inline]
-    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context) -> Poll<Result<(), Self::Error>> {
-        Poll::Ready(Ok(()))
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        self.get_mut().poll_sink_delayed(cx)
+    }
+}
+
+impl Publisher<'_> {
+    // Sends the publication held by the `Sink` implementation once its delay has elapsed.
+    fn poll_sink_delayed(&mut self, cx: &mut Context) -> Poll<ZResult<()>> {
+        let timer = match &mut self.sink_delayed.0 {
+            Some((timer, _)) => timer.get_mut().unwrap(),
+            None => return Poll::Ready(Ok(())),
+        };
+        if timer.poll_unpin(cx).is_pending() {
+            return Poll::Pending;
+        }
+        let (_, value) = self.sink_delayed.0.take().unwrap();
+        Poll::Ready(write_publication(self, value, SampleKind::Put, None, None))
+    }
+}
+
+/// A publication of the `Sink` implementation of a [`Publisher`] delayed by its rate limiter.
+/// It is not cloned along with the [`Publisher`], so that it is only sent once.
+// The timer is only polled through `&mut`: the mutex just keeps the `Publisher` `Sync`.
+#[derive(Default)]
+pub(crate) struct SinkDelayed(Option<(Mutex<BoxFuture<'static, ()>>, Value)>);
+
+impl Clone for SinkDelayed {
+    fn clone(&self) -> Self {
+        SinkDelayed(None)
+    }
+}
+
+impl fmt::Debug for SinkDelayed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SinkDelayed")
+            .field(&self.0.as_ref().map(|(_, value)| value))
+            .finish()
     }
 }
 
@@ -508,6 +1129,7 @@ pub struct PublisherBuilder<'a, 'b: 'a> {
     pub(crate) congestion_control: CongestionControl,
     pub(crate) priority: Priority,
     pub(crate) destination: Locality,
+    pub(crate) rate_limit: Option<(RateLimit, RateLimitBehavior)>,
 }
 
 impl<'a, 'b> Clone for PublisherBuilder<'a, 'b> {
@@ -521,6 +1143,7 @@ impl<'a, 'b> Clone for PublisherBuilder<'a, 'b> {
             congestion_control: self.congestion_control,
             priority: self.priority,
             destination: self.destination,
+            rate_limit: self.rate_limit,
         }
     }
 }
@@ -548,6 +1171,34 @@ impl<'a, 'b> PublisherBuilder<'a, 'b> {
         self.destination = destination;
         self
     }
+
+    /// Limit the rate at which the [`Publisher`] sends data, applying the given
+    /// `behavior` to the publications exceeding the `limit`.
+    ///
+    /// The limit allows bursts of up to one second worth of publications,
+    /// and is shared by the clones of the [`Publisher`]. It doesn't apply to the
+    /// publications made with [`Session::put`] or [`Session::delete`].
+    ///
+    /// # Examples
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use zenoh::prelude::r#async::*;
+    /// use zenoh::publication::{RateLimit, RateLimitBehavior};
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let publisher = session
+    ///     .declare_publisher("key/expression")
+    ///     .rate_limit(RateLimit::MessagesPerSecond(100), RateLimitBehavior::Drop)
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// # })
+    /// ```
+    #[inline]
+    pub fn rate_limit(mut self, limit: RateLimit, behavior: RateLimitBehavior) -> Self {
+        self.rate_limit = Some((limit, behavior));
+        self
+    }
 }
 
 impl<'a, 'b> Resolvable for PublisherBuilder<'a, 'b> {
@@ -557,6 +1208,10 @@ impl<'a, 'b> Resolvable for PublisherBuilder<'a, 'b> {
 impl<'a, 'b> SyncResolve for PublisherBuilder<'a, 'b> {
     fn res_sync(self) -> <Self as Resolvable>::To {
         let mut key_expr = self.key_expr?;
+        let rate_limiter = match self.rate_limit {
+            Some((limit, behavior)) => Some(Arc::new(RateLimiter::new(limit, behavior)?)),
+            None => None,
+        };
         if !key_expr.is_fully_optimized(&self.session) {
             let session_id = self.session.id;
             let expr_id = self.session.declare_prefix(key_expr.as_str()).res_sync();
@@ -585,15 +1240,25 @@ impl<'a, 'b> SyncResolve for PublisherBuilder<'a, 'b> {
                 }
             }
         }
-        self.session
-            .declare_publication_intent(key_expr.clone())
-            .res_sync()?;
+        let id = self.session.declare_publisher_inner(
+            &key_expr,
+            PublisherDeclaration {
+                key_expr: key_expr.clone().into_owned(),
+                congestion_control: self.congestion_control,
+                priority: self.priority,
+                #[cfg(feature = "unstable")]
+                destination: self.destination,
+            },
+        );
         let publisher = Publisher {
             session: self.session,
+            id,
             key_expr,
             congestion_control: self.congestion_control,
             priority: self.priority,
             destination: self.destination,
+            rate_limiter,
+            sink_delayed: SinkDelayed::default(),
         };
         log::trace!("publish({:?})", publisher.key_expr);
         Ok(publisher)
@@ -608,6 +1273,110 @@ impl<'a, 'b> AsyncResolve for PublisherBuilder<'a, 'b> {
     }
 }
 
+/// The maximum rate at which a [`Publisher`] sends data,
+/// set with [`PublisherBuilder::rate_limit`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RateLimit {
+    /// A maximum number of publications per second.
+    MessagesPerSecond(u64),
+    /// A maximum number of payload bytes per second.
+    BytesPerSecond(u64),
+}
+
+/// What a [`Publisher`] does with the publications exceeding its [`RateLimit`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RateLimitBehavior {
+    /// The publication is silently dropped.
+    Drop,
+    /// The publication is delayed until it fits in the limit: `res_async` awaits without
+    /// blocking and the `Sink` implementation holds it until then, `poll_ready` and
+    /// `poll_flush` waiting for it to be sent, while `res_sync` and [`Publisher::put_now`]
+    /// block the calling thread. Called from an async context, where blocking would stall
+    /// the executor, `res_sync` and [`Publisher::put_now`] fail instead of waiting.
+    Delay,
+    /// The publication fails with an error.
+    Error,
+}
+
+/// A token bucket refilled at the limit rate, holding up to one second worth of tokens.
+/// A full bucket admits a publication larger than the rate, which then empties it for
+/// as long as it would take to send it at the limit rate.
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    limit: RateLimit,
+    behavior: RateLimitBehavior,
+    rate: f64,
+    bucket: Mutex<(f64, Instant)>,
+}
+
+impl RateLimiter {
+    fn new(limit: RateLimit, behavior: RateLimitBehavior) -> ZResult<RateLimiter> {
+        let rate = match limit {
+            RateLimit::MessagesPerSecond(rate) | RateLimit::BytesPerSecond(rate) => rate,
+        };
+        if rate == 0 {
+            bail!("Invalid rate limit: {:?}", limit);
+        }
+        let rate = rate as f64;
+        Ok(RateLimiter {
+            limit,
+            behavior,
+            rate,
+            bucket: Mutex::new((rate, Instant::now())),
+        })
+    }
+
+    /// Returns `None` if the publication of `value` must be dropped,
+    /// or the delay to wait before publishing it.
+    fn admit(&self, key_expr: &KeyExpr, value: &Value) -> ZResult<Option<Duration>> {
+        let cost = self.cost(value);
+        let mut bucket = zlock!(self.bucket);
+        let tokens = self.refill(&mut bucket);
+        // A publication larger than the bucket is admitted once the bucket is full
+        if *tokens >= cost.min(self.rate) {
+            *tokens -= cost;
+            return Ok(Some(Duration::ZERO));
+        }
+        match self.behavior {
+            RateLimitBehavior::Drop => {
+                log::trace!("Rate limit exceeded on {}: dropping publication", key_expr);
+                Ok(None)
+            }
+            RateLimitBehavior::Error => {
+                bail!("Rate limit exceeded on {}: {:?}", key_expr, self.limit)
+            }
+            RateLimitBehavior::Delay => {
+                // Go into debt: the next publications wait for the bucket to refill
+                *tokens -= cost;
+                Ok(Some(Duration::from_secs_f64(-*tokens / self.rate)))
+            }
+        }
+    }
+
+    /// Gives back the tokens charged for a publication that is not sent after all.
+    fn refund(&self, value: &Value) {
+        let cost = self.cost(value);
+        let mut bucket = zlock!(self.bucket);
+        let tokens = self.refill(&mut bucket);
+        *tokens = (*tokens + cost).min(self.rate);
+    }
+
+    fn cost(&self, value: &Value) -> f64 {
+        match self.limit {
+            RateLimit::MessagesPerSecond(_) => 1.0,
+            RateLimit::BytesPerSecond(_) => value.payload.len() as f64,
+        }
+    }
+
+    fn refill<'b>(&self, bucket: &'b mut (f64, Instant)) -> &'b mut f64 {
+        let (tokens, last) = bucket;
+        let now = Instant::now();
+        *tokens = (*tokens + now.duration_since(*last).as_secs_f64() * self.rate).min(self.rate);
+        *last = now;
+        tokens
+    }
+}
+
 /// The Priority of zenoh messages.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 #[repr(u8)]
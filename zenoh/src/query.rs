@@ -18,8 +18,10 @@ use crate::handlers::{locked, Callback, DefaultHandler};
 use crate::prelude::*;
 use crate::Session;
 use std::collections::HashMap;
+use std::convert::TryFrom;
 use std::future::Ready;
 use std::time::Duration;
+use zenoh_buffers::ZBuf;
 use zenoh_core::zresult::ZResult;
 use zenoh_core::{AsyncResolve, Resolvable, SyncResolve};
 
@@ -84,14 +86,45 @@ pub struct Reply {
     pub replier_id: ZenohId,
 }
 
+impl TryFrom<Reply> for Sample {
+    type Error = zenoh_core::Error;
+
+    fn try_from(value: Reply) -> Result<Self, Self::Error> {
+        value
+            .sample
+            .map_err(|v| zerror!("Received error reply: {}", v).into())
+    }
+}
+
 pub(crate) struct QueryState {
     pub(crate) nb_final: usize,
     pub(crate) selector: Selector<'static>,
     pub(crate) reception_mode: ConsolidationMode,
     pub(crate) replies: Option<HashMap<OwnedKeyExpr, Reply>>,
+    /// The replies held back until the end of the query to be delivered sorted by timestamp.
+    pub(crate) sorted_replies: Option<Vec<Reply>>,
+    /// Only accept the replies whose key expression is included in the queried one.
+    pub(crate) strict_key_expr: bool,
     pub(crate) callback: Callback<'static, Reply>,
 }
 
+impl QueryState {
+    /// Returns the replies held back until the end of the query, in delivery order.
+    pub(crate) fn take_pending_replies(&mut self) -> Vec<Reply> {
+        let mut replies = Vec::new();
+        if self.reception_mode == ConsolidationMode::Latest {
+            if let Some(latest) = self.replies.take() {
+                replies.extend(latest.into_values());
+            }
+        }
+        if let Some(sorted) = self.sorted_replies.take() {
+            replies.extend(sorted);
+            replies.sort_by_key(|reply| reply.sample.as_ref().ok().and_then(|s| s.timestamp));
+        }
+        replies
+    }
+}
+
 /// A builder for initializing a `query`.
 ///
 /// # Examples
@@ -123,6 +156,9 @@ pub struct GetBuilder<'a, 'b, Handler> {
     pub(crate) timeout: Duration,
     pub(crate) handler: Handler,
     pub(crate) value: Option<Value>,
+    pub(crate) attachment: Option<ZBuf>,
+    pub(crate) sort_by_timestamp: bool,
+    pub(crate) strict_key_expr: bool,
 }
 
 impl<'a, 'b> GetBuilder<'a, 'b, DefaultHandler> {
@@ -155,6 +191,9 @@ impl<'a, 'b> GetBuilder<'a, 'b, DefaultHandler> {
             destination,
             timeout,
             value,
+            attachment,
+            sort_by_timestamp,
+            strict_key_expr,
             handler: _,
         } = self;
         GetBuilder {
@@ -165,6 +204,9 @@ impl<'a, 'b> GetBuilder<'a, 'b, DefaultHandler> {
             destination,
             timeout,
             value,
+            attachment,
+            sort_by_timestamp,
+            strict_key_expr,
             handler: callback,
         }
     }
@@ -232,6 +274,9 @@ impl<'a, 'b> GetBuilder<'a, 'b, DefaultHandler> {
             destination,
             timeout,
             value,
+            attachment,
+            sort_by_timestamp,
+            strict_key_expr,
             handler: _,
         } = self;
         GetBuilder {
@@ -242,6 +287,9 @@ impl<'a, 'b> GetBuilder<'a, 'b, DefaultHandler> {
             destination,
             timeout,
             value,
+            attachment,
+            sort_by_timestamp,
+            strict_key_expr,
             handler,
         }
     }
@@ -288,11 +336,96 @@ impl<'a, 'b, Handler> GetBuilder<'a, 'b, Handler> {
         self
     }
 
+    /// Attach some data to the query, received by the queryables in [`Query::attachment`](crate::queryable::Query::attachment).
+    ///
+    /// # Examples
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let replies = session
+    ///     .get("key/expression")
+    ///     .attachment(b"metadata".to_vec())
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// # })
+    /// ```
+    #[zenoh_core::unstable]
+    #[inline]
+    pub fn attachment<IntoZBuf>(mut self, attachment: IntoZBuf) -> Self
+    where
+        IntoZBuf: Into<ZBuf>,
+    {
+        self.attachment = Some(attachment.into());
+        self
+    }
+
+    /// Restrict the query to the values dated within the given [`TimeRange`](crate::selector::TimeRange),
+    /// setting the `_time` parameter of the selector.
+    ///
+    /// # Examples
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use zenoh::prelude::r#async::*;
+    /// use zenoh::selector::TimeRange;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let time_range: TimeRange = "[now(-1h)..]".parse().unwrap();
+    /// let replies = session
+    ///     .get("key/expression")
+    ///     .time_range(time_range)
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// # })
+    /// ```
+    #[inline]
+    pub fn time_range<T>(mut self, time_range: T) -> Self
+    where
+        T: Into<crate::selector::TimeRange>,
+    {
+        let time_range = time_range.into();
+        self.selector = self.selector.map(|mut selector| {
+            selector.with_time_range(time_range);
+            selector
+        });
+        self
+    }
+
+    /// Deliver the replies sorted by timestamp, the replies without timestamp first.
+    ///
+    /// The replies are then held back until all the queryables answered or the query timed out.
+    ///
+    /// # Examples
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let replies = session
+    ///     .get("key/expression")
+    ///     .sort_by_timestamp(true)
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// # })
+    /// ```
+    #[inline]
+    pub fn sort_by_timestamp(mut self, sort: bool) -> Self {
+        self.sort_by_timestamp = sort;
+        self
+    }
+
     /// By default, `get` guarantees that it will only receive replies whose key expressions intersect
     /// with the queried key expression.
     ///
     /// If allowed to through `accept_replies(ReplyKeyExpr::Any)`, queryables may also reply on key
     /// expressions that don't intersect with the query's.
+    ///
+    /// Through `accept_replies(ReplyKeyExpr::IncludedInQuery)`, the replies whose key expressions
+    /// are not included in the query's are dropped, e.g. a reply on `a/**` to a query on `a/*`.
     #[zenoh_core::unstable]
     pub fn accept_replies(self, accept: ReplyKeyExpr) -> Self {
         let Self {
@@ -303,7 +436,10 @@ impl<'a, 'b, Handler> GetBuilder<'a, 'b, Handler> {
             destination,
             timeout,
             value,
+            attachment,
+            sort_by_timestamp,
             handler,
+            ..
         } = self;
         Self {
             session,
@@ -313,6 +449,9 @@ impl<'a, 'b, Handler> GetBuilder<'a, 'b, Handler> {
             destination,
             timeout,
             value,
+            attachment,
+            sort_by_timestamp,
+            strict_key_expr: accept == ReplyKeyExpr::IncludedInQuery,
             handler,
         }
     }
@@ -327,6 +466,7 @@ pub const REPLY_KEY_EXPR_ANY_SEL_PARAM: &str = _REPLY_KEY_EXPR_ANY_SEL_PARAM;
 pub enum ReplyKeyExpr {
     Any,
     MatchingQuery,
+    IncludedInQuery,
 }
 
 #[zenoh_core::unstable]
@@ -360,6 +500,9 @@ where
                 self.destination,
                 self.timeout,
                 self.value,
+                self.attachment,
+                self.sort_by_timestamp,
+                self.strict_key_expr,
                 callback,
             )
             .map(|_| receiver)
@@ -21,15 +21,18 @@ use crate::query::ReplyKeyExpr;
 use crate::SessionRef;
 use crate::Undeclarable;
 
-use futures::FutureExt;
+use futures::stream::Iter;
+use futures::{FutureExt, Stream, StreamExt};
 use std::fmt;
 use std::future::{Future, Ready};
 use std::ops::Deref;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
+use zenoh_buffers::ZBuf;
 use zenoh_core::{AsyncResolve, Resolvable, Result as ZResult, SyncResolve};
 use zenoh_protocol_core::WireExpr;
+use zenoh_sync::Executor;
 
 /// Structs received by a [`Queryable`](Queryable).
 pub struct Query {
@@ -41,9 +44,16 @@ pub struct Query {
     #[allow(unused_variables)]
     #[allow(dead_code)]
     pub(crate) value: Option<Value>,
+    /// The attachment set by the querier.
+    pub(crate) attachment: Option<ZBuf>,
+    /// The text map of the OpenTelemetry context propagated by the querier.
+    #[cfg(feature = "opentelemetry")]
+    pub(crate) trace_context: Option<ZBuf>,
     /// The sender to use to send replies to this query.
     /// When this sender is dropped, the reply is finalized.
     pub(crate) replies_sender: flume::Sender<Sample>,
+    /// The executor running the replies streams.
+    pub(crate) executor: Executor,
 }
 
 impl Query {
@@ -75,6 +85,23 @@ impl Query {
         self.value.as_ref()
     }
 
+    /// The attachment set by the querier of this Query, if any.
+    ///
+    /// Like the attachments of the samples, it is only forwarded to the peers that advertised
+    /// their support when establishing their transport, and not over multicast transports.
+    #[zenoh_core::unstable]
+    #[inline(always)]
+    pub fn attachment(&self) -> Option<&ZBuf> {
+        self.attachment.as_ref()
+    }
+
+    /// The OpenTelemetry context propagated by the querier of this Query,
+    /// on top of the current context.
+    #[cfg(feature = "opentelemetry")]
+    pub fn trace_context(&self) -> opentelemetry::Context {
+        crate::telemetry::extract(self.trace_context.as_ref())
+    }
+
     /// Sends a reply to this Query.
     ///
     /// By default, queries only accept replies whose key expression intersects with the query's.
@@ -88,6 +115,54 @@ impl Query {
         }
     }
 
+    /// Sends all the replies of the given iterator to this Query.
+    ///
+    /// The replies are sent from a background task, as fast as the transport allows,
+    /// so that the callback of the queryable is not blocked by a large number of replies.
+    /// The query is finalized once all the replies are sent.
+    /// The returned [`ReplyStreamHandle`] allows to wait for the replies to be sent.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # async_std::task::block_on(async {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let queryable = session.declare_queryable("key/expression/**").res().await.unwrap();
+    /// while let Ok(query) = queryable.recv_async().await {
+    ///     let replies = (0..10000).map(|i| {
+    ///         Sample::try_from(format!("key/expression/{}", i), i.to_string()).unwrap()
+    ///     });
+    ///     query.reply_iter(replies).res().await.unwrap();
+    /// }
+    /// # })
+    /// ```
+    #[inline]
+    pub fn reply_iter<I>(&self, replies: I) -> ReplyStreamBuilder<'_, Iter<I::IntoIter>>
+    where
+        I: IntoIterator<Item = Sample>,
+        I::IntoIter: Send + 'static,
+    {
+        self.reply_stream(futures::stream::iter(replies))
+    }
+
+    /// Sends all the replies of the given stream to this Query.
+    ///
+    /// The replies are sent from a background task, as fast as the transport allows,
+    /// so that the callback of the queryable is not blocked by a large number of replies.
+    /// The query is finalized once the stream ends.
+    /// The returned [`ReplyStreamHandle`] allows to wait for the replies to be sent.
+    #[inline]
+    pub fn reply_stream<S>(&self, replies: S) -> ReplyStreamBuilder<'_, S>
+    where
+        S: Stream<Item = Sample> + Send + 'static,
+    {
+        ReplyStreamBuilder {
+            query: self,
+            replies,
+        }
+    }
+
     /// Queries may or may not accept replies on key expressions that do not intersect with their own key expression.
     /// This getter allows you to check whether or not a specific query does.
     #[zenoh_core::unstable]
@@ -195,6 +270,110 @@ impl<'a> AsyncResolve for ReplyBuilder<'a> {
     }
 }
 
+/// A builder returned by [`Query::reply_iter()`](Query::reply_iter) and
+/// [`Query::reply_stream()`](Query::reply_stream).
+#[must_use = "Resolvables do nothing unless you resolve them using the `res` method from either `SyncResolve` or `AsyncResolve`"]
+pub struct ReplyStreamBuilder<'a, S> {
+    query: &'a Query,
+    replies: S,
+}
+
+impl<S> Resolvable for ReplyStreamBuilder<'_, S> {
+    type To = ZResult<ReplyStreamHandle>;
+}
+
+impl<S> SyncResolve for ReplyStreamBuilder<'_, S>
+where
+    S: Stream<Item = Sample> + Send + 'static,
+{
+    fn res_sync(self) -> <Self as Resolvable>::To {
+        let any_replies = self.query._accepts_any_replies().unwrap_or(false);
+        let query_key_expr = self.query.key_expr.clone();
+        // The sender keeps the query open until all the replies are sent
+        let sender = self.query.replies_sender.clone();
+        let mut replies = Box::pin(self.replies);
+        let (result_sender, result_receiver) = flume::bounded(1);
+        self.query.executor.spawn(async move {
+            let mut rejected = 0;
+            let mut result: ZResult<()> = Ok(());
+            while let Some(sample) = replies.next().await {
+                if !any_replies && !query_key_expr.intersects(&sample.key_expr) {
+                    log::error!("Attempted to reply on `{}`, which does not intersect with query `{}`, despite query only allowing replies on matching key expressions", sample.key_expr, query_key_expr);
+                    rejected += 1;
+                    continue;
+                }
+                // Waiting for room in the replies channel paces the replies to the transport
+                if let Err(e) = sender.send_async(sample).await {
+                    result = Err(zerror!("{}", e).into());
+                    break;
+                }
+            }
+            if rejected > 0 && result.is_ok() {
+                let e = zerror!("{} replies did not intersect with query `{}`", rejected, query_key_expr);
+                result = Err(e.into());
+            }
+            let _ = result_sender.send(result);
+        });
+        Ok(ReplyStreamHandle(result_receiver))
+    }
+}
+
+impl<S> AsyncResolve for ReplyStreamBuilder<'_, S>
+where
+    S: Stream<Item = Sample> + Send + 'static,
+{
+    type Future = Ready<Self::To>;
+
+    fn res_async(self) -> Self::Future {
+        std::future::ready(self.res_sync())
+    }
+}
+
+/// A handle on the replies sent in the background by a [`ReplyStreamBuilder`].
+///
+/// Resolving it waits until the stream of replies ends, and fails if some of the replies
+/// could not be sent, e.g. because their key expression does not intersect with the query's:
+/// such replies are skipped, the following ones are still sent.
+/// Dropping it does not interrupt the replies.
+pub struct ReplyStreamHandle(flume::Receiver<ZResult<()>>);
+
+impl Resolvable for ReplyStreamHandle {
+    type To = ZResult<()>;
+}
+
+impl SyncResolve for ReplyStreamHandle {
+    fn res_sync(self) -> <Self as Resolvable>::To {
+        match self.0.recv() {
+            Ok(result) => result,
+            Err(e) => Err(zerror!(e).into()),
+        }
+    }
+}
+
+impl AsyncResolve for ReplyStreamHandle {
+    type Future = ReplyStreamFuture;
+
+    fn res_async(self) -> Self::Future {
+        ReplyStreamFuture(self.0.into_recv_async())
+    }
+}
+
+/// The future returned by a [`ReplyStreamHandle`] when using async.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct ReplyStreamFuture(flume::r#async::RecvFut<'static, ZResult<()>>);
+
+impl Future for ReplyStreamFuture {
+    type Output = ZResult<()>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.get_mut().0.poll_unpin(cx) {
+            Poll::Ready(Ok(result)) => Poll::Ready(result),
+            Poll::Ready(Err(e)) => Poll::Ready(Err(zerror!(e).into())),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
 pub(crate) struct QueryableState {
     pub(crate) id: Id,
     pub(crate) key_expr: WireExpr<'static>,
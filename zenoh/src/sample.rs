@@ -104,6 +104,9 @@ pub struct Sample {
     pub kind: SampleKind,
     /// The [`Timestamp`] of this Sample.
     pub timestamp: Option<Timestamp>,
+    /// `true` if this Sample is a delete published on a wildcard key expression,
+    /// deleting all the keys matching its [`key_expr`](Sample::key_expr).
+    pub wildcard: bool,
 
     #[cfg(feature = "unstable")]
     /// <div class="stab unstable">
@@ -114,6 +117,23 @@ pub struct Sample {
     ///
     /// Infos on the source of this Sample.
     pub source_info: SourceInfo,
+
+    #[cfg(feature = "unstable")]
+    /// <div class="stab unstable">
+    ///   <span class="emoji">🔬</span>
+    ///   This API has been marked as unstable: it works as advertised, but we may change it in a future release.
+    ///   To use it, you must enable zenoh's <code>unstable</code> feature flag.
+    /// </div>
+    ///
+    /// The attachment set by the publisher of this Sample, if any.
+    ///
+    /// The attachments are only forwarded to the peers that advertised their support
+    /// when establishing their transport, and not over multicast transports.
+    pub attachment: Option<ZBuf>,
+
+    /// The text map of the OpenTelemetry context propagated by the publisher of this Sample.
+    #[cfg(feature = "opentelemetry")]
+    pub(crate) trace_context: Option<ZBuf>,
 }
 
 impl Sample {
@@ -129,8 +149,13 @@ impl Sample {
             value: value.into(),
             kind: SampleKind::default(),
             timestamp: None,
+            wildcard: false,
             #[cfg(feature = "unstable")]
             source_info: SourceInfo::empty(),
+            #[cfg(feature = "unstable")]
+            attachment: None,
+            #[cfg(feature = "opentelemetry")]
+            trace_context: None,
         }
     }
     /// Creates a new Sample.
@@ -149,8 +174,13 @@ impl Sample {
             value: value.into(),
             kind: SampleKind::default(),
             timestamp: None,
+            wildcard: false,
             #[cfg(feature = "unstable")]
             source_info: SourceInfo::empty(),
+            #[cfg(feature = "unstable")]
+            attachment: None,
+            #[cfg(feature = "opentelemetry")]
+            trace_context: None,
         })
     }
 
@@ -166,11 +196,20 @@ impl Sample {
             if let Some(encoding) = &data_info.encoding {
                 value.encoding = encoding.clone();
             }
+            // The trace context is only surfaced with the opentelemetry feature
+            #[cfg_attr(not(feature = "opentelemetry"), allow(unused_variables))]
+            let (trace_context, attachment) =
+                crate::telemetry::unwrap(data_info.attachment.clone());
             Sample {
                 key_expr,
                 value,
                 kind: data_info.kind,
                 timestamp: data_info.timestamp,
+                wildcard: data_info.wildcard,
+                #[cfg(feature = "unstable")]
+                attachment,
+                #[cfg(feature = "opentelemetry")]
+                trace_context,
                 #[cfg(feature = "unstable")]
                 source_info: data_info.into(),
             }
@@ -180,8 +219,13 @@ impl Sample {
                 value,
                 kind: SampleKind::default(),
                 timestamp: None,
+                wildcard: false,
                 #[cfg(feature = "unstable")]
                 source_info: SourceInfo::empty(),
+                #[cfg(feature = "unstable")]
+                attachment: None,
+                #[cfg(feature = "opentelemetry")]
+                trace_context: None,
             }
         }
     }
@@ -192,6 +236,14 @@ impl Sample {
             kind: self.kind,
             encoding: Some(self.value.encoding),
             timestamp: self.timestamp,
+            ack_id: None,
+            #[cfg(feature = "opentelemetry")]
+            attachment: crate::telemetry::wrap(self.trace_context, self.attachment),
+            #[cfg(all(feature = "unstable", not(feature = "opentelemetry")))]
+            attachment: crate::telemetry::wrap(None, self.attachment),
+            #[cfg(not(feature = "unstable"))]
+            attachment: None,
+            wildcard: self.wildcard,
             #[cfg(feature = "shared-memory")]
             sliced: false,
             #[cfg(feature = "unstable")]
@@ -219,6 +271,36 @@ impl Sample {
         self
     }
 
+    /// Sets the attachment of this Sample.
+    #[zenoh_core::unstable]
+    #[inline]
+    pub fn with_attachment(mut self, attachment: ZBuf) -> Self {
+        self.attachment = Some(attachment);
+        self
+    }
+
+    /// The OpenTelemetry context propagated by the publisher of this Sample,
+    /// on top of the current context.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # async_std::task::block_on(async {
+    /// use opentelemetry::trace::Tracer;
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let subscriber = session.declare_subscriber("key/expression").res().await.unwrap();
+    /// while let Ok(sample) = subscriber.recv_async().await {
+    ///     let tracer = opentelemetry::global::tracer("zenoh");
+    ///     let _span = tracer.start_with_context("process", &sample.trace_context());
+    /// }
+    /// # })
+    /// ```
+    #[cfg(feature = "opentelemetry")]
+    pub fn trace_context(&self) -> opentelemetry::Context {
+        crate::telemetry::extract(self.trace_context.as_ref())
+    }
+
     /// Sets the source info of this Sample.
     #[zenoh_core::unstable]
     #[inline]
@@ -15,13 +15,15 @@ use crate::handlers::{locked, Callback, DefaultHandler};
 use crate::net::runtime::{orchestrator::Loop, Runtime};
 
 use async_std::net::UdpSocket;
-use futures::StreamExt;
+use futures::{Stream, StreamExt};
 use std::future::Ready;
+use std::time::Duration;
 use std::{fmt, ops::Deref};
 use zenoh_config::{
     whatami::WhatAmIMatcher, ZN_MULTICAST_INTERFACE_DEFAULT, ZN_MULTICAST_IPV4_ADDRESS_DEFAULT,
 };
 use zenoh_core::{AsyncResolve, Resolvable, Result as ZResult, SyncResolve};
+use zenoh_sync::Executor;
 
 /// Constants and helpers for zenoh `whatami` flags.
 pub use zenoh_protocol_core::WhatAmI;
@@ -51,9 +53,50 @@ pub use zenoh_protocol::proto::Hello;
 pub struct ScoutBuilder<Handler> {
     pub(crate) what: WhatAmIMatcher,
     pub(crate) config: ZResult<crate::config::Config>,
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) interface: Option<String>,
     pub(crate) handler: Handler,
 }
 
+impl<Handler> ScoutBuilder<Handler> {
+    /// Stop scouting after the given `timeout`.
+    ///
+    /// The receiver of the [`Hello`] messages is then disconnected.
+    ///
+    /// # Examples
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use zenoh::prelude::r#async::*;
+    /// use zenoh::scouting::WhatAmI;
+    ///
+    /// let receiver = zenoh::scout(WhatAmI::Peer | WhatAmI::Router, config::default())
+    ///     .timeout(std::time::Duration::from_secs(1))
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// while let Ok(hello) = receiver.recv_async().await {
+    ///     println!("{}", hello);
+    /// }
+    /// # })
+    /// ```
+    #[inline]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Change the network interface(s) used to send the scout messages,
+    /// overriding the `scouting/multicast/interface` of the configuration.
+    #[inline]
+    pub fn multicast_interface<IntoString>(mut self, interface: IntoString) -> Self
+    where
+        IntoString: Into<String>,
+    {
+        self.interface = Some(interface.into());
+        self
+    }
+}
+
 impl ScoutBuilder<DefaultHandler> {
     /// Receive the [`Hello`] messages from this scout with a callback.
     ///
@@ -78,11 +121,15 @@ impl ScoutBuilder<DefaultHandler> {
         let ScoutBuilder {
             what,
             config,
+            timeout,
+            interface,
             handler: _,
         } = self;
         ScoutBuilder {
             what,
             config,
+            timeout,
+            interface,
             handler: callback,
         }
     }
@@ -143,11 +190,15 @@ impl ScoutBuilder<DefaultHandler> {
         let ScoutBuilder {
             what,
             config,
+            timeout,
+            interface,
             handler: _,
         } = self;
         ScoutBuilder {
             what,
             config,
+            timeout,
+            interface,
             handler,
         }
     }
@@ -168,7 +219,14 @@ where
 {
     fn res_sync(self) -> <Self as Resolvable>::To {
         let (callback, receiver) = self.handler.into_cb_receiver_pair();
-        scout(self.what, self.config?, callback).map(|scout| Scout { scout, receiver })
+        scout(
+            self.what,
+            self.config?,
+            self.timeout,
+            self.interface,
+            callback,
+        )
+        .map(|scout| Scout { scout, receiver })
     }
 }
 
@@ -288,9 +346,45 @@ impl<Receiver> Scout<Receiver> {
     }
 }
 
+impl Scout<flume::Receiver<Hello>> {
+    /// Turn this scout into a [`Stream`] of the received [`Hello`] messages,
+    /// that ends when the scouting stops.
+    ///
+    /// Scouting goes on until the stream is dropped or the scout timeout expires.
+    ///
+    /// # Examples
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use futures::StreamExt;
+    /// use zenoh::prelude::r#async::*;
+    /// use zenoh::scouting::WhatAmI;
+    ///
+    /// let mut hellos = zenoh::scout(WhatAmI::Router, config::default())
+    ///     .timeout(std::time::Duration::from_secs(1))
+    ///     .res()
+    ///     .await
+    ///     .unwrap()
+    ///     .into_stream();
+    /// while let Some(hello) = hellos.next().await {
+    ///     println!("{}", hello);
+    /// }
+    /// # })
+    /// ```
+    pub fn into_stream(self) -> impl Stream<Item = Hello> + Send + 'static {
+        let Scout { scout, receiver } = self;
+        receiver.into_stream().map(move |hello| {
+            // keep scouting as long as the stream is alive
+            let _ = &scout;
+            hello
+        })
+    }
+}
+
 fn scout(
     what: WhatAmIMatcher,
     config: zenoh_config::Config,
+    timeout: Option<Duration>,
+    interface: Option<String>,
     callback: Callback<'static, Hello>,
 ) -> ZResult<ScoutInner> {
     log::trace!("scout({}, {})", what, &config);
@@ -305,11 +399,9 @@ fn scout(
         }
     };
     let addr = config.scouting.multicast.address().unwrap_or(default_addr);
-    let ifaces = config
-        .scouting
-        .multicast
-        .interface()
+    let ifaces = interface
         .as_ref()
+        .or_else(|| config.scouting.multicast.interface().as_ref())
         .map_or(ZN_MULTICAST_INTERFACE_DEFAULT, |s| s.as_ref());
     let (stop_sender, stop_receiver) = flume::bounded::<()>(1);
     let ifaces = Runtime::get_interfaces(ifaces);
@@ -319,7 +411,7 @@ fn scout(
             .filter_map(|iface| Runtime::bind_ucast_port(iface).ok())
             .collect();
         if !sockets.is_empty() {
-            async_std::task::spawn(async move {
+            Executor::current().spawn(async move {
                 let mut stop_receiver = stop_receiver.stream();
                 let scout = Runtime::scout(&sockets, what, &addr, move |hello| {
                     let callback = callback.clone();
@@ -329,7 +421,14 @@ fn scout(
                     }
                 });
                 let stop = async move {
-                    stop_receiver.next().await;
+                    match timeout {
+                        Some(timeout) => {
+                            let _ = async_std::future::timeout(timeout, stop_receiver.next()).await;
+                        }
+                        None => {
+                            stop_receiver.next().await;
+                        }
+                    }
                     log::trace!("stop scout({}, {})", what, &config);
                 };
                 async_std::prelude::FutureExt::race(scout, stop).await;
@@ -15,14 +15,17 @@
 use crate::admin;
 use crate::config::Config;
 use crate::config::Notifier;
+use crate::encryption::{incoming_payload, outgoing_payload, KeyProvider, PayloadEncryption};
 use crate::handlers::{Callback, DefaultHandler};
 use crate::info::*;
 use crate::key_expr::keyexpr;
 use crate::key_expr::KeyExprInner;
 use crate::key_expr::OwnedKeyExpr;
 use crate::net::routing::face::Face;
+use crate::net::routing::interceptor::InterceptorFactory;
+use crate::net::routing::pubsub::set_pull_buffer_policy;
 use crate::net::runtime::Runtime;
-use crate::net::transport::Primitives;
+use crate::net::transport::{Primitives, TransportEventHandler};
 use crate::prelude::Locality;
 use crate::prelude::{KeyExpr, Parameters};
 use crate::publication::*;
@@ -46,7 +49,7 @@ use std::fmt;
 use std::ops::Deref;
 use std::sync::atomic::{AtomicU16, AtomicUsize, Ordering};
 use std::sync::Arc;
-use std::sync::RwLock;
+use std::sync::{RwLock, RwLockWriteGuard};
 use std::time::Duration;
 use uhlc::HLC;
 use zenoh_collections::SingleOrVec;
@@ -65,6 +68,7 @@ use zenoh_protocol::{
 };
 use zenoh_protocol_core::ZenohId;
 use zenoh_protocol_core::EMPTY_EXPR_ID;
+use zenoh_sync::Executor;
 use zenoh_util::core::AsyncResolve;
 
 zconfigurable! {
@@ -83,17 +87,23 @@ pub(crate) struct SessionState {
     pub(crate) local_resources: HashMap<ExprId, Resource>,
     pub(crate) remote_resources: HashMap<ExprId, Resource>,
     pub(crate) publications: Vec<OwnedKeyExpr>,
+    pub(crate) publishers: HashMap<Id, PublisherDeclaration>,
     pub(crate) subscribers: HashMap<Id, Arc<SubscriberState>>,
     pub(crate) queryables: HashMap<Id, Arc<QueryableState>>,
     pub(crate) queries: HashMap<ZInt, QueryState>,
+    pub(crate) pending_acks: HashMap<ZInt, flume::Sender<()>>,
     pub(crate) aggregated_subscribers: Vec<OwnedKeyExpr>,
     pub(crate) aggregated_publishers: Vec<OwnedKeyExpr>,
+    pub(crate) encryption: Option<Arc<PayloadEncryption>>,
+    // The transport event handler registered on the runtime for the admin space of this session
+    pub(crate) admin_handler: Option<Arc<dyn TransportEventHandler>>,
 }
 
 impl SessionState {
     pub(crate) fn new(
         aggregated_subscribers: Vec<OwnedKeyExpr>,
         aggregated_publishers: Vec<OwnedKeyExpr>,
+        encryption: Option<Arc<PayloadEncryption>>,
     ) -> SessionState {
         SessionState {
             primitives: None,
@@ -103,11 +113,15 @@ impl SessionState {
             local_resources: HashMap::new(),
             remote_resources: HashMap::new(),
             publications: Vec::new(),
+            publishers: HashMap::new(),
             subscribers: HashMap::new(),
             queryables: HashMap::new(),
             queries: HashMap::new(),
+            pending_acks: HashMap::new(),
             aggregated_subscribers,
             aggregated_publishers,
+            encryption,
+            admin_handler: None,
         }
     }
 }
@@ -305,6 +319,10 @@ pub struct Session {
     pub(crate) state: Arc<RwLock<SessionState>>,
     pub(crate) id: u16,
     pub(crate) alive: bool,
+    // Whether the runtime was started by this session (and must be closed with it)
+    // or shared with other sessions and plugins (see [`init`](crate::init)).
+    pub(crate) owns_runtime: bool,
+    pub(crate) executor: Executor,
 }
 
 static SESSION_ID_COUNTER: AtomicU16 = AtomicU16::new(0);
@@ -313,21 +331,28 @@ impl Session {
         runtime: Runtime,
         aggregated_subscribers: Vec<OwnedKeyExpr>,
         aggregated_publishers: Vec<OwnedKeyExpr>,
+        encryption: Option<Arc<PayloadEncryption>>,
     ) -> impl Resolve<Session> {
         ResolveClosure::new(move || {
             let router = runtime.router.clone();
             let state = Arc::new(RwLock::new(SessionState::new(
                 aggregated_subscribers,
                 aggregated_publishers,
+                encryption,
             )));
             let session = Session {
                 runtime: runtime.clone(),
                 state: state.clone(),
                 id: SESSION_ID_COUNTER.fetch_add(1, Ordering::SeqCst),
                 alive: true,
+                owns_runtime: false,
+                executor: runtime.executor.clone(),
             };
 
-            runtime.new_handler(Arc::new(admin::Handler::new(session.clone())));
+            let admin_handler: Arc<dyn TransportEventHandler> =
+                Arc::new(admin::Handler::new(session.clone()));
+            runtime.new_handler(admin_handler.clone());
+            zwrite!(state).admin_handler = Some(admin_handler);
 
             let primitives = Some(router.new_primitives(Arc::new(session.clone())));
             zwrite!(state).primitives = primitives;
@@ -416,6 +441,11 @@ impl Session {
     /// Sessions are automatically closed when dropped, but you may want to use this function to handle errors or
     /// close the Session asynchronously.
     ///
+    /// The messages sent so far are [flushed](Session::flush) before the Session is closed.
+    ///
+    /// Closing a Session initialized on an existing [`Runtime`](crate::runtime::Runtime)
+    /// with [`init`](crate::init) leaves this runtime running.
+    ///
     /// # Examples
     /// ```
     /// # async_std::task::block_on(async {
@@ -428,7 +458,15 @@ impl Session {
     pub fn close(self) -> impl Resolve<ZResult<()>> {
         ResolveFuture::new(async move {
             trace!("close()");
-            self.runtime.close().await?;
+            // Let the messages sent so far leave before closing the transports
+            self.flush().res_async().await?;
+            // Unregister from the runtime, that may be shared with other sessions and outlive this one
+            if let Some(admin_handler) = zwrite!(self.state).admin_handler.take() {
+                self.runtime.del_handler(&admin_handler);
+            }
+            if self.owns_runtime {
+                self.runtime.close().await?;
+            }
 
             let primitives = zwrite!(self.state).primitives.as_ref().unwrap().clone();
             primitives.send_close();
@@ -437,6 +475,49 @@ impl Session {
         })
     }
 
+    /// Flush the messages sent by this [`Session`](Session) to the network.
+    ///
+    /// The returned [`Resolve`] resolves once all the messages queued on the transports
+    /// of this Session up to this call have been written on their links.
+    /// This guarantees for instance that the last samples of a batch job actually left
+    /// the process before it exits.
+    ///
+    /// The messages are waited for priority by priority, the higher priorities first.
+    ///
+    /// # Examples
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// session.put("key/expression", "value").res().await.unwrap();
+    /// session.flush().res().await.unwrap();
+    /// # })
+    /// ```
+    pub fn flush(&self) -> impl Resolve<ZResult<()>> + '_ {
+        self.flush_priority(None)
+    }
+
+    /// Flushes the messages sent with `priority`, or with any priority if `None`.
+    pub(crate) fn flush_priority(
+        &self,
+        priority: Option<Priority>,
+    ) -> impl Resolve<ZResult<()>> + '_ {
+        ResolveFuture::new(async move {
+            trace!("flush({:?})", priority);
+            let priority = priority.map(Into::into);
+            let manager = self.runtime.manager();
+            for transport in manager.get_transports() {
+                // Ignore the transports closed in the meantime
+                let _ = transport.flush(priority).await;
+            }
+            for transport in manager.get_transports_multicast() {
+                let _ = transport.flush(priority).await;
+            }
+            Ok(())
+        })
+    }
+
     pub fn undeclare<'a, T, O>(&'a self, decl: T) -> O
     where
         O: Resolve<ZResult<()>>,
@@ -493,6 +574,63 @@ impl Session {
         }
     }
 
+    /// Get the subscribers, queryables and publishers currently declared on this [`Session`](Session).
+    ///
+    /// This includes the queryable serving the admin space of the session (`@/session/<zid>/**`).
+    ///
+    /// # Examples
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let subscriber = session.declare_subscriber("key/expression").res().await.unwrap();
+    /// for sub in session.declarations().subscribers {
+    ///     println!("{} ({:?})", sub.key_expr, sub.reliability);
+    /// }
+    /// # })
+    /// ```
+    pub fn declarations(&self) -> Declarations {
+        let state = zread!(self.state);
+        let mut subscribers = state.subscribers.values().collect::<Vec<_>>();
+        subscribers.sort_by_key(|s| s.id);
+        let mut queryables = state.queryables.values().collect::<Vec<_>>();
+        queryables.sort_by_key(|q| q.id);
+        let mut publishers = state.publishers.iter().collect::<Vec<_>>();
+        publishers.sort_by_key(|(id, _)| **id);
+        Declarations {
+            subscribers: subscribers
+                .into_iter()
+                .map(|s| SubscriberDeclaration {
+                    key_expr: s.key_expr.clone(),
+                    reliability: s.reliability,
+                    mode: s.mode,
+                    #[cfg(feature = "unstable")]
+                    origin: s.origin,
+                })
+                .collect(),
+            queryables: queryables
+                .into_iter()
+                .filter_map(|q| match state.local_wireexpr_to_expr(&q.key_expr) {
+                    Ok(key_expr) => Some(QueryableDeclaration {
+                        key_expr: key_expr.into_owned(),
+                        complete: q.complete,
+                        #[cfg(feature = "unstable")]
+                        origin: q.origin,
+                    }),
+                    Err(e) => {
+                        warn!(
+                            "Unable to resolve the key expression of queryable {}: {}",
+                            q.id, e
+                        );
+                        None
+                    }
+                })
+                .collect(),
+            publishers: publishers.into_iter().map(|(_, p)| p.clone()).collect(),
+        }
+    }
+
     /// Create a [`Subscriber`](Subscriber) for the given key expression.
     ///
     /// # Arguments
@@ -601,6 +739,7 @@ impl Session {
             congestion_control: CongestionControl::default(),
             priority: Priority::default(),
             destination: Locality::default(),
+            rate_limit: None,
         }
     }
 
@@ -663,6 +802,9 @@ impl Session {
 
     /// Put data.
     ///
+    /// The publication isn't rate limited: use a [`Publisher`] declared with
+    /// [`rate_limit`](PublisherBuilder::rate_limit) for that.
+    ///
     /// # Arguments
     ///
     /// * `key_expr` - Key expression matching the resources to put
@@ -695,8 +837,11 @@ impl Session {
     {
         PutBuilder {
             publisher: self.declare_publisher(key_expr),
-            value: value.into(),
+            value: Ok(value.into()),
+            payload_reader: None,
             kind: SampleKind::Put,
+            acked: false,
+            attachment: None,
         }
     }
 
@@ -706,6 +851,11 @@ impl Session {
     ///
     /// * `key_expr` - Key expression matching the resources to delete
     ///
+    /// A delete on a wildcard key expression (e.g. `room/*/temp`) deletes all the keys
+    /// it matches: the subscribers receive a single [`Sample`](crate::sample::Sample) of kind
+    /// [`SampleKind::Delete`](crate::prelude::SampleKind::Delete) carrying the wildcard key expression,
+    /// with its [`wildcard`](crate::sample::Sample::wildcard) flag set.
+    ///
     /// # Examples
     /// ```
     /// # async_std::task::block_on(async {
@@ -724,10 +874,10 @@ impl Session {
         TryIntoKeyExpr: TryInto<KeyExpr<'b>>,
         <TryIntoKeyExpr as TryInto<KeyExpr<'b>>>::Error: Into<zenoh_core::Error>,
     {
-        PutBuilder {
+        DeleteBuilder {
             publisher: self.declare_publisher(key_expr),
-            value: Value::empty(),
-            kind: SampleKind::Delete,
+            timestamp: None,
+            attachment: None,
         }
     }
     /// Query data from the matching queryables in the system.
@@ -769,6 +919,9 @@ impl Session {
             destination: Locality::default(),
             timeout: Duration::from_millis(unwrap_or_default!(conf.queries_default_timeout())),
             value: None,
+            attachment: None,
+            sort_by_timestamp: false,
+            strict_key_expr: false,
             handler: DefaultHandler,
         }
     }
@@ -781,24 +934,34 @@ impl Session {
             state: self.state.clone(),
             id: self.id,
             alive: false,
+            owns_runtime: self.owns_runtime,
+            executor: self.executor.clone(),
         }
     }
 
     #[allow(clippy::new_ret_no_self)]
-    pub(super) fn new(config: Config) -> impl Resolve<ZResult<Session>> + Send {
+    pub(super) fn new(
+        config: Config,
+        interceptors: Vec<InterceptorFactory>,
+        key_provider: Option<KeyProvider>,
+    ) -> impl Resolve<ZResult<Session>> + Send {
         ResolveFuture::new(async move {
             log::debug!("Config: {:?}", &config);
             let aggregated_subscribers = config.aggregation().subscribers().clone();
             let aggregated_publishers = config.aggregation().publishers().clone();
-            match Runtime::init(config).await {
+            let encryption =
+                PayloadEncryption::new(config.encryption(), key_provider)?.map(Arc::new);
+            match Runtime::init(config, interceptors).await {
                 Ok(mut runtime) => {
-                    let session = Self::init(
+                    let mut session = Self::init(
                         runtime.clone(),
                         aggregated_subscribers,
                         aggregated_publishers,
+                        encryption,
                     )
                     .res_async()
                     .await;
+                    session.owns_runtime = true;
                     match runtime.start().await {
                         Ok(()) => {
                             // Workaround for the declare_and_shoot problem
@@ -854,84 +1017,104 @@ impl Session {
         })
     }
 
+    /// Declare a publisher with the given declaration, and its publication intent
+    /// unless another publisher of this session already publishes on its key expression.
+    pub(crate) fn declare_publisher_inner(
+        &self,
+        key_expr: &KeyExpr,
+        declaration: PublisherDeclaration,
+    ) -> Id {
+        let mut state = zwrite!(self.state);
+        let id = state.decl_id_counter.fetch_add(1, Ordering::SeqCst);
+        state.publishers.insert(id, declaration);
+        self.declare_publication_intent(state, key_expr);
+        id
+    }
+
+    /// Undeclare the publisher with the given id, and its publication intent
+    /// unless another publisher of this session still publishes on its key expression.
+    pub(crate) fn undeclare_publisher_inner(&self, id: Id, key_expr: &KeyExpr) -> ZResult<()> {
+        let mut state = zwrite!(self.state);
+        state.publishers.remove(&id);
+        if state
+            .publishers
+            .values()
+            .any(|p| p.key_expr.as_str() == key_expr.as_str())
+        {
+            return Ok(());
+        }
+        self.undeclare_publication_intent(state, key_expr)
+    }
+
     /// Declare a publication for the given key expression.
     ///
     /// Puts that match the given key expression will only be sent on the network
     /// if matching subscribers exist in the system.
     ///
-    /// # Arguments
-    ///
-    /// * `key_expr` - The key expression to publish
-    pub(crate) fn declare_publication_intent<'a>(
-        &'a self,
-        key_expr: KeyExpr<'a>,
-    ) -> impl Resolve<Result<(), std::convert::Infallible>> + Send + 'a {
-        ResolveClosure::new(move || {
-            log::trace!("declare_publication({:?})", key_expr);
-            let mut state = zwrite!(self.state);
-            if !state.publications.iter().any(|p| **p == **key_expr) {
-                let declared_pub = if let Some(join_pub) = state
-                    .aggregated_publishers
-                    .iter()
-                    .find(|s| s.includes(&key_expr))
-                {
-                    let joined_pub = state.publications.iter().any(|p| join_pub.includes(p));
-                    (!joined_pub).then(|| join_pub.clone().into())
-                } else {
-                    Some(key_expr.clone())
-                };
-                state.publications.push(key_expr.into());
+    /// The session state lock is released before the declaration is sent.
+    fn declare_publication_intent(
+        &self,
+        mut state: RwLockWriteGuard<'_, SessionState>,
+        key_expr: &KeyExpr,
+    ) {
+        log::trace!("declare_publication({:?})", key_expr);
+        if !state.publications.iter().any(|p| **p == **key_expr) {
+            let declared_pub = if let Some(join_pub) = state
+                .aggregated_publishers
+                .iter()
+                .find(|s| s.includes(key_expr))
+            {
+                let joined_pub = state.publications.iter().any(|p| join_pub.includes(p));
+                (!joined_pub).then(|| join_pub.clone().into())
+            } else {
+                Some(key_expr.clone())
+            };
+            state.publications.push(key_expr.clone().into());
 
-                if let Some(res) = declared_pub {
-                    let primitives = state.primitives.as_ref().unwrap().clone();
-                    drop(state);
-                    primitives.decl_publisher(&res.to_wire(self), None);
-                }
+            if let Some(res) = declared_pub {
+                let primitives = state.primitives.as_ref().unwrap().clone();
+                drop(state);
+                primitives.decl_publisher(&res.to_wire(self), None);
             }
-            Ok(())
-        })
+        }
     }
 
     /// Undeclare a publication previously declared
-    /// with [`declare_publication`](Session::declare_publication).
+    /// with `declare_publication_intent`.
     ///
-    /// # Arguments
-    ///
-    /// * `key_expr` - The key expression of the publication to undeclarte
-    pub(crate) fn undeclare_publication_intent<'a>(
-        &'a self,
-        key_expr: KeyExpr<'a>,
-    ) -> impl Resolve<ZResult<()>> + 'a {
-        ResolveClosure::new(move || {
-            let mut state = zwrite!(self.state);
-            if let Some(idx) = state.publications.iter().position(|p| **p == *key_expr) {
-                trace!("undeclare_publication({:?})", key_expr);
-                state.publications.remove(idx);
-                match state
-                    .aggregated_publishers
-                    .iter()
-                    .find(|s| s.includes(&key_expr))
-                {
-                    Some(join_pub) => {
-                        let joined_pub = state.publications.iter().any(|p| join_pub.includes(p));
-                        if !joined_pub {
-                            let primitives = state.primitives.as_ref().unwrap().clone();
-                            let key_expr = WireExpr::from(join_pub).to_owned();
-                            drop(state);
-                            primitives.forget_publisher(&key_expr, None);
-                        }
-                    }
-                    None => {
+    /// The session state lock is released before the undeclaration is sent.
+    fn undeclare_publication_intent(
+        &self,
+        mut state: RwLockWriteGuard<'_, SessionState>,
+        key_expr: &KeyExpr,
+    ) -> ZResult<()> {
+        if let Some(idx) = state.publications.iter().position(|p| **p == **key_expr) {
+            trace!("undeclare_publication({:?})", key_expr);
+            state.publications.remove(idx);
+            match state
+                .aggregated_publishers
+                .iter()
+                .find(|s| s.includes(key_expr))
+            {
+                Some(join_pub) => {
+                    let joined_pub = state.publications.iter().any(|p| join_pub.includes(p));
+                    if !joined_pub {
                         let primitives = state.primitives.as_ref().unwrap().clone();
+                        let key_expr = WireExpr::from(join_pub).to_owned();
                         drop(state);
-                        primitives.forget_publisher(&key_expr.to_wire(self), None);
+                        primitives.forget_publisher(&key_expr, None);
                     }
-                };
-            } else {
-                bail!("Unable to find publication")
-            }
-            Ok(())
-        })
+                }
+                None => {
+                    let primitives = state.primitives.as_ref().unwrap().clone();
+                    drop(state);
+                    primitives.forget_publisher(&key_expr.to_wire(self), None);
+                }
+            };
+        } else {
+            bail!("Unable to find publication")
+        }
+        Ok(())
     }
 
     pub(crate) fn declare_subscriber_inner(
@@ -947,6 +1130,8 @@ impl Session {
         let sub_state = Arc::new(SubscriberState {
             id,
             key_expr: key_expr.clone().into_owned(),
+            reliability: info.reliability,
+            mode: info.mode,
             origin,
             callback,
         });
@@ -1222,7 +1407,7 @@ impl Session {
         payload: ZBuf,
     ) {
         let mut callbacks = SingleOrVec::default();
-        let sample = {
+        let (mut sample, encryption) = {
             let state = zread!(self.state);
             let sample = if key_expr.suffix.is_empty() {
                 match state.get_res(&key_expr.scope, local) {
@@ -1267,8 +1452,23 @@ impl Session {
                     }
                 }
             };
-            sample
+            (sample, state.encryption.clone())
         };
+        if !local && !callbacks.is_empty() {
+            let payload = std::mem::take(&mut sample.value.payload);
+            match incoming_payload(
+                encryption.as_deref(),
+                &sample.key_expr,
+                sample.kind,
+                payload,
+            ) {
+                Ok(payload) => sample.value.payload = payload,
+                Err(e) => {
+                    log::error!("Unable to decrypt Data for {}: {}", sample.key_expr, e);
+                    return;
+                }
+            }
+        }
         let zenoh_collections::single_or_vec::IntoIter { drain, last } = callbacks.into_iter();
         for cb in drain {
             cb(sample.clone());
@@ -1278,17 +1478,176 @@ impl Session {
         }
     }
 
-    pub(crate) fn pull<'a>(&'a self, key_expr: &'a KeyExpr) -> impl Resolve<ZResult<()>> + 'a {
+    pub(crate) fn handle_reply_data(
+        &self,
+        local: bool,
+        qid: ZInt,
+        replier_id: ZenohId,
+        key_expr: WireExpr,
+        data_info: Option<DataInfo>,
+        mut payload: ZBuf,
+    ) {
+        trace!(
+            "recv ReplyData {:?} {:?} {:?} {:?} {:?}",
+            qid,
+            replier_id,
+            key_expr,
+            data_info,
+            payload
+        );
+        let mut state = zwrite!(self.state);
+        let key_expr = match state.remote_key_to_expr(&key_expr) {
+            Ok(key) => key.into_owned(),
+            Err(e) => {
+                error!("Received ReplyData for unkown key_expr: {}", e);
+                return;
+            }
+        };
+        if !local {
+            let kind = data_info.as_ref().map(|i| i.kind).unwrap_or_default();
+            match incoming_payload(state.encryption.as_deref(), &key_expr, kind, payload) {
+                Ok(clear) => payload = clear,
+                Err(e) => {
+                    error!("Unable to decrypt ReplyData for {}: {}", key_expr, e);
+                    return;
+                }
+            }
+        }
+        match state.queries.get_mut(&qid) {
+            Some(query) => {
+                if !matches!(
+                    query
+                        .selector
+                        .parameters()
+                        .get_bools([crate::query::_REPLY_KEY_EXPR_ANY_SEL_PARAM]),
+                    Ok([true])
+                ) && !query.selector.key_expr.intersects(&key_expr)
+                {
+                    log::warn!(
+                        "Received ReplyData for `{}` from `{:?}, which didn't match query `{}`: dropping ReplyData.",
+                        key_expr,
+                        replier_id,
+                        query.selector
+                    );
+                    return;
+                }
+                if query.strict_key_expr && !query.selector.key_expr.includes(&key_expr) {
+                    log::debug!(
+                        "Received ReplyData for `{}` from `{:?}, which isn't included in query `{}`: dropping ReplyData.",
+                        key_expr,
+                        replier_id,
+                        query.selector
+                    );
+                    return;
+                }
+                let new_reply = Reply {
+                    sample: Ok(Sample::with_info(key_expr.into_owned(), payload, data_info)),
+                    replier_id,
+                };
+                let callback = match query.reception_mode {
+                    ConsolidationMode::None => Some((query.callback.clone(), new_reply)),
+                    ConsolidationMode::Monotonic => {
+                        match query
+                            .replies
+                            .as_ref()
+                            .unwrap()
+                            .get(new_reply.sample.as_ref().unwrap().key_expr.as_keyexpr())
+                        {
+                            Some(reply) => {
+                                if new_reply.sample.as_ref().unwrap().timestamp
+                                    > reply.sample.as_ref().unwrap().timestamp
+                                {
+                                    query.replies.as_mut().unwrap().insert(
+                                        new_reply.sample.as_ref().unwrap().key_expr.clone().into(),
+                                        new_reply.clone(),
+                                    );
+                                    Some((query.callback.clone(), new_reply))
+                                } else {
+                                    None
+                                }
+                            }
+                            None => {
+                                query.replies.as_mut().unwrap().insert(
+                                    new_reply.sample.as_ref().unwrap().key_expr.clone().into(),
+                                    new_reply.clone(),
+                                );
+                                Some((query.callback.clone(), new_reply))
+                            }
+                        }
+                    }
+                    ConsolidationMode::Latest => {
+                        match query
+                            .replies
+                            .as_ref()
+                            .unwrap()
+                            .get(new_reply.sample.as_ref().unwrap().key_expr.as_keyexpr())
+                        {
+                            Some(reply) => {
+                                if new_reply.sample.as_ref().unwrap().timestamp
+                                    > reply.sample.as_ref().unwrap().timestamp
+                                {
+                                    query.replies.as_mut().unwrap().insert(
+                                        new_reply.sample.as_ref().unwrap().key_expr.clone().into(),
+                                        new_reply,
+                                    );
+                                }
+                            }
+                            None => {
+                                query.replies.as_mut().unwrap().insert(
+                                    new_reply.sample.as_ref().unwrap().key_expr.clone().into(),
+                                    new_reply,
+                                );
+                            }
+                        };
+                        None
+                    }
+                };
+                let callback = match (callback, query.sorted_replies.as_mut()) {
+                    (Some((_, new_reply)), Some(sorted)) => {
+                        sorted.push(new_reply);
+                        None
+                    }
+                    (callback, _) => callback,
+                };
+                std::mem::drop(state);
+                if let Some((callback, new_reply)) = callback {
+                    callback(new_reply);
+                }
+            }
+            None => {
+                log::warn!("Received ReplyData for unkown Query: {}", qid);
+            }
+        }
+    }
+
+    pub(crate) fn pull<'a>(
+        &'a self,
+        key_expr: &'a KeyExpr,
+        max_samples: Option<ZInt>,
+    ) -> impl Resolve<ZResult<()>> + 'a {
         ResolveClosure::new(move || {
-            trace!("pull({:?})", key_expr);
+            trace!("pull({:?}, {:?})", key_expr, max_samples);
             let state = zread!(self.state);
             let primitives = state.primitives.as_ref().unwrap().clone();
             drop(state);
-            primitives.send_pull(true, &key_expr.to_wire(self), 0, &None);
+            primitives.send_pull(true, &key_expr.to_wire(self), 0, &max_samples);
             Ok(())
         })
     }
 
+    pub(crate) fn set_pull_buffer_policy(&self, key_expr: &KeyExpr, policy: PullBufferPolicy) {
+        trace!("set_pull_buffer_policy({:?}, {:?})", key_expr, policy);
+        let state = zread!(self.state);
+        let primitives = state.primitives.as_ref().unwrap().clone();
+        drop(state);
+        set_pull_buffer_policy(
+            &primitives.tables,
+            &primitives.state,
+            &key_expr.to_wire(self),
+            policy,
+        );
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn query(
         &self,
@@ -1298,9 +1657,13 @@ impl Session {
         destination: Locality,
         timeout: Duration,
         value: Option<Value>,
+        attachment: Option<ZBuf>,
+        sort_by_timestamp: bool,
+        strict_key_expr: bool,
         callback: Callback<'static, Reply>,
     ) -> ZResult<()> {
         log::trace!("get({}, {:?}, {:?})", selector, target, consolidation);
+        let attachment = crate::telemetry::wrap(crate::telemetry::inject_current(), attachment);
         let mut state = zwrite!(self.state);
         let consolidation = match consolidation.mode {
             Mode::Auto => {
@@ -1317,19 +1680,17 @@ impl Session {
             Locality::Any => 2,
             _ => 1,
         };
-        task::spawn({
+        self.executor.spawn({
             let state = self.state.clone();
             let zid = self.runtime.zid;
             async move {
                 task::sleep(timeout).await;
                 let mut state = zwrite!(state);
-                if let Some(query) = state.queries.remove(&qid) {
+                if let Some(mut query) = state.queries.remove(&qid) {
                     std::mem::drop(state);
                     log::debug!("Timout on query {}! Send error and close.", qid);
-                    if query.reception_mode == ConsolidationMode::Latest {
-                        for (_, reply) in query.replies.unwrap().into_iter() {
-                            (query.callback)(reply);
-                        }
+                    for reply in query.take_pending_replies() {
+                        (query.callback)(reply);
                     }
                     (query.callback)(Reply {
                         sample: Err("Timeout".into()),
@@ -1348,6 +1709,8 @@ impl Session {
                 selector: selector.clone().into_owned(),
                 reception_mode: consolidation,
                 replies: (consolidation != ConsolidationMode::None).then(HashMap::new),
+                sorted_replies: sort_by_timestamp.then(Vec::new),
+                strict_key_expr,
                 callback,
             },
         );
@@ -1362,14 +1725,7 @@ impl Session {
                 qid,
                 target,
                 consolidation,
-                value.as_ref().map(|v| {
-                    let mut data_info = DataInfo::new();
-                    data_info.encoding = Some(v.encoding.clone());
-                    QueryBody {
-                        data_info,
-                        payload: v.payload.clone(),
-                    }
-                }),
+                query_body(value.clone(), attachment.clone()),
                 None,
             );
         }
@@ -1381,14 +1737,7 @@ impl Session {
                 qid,
                 target,
                 consolidation,
-                value.map(|v| {
-                    let mut data_info = DataInfo::new();
-                    data_info.encoding = Some(v.encoding);
-                    QueryBody {
-                        data_info,
-                        payload: v.payload,
-                    }
-                }),
+                query_body(value, attachment),
             );
         }
         Ok(())
@@ -1450,15 +1799,26 @@ impl Session {
 
         let zid = self.runtime.zid; // @TODO build/use prebuilt specific zid
 
+        // The trace context of the query is only surfaced with the opentelemetry feature
+        #[cfg_attr(not(feature = "opentelemetry"), allow(unused_variables))]
+        let (trace_context, attachment) =
+            crate::telemetry::unwrap(body.as_ref().and_then(|b| b.data_info.attachment.clone()));
         for req_sender in senders.iter() {
             req_sender(Query {
                 key_expr: key_expr.clone().into_owned(),
                 parameters: parameters.clone(),
                 replies_sender: rep_sender.clone(),
-                value: body.as_ref().map(|b| Value {
-                    payload: b.payload.clone(),
-                    encoding: b.data_info.encoding.as_ref().cloned().unwrap_or_default(),
+                executor: self.executor.clone(),
+                // A body without encoding only carries the attachment of a query without value
+                value: body.as_ref().and_then(|b| {
+                    b.data_info.encoding.as_ref().map(|encoding| Value {
+                        payload: b.payload.clone(),
+                        encoding: encoding.clone(),
+                    })
                 }),
+                attachment: attachment.clone(),
+                #[cfg(feature = "opentelemetry")]
+                trace_context: trace_context.clone(),
             });
         }
         drop(rep_sender); // all senders need to be dropped for the channel to close
@@ -1467,10 +1827,11 @@ impl Session {
 
         if local {
             let this = self.clone();
-            task::spawn(async move {
+            self.executor.spawn(async move {
                 while let Some(sample) = rep_receiver.stream().next().await {
                     let (key_expr, payload, data_info) = sample.split();
-                    this.send_reply_data(
+                    this.handle_reply_data(
+                        true,
                         qid,
                         zid,
                         key_expr.to_wire(&this).to_owned(),
@@ -1482,9 +1843,22 @@ impl Session {
             });
         } else {
             let this = self.clone();
-            task::spawn(async move {
+            let encryption = zread!(self.state).encryption.clone();
+            self.executor.spawn(async move {
                 while let Some(sample) = rep_receiver.stream().next().await {
                     let (key_expr, payload, data_info) = sample.split();
+                    let payload = match outgoing_payload(
+                        encryption.as_deref(),
+                        &key_expr,
+                        data_info.kind,
+                        &payload,
+                    ) {
+                        Ok(payload) => payload,
+                        Err(e) => {
+                            log::error!("Unable to encrypt reply for {}: {}", key_expr, e);
+                            continue;
+                        }
+                    };
                     primitives.send_reply_data(
                         qid,
                         zid,
@@ -1499,6 +1873,23 @@ impl Session {
     }
 }
 
+/// Builds the body of a query carrying its value and its attachment, if any.
+fn query_body(value: Option<Value>, attachment: Option<ZBuf>) -> Option<QueryBody> {
+    if value.is_none() && attachment.is_none() {
+        return None;
+    }
+    let mut data_info = DataInfo::new();
+    data_info.attachment = attachment;
+    let payload = match value {
+        Some(value) => {
+            data_info.encoding = Some(value.encoding);
+            value.payload
+        }
+        None => ZBuf::default(),
+    };
+    Some(QueryBody { data_info, payload })
+}
+
 impl SessionDeclarations for Arc<Session> {
     /// Create a [`Subscriber`](Subscriber) for the given key expression.
     ///
@@ -1618,6 +2009,7 @@ impl SessionDeclarations for Arc<Session> {
             congestion_control: CongestionControl::default(),
             priority: Priority::default(),
             destination: Locality::default(),
+            rate_limit: None,
         }
     }
 }
@@ -1742,126 +2134,26 @@ impl Primitives for Session {
         data_info: Option<DataInfo>,
         payload: ZBuf,
     ) {
-        trace!(
-            "recv ReplyData {:?} {:?} {:?} {:?} {:?}",
-            qid,
-            replier_id,
-            key_expr,
-            data_info,
-            payload
-        );
-        let mut state = zwrite!(self.state);
-        let key_expr = match state.remote_key_to_expr(&key_expr) {
-            Ok(key) => key.into_owned(),
-            Err(e) => {
-                error!("Received ReplyData for unkown key_expr: {}", e);
-                return;
-            }
-        };
-        match state.queries.get_mut(&qid) {
-            Some(query) => {
-                if !matches!(
-                    query
-                        .selector
-                        .parameters()
-                        .get_bools([crate::query::_REPLY_KEY_EXPR_ANY_SEL_PARAM]),
-                    Ok([true])
-                ) && !query.selector.key_expr.intersects(&key_expr)
-                {
-                    log::warn!(
-                        "Received ReplyData for `{}` from `{:?}, which didn't match query `{}`: dropping ReplyData.",
-                        key_expr,
-                        replier_id,
-                        query.selector
-                    );
-                    return;
-                }
-                let new_reply = Reply {
-                    sample: Ok(Sample::with_info(key_expr.into_owned(), payload, data_info)),
-                    replier_id,
-                };
-                let callback = match query.reception_mode {
-                    ConsolidationMode::None => Some((query.callback.clone(), new_reply)),
-                    ConsolidationMode::Monotonic => {
-                        match query
-                            .replies
-                            .as_ref()
-                            .unwrap()
-                            .get(new_reply.sample.as_ref().unwrap().key_expr.as_keyexpr())
-                        {
-                            Some(reply) => {
-                                if new_reply.sample.as_ref().unwrap().timestamp
-                                    > reply.sample.as_ref().unwrap().timestamp
-                                {
-                                    query.replies.as_mut().unwrap().insert(
-                                        new_reply.sample.as_ref().unwrap().key_expr.clone().into(),
-                                        new_reply.clone(),
-                                    );
-                                    Some((query.callback.clone(), new_reply))
-                                } else {
-                                    None
-                                }
-                            }
-                            None => {
-                                query.replies.as_mut().unwrap().insert(
-                                    new_reply.sample.as_ref().unwrap().key_expr.clone().into(),
-                                    new_reply.clone(),
-                                );
-                                Some((query.callback.clone(), new_reply))
-                            }
-                        }
-                    }
-                    ConsolidationMode::Latest => {
-                        match query
-                            .replies
-                            .as_ref()
-                            .unwrap()
-                            .get(new_reply.sample.as_ref().unwrap().key_expr.as_keyexpr())
-                        {
-                            Some(reply) => {
-                                if new_reply.sample.as_ref().unwrap().timestamp
-                                    > reply.sample.as_ref().unwrap().timestamp
-                                {
-                                    query.replies.as_mut().unwrap().insert(
-                                        new_reply.sample.as_ref().unwrap().key_expr.clone().into(),
-                                        new_reply,
-                                    );
-                                }
-                            }
-                            None => {
-                                query.replies.as_mut().unwrap().insert(
-                                    new_reply.sample.as_ref().unwrap().key_expr.clone().into(),
-                                    new_reply,
-                                );
-                            }
-                        };
-                        None
-                    }
-                };
-                std::mem::drop(state);
-                if let Some((callback, new_reply)) = callback {
-                    callback(new_reply);
-                }
-            }
-            None => {
-                log::warn!("Received ReplyData for unkown Query: {}", qid);
-            }
-        }
+        self.handle_reply_data(false, qid, replier_id, key_expr, data_info, payload)
     }
 
     fn send_reply_final(&self, qid: ZInt) {
         trace!("recv ReplyFinal {:?}", qid);
         let mut state = zwrite!(self.state);
+        if let Some(ack) = state.pending_acks.remove(&qid) {
+            std::mem::drop(state);
+            trace!("Acknowledged put {}", qid);
+            let _ = ack.send(());
+            return;
+        }
         match state.queries.get_mut(&qid) {
             Some(mut query) => {
                 query.nb_final -= 1;
                 if query.nb_final == 0 {
-                    let query = state.queries.remove(&qid).unwrap();
+                    let mut query = state.queries.remove(&qid).unwrap();
                     std::mem::drop(state);
-                    if query.reception_mode == ConsolidationMode::Latest {
-                        for (_, reply) in query.replies.unwrap().into_iter() {
-                            (query.callback)(reply);
-                        }
+                    for reply in query.take_pending_replies() {
+                        (query.callback)(reply);
                     }
                     trace!("Close query {}", qid);
                 }
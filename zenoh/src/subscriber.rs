@@ -13,7 +13,7 @@
 //
 
 //! Subscribing primitives.
-use crate::handlers::{locked, Callback, DefaultHandler};
+use crate::handlers::{locked, BoundedQueue, Callback, DefaultHandler, OverflowPolicy};
 use crate::prelude::Locality;
 use crate::prelude::{Id, IntoCallbackReceiverPair, KeyExpr, Sample};
 use crate::Undeclarable;
@@ -23,7 +23,7 @@ use std::future::Ready;
 use std::ops::{Deref, DerefMut};
 use std::sync::Arc;
 use zenoh_core::{AsyncResolve, Resolvable, Resolve, SyncResolve};
-use zenoh_protocol_core::SubInfo;
+use zenoh_protocol_core::{SubInfo, ZInt};
 
 /// The subscription mode.
 pub use zenoh_protocol_core::SubMode;
@@ -34,6 +34,8 @@ pub use zenoh_protocol_core::Reliability;
 pub(crate) struct SubscriberState {
     pub(crate) id: Id,
     pub(crate) key_expr: KeyExpr<'static>,
+    pub(crate) reliability: Reliability,
+    pub(crate) mode: SubMode,
     pub(crate) origin: Locality,
     pub(crate) callback: Callback<'static, Sample>,
 }
@@ -131,7 +133,18 @@ impl<'a> PullSubscriberInner<'a> {
     /// ```
     #[inline]
     pub fn pull(&self) -> impl Resolve<ZResult<()>> + '_ {
-        self.inner.session.pull(&self.inner.state.key_expr)
+        self.inner.session.pull(&self.inner.state.key_expr, None)
+    }
+
+    /// Pull at most `max_samples` of the available data for a [`CallbackPullSubscriber`].
+    ///
+    /// If less than `max_samples` samples are available, the remaining ones are
+    /// delivered as soon as they are received.
+    #[inline]
+    pub fn pull_max(&self, max_samples: usize) -> impl Resolve<ZResult<()>> + '_ {
+        self.inner
+            .session
+            .pull(&self.inner.state.key_expr, Some(max_samples as ZInt))
     }
 
     /// Close a [`CallbackPullSubscriber`](CallbackPullSubscriber).
@@ -245,10 +258,31 @@ impl Drop for SubscriberInner<'_> {
     }
 }
 
+/// The policy applied to buffer the samples matching a pull subscriber until they are pulled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PullBufferPolicy {
+    /// Keep only the last sample of each key expression.
+    KeepLast,
+    /// Keep all the samples in reception order, up to the given capacity,
+    /// dropping the oldest ones when full.
+    DropOldest(usize),
+    /// Keep all the samples in reception order, up to the given capacity,
+    /// dropping the newly received ones when full.
+    DropNewest(usize),
+}
+
+impl Default for PullBufferPolicy {
+    fn default() -> Self {
+        PullBufferPolicy::KeepLast
+    }
+}
+
 /// The mode for pull subscribers.
 #[non_exhaustive]
-#[derive(Debug, Clone, Copy)]
-pub struct PullMode;
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PullMode {
+    pub(crate) buffer: PullBufferPolicy,
+}
 
 impl From<PullMode> for SubMode {
     fn from(_: PullMode) -> Self {
@@ -407,6 +441,36 @@ impl<'a, 'b, Mode> SubscriberBuilder<'a, 'b, Mode, DefaultHandler> {
             handler,
         }
     }
+
+    /// Receive the samples for this subscription through a queue of the given capacity,
+    /// applying the given [`OverflowPolicy`] when it's full.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # async_std::task::block_on(async {
+    /// use zenoh::prelude::r#async::*;
+    /// use zenoh::handlers::OverflowPolicy;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let subscriber = session
+    ///     .declare_subscriber("key/expression")
+    ///     .queue(64, OverflowPolicy::DropOldest)
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// while let Ok(sample) = subscriber.recv_async().await {
+    ///     println!("Received : {} {}", sample.key_expr, sample.value);
+    /// }
+    /// # })
+    /// ```
+    #[inline]
+    pub fn queue(
+        self,
+        capacity: usize,
+        policy: OverflowPolicy,
+    ) -> SubscriberBuilder<'a, 'b, Mode, BoundedQueue> {
+        self.with(BoundedQueue::new(capacity, policy))
+    }
 }
 impl<'a, 'b, Mode, Handler> SubscriberBuilder<'a, 'b, Mode, Handler> {
     /// Change the subscription reliability.
@@ -454,7 +518,7 @@ impl<'a, 'b, Mode, Handler> SubscriberBuilder<'a, 'b, Mode, Handler> {
             session,
             key_expr,
             reliability,
-            mode: PullMode,
+            mode: PullMode::default(),
             origin,
             handler,
         }
@@ -533,6 +597,16 @@ where
     }
 }
 
+impl<'a, 'b, Handler> SubscriberBuilder<'a, 'b, PullMode, Handler> {
+    /// Change the policy applied to buffer the samples until they are pulled
+    /// ([`KeepLast`](PullBufferPolicy::KeepLast) by default).
+    #[inline]
+    pub fn buffer_policy(mut self, policy: PullBufferPolicy) -> Self {
+        self.mode.buffer = policy;
+        self
+    }
+}
+
 // Pull mode
 impl<'a, Handler> Resolvable for SubscriberBuilder<'a, '_, PullMode, Handler>
 where
@@ -551,6 +625,7 @@ where
         let key_expr = self.key_expr?;
         let session = self.session;
         let (callback, receiver) = self.handler.into_cb_receiver_pair();
+        let buffer = self.mode.buffer;
         session
             .declare_subscriber_inner(
                 &key_expr,
@@ -561,6 +636,12 @@ where
                     mode: self.mode.into(),
                 },
             )
+            .map(|sub_state| {
+                if buffer != PullBufferPolicy::default() {
+                    session.set_pull_buffer_policy(&sub_state.key_expr, buffer);
+                }
+                sub_state
+            })
             .map(|sub_state| PullSubscriber {
                 subscriber: PullSubscriberInner {
                     inner: SubscriberInner {
@@ -691,6 +772,36 @@ impl<'a, Receiver> PullSubscriber<'a, Receiver> {
         self.subscriber.pull()
     }
 
+    /// Pull at most `max_samples` of the available data for a [`PullSubscriber`].
+    ///
+    /// If less than `max_samples` samples are available, the remaining ones are
+    /// delivered as soon as they are received, so that the application can pace
+    /// the delivery by granting credits. Each pull replaces the credits left by
+    /// the previous one.
+    ///
+    /// # Examples
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use zenoh::prelude::r#async::*;
+    /// use zenoh::subscriber::PullBufferPolicy;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let subscriber = session
+    ///     .declare_subscriber("key/expression")
+    ///     .with(flume::bounded(32))
+    ///     .pull_mode()
+    ///     .buffer_policy(PullBufferPolicy::DropOldest(100))
+    ///     .res()
+    ///     .await
+    ///     .unwrap();
+    /// subscriber.pull_max(10).res().await.unwrap();
+    /// # })
+    /// ```
+    #[inline]
+    pub fn pull_max(&self, max_samples: usize) -> impl Resolve<ZResult<()>> + '_ {
+        self.subscriber.pull_max(max_samples)
+    }
+
     /// Close a [`PullSubscriber`].
     ///
     /// Subscribers are automatically closed when dropped, but you may want to use this function to handle errors or
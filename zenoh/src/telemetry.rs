@@ -0,0 +1,137 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! Propagation of the OpenTelemetry context along the publications and the queries.
+//!
+//! With the `opentelemetry` feature, the puts, deletes and queries carry the current
+//! OpenTelemetry [`Context`], injected by the global text map propagator (see
+//! [`opentelemetry::global::set_text_map_propagator`]). The receiving side extracts it with
+//! [`Sample::trace_context`](crate::sample::Sample::trace_context) and
+//! [`Query::trace_context`](crate::queryable::Query::trace_context).
+//!
+//! The context travels in the attachment slot of the messages, in an envelope starting with
+//! `TRACE_CONTEXT_PREFIX` that the receivers always strip, with or without the feature:
+//! - the prefix,
+//! - the length of the text map (4 bytes, little-endian),
+//! - the text map, as `key=value` lines like HTTP headers,
+//! - `1` followed by the attachment set by the user, or `0` if there is none.
+//!
+//! An attachment set by the user that starts with the prefix is always sent in an envelope,
+//! with an empty text map, so that it is received unchanged.
+#[cfg(feature = "opentelemetry")]
+use opentelemetry::{global, Context};
+#[cfg(feature = "opentelemetry")]
+use std::collections::HashMap;
+use std::convert::TryInto;
+use zenoh_buffers::{SplitBuffer, ZBuf};
+
+/// The prefix of the attachments carrying a trace context.
+const TRACE_CONTEXT_PREFIX: &[u8] = b"\0zenoh/trace-context\0";
+// The length of the text map following the prefix.
+const TEXT_MAP_LEN_SIZE: usize = 4;
+
+/// Returns the text map of the current context, or `None` if the global propagator
+/// has nothing to propagate.
+#[cfg(feature = "opentelemetry")]
+pub(crate) fn inject_current() -> Option<ZBuf> {
+    let mut fields: HashMap<String, String> = HashMap::new();
+    global::get_text_map_propagator(|propagator| {
+        propagator.inject_context(&Context::current(), &mut fields)
+    });
+    if fields.is_empty() {
+        return None;
+    }
+    let mut text = String::new();
+    for (key, value) in fields {
+        text.push_str(&key);
+        text.push('=');
+        text.push_str(&value);
+        text.push('\n');
+    }
+    Some(ZBuf::from(text.into_bytes()))
+}
+
+#[cfg(not(feature = "opentelemetry"))]
+#[inline(always)]
+pub(crate) fn inject_current() -> Option<ZBuf> {
+    None
+}
+
+/// Extracts the context carried by the `text_map` on top of the current one.
+#[cfg(feature = "opentelemetry")]
+pub(crate) fn extract(text_map: Option<&ZBuf>) -> Context {
+    let mut fields: HashMap<String, String> = HashMap::new();
+    if let Some(text_map) = text_map {
+        if let Ok(text) = std::str::from_utf8(&text_map.contiguous()) {
+            for (key, value) in text.lines().filter_map(|line| line.split_once('=')) {
+                fields.insert(key.to_string(), value.to_string());
+            }
+        }
+    }
+    global::get_text_map_propagator(|propagator| propagator.extract(&fields))
+}
+
+/// Returns the attachment to send for the `text_map` of a context and the `attachment`
+/// set by the user.
+pub(crate) fn wrap(text_map: Option<ZBuf>, attachment: Option<ZBuf>) -> Option<ZBuf> {
+    let escape = attachment
+        .as_ref()
+        .map_or(false, |a| a.contiguous().starts_with(TRACE_CONTEXT_PREFIX));
+    if text_map.is_none() && !escape {
+        return attachment;
+    }
+    let text_map = text_map.map(|t| t.contiguous().into_owned());
+    let text_map = text_map.as_deref().unwrap_or_default();
+    let mut bytes = TRACE_CONTEXT_PREFIX.to_vec();
+    bytes.extend_from_slice(&(text_map.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(text_map);
+    match attachment {
+        Some(attachment) => {
+            bytes.push(1);
+            bytes.extend_from_slice(&attachment.contiguous());
+        }
+        None => bytes.push(0),
+    }
+    Some(ZBuf::from(bytes))
+}
+
+/// Splits a received attachment into the text map of its context, if any, and the
+/// attachment set by the user.
+pub(crate) fn unwrap(attachment: Option<ZBuf>) -> (Option<ZBuf>, Option<ZBuf>) {
+    match attachment.as_ref().and_then(open_envelope) {
+        Some(split) => split,
+        None => (None, attachment),
+    }
+}
+
+// Returns `None` if `attachment` is not an envelope.
+fn open_envelope(attachment: &ZBuf) -> Option<(Option<ZBuf>, Option<ZBuf>)> {
+    let envelope = attachment.contiguous();
+    let body = envelope.strip_prefix(TRACE_CONTEXT_PREFIX)?;
+    if body.len() <= TEXT_MAP_LEN_SIZE {
+        return None;
+    }
+    let (len, body) = body.split_at(TEXT_MAP_LEN_SIZE);
+    let len = u32::from_le_bytes(len.try_into().unwrap()) as usize;
+    if body.len() <= len {
+        return None;
+    }
+    let (text_map, body) = body.split_at(len);
+    let text_map = (!text_map.is_empty()).then(|| ZBuf::from(text_map.to_vec()));
+    match body.split_first() {
+        Some((0, [])) => Some((text_map, None)),
+        Some((1, user)) => Some((text_map, Some(ZBuf::from(user.to_vec())))),
+        _ => None,
+    }
+}
@@ -0,0 +1,121 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! Helpers to test zenoh applications within a single process.
+//!
+//! The sessions opened with the configurations of this module communicate through
+//! in-process links (`mem/<name>` endpoints): no network port is bound, and the messages
+//! are delivered reliably and in order. The time of the timestamps generated by all the
+//! sessions of the process can be controlled with [`set_time`] and [`advance_time`].
+//!
+//! This module, and the `mem` transport it relies on, are only available with the
+//! non-default `test` feature.
+//!
+//! # Examples
+//! ```
+//! # async_std::task::block_on(async {
+//! use zenoh::prelude::r#async::*;
+//!
+//! let peer01 = zenoh::open(zenoh::test::config(&["example"], &[])).res().await.unwrap();
+//! let peer02 = zenoh::open(zenoh::test::config(&[], &["example"])).res().await.unwrap();
+//! # })
+//! ```
+use crate::config::{self, Config, EndPoint};
+use crate::prelude::{KeyExpr, Sample, Value};
+use crate::time::NTP64;
+use crate::Session;
+use std::convert::TryFrom;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use zenoh_core::{AsyncResolve, Result as ZResult, SyncResolve};
+
+pub use zenoh_link::mem::MEM_LOCATOR_PREFIX;
+
+/// Returns the in-process endpoint of the given name.
+pub fn endpoint(name: &str) -> EndPoint {
+    format!("{}/{}", MEM_LOCATOR_PREFIX, name).parse().unwrap()
+}
+
+/// Returns a peer configuration listening on the in-process endpoints named `listen`
+/// and connecting to the ones named `connect`, with the multicast scouting disabled.
+pub fn config(listen: &[&str], connect: &[&str]) -> Config {
+    let mut config = config::peer();
+    config.listen.endpoints = listen.iter().map(|name| endpoint(name)).collect();
+    config.connect.endpoints = connect.iter().map(|name| endpoint(name)).collect();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    config
+}
+
+static SYNC_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Waits until `session` has processed all the declarations made so far by `peer`.
+///
+/// The messages of the in-process links are delivered in order: once `session` gets a reply
+/// from a queryable declared by `peer` after its other declarations, those declarations have
+/// been processed. This replaces sleeping for the declarations to propagate.
+pub async fn sync(session: &Session, peer: &Session) -> ZResult<()> {
+    let key_expr = KeyExpr::try_from(format!(
+        "zenoh-test/sync/{}/{}",
+        peer.zid(),
+        SYNC_COUNTER.fetch_add(1, Ordering::SeqCst)
+    ))?;
+    let c_key_expr = key_expr.clone();
+    let queryable = peer
+        .declare_queryable(&key_expr)
+        .callback(move |query| {
+            let _ = query
+                .reply(Ok(Sample::new(c_key_expr.clone(), Value::empty())))
+                .res_sync();
+        })
+        .res_async()
+        .await?;
+    loop {
+        let replies = session.get(&key_expr).res_async().await?;
+        // Only a reply from the queryable proves the declarations were processed
+        if matches!(replies.recv_async().await, Ok(reply) if reply.sample.is_ok()) {
+            break;
+        }
+    }
+    queryable.undeclare().res_async().await
+}
+
+static MANUAL_CLOCK: AtomicBool = AtomicBool::new(false);
+static MANUAL_TIME: AtomicU64 = AtomicU64::new(0);
+
+/// Sets the time of the timestamps generated by the sessions of this process.
+///
+/// Once called, the time only changes with [`set_time`] and [`advance_time`], for the
+/// sessions opened afterwards. The hybrid logical clocks still guarantee the uniqueness
+/// and the monotonicity of the timestamps.
+pub fn set_time(time: SystemTime) {
+    let nanos = time.duration_since(UNIX_EPOCH).unwrap().as_nanos() as u64;
+    MANUAL_TIME.store(nanos, Ordering::SeqCst);
+    MANUAL_CLOCK.store(true, Ordering::SeqCst);
+}
+
+/// Advances the time set with [`set_time`] by the given duration.
+pub fn advance_time(duration: Duration) {
+    MANUAL_TIME.fetch_add(duration.as_nanos() as u64, Ordering::SeqCst);
+}
+
+fn manual_time() -> NTP64 {
+    Duration::from_nanos(MANUAL_TIME.load(Ordering::SeqCst)).into()
+}
+
+/// Returns the clock to use for the sessions' timestamps, if set with [`set_time`].
+pub(crate) fn manual_clock() -> Option<fn() -> NTP64> {
+    MANUAL_CLOCK
+        .load(Ordering::SeqCst)
+        .then(|| manual_time as fn() -> NTP64)
+}
@@ -15,12 +15,16 @@
 //! Value primitives.
 
 use std::borrow::Cow;
+use std::collections::HashMap;
 use std::convert::TryFrom;
-#[cfg(feature = "shared-memory")]
-use std::sync::Arc;
+use std::io::IoSlice;
+use std::sync::{Arc, RwLock};
 
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use zenoh_cfg_properties::Properties;
 use zenoh_core::zresult::ZError;
+use zenoh_core::{bail, zread, zwrite, Result as ZResult};
 
 #[cfg(feature = "shared-memory")]
 use crate::buffers::SharedMemoryBuf;
@@ -32,6 +36,9 @@ use crate::prelude::{Encoding, KnownEncoding, Sample, SplitBuffer};
 #[derive(Clone)]
 pub struct Value {
     /// The payload of this Value.
+    ///
+    /// The payload may be composed of several non-contiguous slices: use
+    /// [`slices()`](SplitBuffer::slices) or [`reader()`](ZBuf::reader) to access it without copy.
     pub payload: ZBuf,
     /// An encoding description indicating how the associated payload is encoded.
     pub encoding: Encoding,
@@ -60,6 +67,199 @@ impl Value {
         self.encoding = encoding;
         self
     }
+
+    /// Creates a zenoh Value serializing the given data with the given [`SerdeFormat`].
+    /// The encoding of the Value is set accordingly.
+    ///
+    /// # Examples
+    /// ```
+    /// # async_std::task::block_on(async {
+    /// use std::collections::HashMap;
+    /// use zenoh::prelude::r#async::*;
+    /// use zenoh::value::SerdeFormat;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let data: HashMap<String, f64> = [("temperature".to_string(), 21.5)].into();
+    /// let value = Value::serialize(&data, SerdeFormat::Json).unwrap();
+    /// session.put("key/expression", value).res().await.unwrap();
+    /// # })
+    /// ```
+    pub fn serialize<T>(data: &T, format: SerdeFormat) -> ZResult<Self>
+    where
+        T: Serialize + ?Sized,
+    {
+        let payload = match format {
+            SerdeFormat::Json => serde_json::to_vec(data).map_err(|e| zerror!("{}", e))?,
+            #[cfg(feature = "serde-formats")]
+            SerdeFormat::Cbor => {
+                let mut buf = vec![];
+                ciborium::ser::into_writer(data, &mut buf).map_err(|e| zerror!("{}", e))?;
+                buf
+            }
+            #[cfg(feature = "serde-formats")]
+            SerdeFormat::Bincode => bincode::serialize(data).map_err(|e| zerror!("{}", e))?,
+        };
+        Ok(Value {
+            payload: ZBuf::from(payload),
+            encoding: format.encoding(),
+        })
+    }
+
+    /// Deserializes this zenoh Value, using the [`SerdeFormat`] corresponding to its encoding.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # async_std::task::block_on(async {
+    /// use std::collections::HashMap;
+    /// use zenoh::prelude::r#async::*;
+    ///
+    /// let session = zenoh::open(config::peer()).res().await.unwrap();
+    /// let subscriber = session.declare_subscriber("key/expression").res().await.unwrap();
+    /// while let Ok(sample) = subscriber.recv_async().await {
+    ///     let data: HashMap<String, f64> = sample.value.deserialize().unwrap();
+    ///     println!("Received {:?}", data);
+    /// }
+    /// # })
+    /// ```
+    pub fn deserialize<T>(&self) -> ZResult<T>
+    where
+        T: DeserializeOwned,
+    {
+        let format = SerdeFormat::from_encoding(&self.encoding)
+            .ok_or_else(|| zerror!("{} can not be deserialized", self.encoding))?;
+        let payload = self.payload.contiguous();
+        let data = match format {
+            SerdeFormat::Json => serde_json::from_slice(&payload).map_err(|e| zerror!("{}", e))?,
+            #[cfg(feature = "serde-formats")]
+            SerdeFormat::Cbor => {
+                ciborium::de::from_reader(payload.as_ref()).map_err(|e| zerror!("{}", e))?
+            }
+            #[cfg(feature = "serde-formats")]
+            SerdeFormat::Bincode => bincode::deserialize(&payload).map_err(|e| zerror!("{}", e))?,
+        };
+        Ok(data)
+    }
+
+    /// Converts this zenoh Value into a Value of the `target` [`Encoding`],
+    /// using the conversion hooks of the custom encodings registered with [`register_encoding`].
+    ///
+    /// Values of the same encoding (regardless of their schema) are converted by a simple copy.
+    pub fn convert(&self, target: &Encoding) -> ZResult<Value> {
+        let source = self.encoding.without_schema();
+        let target_base = target.without_schema();
+        if source == target_base {
+            return Ok(self.clone().encoding(target.clone()));
+        }
+        if let Some(converter) = encoding_converter(&source) {
+            converter.decode(self, target)
+        } else if let Some(converter) = encoding_converter(&target_base) {
+            Ok(converter.encode(self)?.encoding(target.clone()))
+        } else {
+            bail!("No conversion from {} to {}", self.encoding, target)
+        }
+    }
+}
+
+lazy_static::lazy_static! {
+    static ref ENCODINGS: RwLock<HashMap<String, Arc<dyn EncodingConverter>>> =
+        RwLock::new(HashMap::new());
+}
+
+/// The conversion hooks of a custom encoding registered with [`register_encoding`].
+pub trait EncodingConverter: Send + Sync {
+    /// Converts a [`Value`] of another encoding into a Value of the custom encoding.
+    fn encode(&self, value: &Value) -> ZResult<Value>;
+
+    /// Converts a [`Value`] of the custom encoding into a Value of the `target` encoding.
+    fn decode(&self, value: &Value, target: &Encoding) -> ZResult<Value>;
+}
+
+/// Registers a custom encoding, identified by its mime type, with the given conversion hooks.
+/// Returns the [`Encoding`] to use for the Values of this custom encoding.
+///
+/// The registered conversions are used by [`Value::convert`], allowing heterogeneous
+/// applications to exchange payloads in their own formats instead of overloading
+/// [`KnownEncoding::AppOctetStream`].
+pub fn register_encoding<C>(mime: &str, converter: C) -> ZResult<Encoding>
+where
+    C: EncodingConverter + 'static,
+{
+    let encoding = Encoding::from(mime.to_string());
+    if *encoding.prefix() != KnownEncoding::Empty {
+        bail!(
+            "Can not register encoding {}: it conflicts with {:?}",
+            mime,
+            encoding.prefix()
+        );
+    }
+    if encoding.schema().is_some() {
+        bail!("Can not register encoding {}: it has a schema", mime);
+    }
+    let mut encodings = zwrite!(ENCODINGS);
+    if encodings.contains_key(mime) {
+        bail!("Encoding {} is already registered", mime);
+    }
+    encodings.insert(mime.to_string(), Arc::new(converter));
+    Ok(encoding)
+}
+
+/// Unregisters a custom encoding registered with [`register_encoding`].
+pub fn unregister_encoding(mime: &str) {
+    zwrite!(ENCODINGS).remove(mime);
+}
+
+fn encoding_converter(encoding: &Encoding) -> Option<Arc<dyn EncodingConverter>> {
+    zread!(ENCODINGS).get(&encoding.to_string()).cloned()
+}
+
+/// The serialization formats of the zenoh [`Value`]s built with [`Value::serialize`].
+///
+/// CBOR and bincode require the `serde-formats` feature.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SerdeFormat {
+    /// JSON, with the `application/json` encoding.
+    Json,
+    /// CBOR, with the `application/cbor` encoding.
+    #[cfg(feature = "serde-formats")]
+    Cbor,
+    /// bincode, with the `application/bincode` encoding.
+    #[cfg(feature = "serde-formats")]
+    Bincode,
+}
+
+impl SerdeFormat {
+    #[cfg(feature = "serde-formats")]
+    const CBOR_MIME: &'static str = "application/cbor";
+    #[cfg(feature = "serde-formats")]
+    const BINCODE_MIME: &'static str = "application/bincode";
+
+    /// Returns the [`Encoding`] of the Values serialized with this format.
+    pub fn encoding(&self) -> Encoding {
+        match self {
+            SerdeFormat::Json => KnownEncoding::AppJson.into(),
+            #[cfg(feature = "serde-formats")]
+            SerdeFormat::Cbor => Encoding::from(Self::CBOR_MIME),
+            #[cfg(feature = "serde-formats")]
+            SerdeFormat::Bincode => Encoding::from(Self::BINCODE_MIME),
+        }
+    }
+
+    /// Returns the format of the Values with the given [`Encoding`], if any.
+    /// The schema of the encoding is ignored.
+    pub fn from_encoding(encoding: &Encoding) -> Option<Self> {
+        let encoding = encoding.without_schema();
+        match encoding.prefix() {
+            KnownEncoding::AppJson | KnownEncoding::TextJson => Some(SerdeFormat::Json),
+            #[cfg(feature = "serde-formats")]
+            KnownEncoding::Empty => match encoding.suffix() {
+                Self::CBOR_MIME => Some(SerdeFormat::Cbor),
+                Self::BINCODE_MIME => Some(SerdeFormat::Bincode),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
 }
 
 impl std::fmt::Debug for Value {
@@ -173,6 +373,27 @@ impl From<Vec<u8>> for Value {
     }
 }
 
+impl From<bytes::Bytes> for Value {
+    fn from(buf: bytes::Bytes) -> Self {
+        Value::from(ZBuf::from(buf))
+    }
+}
+
+/// Builds a Value from owned chunks, without copying them.
+impl From<Vec<bytes::Bytes>> for Value {
+    fn from(chunks: Vec<bytes::Bytes>) -> Self {
+        Value::from(ZBuf::from(chunks))
+    }
+}
+
+/// Builds a Value copying each borrowed slice in its own chunk, without assembling
+/// them in one contiguous buffer. Use `Vec<bytes::Bytes>` to avoid the copies.
+impl<'a> From<Vec<IoSlice<'a>>> for Value {
+    fn from(slices: Vec<IoSlice<'a>>) -> Self {
+        Value::from(ZBuf::from(slices))
+    }
+}
+
 impl TryFrom<&Value> for Vec<u8> {
     type Error = ZError;
 
@@ -0,0 +1,181 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use async_std::io::{ReadExt, WriteExt};
+use async_std::net::{Shutdown, TcpListener, TcpStream};
+use async_std::prelude::FutureExt;
+use async_std::task;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use zenoh::prelude::r#async::*;
+use zenoh_core::zasync_executor_init;
+
+const TIMEOUT: Duration = Duration::from_secs(60);
+const SLEEP: Duration = Duration::from_secs(1);
+
+macro_rules! ztimeout {
+    ($f:expr) => {
+        $f.timeout(TIMEOUT).await.unwrap()
+    };
+}
+
+// A TCP proxy that can silently drop the bytes it receives from the connecting side
+// and then abruptly close all the connections it forwards
+struct Proxy {
+    blackhole: Arc<AtomicBool>,
+    killed: Arc<AtomicBool>,
+    streams: Arc<Mutex<Vec<TcpStream>>>,
+}
+
+impl Proxy {
+    async fn new(listen: &str, target: &str) -> Self {
+        let blackhole = Arc::new(AtomicBool::new(false));
+        let killed = Arc::new(AtomicBool::new(false));
+        let streams = Arc::new(Mutex::new(vec![]));
+
+        let listener = TcpListener::bind(listen).await.unwrap();
+        let target = target.to_string();
+        let c_blackhole = blackhole.clone();
+        let c_killed = killed.clone();
+        let c_streams = streams.clone();
+        task::spawn(async move {
+            while let Ok((inbound, _)) = listener.accept().await {
+                if c_killed.load(Ordering::SeqCst) {
+                    continue;
+                }
+                let outbound = match TcpStream::connect(&target).await {
+                    Ok(outbound) => outbound,
+                    Err(_) => continue,
+                };
+                c_streams
+                    .lock()
+                    .unwrap()
+                    .extend([inbound.clone(), outbound.clone()]);
+                task::spawn(forward(
+                    inbound.clone(),
+                    outbound.clone(),
+                    c_blackhole.clone(),
+                ));
+                task::spawn(forward(outbound, inbound, Arc::new(AtomicBool::new(false))));
+            }
+        });
+
+        Self {
+            blackhole,
+            killed,
+            streams,
+        }
+    }
+
+    fn blackhole(&self) {
+        self.blackhole.store(true, Ordering::SeqCst);
+    }
+
+    fn kill(&self) {
+        self.killed.store(true, Ordering::SeqCst);
+        for stream in self.streams.lock().unwrap().drain(..) {
+            let _ = stream.shutdown(Shutdown::Both);
+        }
+    }
+}
+
+async fn forward(mut from: TcpStream, mut to: TcpStream, blackhole: Arc<AtomicBool>) {
+    let mut buffer = vec![0u8; 65_535];
+    loop {
+        let n = match from.read(&mut buffer).await {
+            Ok(0) | Err(_) => break,
+            Ok(n) => n,
+        };
+        if !blackhole.load(Ordering::SeqCst) && to.write_all(&buffer[..n]).await.is_err() {
+            break;
+        }
+    }
+}
+
+async fn failover_declarations(listen: &[&str], connect: &[&str], proxy: &Proxy) {
+    let key_expr = "test/failover";
+
+    let mut config = config::peer();
+    config.listen.endpoints = listen.iter().map(|e| e.parse().unwrap()).collect();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    config.transport.unicast.set_max_links(Some(2)).unwrap();
+    println!("[FO][01a] Opening peer01 session");
+    let peer01 = ztimeout!(zenoh::open(config).res_async()).unwrap();
+
+    let mut config = config::peer();
+    config.connect.endpoints = connect.iter().map(|e| e.parse().unwrap()).collect();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    config.transport.unicast.set_max_links(Some(2)).unwrap();
+    println!("[FO][02a] Opening peer02 session");
+    let peer02 = ztimeout!(zenoh::open(config).res_async()).unwrap();
+
+    // Wait for both links to be established
+    task::sleep(3 * SLEEP).await;
+
+    // The declaration sent on the primary link is lost
+    println!("[FO][01b] Blackholing the primary link");
+    proxy.blackhole();
+    let msgs = Arc::new(AtomicUsize::new(0));
+    let c_msgs = msgs.clone();
+    println!("[FO][02b] Subscribing on peer02 session");
+    let sub = ztimeout!(peer02
+        .declare_subscriber(key_expr)
+        .callback(move |_| {
+            c_msgs.fetch_add(1, Ordering::SeqCst);
+        })
+        .res_async())
+    .unwrap();
+    task::sleep(SLEEP).await;
+
+    println!("[FO][03b] Putting on peer01 session");
+    ztimeout!(peer01.put(key_expr, "lost").res_async()).unwrap();
+    task::sleep(SLEEP).await;
+    assert_eq!(msgs.load(Ordering::SeqCst), 0);
+
+    // The declaration is re-sent on the backup link once the primary one is down
+    println!("[FO][04b] Killing the primary link");
+    proxy.kill();
+    task::sleep(SLEEP).await;
+
+    println!("[FO][05b] Putting on peer01 session");
+    ztimeout!(peer01.put(key_expr, "received").res_async()).unwrap();
+    ztimeout!(async {
+        while msgs.load(Ordering::SeqCst) == 0 {
+            task::sleep(SLEEP).await;
+        }
+    });
+
+    ztimeout!(sub.undeclare().res_async()).unwrap();
+    ztimeout!(peer02.close().res_async()).unwrap();
+    ztimeout!(peer01.close().res_async()).unwrap();
+}
+
+#[test]
+fn failover_declarations_tcp() {
+    task::block_on(async {
+        zasync_executor_init!();
+        let _ = env_logger::try_init();
+
+        let proxy = Proxy::new("127.0.0.1:17470", "127.0.0.1:17471").await;
+        failover_declarations(
+            &["tcp/127.0.0.1:17471", "tcp/127.0.0.1:17472"],
+            &[
+                "tcp/127.0.0.1:17470#link_priority=0",
+                "tcp/127.0.0.1:17472#link_priority=1",
+            ],
+            &proxy,
+        )
+        .await;
+    });
+}
@@ -0,0 +1,139 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use async_std::prelude::FutureExt;
+use async_std::task;
+use std::time::Duration;
+use zenoh::prelude::r#async::*;
+use zenoh_core::zasync_executor_init;
+
+const TIMEOUT: Duration = Duration::from_secs(60);
+const SLEEP: Duration = Duration::from_millis(100);
+
+macro_rules! ztimeout {
+    ($f:expr) => {
+        $f.timeout(TIMEOUT).await.unwrap()
+    };
+}
+
+async fn open_session(listen: &str, connect: &[&str], gossip: bool) -> Session {
+    let mut config = config::peer();
+    config.listen.endpoints = vec![listen.parse().unwrap()];
+    config.connect.endpoints = connect
+        .iter()
+        .map(|e| e.parse().unwrap())
+        .collect::<Vec<_>>();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    config
+        .insert_json5("scouting/gossip/enabled", &gossip.to_string())
+        .unwrap();
+    config
+        .insert_json5("scouting/gossip/multihop", "true")
+        .unwrap();
+    config
+        .insert_json5("scouting/gossip/autoconnect", r#"{router: "", peer: ""}"#)
+        .unwrap();
+    println!("[  ][01a] Opening session");
+    ztimeout!(zenoh::open(config).res_async()).unwrap()
+}
+
+async fn close_session(session: Session) {
+    println!("[  ][01d] Closing session");
+    ztimeout!(session.close().res_async()).unwrap();
+}
+
+async fn discovered(session: &Session, zid: ZenohId) {
+    while !session
+        .info()
+        .discovered_nodes()
+        .res()
+        .await
+        .any(|hello| hello.zid == Some(zid))
+    {
+        task::sleep(SLEEP).await;
+    }
+}
+
+async fn connected(session: &Session, zid: ZenohId) {
+    while !session
+        .info()
+        .peers_zid()
+        .res()
+        .await
+        .any(|peer| peer == zid)
+    {
+        task::sleep(SLEEP).await;
+    }
+}
+
+#[test]
+fn gossip_discovered_nodes() {
+    task::block_on(async {
+        zasync_executor_init!();
+
+        let s01 = open_session("tcp/127.0.0.1:18471", &[], true).await;
+        let s02 = open_session("tcp/127.0.0.1:18472", &["tcp/127.0.0.1:18471"], true).await;
+        let s03 = open_session("tcp/127.0.0.1:18473", &["tcp/127.0.0.1:18471"], true).await;
+
+        // s03 learns s02 through s01 and doesn't connect to it
+        ztimeout!(discovered(&s03, s02.zid()));
+        assert!(!s03
+            .info()
+            .discovered_nodes()
+            .res()
+            .await
+            .any(|hello| hello.zid == Some(s01.zid())));
+        assert!(!s03
+            .info()
+            .peers_zid()
+            .res()
+            .await
+            .any(|peer| peer == s02.zid()));
+
+        close_session(s03).await;
+        close_session(s02).await;
+        close_session(s01).await;
+    });
+}
+
+#[test]
+fn gossip_runtime_config() {
+    task::block_on(async {
+        zasync_executor_init!();
+
+        let s01 = open_session("tcp/127.0.0.1:18474", &[], true).await;
+        let s02 = open_session("tcp/127.0.0.1:18475", &["tcp/127.0.0.1:18474"], false).await;
+        ztimeout!(connected(&s02, s01.zid()));
+
+        // Enable gossip on s02 at runtime and let it connect to the discovered peers
+        s02.config()
+            .insert_json5(
+                "scouting/gossip/autoconnect",
+                r#"{router: "", peer: "peer"}"#,
+            )
+            .unwrap();
+        s02.config()
+            .insert_json5("scouting/gossip/enabled", "true")
+            .unwrap();
+        // Let the runtime apply the configuration changes
+        task::sleep(Duration::from_secs(1)).await;
+
+        // s02 learns s03 through s01 and connects to it
+        let s03 = open_session("tcp/127.0.0.1:18476", &["tcp/127.0.0.1:18474"], true).await;
+        ztimeout!(connected(&s02, s03.zid()));
+
+        close_session(s03).await;
+        close_session(s02).await;
+        close_session(s01).await;
+    });
+}
@@ -0,0 +1,424 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use async_std::prelude::FutureExt;
+use async_std::task;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use zenoh::prelude::r#async::*;
+use zenoh_core::{zasync_executor_init, SyncResolve};
+
+const TIMEOUT: Duration = Duration::from_secs(60);
+const SLEEP: Duration = Duration::from_secs(1);
+
+const MSG_COUNT: usize = 100;
+const MSG_PERIOD: Duration = Duration::from_millis(10);
+
+macro_rules! ztimeout {
+    ($f:expr) => {
+        $f.timeout(TIMEOUT).await.unwrap()
+    };
+}
+
+async fn downsampling(endpoint: &str, flow: &str) {
+    let key_exprs = ["test/downsampling/a", "test/downsampling/b"];
+    let other_expr = "test/other";
+
+    // Open the sessions
+    let mut config = config::peer();
+    config.listen.endpoints = vec![endpoint.parse().unwrap()];
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    if flow == "ingress" {
+        config
+            .insert_json5(
+                "downsampling",
+                r#"[{ flow: "ingress", rules: [{ key_expr: "test/downsampling/**", freq: 10.0 }] }]"#,
+            )
+            .unwrap();
+    }
+    let sub_session = ztimeout!(zenoh::open(config).res_async()).unwrap();
+
+    let mut config = config::peer();
+    config.connect.endpoints = vec![endpoint.parse().unwrap()];
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    if flow == "egress" {
+        config
+            .insert_json5(
+                "downsampling",
+                r#"[{ flow: "egress", rules: [{ key_expr: "test/downsampling/**", freq: 10.0 }] }]"#,
+            )
+            .unwrap();
+    }
+    let pub_session = ztimeout!(zenoh::open(config).res_async()).unwrap();
+
+    let mut subs = vec![];
+    let mut downsampled = vec![];
+    for key_expr in key_exprs {
+        let count = Arc::new(AtomicUsize::new(0));
+        let c_count = count.clone();
+        subs.push(
+            ztimeout!(sub_session
+                .declare_subscriber(key_expr)
+                .callback(move |_| {
+                    c_count.fetch_add(1, Ordering::SeqCst);
+                })
+                .res_async())
+            .unwrap(),
+        );
+        downsampled.push(count);
+    }
+
+    let others = Arc::new(AtomicUsize::new(0));
+    let c_others = others.clone();
+    let other_sub = ztimeout!(sub_session
+        .declare_subscriber(other_expr)
+        .callback(move |_| {
+            c_others.fetch_add(1, Ordering::SeqCst);
+        })
+        .res_async())
+    .unwrap();
+
+    // Wait for the declarations to propagate
+    task::sleep(SLEEP).await;
+
+    // Put data at 100 Hz on all the key expressions
+    for _ in 0..MSG_COUNT {
+        for key_expr in key_exprs {
+            ztimeout!(pub_session.put(key_expr, "data").res_async()).unwrap();
+        }
+        ztimeout!(pub_session.put(other_expr, "data").res_async()).unwrap();
+        task::sleep(MSG_PERIOD).await;
+    }
+
+    // The non-matching key expression must not be downsampled
+    ztimeout!(async {
+        while others.load(Ordering::SeqCst) < MSG_COUNT {
+            task::sleep(SLEEP).await;
+        }
+    });
+
+    // Each key matching the rule must be downsampled to ~10 Hz on its own
+    for (key_expr, count) in key_exprs.iter().zip(downsampled.iter()) {
+        let count = count.load(Ordering::SeqCst);
+        println!(
+            "[DS] {} {}: received {}/{}",
+            flow, key_expr, count, MSG_COUNT
+        );
+        assert!(count > 0);
+        assert!(count < MSG_COUNT / 2);
+    }
+
+    for sub in subs {
+        ztimeout!(sub.undeclare().res_async()).unwrap();
+    }
+    ztimeout!(other_sub.undeclare().res_async()).unwrap();
+    ztimeout!(pub_session.close().res_async()).unwrap();
+    ztimeout!(sub_session.close().res_async()).unwrap();
+}
+
+#[test]
+fn downsampling_egress() {
+    task::block_on(async {
+        zasync_executor_init!();
+        downsampling("tcp/127.0.0.1:17460", "egress").await;
+    });
+}
+
+#[test]
+fn downsampling_ingress() {
+    task::block_on(async {
+        zasync_executor_init!();
+        downsampling("tcp/127.0.0.1:17461", "ingress").await;
+    });
+}
+
+#[test]
+fn downsampling_invalid_freq() {
+    for freq in ["0.0", "-10.0", "Infinity", "NaN"] {
+        let mut config = config::peer();
+        let rules = format!(
+            r#"[{{ flow: "egress", rules: [{{ key_expr: "test/downsampling", freq: {} }}] }}]"#,
+            freq
+        );
+        assert!(config.insert_json5("downsampling", &rules).is_err());
+    }
+}
+
+async fn access_control(endpoint: &str) {
+    let allowed_expr = "test/acl/allowed";
+    let denied_expr = "test/acl/denied";
+
+    // Open the sessions
+    let mut config = config::peer();
+    config.listen.endpoints = vec![endpoint.parse().unwrap()];
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    config
+        .insert_json5(
+            "access_control",
+            r#"{
+                enabled: true,
+                default_permission: "allow",
+                rules: [
+                    { flows: ["ingress"], actions: ["put"], key_exprs: ["test/acl/denied"], permission: "deny" },
+                ],
+            }"#,
+        )
+        .unwrap();
+    let sub_session = ztimeout!(zenoh::open(config).res_async()).unwrap();
+
+    let mut config = config::peer();
+    config.connect.endpoints = vec![endpoint.parse().unwrap()];
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    let pub_session = ztimeout!(zenoh::open(config).res_async()).unwrap();
+
+    let allowed = Arc::new(AtomicUsize::new(0));
+    let c_allowed = allowed.clone();
+    let allowed_sub = ztimeout!(sub_session
+        .declare_subscriber(allowed_expr)
+        .callback(move |_| {
+            c_allowed.fetch_add(1, Ordering::SeqCst);
+        })
+        .res_async())
+    .unwrap();
+
+    let denied = Arc::new(AtomicUsize::new(0));
+    let c_denied = denied.clone();
+    let denied_sub = ztimeout!(sub_session
+        .declare_subscriber(denied_expr)
+        .callback(move |_| {
+            c_denied.fetch_add(1, Ordering::SeqCst);
+        })
+        .res_async())
+    .unwrap();
+
+    // Wait for the declarations to propagate
+    task::sleep(SLEEP).await;
+
+    for _ in 0..MSG_COUNT {
+        ztimeout!(pub_session.put(denied_expr, "data").res_async()).unwrap();
+        ztimeout!(pub_session.put(allowed_expr, "data").res_async()).unwrap();
+    }
+
+    ztimeout!(async {
+        while allowed.load(Ordering::SeqCst) < MSG_COUNT {
+            task::sleep(SLEEP).await;
+        }
+    });
+    assert_eq!(denied.load(Ordering::SeqCst), 0);
+
+    ztimeout!(allowed_sub.undeclare().res_async()).unwrap();
+    ztimeout!(denied_sub.undeclare().res_async()).unwrap();
+    ztimeout!(pub_session.close().res_async()).unwrap();
+    ztimeout!(sub_session.close().res_async()).unwrap();
+}
+
+#[test]
+fn access_control_put() {
+    task::block_on(async {
+        zasync_executor_init!();
+        access_control("tcp/127.0.0.1:17462").await;
+    });
+}
+
+async fn access_control_reply(endpoint: &str) {
+    let queried_expr = "test/acl/reply/**";
+    let allowed_expr = "test/acl/reply/allowed";
+    let denied_expr = "test/acl/reply/denied";
+
+    // Open the sessions
+    let mut config = config::peer();
+    config.listen.endpoints = vec![endpoint.parse().unwrap()];
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    config
+        .insert_json5(
+            "access_control",
+            r#"{
+                enabled: true,
+                default_permission: "allow",
+                rules: [
+                    { flows: ["ingress"], actions: ["get"], key_exprs: ["test/acl/reply/denied"], permission: "deny" },
+                ],
+            }"#,
+        )
+        .unwrap();
+    let get_session = ztimeout!(zenoh::open(config).res_async()).unwrap();
+
+    let mut config = config::peer();
+    config.connect.endpoints = vec![endpoint.parse().unwrap()];
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    let qbl_session = ztimeout!(zenoh::open(config).res_async()).unwrap();
+
+    // The queryable replies on both the allowed and the denied key expressions
+    let queryable = ztimeout!(qbl_session
+        .declare_queryable(queried_expr)
+        .callback(move |query| {
+            for key_expr in [allowed_expr, denied_expr] {
+                let sample = Sample::try_from(key_expr, "data").unwrap();
+                query.reply(Ok(sample)).res_sync().unwrap();
+            }
+        })
+        .res_async())
+    .unwrap();
+
+    // Wait for the declarations to propagate
+    task::sleep(SLEEP).await;
+
+    let replies = ztimeout!(get_session.get(queried_expr).res_async()).unwrap();
+    let mut keys = vec![];
+    while let Ok(reply) = ztimeout!(replies.recv_async()) {
+        keys.push(reply.sample.unwrap().key_expr.to_string());
+    }
+    assert_eq!(keys, vec![allowed_expr.to_string()]);
+
+    ztimeout!(queryable.undeclare().res_async()).unwrap();
+    ztimeout!(qbl_session.close().res_async()).unwrap();
+    ztimeout!(get_session.close().res_async()).unwrap();
+}
+
+#[test]
+fn access_control_get_reply() {
+    task::block_on(async {
+        zasync_executor_init!();
+        access_control_reply("tcp/127.0.0.1:17465").await;
+    });
+}
+
+#[cfg(feature = "unstable")]
+mod user_interceptor {
+    use super::*;
+    use zenoh::interceptor::*;
+
+    // Drops the data published on the dropped key expression and counts the other ones
+    struct DropData {
+        dropped_expr: &'static str,
+        forwarded: Arc<AtomicUsize>,
+    }
+
+    impl InterceptorTrait for DropData {
+        fn intercept(&self, msg: ZenohMessage, mappings: &KeyExprMappings) -> Option<ZenohMessage> {
+            if let ZenohBody::Data(data) = &msg.body {
+                if mappings
+                    .resolve(&data.key)
+                    .map_or(false, |key| key.as_str() == self.dropped_expr)
+                {
+                    return None;
+                }
+                self.forwarded.fetch_add(1, Ordering::SeqCst);
+            }
+            Some(msg)
+        }
+    }
+
+    struct DropDataFactory {
+        dropped_expr: &'static str,
+        forwarded: Arc<AtomicUsize>,
+    }
+
+    impl InterceptorFactoryTrait for DropDataFactory {
+        fn new_transport_unicast(
+            &self,
+            _transport: &TransportUnicast,
+        ) -> (Option<Interceptor>, Option<Interceptor>) {
+            let egress: Interceptor = Box::new(DropData {
+                dropped_expr: self.dropped_expr,
+                forwarded: self.forwarded.clone(),
+            });
+            (None, Some(egress))
+        }
+
+        fn new_transport_multicast(&self, _transport: &TransportMulticast) -> Option<Interceptor> {
+            None
+        }
+
+        fn new_peer_multicast(
+            &self,
+            _transport: &TransportMulticast,
+            _peer: &TransportPeer,
+        ) -> Option<Interceptor> {
+            None
+        }
+    }
+
+    async fn user_interceptor(endpoint: &str) {
+        let forwarded_expr = "test/intercepted/forwarded";
+        let dropped_expr = "test/intercepted/dropped";
+
+        // Open the sessions
+        let mut config = config::peer();
+        config.listen.endpoints = vec![endpoint.parse().unwrap()];
+        config.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let sub_session = ztimeout!(zenoh::open(config).res_async()).unwrap();
+
+        let mut config = config::peer();
+        config.connect.endpoints = vec![endpoint.parse().unwrap()];
+        config.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let intercepted = Arc::new(AtomicUsize::new(0));
+        let pub_session = ztimeout!(zenoh::open(config)
+            .with_interceptor(DropDataFactory {
+                dropped_expr,
+                forwarded: intercepted.clone(),
+            })
+            .res_async())
+        .unwrap();
+
+        let forwarded = Arc::new(AtomicUsize::new(0));
+        let c_forwarded = forwarded.clone();
+        let forwarded_sub = ztimeout!(sub_session
+            .declare_subscriber(forwarded_expr)
+            .callback(move |_| {
+                c_forwarded.fetch_add(1, Ordering::SeqCst);
+            })
+            .res_async())
+        .unwrap();
+
+        let dropped = Arc::new(AtomicUsize::new(0));
+        let c_dropped = dropped.clone();
+        let dropped_sub = ztimeout!(sub_session
+            .declare_subscriber(dropped_expr)
+            .callback(move |_| {
+                c_dropped.fetch_add(1, Ordering::SeqCst);
+            })
+            .res_async())
+        .unwrap();
+
+        // Wait for the declarations to propagate
+        task::sleep(SLEEP).await;
+
+        for _ in 0..MSG_COUNT {
+            ztimeout!(pub_session.put(dropped_expr, "data").res_async()).unwrap();
+            ztimeout!(pub_session.put(forwarded_expr, "data").res_async()).unwrap();
+        }
+
+        ztimeout!(async {
+            while forwarded.load(Ordering::SeqCst) < MSG_COUNT {
+                task::sleep(SLEEP).await;
+            }
+        });
+        assert_eq!(dropped.load(Ordering::SeqCst), 0);
+        assert_eq!(intercepted.load(Ordering::SeqCst), MSG_COUNT);
+
+        ztimeout!(forwarded_sub.undeclare().res_async()).unwrap();
+        ztimeout!(dropped_sub.undeclare().res_async()).unwrap();
+        ztimeout!(pub_session.close().res_async()).unwrap();
+        ztimeout!(sub_session.close().res_async()).unwrap();
+    }
+
+    #[test]
+    fn user_interceptor_put() {
+        task::block_on(async {
+            zasync_executor_init!();
+            user_interceptor("tcp/127.0.0.1:17463").await;
+        });
+    }
+}
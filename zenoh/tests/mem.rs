@@ -0,0 +1,80 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#![cfg(feature = "test")]
+use async_std::prelude::FutureExt;
+use async_std::task;
+use std::convert::TryFrom;
+use std::time::{Duration, SystemTime};
+use zenoh::prelude::r#async::*;
+use zenoh_core::zasync_executor_init;
+
+const TIMEOUT: Duration = Duration::from_secs(60);
+
+macro_rules! ztimeout {
+    ($f:expr) => {
+        $f.timeout(TIMEOUT).await.unwrap()
+    };
+}
+
+#[test]
+fn zenoh_mem_transport() {
+    task::block_on(async {
+        zasync_executor_init!();
+        let key_expr = "test/mem";
+
+        // Timestamp the publications with a manual clock
+        let t0 = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+        zenoh::test::set_time(t0);
+        let mut config = zenoh::test::config(&["test-mem"], &[]);
+        config.insert_json5("timestamping/enabled", "true").unwrap();
+        println!("[  ][01a] Opening peer01 session");
+        let peer01 = ztimeout!(zenoh::open(config).res_async()).unwrap();
+        println!("[  ][02a] Opening peer02 session");
+        let peer02 =
+            ztimeout!(zenoh::open(zenoh::test::config(&[], &["test-mem"])).res_async()).unwrap();
+
+        let sub = ztimeout!(peer02.declare_subscriber(key_expr).res_async()).unwrap();
+        let qbl = ztimeout!(peer02
+            .declare_queryable(key_expr)
+            .callback(move |query| {
+                let rep = Sample::try_from(key_expr, "reply").unwrap();
+                task::block_on(async { ztimeout!(query.reply(Ok(rep)).res_async()).unwrap() });
+            })
+            .res_async())
+        .unwrap();
+        ztimeout!(zenoh::test::sync(&peer01, &peer02)).unwrap();
+
+        // The samples are delivered in order, timestamped by the manual clock
+        for i in 0..100_u64 {
+            zenoh::test::advance_time(Duration::from_secs(1));
+            ztimeout!(peer01.put(key_expr, i as i64).res_async()).unwrap();
+        }
+        for i in 0..100_u64 {
+            let sample = ztimeout!(sub.recv_async()).unwrap();
+            assert_eq!(i64::try_from(&sample.value).unwrap(), i as i64);
+            let time = sample.timestamp.unwrap().get_time().to_system_time();
+            let expected = t0 + Duration::from_secs(i + 1);
+            assert!(time >= expected && time < expected + Duration::from_millis(1));
+        }
+
+        let replies = ztimeout!(peer01.get(key_expr).res_async()).unwrap();
+        let reply = ztimeout!(replies.recv_async()).unwrap();
+        assert_eq!(reply.sample.unwrap().value.to_string(), "reply");
+
+        ztimeout!(qbl.undeclare().res_async()).unwrap();
+        ztimeout!(sub.undeclare().res_async()).unwrap();
+        ztimeout!(peer01.close().res_async()).unwrap();
+        ztimeout!(peer02.close().res_async()).unwrap();
+    });
+}
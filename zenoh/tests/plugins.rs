@@ -0,0 +1,147 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use async_std::prelude::FutureExt;
+use async_std::task;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use zenoh::plugins::{
+    Plugin, PluginsManager, Response, RunningPlugin, RunningPluginTrait, StartArgs,
+    ValidationFunction, ZenohPlugin,
+};
+use zenoh::prelude::r#async::*;
+use zenoh::query::Reply;
+use zenoh::runtime::Runtime;
+use zenoh_core::{zasync_executor_init, zerror, Result as ZResult};
+
+const TIMEOUT: Duration = Duration::from_secs(60);
+
+macro_rules! ztimeout {
+    ($f:expr) => {
+        $f.timeout(TIMEOUT).await.unwrap()
+    };
+}
+
+static STARTED: AtomicUsize = AtomicUsize::new(0);
+
+// A plugin counting its starts
+struct TestPlugin;
+
+impl ZenohPlugin for TestPlugin {}
+
+impl Plugin for TestPlugin {
+    type StartArgs = StartArgs;
+    type RunningPlugin = RunningPlugin;
+    const STATIC_NAME: &'static str = "test";
+
+    fn start(_name: &str, _runtime: &Self::StartArgs) -> ZResult<RunningPlugin> {
+        STARTED.fetch_add(1, Ordering::SeqCst);
+        Ok(Box::new(RunningTestPlugin))
+    }
+}
+
+struct RunningTestPlugin;
+
+impl RunningPluginTrait for RunningTestPlugin {
+    fn config_checker(&self) -> ValidationFunction {
+        Arc::new(|_, _, _| Ok(None))
+    }
+
+    fn adminspace_getter<'a>(
+        &'a self,
+        _selector: &'a Selector<'a>,
+        plugin_status_key: &str,
+    ) -> ZResult<Vec<Response>> {
+        Ok(vec![Response::new(
+            format!("{}/status", plugin_status_key),
+            "running".into(),
+        )])
+    }
+}
+
+// A plugin failing to start
+struct FailingPlugin;
+
+impl ZenohPlugin for FailingPlugin {}
+
+impl Plugin for FailingPlugin {
+    type StartArgs = StartArgs;
+    type RunningPlugin = RunningPlugin;
+    const STATIC_NAME: &'static str = "failing";
+
+    fn start(name: &str, _runtime: &Self::StartArgs) -> ZResult<RunningPlugin> {
+        Err(zerror!("Plugin {} failed to start", name).into())
+    }
+}
+
+fn config() -> Config {
+    let mut config = config::peer();
+    config.scouting.multicast.set_enabled(Some(false)).unwrap();
+    config
+}
+
+#[test]
+fn runtime_with_plugins() {
+    task::block_on(async {
+        zasync_executor_init!();
+
+        let plugins = PluginsManager::static_plugins_only()
+            .add_static::<TestPlugin>()
+            .add_static::<FailingPlugin>();
+
+        // The plugins failing to start do not prevent the runtime from starting
+        let runtime = ztimeout!(Runtime::new(config()).with_plugins(plugins).res_async()).unwrap();
+        assert_eq!(STARTED.load(Ordering::SeqCst), 1);
+
+        // Only the running plugins are exposed on the admin space
+        let zid = runtime.zid;
+        let session = ztimeout!(zenoh::init(runtime.clone()).res_async()).unwrap();
+        let replies: Vec<Reply> = ztimeout!(session
+            .get(format!("@/router/{}/status/plugins/**", zid))
+            .res_async())
+        .unwrap()
+        .into_iter()
+        .collect();
+        let keys: Vec<String> = replies
+            .into_iter()
+            .filter_map(|reply| reply.sample.ok())
+            .map(|sample| sample.key_expr.to_string())
+            .collect();
+        assert!(keys.contains(&format!("@/router/{}/status/plugins/test/status", zid)));
+        assert!(!keys.iter().any(|key| key.contains("/failing")));
+
+        ztimeout!(session.close().res_async()).unwrap();
+        ztimeout!(runtime.close()).unwrap();
+    });
+}
+
+#[test]
+fn runtime_with_required_plugin_failure() {
+    task::block_on(async {
+        zasync_executor_init!();
+
+        let mut config = config();
+        config
+            .insert_json5(
+                "plugins/missing",
+                r#"{ __required__: true, __path__: ["/nonexistent/libzplugin_missing.so"] }"#,
+            )
+            .unwrap();
+        let plugins = PluginsManager::dynamic(config.libloader());
+
+        // A required plugin failing to load makes the runtime fail to start
+        let res = ztimeout!(Runtime::new(config).with_plugins(plugins).res_async());
+        assert!(res.is_err());
+    });
+}
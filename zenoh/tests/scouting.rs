@@ -0,0 +1,92 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use async_std::prelude::FutureExt;
+use async_std::task;
+use futures::StreamExt;
+use std::time::{Duration, Instant};
+use zenoh::prelude::r#async::*;
+use zenoh::scouting::WhatAmI;
+use zenoh_core::zasync_executor_init;
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+const SCOUT_TIMEOUT: Duration = Duration::from_secs(1);
+
+macro_rules! ztimeout {
+    ($f:expr) => {
+        $f.timeout(TIMEOUT).await.unwrap()
+    };
+}
+
+#[test]
+fn scout_timeout() {
+    task::block_on(async {
+        zasync_executor_init!();
+
+        let start = Instant::now();
+        let receiver = zenoh::scout(WhatAmI::Router, config::default())
+            .multicast_interface("127.0.0.1")
+            .timeout(SCOUT_TIMEOUT)
+            .res()
+            .await
+            .unwrap();
+        // The receiver is disconnected once the scout timeout expired
+        while ztimeout!(receiver.recv_async()).is_ok() {}
+        assert!(start.elapsed() >= SCOUT_TIMEOUT);
+    });
+}
+
+#[test]
+fn scout_multicast_interface() {
+    task::block_on(async {
+        zasync_executor_init!();
+
+        // No scout message can be sent on an unknown interface: scouting
+        // stops right away even without timeout
+        let receiver = zenoh::scout(WhatAmI::Router, config::default())
+            .multicast_interface("zenoh-unknown-interface")
+            .res()
+            .await
+            .unwrap();
+        assert!(ztimeout!(receiver.recv_async()).is_err());
+    });
+}
+
+#[test]
+fn scout_into_stream() {
+    task::block_on(async {
+        zasync_executor_init!();
+
+        // The stream keeps scouting alive until it is dropped
+        let mut hellos = zenoh::scout(WhatAmI::Router, config::default())
+            .multicast_interface("127.0.0.1")
+            .res()
+            .await
+            .unwrap()
+            .into_stream();
+        assert!(hellos.next().timeout(SCOUT_TIMEOUT).await.is_err());
+        drop(hellos);
+
+        // The stream ends once the scout timeout expired
+        let start = Instant::now();
+        let hellos = zenoh::scout(WhatAmI::Router, config::default())
+            .multicast_interface("127.0.0.1")
+            .timeout(SCOUT_TIMEOUT)
+            .res()
+            .await
+            .unwrap()
+            .into_stream();
+        ztimeout!(hellos.count());
+        assert!(start.elapsed() >= SCOUT_TIMEOUT);
+    });
+}
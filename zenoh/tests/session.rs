@@ -13,10 +13,17 @@
 //
 use async_std::prelude::FutureExt;
 use async_std::task;
+use futures::SinkExt;
+use std::convert::TryFrom;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use zenoh::handlers::OverflowPolicy;
 use zenoh::prelude::r#async::*;
+use zenoh::publication::{RateLimit, RateLimitBehavior};
+use zenoh::runtime::Runtime;
+use zenoh::selector::TimeRange;
+use zenoh::subscriber::{PullBufferPolicy, Reliability, SubMode, Subscriber};
 use zenoh_core::zasync_executor_init;
 
 const TIMEOUT: Duration = Duration::from_secs(60);
@@ -54,6 +61,14 @@ async fn open_session(endpoints: &[&str]) -> (Session, Session) {
     (peer01, peer02)
 }
 
+async fn init_session(runtime: &Runtime) -> (Session, Session) {
+    println!("[  ][01a] Initializing peer01 session");
+    let peer01 = ztimeout!(zenoh::init(runtime.clone()).res_async()).unwrap();
+    println!("[  ][02a] Initializing peer02 session");
+    let peer02 = ztimeout!(zenoh::init(runtime.clone()).res_async()).unwrap();
+    (peer01, peer02)
+}
+
 async fn close_session(peer01: Session, peer02: Session) {
     println!("[  ][01d] Closing peer02 session");
     ztimeout!(peer01.close().res_async()).unwrap();
@@ -97,6 +112,9 @@ async fn test_session_pubsub(peer01: &Session, peer02: &Session) {
             .unwrap();
         }
 
+        // Flush the data to the network
+        ztimeout!(peer02.flush().res_async()).unwrap();
+
         ztimeout!(async {
             loop {
                 let cnt = msgs.load(Ordering::SeqCst);
@@ -117,6 +135,302 @@ async fn test_session_pubsub(peer01: &Session, peer02: &Session) {
     }
 }
 
+async fn test_session_delete(peer01: &Session, peer02: &Session) {
+    let key_expr = "test/session/delete";
+
+    // Subscribe to data
+    println!("[DL][01b] Subscribing on peer01 session");
+    let sub = ztimeout!(peer01.declare_subscriber(key_expr).res_async()).unwrap();
+
+    // Wait for the declaration to propagate
+    task::sleep(SLEEP).await;
+
+    // Delete data with an explicit timestamp
+    println!("[DL][02b] Deleting on peer02 session");
+    let timestamp = zenoh::time::new_reception_timestamp();
+    ztimeout!(peer02
+        .delete(key_expr)
+        .timestamp(timestamp)
+        .congestion_control(CongestionControl::Block)
+        .res_async())
+    .unwrap();
+
+    let sample = ztimeout!(sub.recv_async()).unwrap();
+    assert_eq!(sample.kind, SampleKind::Delete);
+    assert_eq!(sample.timestamp, Some(timestamp));
+    assert_eq!(sample.value.payload.len(), 0);
+
+    // Put and delete data through a publisher with explicit options
+    println!("[DL][03b] Publishing on peer02 session");
+    let publisher = ztimeout!(peer02.declare_publisher(key_expr).res_async()).unwrap();
+    let timestamp = zenoh::time::new_reception_timestamp();
+    ztimeout!(publisher
+        .put("value")
+        .encoding(KnownEncoding::TextPlain)
+        .timestamp(timestamp)
+        .res_async())
+    .unwrap();
+    let sample = ztimeout!(sub.recv_async()).unwrap();
+    assert_eq!(sample.kind, SampleKind::Put);
+    assert_eq!(sample.value.encoding, KnownEncoding::TextPlain.into());
+    assert_eq!(sample.timestamp, Some(timestamp));
+
+    let timestamp = zenoh::time::new_reception_timestamp();
+    ztimeout!(publisher.delete().timestamp(timestamp).res_async()).unwrap();
+    let sample = ztimeout!(sub.recv_async()).unwrap();
+    assert_eq!(sample.kind, SampleKind::Delete);
+    assert_eq!(sample.timestamp, Some(timestamp));
+    assert!(!sample.wildcard);
+    ztimeout!(publisher.undeclare().res_async()).unwrap();
+
+    // Delete data on a wildcard key expression
+    println!("[DL][04b] Deleting on a wildcard key expression on peer02 session");
+    ztimeout!(peer02.delete("test/session/*").res_async()).unwrap();
+    let sample = ztimeout!(sub.recv_async()).unwrap();
+    assert_eq!(sample.kind, SampleKind::Delete);
+    assert_eq!(sample.key_expr.as_str(), "test/session/*");
+    assert!(sample.wildcard);
+
+    println!("[DL][05b] Unsubscribing on peer01 session");
+    ztimeout!(sub.undeclare().res_async()).unwrap();
+
+    // Wait for the declaration to propagate
+    task::sleep(SLEEP).await;
+}
+
+async fn test_session_pull(peer01: &Session, peer02: &Session) {
+    let key_expr = "test/session/pull";
+
+    // Subscribe to data in pull mode, buffering the 2 last samples
+    println!("[PL][01b] Pull subscribing on peer01 session");
+    let sub = ztimeout!(peer01
+        .declare_subscriber(key_expr)
+        .pull_mode()
+        .buffer_policy(PullBufferPolicy::DropOldest(2))
+        .res_async())
+    .unwrap();
+
+    // Wait for the declaration to propagate
+    task::sleep(SLEEP).await;
+
+    println!("[PL][02b] Putting on peer02 session");
+    for i in 0..4 {
+        ztimeout!(peer02.put(key_expr, i.to_string()).res_async()).unwrap();
+    }
+    task::sleep(SLEEP).await;
+    assert!(sub.receiver.is_empty());
+
+    // Pull the buffered samples one by one, the oldest ones being dropped
+    println!("[PL][03b] Pulling on peer01 session");
+    ztimeout!(sub.pull_max(1).res_async()).unwrap();
+    let sample = ztimeout!(sub.recv_async()).unwrap();
+    assert_eq!(String::try_from(sample.value).unwrap(), "2");
+    ztimeout!(sub.pull().res_async()).unwrap();
+    let sample = ztimeout!(sub.recv_async()).unwrap();
+    assert_eq!(String::try_from(sample.value).unwrap(), "3");
+
+    // The samples pulled before being received are delivered on reception
+    ztimeout!(sub.pull_max(1).res_async()).unwrap();
+    task::sleep(SLEEP).await;
+    ztimeout!(peer02.put(key_expr, "4").res_async()).unwrap();
+    let sample = ztimeout!(sub.recv_async()).unwrap();
+    assert_eq!(String::try_from(sample.value).unwrap(), "4");
+    ztimeout!(peer02.put(key_expr, "5").res_async()).unwrap();
+    task::sleep(SLEEP).await;
+    assert!(sub.receiver.is_empty());
+    ztimeout!(sub.pull().res_async()).unwrap();
+    let sample = ztimeout!(sub.recv_async()).unwrap();
+    assert_eq!(String::try_from(sample.value).unwrap(), "5");
+
+    // A pull replaces the samples left pending by the previous one
+    println!("[PL][04b] Pulling twice on peer01 session");
+    ztimeout!(sub.pull_max(1).res_async()).unwrap();
+    ztimeout!(sub.pull_max(1).res_async()).unwrap();
+    task::sleep(SLEEP).await;
+    for i in 6..9 {
+        ztimeout!(peer02.put(key_expr, i.to_string()).res_async()).unwrap();
+    }
+    let sample = ztimeout!(sub.recv_async()).unwrap();
+    assert_eq!(String::try_from(sample.value).unwrap(), "6");
+    task::sleep(SLEEP).await;
+    assert!(sub.receiver.is_empty());
+
+    println!("[PL][05b] Unsubscribing on peer01 session");
+    ztimeout!(sub.undeclare().res_async()).unwrap();
+
+    // Wait for the declaration to propagate
+    task::sleep(SLEEP).await;
+}
+
+async fn test_session_queue(peer01: &Session, peer02: &Session) {
+    let key_expr = "test/session/queue";
+
+    // Subscribe to data with bounded queues
+    println!("[QU][01b] Subscribing on peer01 session");
+    let sub_oldest = ztimeout!(peer01
+        .declare_subscriber(key_expr)
+        .queue(2, OverflowPolicy::DropOldest)
+        .res_async())
+    .unwrap();
+    let sub_newest = ztimeout!(peer01
+        .declare_subscriber(key_expr)
+        .queue(2, OverflowPolicy::DropNewest)
+        .res_async())
+    .unwrap();
+
+    // Wait for the declaration to propagate
+    task::sleep(SLEEP).await;
+
+    println!("[QU][02b] Putting on peer02 session");
+    for i in 0..4 {
+        ztimeout!(peer02.put(key_expr, i.to_string()).res_async()).unwrap();
+    }
+    task::sleep(SLEEP).await;
+
+    let values = |sub: &Subscriber<'_, flume::Receiver<Sample>>| {
+        sub.try_iter()
+            .map(|sample| String::try_from(sample.value).unwrap())
+            .collect::<Vec<String>>()
+    };
+    assert_eq!(values(&sub_oldest), ["2", "3"]);
+    assert_eq!(values(&sub_newest), ["0", "1"]);
+
+    println!("[QU][03b] Unsubscribing on peer01 session");
+    ztimeout!(sub_oldest.undeclare().res_async()).unwrap();
+    ztimeout!(sub_newest.undeclare().res_async()).unwrap();
+
+    // Wait for the declaration to propagate
+    task::sleep(SLEEP).await;
+}
+
+#[cfg(feature = "unstable")]
+async fn test_session_acked(peer01: &Session, peer02: &Session) {
+    let key_expr = "test/session/acked";
+
+    // Put acked data without any matching subscriber
+    println!("[AK][01b] Putting acked data on peer02 session without subscriber");
+    ztimeout!(peer02.put(key_expr, "value").acked(true).res_async()).unwrap();
+
+    // Subscribe to data
+    println!("[AK][02b] Subscribing on peer01 session");
+    let sub = ztimeout!(peer01.declare_subscriber(key_expr).res_async()).unwrap();
+
+    // Wait for the declaration to propagate
+    task::sleep(SLEEP).await;
+
+    // The acked put resolves once peer01 received the data
+    println!("[AK][03b] Putting acked data on peer02 session");
+    for _ in 0..MSG_COUNT {
+        ztimeout!(peer02.put(key_expr, "value").acked(true).res_async()).unwrap();
+    }
+    assert_eq!(sub.receiver.len(), MSG_COUNT);
+
+    println!("[AK][04b] Unsubscribing on peer01 session");
+    ztimeout!(sub.undeclare().res_async()).unwrap();
+
+    // Wait for the declaration to propagate
+    task::sleep(SLEEP).await;
+}
+
+#[cfg(feature = "unstable")]
+async fn test_session_attachment(peer01: &Session, peer02: &Session) {
+    let key_expr = "test/session/attachment";
+
+    // Subscribe to data
+    println!("[AT][01b] Subscribing on peer01 session");
+    let sub = ztimeout!(peer01.declare_subscriber(key_expr).res_async()).unwrap();
+
+    // Wait for the declaration to propagate
+    task::sleep(SLEEP).await;
+
+    // The attachments of puts and deletes are received with the samples
+    println!("[AT][02b] Putting and deleting data with attachments on peer02 session");
+    ztimeout!(peer02
+        .put(key_expr, "value")
+        .attachment(b"put".to_vec())
+        .res_async())
+    .unwrap();
+    ztimeout!(peer02
+        .delete(key_expr)
+        .attachment(b"delete".to_vec())
+        .res_async())
+    .unwrap();
+    ztimeout!(peer02.put(key_expr, "value").res_async()).unwrap();
+
+    let sample = ztimeout!(sub.recv_async()).unwrap();
+    assert_eq!(sample.kind, SampleKind::Put);
+    assert_eq!(sample.attachment.unwrap().contiguous().as_ref(), b"put");
+    let sample = ztimeout!(sub.recv_async()).unwrap();
+    assert_eq!(sample.kind, SampleKind::Delete);
+    assert_eq!(sample.attachment.unwrap().contiguous().as_ref(), b"delete");
+    let sample = ztimeout!(sub.recv_async()).unwrap();
+    assert!(sample.attachment.is_none());
+
+    // The same goes for the publications of a publisher
+    println!("[AT][03b] Publishing data with an attachment on peer02 session");
+    let publisher = ztimeout!(peer02.declare_publisher(key_expr).res_async()).unwrap();
+    ztimeout!(publisher
+        .put("value")
+        .attachment(b"publication".to_vec())
+        .res_async())
+    .unwrap();
+    let sample = ztimeout!(sub.recv_async()).unwrap();
+    assert_eq!(
+        sample.attachment.unwrap().contiguous().as_ref(),
+        b"publication"
+    );
+    ztimeout!(publisher.undeclare().res_async()).unwrap();
+
+    println!("[AT][04b] Unsubscribing on peer01 session");
+    ztimeout!(sub.undeclare().res_async()).unwrap();
+
+    // The attachments of queries are received by the queryables, with or without value
+    println!("[AT][05b] Queryable on peer01 session");
+    let qbl = ztimeout!(peer01.declare_queryable(key_expr).res_async()).unwrap();
+
+    // Wait for the declaration to propagate
+    task::sleep(SLEEP).await;
+
+    println!("[AT][06b] Querying with attachments on peer02 session");
+    let _replies = ztimeout!(peer02
+        .get(key_expr)
+        .attachment(b"query".to_vec())
+        .res_async())
+    .unwrap();
+    let query = ztimeout!(qbl.recv_async()).unwrap();
+    assert_eq!(query.attachment().unwrap().contiguous().as_ref(), b"query");
+    assert!(query.value().is_none());
+    drop(query);
+
+    let _replies = ztimeout!(peer02
+        .get(key_expr)
+        .with_value("value")
+        .attachment(b"query".to_vec())
+        .res_async())
+    .unwrap();
+    let query = ztimeout!(qbl.recv_async()).unwrap();
+    assert_eq!(query.attachment().unwrap().contiguous().as_ref(), b"query");
+    assert!(query.value().is_some());
+    drop(query);
+
+    // Restricting the accepted replies keeps the attachment
+    let _replies = ztimeout!(peer02
+        .get(key_expr)
+        .attachment(b"query".to_vec())
+        .accept_replies(zenoh::query::ReplyKeyExpr::Any)
+        .res_async())
+    .unwrap();
+    let query = ztimeout!(qbl.recv_async()).unwrap();
+    assert_eq!(query.attachment().unwrap().contiguous().as_ref(), b"query");
+    drop(query);
+
+    println!("[AT][07b] Unqueryable on peer01 session");
+    ztimeout!(qbl.undeclare().res_async()).unwrap();
+
+    // Wait for the declaration to propagate
+    task::sleep(SLEEP).await;
+}
+
 async fn test_session_qryrep(peer01: &Session, peer02: &Session) {
     let key_expr = "test/session";
 
@@ -166,6 +480,325 @@ async fn test_session_qryrep(peer01: &Session, peer02: &Session) {
     }
 }
 
+async fn test_session_reply_iter(peer01: &Session, peer02: &Session) {
+    let key_expr = "test/session/bulk";
+
+    // Queryable replying in bulk, with a reply not intersecting with the query in the middle
+    println!("[RI][01c] Queryable on peer01 session");
+    let (result_sender, result_receiver) = flume::bounded(1);
+    let qbl = ztimeout!(peer01
+        .declare_queryable(format!("{}/**", key_expr))
+        .callback(move |query| {
+            let replies = (0..MSG_COUNT).map(move |i| {
+                if i == MSG_COUNT / 2 {
+                    Sample::try_from("test/session/disjoint", i.to_string()).unwrap()
+                } else {
+                    Sample::try_from(format!("{}/{}", key_expr, i), i.to_string()).unwrap()
+                }
+            });
+            let handle =
+                task::block_on(async { ztimeout!(query.reply_iter(replies).res_async()).unwrap() });
+            let result_sender = result_sender.clone();
+            task::spawn(async move { result_sender.send(handle.res_async().await) });
+        })
+        .res_async())
+    .unwrap();
+
+    // Wait for the declaration to propagate
+    task::sleep(SLEEP).await;
+
+    // Get data
+    println!("[RI][02c] Getting on peer02 session");
+    let rs = ztimeout!(peer02.get(format!("{}/**", key_expr)).res_async()).unwrap();
+    let mut cnt = 0;
+    while let Ok(s) = ztimeout!(rs.recv_async()) {
+        assert!(s.sample.is_ok());
+        cnt += 1;
+    }
+    // The disjoint reply is skipped, and reported once all the others are sent
+    assert_eq!(cnt, MSG_COUNT - 1);
+    assert!(ztimeout!(result_receiver.recv_async()).unwrap().is_err());
+
+    println!("[RI][03c] Unqueryable on peer01 session");
+    ztimeout!(qbl.undeclare().res_async()).unwrap();
+
+    // Wait for the declaration to propagate
+    task::sleep(SLEEP).await;
+}
+
+#[cfg(feature = "unstable")]
+async fn test_session_reply_order(peer01: &Session, peer02: &Session) {
+    use zenoh::query::ReplyKeyExpr;
+    let key_expr = "test/session/order";
+
+    // Queryable replying in reverse timestamp order, and on a key expression
+    // intersecting the query's one without being included in it
+    println!("[RO][01c] Queryable on peer01 session");
+    let qbl = ztimeout!(peer01
+        .declare_queryable(format!("{}/**", key_expr))
+        .callback(move |query| {
+            let timestamps: Vec<_> = (0..3)
+                .map(|_| zenoh::time::new_reception_timestamp())
+                .collect();
+            let mut replies: Vec<Sample> = timestamps
+                .into_iter()
+                .enumerate()
+                .rev()
+                .map(|(i, timestamp)| {
+                    let mut sample =
+                        Sample::try_from(format!("{}/{}", key_expr, i), i.to_string()).unwrap();
+                    sample.timestamp = Some(timestamp);
+                    sample
+                })
+                .collect();
+            replies.push(Sample::try_from(format!("{}/**", key_expr), "disjoint").unwrap());
+            task::block_on(async { ztimeout!(query.reply_iter(replies).res_async()).unwrap() });
+        })
+        .res_async())
+    .unwrap();
+
+    // Wait for the declaration to propagate
+    task::sleep(SLEEP).await;
+
+    // Get data
+    println!("[RO][02c] Getting on peer02 session");
+    let rs = ztimeout!(peer02
+        .get(format!("{}/*", key_expr))
+        .consolidation(ConsolidationMode::None)
+        .sort_by_timestamp(true)
+        .accept_replies(ReplyKeyExpr::IncludedInQuery)
+        .res_async())
+    .unwrap();
+    let mut values = vec![];
+    while let Ok(s) = ztimeout!(rs.recv_async()) {
+        values.push(s.sample.unwrap().value.to_string());
+    }
+    assert_eq!(values, ["0", "1", "2"]);
+
+    println!("[RO][03c] Unqueryable on peer01 session");
+    ztimeout!(qbl.undeclare().res_async()).unwrap();
+
+    // Wait for the declaration to propagate
+    task::sleep(SLEEP).await;
+}
+
+async fn test_session_time_range(peer01: &Session, peer02: &Session) {
+    let key_expr = "test/session/time_range";
+    let time_range: TimeRange = "[now(-1h)..now()]".parse().unwrap();
+
+    // Queryable replying with the time range of the query
+    println!("[TR][01c] Queryable on peer01 session");
+    let qbl = ztimeout!(peer01
+        .declare_queryable(key_expr)
+        .callback(move |query| {
+            let time_range = query.selector().time_range().unwrap().unwrap();
+            let rep = Sample::try_from(key_expr, time_range.to_string()).unwrap();
+            task::block_on(async { ztimeout!(query.reply(Ok(rep)).res_async()).unwrap() });
+        })
+        .res_async())
+    .unwrap();
+
+    // Wait for the declaration to propagate
+    task::sleep(SLEEP).await;
+
+    // Get data
+    println!("[TR][02c] Getting on peer02 session");
+    let rs = ztimeout!(peer02
+        .get(format!("{}?_time=[..]", key_expr))
+        .time_range(time_range)
+        .res_async())
+    .unwrap();
+    let reply = ztimeout!(rs.recv_async()).unwrap();
+    assert_eq!(
+        reply.sample.unwrap().value.to_string(),
+        time_range.to_string()
+    );
+
+    println!("[TR][03c] Unqueryable on peer01 session");
+    ztimeout!(qbl.undeclare().res_async()).unwrap();
+
+    // Wait for the declaration to propagate
+    task::sleep(SLEEP).await;
+}
+
+async fn test_session_declarations(peer01: &Session) {
+    let key_expr = "test/session/declarations";
+
+    let before = peer01.declarations();
+
+    println!("[DC][01c] Declaring on peer01 session");
+    let sub = ztimeout!(peer01
+        .declare_subscriber(key_expr)
+        .best_effort()
+        .res_async())
+    .unwrap();
+    let qbl = ztimeout!(peer01
+        .declare_queryable(key_expr)
+        .complete(true)
+        .res_async())
+    .unwrap();
+    let publ = ztimeout!(peer01.declare_publisher(key_expr).res_async()).unwrap();
+    let publ2 = ztimeout!(peer01
+        .declare_publisher(key_expr)
+        .congestion_control(CongestionControl::Block)
+        .priority(Priority::RealTime)
+        .res_async())
+    .unwrap();
+
+    let declarations = peer01.declarations();
+    assert_eq!(declarations.subscribers.len(), before.subscribers.len() + 1);
+    let sub_decl = declarations.subscribers.last().unwrap();
+    assert_eq!(sub_decl.key_expr.as_str(), key_expr);
+    assert_eq!(sub_decl.reliability, Reliability::BestEffort);
+    assert_eq!(sub_decl.mode, SubMode::Push);
+    let qbl_decl = declarations.queryables.last().unwrap();
+    assert_eq!(qbl_decl.key_expr.as_str(), key_expr);
+    assert!(qbl_decl.complete);
+    // Publishers on the same key expression are listed separately, with their options
+    assert_eq!(declarations.publishers.len(), before.publishers.len() + 2);
+    let pub_decl = &declarations.publishers[declarations.publishers.len() - 1];
+    assert_eq!(pub_decl.key_expr.as_str(), key_expr);
+    assert_eq!(pub_decl.congestion_control, CongestionControl::Block);
+    assert_eq!(pub_decl.priority, Priority::RealTime);
+
+    println!("[DC][02c] Undeclaring on peer01 session");
+    ztimeout!(publ.undeclare().res_async()).unwrap();
+    assert_eq!(
+        peer01.declarations().publishers.len(),
+        before.publishers.len() + 1
+    );
+    ztimeout!(publ2.undeclare().res_async()).unwrap();
+    ztimeout!(qbl.undeclare().res_async()).unwrap();
+    ztimeout!(sub.undeclare().res_async()).unwrap();
+
+    // Nothing is left behind
+    let after = peer01.declarations();
+    assert_eq!(after.subscribers.len(), before.subscribers.len());
+    assert_eq!(after.queryables.len(), before.queryables.len());
+    assert_eq!(after.publishers.len(), before.publishers.len());
+}
+
+async fn test_session_rate_limit(peer01: &Session, peer02: &Session) {
+    let key_expr = "test/session/rate_limit";
+
+    // Subscribe to data
+    println!("[RL][01b] Subscribing on peer01 session");
+    let sub = ztimeout!(peer01.declare_subscriber(key_expr).res_async()).unwrap();
+
+    // Wait for the declaration to propagate
+    task::sleep(SLEEP).await;
+
+    // Exceeding publications fail
+    println!("[RL][02b] Publishing with an error rate limit on peer02 session");
+    let publisher = ztimeout!(peer02
+        .declare_publisher(key_expr)
+        .rate_limit(RateLimit::MessagesPerSecond(1), RateLimitBehavior::Error)
+        .res_async())
+    .unwrap();
+    let start = Instant::now();
+    let mut published = 0;
+    for _ in 0..20 {
+        if ztimeout!(publisher.put("value").res_async()).is_ok() {
+            published += 1;
+        }
+    }
+    // The bucket initially holds one publication and is refilled with one publication per second
+    assert!(published >= 1);
+    assert!(published <= 2 + start.elapsed().as_secs());
+    ztimeout!(publisher.undeclare().res_async()).unwrap();
+    for _ in 0..published {
+        ztimeout!(sub.recv_async()).unwrap();
+    }
+
+    // Exceeding publications are dropped, while a full bucket admits an oversized publication
+    println!("[RL][03b] Publishing with a drop rate limit on peer02 session");
+    let publisher = ztimeout!(peer02
+        .declare_publisher(key_expr)
+        .rate_limit(RateLimit::BytesPerSecond(1), RateLimitBehavior::Drop)
+        .res_async())
+    .unwrap();
+    ztimeout!(publisher.put("01234567").res_async()).unwrap();
+    ztimeout!(publisher.put("dropped").res_async()).unwrap();
+    ztimeout!(publisher.undeclare().res_async()).unwrap();
+    task::sleep(SLEEP).await;
+    let sample = ztimeout!(sub.recv_async()).unwrap();
+    assert_eq!(sample.value.to_string(), "01234567");
+    assert!(sub.try_recv().is_err());
+
+    // Exceeding publications are delayed
+    println!("[RL][04b] Publishing with a delay rate limit on peer02 session");
+    let publisher = ztimeout!(peer02
+        .declare_publisher(key_expr)
+        .rate_limit(RateLimit::MessagesPerSecond(10), RateLimitBehavior::Delay)
+        .res_async())
+    .unwrap();
+    let start = Instant::now();
+    for _ in 0..15 {
+        ztimeout!(publisher.put("value").res_async()).unwrap();
+    }
+    assert!(start.elapsed() >= Duration::from_millis(400));
+    ztimeout!(publisher.undeclare().res_async()).unwrap();
+    for _ in 0..15 {
+        ztimeout!(sub.recv_async()).unwrap();
+    }
+
+    // Delayed publications are not waited for by blocking the executor
+    println!("[RL][04c] Publishing synchronously with a delay rate limit on peer02 session");
+    let publisher = ztimeout!(peer02
+        .declare_publisher(key_expr)
+        .rate_limit(RateLimit::MessagesPerSecond(1), RateLimitBehavior::Delay)
+        .res_async())
+    .unwrap();
+    publisher.put_now("value").unwrap();
+    assert!(publisher.put_now("value").is_err());
+    ztimeout!(publisher.undeclare().res_async()).unwrap();
+    task::sleep(SLEEP).await;
+    ztimeout!(sub.recv_async()).unwrap();
+    assert!(sub.try_recv().is_err());
+
+    // The sink waits for the delay rate limiter before sending
+    println!("[RL][05b] Forwarding with a delay rate limit on peer02 session");
+    let mut publisher = ztimeout!(peer02
+        .declare_publisher(key_expr)
+        .rate_limit(RateLimit::MessagesPerSecond(10), RateLimitBehavior::Delay)
+        .res_async())
+    .unwrap();
+    let start = Instant::now();
+    for _ in 0..16 {
+        ztimeout!(publisher.send("value")).unwrap();
+    }
+    assert!(start.elapsed() >= Duration::from_millis(400));
+    ztimeout!(publisher.undeclare().res_async()).unwrap();
+    for _ in 0..16 {
+        ztimeout!(sub.recv_async()).unwrap();
+    }
+
+    // The sink achieves the limit rate once the initial bucket has been spent
+    println!("[RL][06b] Forwarding a stream with a delay rate limit on peer02 session");
+    let mut publisher = ztimeout!(peer02
+        .declare_publisher(key_expr)
+        .rate_limit(RateLimit::MessagesPerSecond(10), RateLimitBehavior::Delay)
+        .res_async())
+    .unwrap();
+    let start = Instant::now();
+    let mut stream = futures::stream::iter((0..30).map(|_| Ok("value")));
+    ztimeout!(publisher.send_all(&mut stream)).unwrap();
+    // The 10 publications of the bucket are sent right away and the 20 others at 10 per
+    // second: sending each of them as soon as the sink is ready would exceed 10.5 per second
+    let rate = 20.0 / start.elapsed().as_secs_f64();
+    assert!(rate <= 10.25, "Achieved rate: {} msg/s", rate);
+    ztimeout!(publisher.undeclare().res_async()).unwrap();
+    for _ in 0..30 {
+        ztimeout!(sub.recv_async()).unwrap();
+    }
+
+    println!("[RL][07b] Unsubscribing on peer01 session");
+    ztimeout!(sub.undeclare().res_async()).unwrap();
+
+    // Wait for the declaration to propagate
+    task::sleep(SLEEP).await;
+}
+
 #[test]
 fn zenoh_session() {
     task::block_on(async {
@@ -173,7 +806,227 @@ fn zenoh_session() {
 
         let (peer01, peer02) = open_session(&["tcp/127.0.0.1:17447"]).await;
         test_session_pubsub(&peer01, &peer02).await;
+        test_session_delete(&peer01, &peer02).await;
+        test_session_pull(&peer01, &peer02).await;
+        test_session_queue(&peer01, &peer02).await;
+        #[cfg(feature = "unstable")]
+        test_session_acked(&peer01, &peer02).await;
+        #[cfg(feature = "unstable")]
+        test_session_attachment(&peer01, &peer02).await;
         test_session_qryrep(&peer01, &peer02).await;
+        test_session_reply_iter(&peer01, &peer02).await;
+        #[cfg(feature = "unstable")]
+        test_session_reply_order(&peer01, &peer02).await;
+        test_session_time_range(&peer01, &peer02).await;
+        test_session_declarations(&peer01).await;
+        test_session_rate_limit(&peer01, &peer02).await;
+        close_session(peer01, peer02).await;
+    });
+}
+
+#[test]
+fn zenoh_session_runtime() {
+    task::block_on(async {
+        zasync_executor_init!();
+
+        let mut config = config::peer();
+        config.scouting.multicast.set_enabled(Some(false)).unwrap();
+        let runtime = ztimeout!(Runtime::new(config).res_async()).unwrap();
+        let handlers = runtime.transport_handlers.read().unwrap().len();
+
+        let (peer01, peer02) = init_session(&runtime).await;
+        test_session_pubsub(&peer01, &peer02).await;
+        test_session_qryrep(&peer01, &peer02).await;
+        close_session(peer01, peer02).await;
+
+        // Closing the sessions must leave the shared runtime running
+        let (peer01, peer02) = init_session(&runtime).await;
+        test_session_pubsub(&peer01, &peer02).await;
+        close_session(peer01, peer02).await;
+
+        // Closing or dropping the sessions must unregister them from the shared runtime
+        for _ in 0..4 {
+            let (peer01, peer02) = init_session(&runtime).await;
+            assert_eq!(
+                runtime.transport_handlers.read().unwrap().len(),
+                handlers + 2
+            );
+            ztimeout!(peer01.close().res_async()).unwrap();
+            drop(peer02);
+        }
+        assert_eq!(runtime.transport_handlers.read().unwrap().len(), handlers);
+
+        ztimeout!(runtime.close()).unwrap();
+    });
+}
+
+#[test]
+fn zenoh_session_encryption() {
+    task::block_on(async {
+        zasync_executor_init!();
+        let endpoint = "tcp/127.0.0.1:17464";
+        let key_expr = "test/session/secret";
+        let payload = "clear payload";
+        let encryption =
+            r#"{ keys: [{ key_exprs: ["test/session/secret/**"], secret: "test secret" }] }"#;
+
+        // Open the sessions: peer01 and peer02 share the secret, peer03 has none
+        // and peer04 has another one
+        let mut config = config::peer();
+        config.listen.endpoints = vec![endpoint.parse().unwrap()];
+        config.scouting.multicast.set_enabled(Some(false)).unwrap();
+        config.insert_json5("encryption", encryption).unwrap();
+        println!("[EN][01a] Opening peer01 session");
+        let peer01 = ztimeout!(zenoh::open(config).res_async()).unwrap();
+
+        let mut config = config::peer();
+        config.connect.endpoints = vec![endpoint.parse().unwrap()];
+        config.scouting.multicast.set_enabled(Some(false)).unwrap();
+        config.insert_json5("encryption", encryption).unwrap();
+        println!("[EN][02a] Opening peer02 session");
+        let peer02 = ztimeout!(zenoh::open(config).res_async()).unwrap();
+
+        let mut config = config::peer();
+        config.connect.endpoints = vec![endpoint.parse().unwrap()];
+        config.scouting.multicast.set_enabled(Some(false)).unwrap();
+        println!("[EN][03a] Opening peer03 session");
+        let peer03 = ztimeout!(zenoh::open(config).res_async()).unwrap();
+
+        let mut config = config::peer();
+        config.connect.endpoints = vec![endpoint.parse().unwrap()];
+        config.scouting.multicast.set_enabled(Some(false)).unwrap();
+        config
+            .insert_json5(
+                "encryption",
+                &encryption.replace("test secret", "other secret"),
+            )
+            .unwrap();
+        println!("[EN][04a] Opening peer04 session");
+        let peer04 = ztimeout!(zenoh::open(config).res_async()).unwrap();
+
+        println!("[EN][01b] Declaring subscribers and queryable");
+        let sub01 = ztimeout!(peer01.declare_subscriber(key_expr).res_async()).unwrap();
+        let sub03 = ztimeout!(peer03.declare_subscriber(key_expr).res_async()).unwrap();
+        let sub04 = ztimeout!(peer04.declare_subscriber(key_expr).res_async()).unwrap();
+        let qbl = ztimeout!(peer01
+            .declare_queryable(key_expr)
+            .callback(move |query| {
+                let rep = Sample::try_from(key_expr, payload).unwrap();
+                task::block_on(async { ztimeout!(query.reply(Ok(rep)).res_async()).unwrap() });
+            })
+            .res_async())
+        .unwrap();
+
+        // Wait for the declarations to propagate
+        task::sleep(SLEEP).await;
+
+        println!("[EN][02b] Putting on peer02 session");
+        ztimeout!(peer02.put(key_expr, payload).res_async()).unwrap();
+
+        // The subscriber knowing the secret receives the clear payload, the others
+        // don't receive the ciphertext
+        let sample = ztimeout!(sub01.recv_async()).unwrap();
+        assert_eq!(sample.value.to_string(), payload);
+        task::sleep(SLEEP).await;
+        assert!(sub03.try_recv().is_err());
+        assert!(sub04.try_recv().is_err());
+
+        println!("[EN][02c] Getting on peer02 session");
+        let rs = ztimeout!(peer02.get(key_expr).res_async()).unwrap();
+        let mut cnt = 0;
+        while let Ok(s) = ztimeout!(rs.recv_async()) {
+            assert_eq!(s.sample.unwrap().value.to_string(), payload);
+            cnt += 1;
+        }
+        assert_eq!(cnt, 1);
+
+        println!("[EN][03c] Getting on peer03 session");
+        let rs = ztimeout!(peer03.get(key_expr).res_async()).unwrap();
+        assert!(ztimeout!(rs.recv_async()).is_err());
+
+        ztimeout!(qbl.undeclare().res_async()).unwrap();
+        ztimeout!(sub01.undeclare().res_async()).unwrap();
+        ztimeout!(sub03.undeclare().res_async()).unwrap();
+        ztimeout!(sub04.undeclare().res_async()).unwrap();
+        close_session(peer01, peer02).await;
+        ztimeout!(peer03.close().res_async()).unwrap();
+        ztimeout!(peer04.close().res_async()).unwrap();
+    });
+}
+
+#[cfg(feature = "opentelemetry")]
+#[test]
+fn zenoh_session_trace_context() {
+    use opentelemetry::sdk::propagation::TraceContextPropagator;
+    use opentelemetry::trace::{
+        SpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState,
+    };
+
+    task::block_on(async {
+        zasync_executor_init!();
+        let key_expr = "test/session/trace";
+
+        opentelemetry::global::set_text_map_propagator(TraceContextPropagator::new());
+        let span_context = SpanContext::new(
+            TraceId::from_u128(0x0102_0304_0506_0708_090a_0b0c_0d0e_0f10),
+            SpanId::from_u64(0x1112_1314_1516_1718),
+            TraceFlags::SAMPLED,
+            true,
+            TraceState::default(),
+        );
+
+        let (peer01, peer02) = open_session(&["tcp/127.0.0.1:17473"]).await;
+        println!("[TC][01b] Declaring a subscriber and a queryable on peer01 session");
+        let sub = ztimeout!(peer01.declare_subscriber(key_expr).res_async()).unwrap();
+        let qbl = ztimeout!(peer01.declare_queryable(key_expr).res_async()).unwrap();
+
+        // Wait for the declarations to propagate
+        task::sleep(SLEEP).await;
+
+        let _guard = opentelemetry::Context::current()
+            .with_remote_span_context(span_context.clone())
+            .attach();
+
+        // The context is carried next to the attachment set by the user, not in it
+        println!("[TC][02b] Putting with and without attachment on peer02 session");
+        ztimeout!(peer02
+            .put(key_expr, "value")
+            .attachment(b"put".to_vec())
+            .res_async())
+        .unwrap();
+        ztimeout!(peer02.put(key_expr, "value").res_async()).unwrap();
+
+        let sample = ztimeout!(sub.recv_async()).unwrap();
+        assert_eq!(
+            sample.attachment.as_ref().unwrap().contiguous().as_ref(),
+            b"put"
+        );
+        let received = sample.trace_context().span().span_context().clone();
+        assert_eq!(received.trace_id(), span_context.trace_id());
+        assert_eq!(received.span_id(), span_context.span_id());
+        assert!(received.is_remote());
+        let sample = ztimeout!(sub.recv_async()).unwrap();
+        assert!(sample.attachment.is_none());
+        assert_eq!(
+            sample.trace_context().span().span_context().trace_id(),
+            span_context.trace_id()
+        );
+
+        println!("[TC][03b] Querying with an attachment on peer02 session");
+        let _replies = ztimeout!(peer02
+            .get(key_expr)
+            .attachment(b"query".to_vec())
+            .res_async())
+        .unwrap();
+        let query = ztimeout!(qbl.recv_async()).unwrap();
+        assert_eq!(query.attachment().unwrap().contiguous().as_ref(), b"query");
+        let received = query.trace_context().span().span_context().clone();
+        assert_eq!(received.trace_id(), span_context.trace_id());
+        assert_eq!(received.span_id(), span_context.span_id());
+        drop(query);
+
+        ztimeout!(sub.undeclare().res_async()).unwrap();
+        ztimeout!(qbl.undeclare().res_async()).unwrap();
         close_session(peer01, peer02).await;
     });
 }
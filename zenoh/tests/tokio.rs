@@ -0,0 +1,148 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+#![cfg(all(feature = "tokio", feature = "test"))]
+use async_std::prelude::FutureExt;
+use futures::StreamExt;
+use std::convert::TryFrom;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use zenoh::prelude::r#async::*;
+use zenoh_core::SyncResolve;
+
+const TIMEOUT: Duration = Duration::from_secs(60);
+
+macro_rules! ztimeout {
+    ($f:expr) => {
+        $f.timeout(TIMEOUT).await.unwrap()
+    };
+}
+
+#[test]
+fn zenoh_tokio_executor() {
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    rt.block_on(async {
+        let key_expr = "test/tokio";
+        let peer01 =
+            ztimeout!(zenoh::open(zenoh::test::config(&["test-tokio"], &[])).res_async()).unwrap();
+        let peer02 =
+            ztimeout!(zenoh::open(zenoh::test::config(&[], &["test-tokio"])).res_async()).unwrap();
+
+        // The tasks of a current thread runtime all run on the thread blocking on it
+        let tokio_thread = std::thread::current().id();
+
+        // The subscriber callback is called by a transport task of the Tokio runtime
+        let on_tokio = Arc::new(AtomicBool::new(false));
+        let c_on_tokio = on_tokio.clone();
+        let sub = ztimeout!(peer02
+            .declare_subscriber(key_expr)
+            .callback(move |_| {
+                c_on_tokio.store(
+                    std::thread::current().id() == tokio_thread,
+                    Ordering::SeqCst,
+                );
+            })
+            .res_async())
+        .unwrap();
+        ztimeout!(zenoh::test::sync(&peer01, &peer02)).unwrap();
+        ztimeout!(peer01.put(key_expr, 0i64).res_async()).unwrap();
+        ztimeout!(async {
+            while !on_tokio.load(Ordering::SeqCst) {
+                async_std::task::sleep(Duration::from_millis(10)).await;
+            }
+        });
+        ztimeout!(sub.undeclare().res_async()).unwrap();
+
+        // The replies stream is polled by a task of the Tokio runtime the session was opened in
+        let on_tokio = Arc::new(AtomicBool::new(false));
+        let c_on_tokio = on_tokio.clone();
+        let qbl = ztimeout!(peer01
+            .declare_queryable(key_expr)
+            .callback(move |query| {
+                let c_on_tokio = c_on_tokio.clone();
+                let replies = futures::stream::iter(0..10).map(move |i| {
+                    c_on_tokio.store(
+                        std::thread::current().id() == tokio_thread,
+                        Ordering::SeqCst,
+                    );
+                    Sample::try_from(key_expr, i as i64).unwrap()
+                });
+                query.reply_stream(replies).res_sync().unwrap();
+            })
+            .res_async())
+        .unwrap();
+        ztimeout!(zenoh::test::sync(&peer02, &peer01)).unwrap();
+
+        let replies = ztimeout!(peer02.get(key_expr).res_async()).unwrap();
+        let mut cnt = 0;
+        while let Ok(reply) = ztimeout!(replies.recv_async()) {
+            assert!(reply.sample.is_ok());
+            cnt += 1;
+        }
+        assert_eq!(cnt, 10);
+        assert!(on_tokio.load(Ordering::SeqCst));
+
+        ztimeout!(qbl.undeclare().res_async()).unwrap();
+        ztimeout!(peer01.close().res_async()).unwrap();
+        ztimeout!(peer02.close().res_async()).unwrap();
+    });
+}
+
+#[test]
+fn zenoh_tokio_executor_shutdown() {
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let (peer01, peer02) = rt.block_on(async {
+        let peer01 =
+            ztimeout!(zenoh::open(zenoh::test::config(&["test-tokio-shutdown"], &[])).res_async())
+                .unwrap();
+        let peer02 =
+            ztimeout!(zenoh::open(zenoh::test::config(&[], &["test-tokio-shutdown"])).res_async())
+                .unwrap();
+        (peer01, peer02)
+    });
+    // The tasks of the sessions fall back to async-std once the Tokio runtime is gone
+    drop(rt);
+
+    async_std::task::block_on(async {
+        let key_expr = "test/tokio/shutdown";
+        let qbl = ztimeout!(peer01
+            .declare_queryable(key_expr)
+            .callback(move |query| {
+                let replies = futures::stream::iter(0..10)
+                    .map(|i| Sample::try_from(key_expr, i as i64).unwrap());
+                query.reply_stream(replies).res_sync().unwrap();
+            })
+            .res_async())
+        .unwrap();
+        ztimeout!(zenoh::test::sync(&peer02, &peer01)).unwrap();
+
+        let replies = ztimeout!(peer02.get(key_expr).res_async()).unwrap();
+        let mut cnt = 0;
+        while let Ok(reply) = ztimeout!(replies.recv_async()) {
+            assert!(reply.sample.is_ok());
+            cnt += 1;
+        }
+        assert_eq!(cnt, 10);
+
+        ztimeout!(qbl.undeclare().res_async()).unwrap();
+        ztimeout!(peer01.close().res_async()).unwrap();
+        ztimeout!(peer02.close().res_async()).unwrap();
+    });
+}
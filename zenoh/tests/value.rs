@@ -0,0 +1,116 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::io::IoSlice;
+use zenoh::prelude::*;
+use zenoh::value::{register_encoding, EncodingConverter, SerdeFormat};
+use zenoh_core::Result as ZResult;
+
+#[test]
+fn value_serde() {
+    let data: HashMap<String, (u32, Vec<f64>)> = [
+        ("a".to_string(), (1, vec![0.5, 1.5])),
+        ("b".to_string(), (2, vec![])),
+    ]
+    .into();
+
+    #[cfg(not(feature = "serde-formats"))]
+    let formats = [SerdeFormat::Json];
+    #[cfg(feature = "serde-formats")]
+    let formats = [SerdeFormat::Json, SerdeFormat::Cbor, SerdeFormat::Bincode];
+    for format in formats {
+        let value = Value::serialize(&data, format).unwrap();
+        assert_eq!(value.encoding, format.encoding());
+        assert_eq!(SerdeFormat::from_encoding(&value.encoding), Some(format));
+        let decoded: HashMap<String, (u32, Vec<f64>)> = value.deserialize().unwrap();
+        assert_eq!(decoded, data);
+    }
+
+    // Values without a serde encoding can not be deserialized
+    assert!(Value::from("text").deserialize::<String>().is_err());
+}
+
+#[test]
+fn value_encoding_schema() {
+    let encoding = SerdeFormat::Json
+        .encoding()
+        .with_schema("my.proto.SensorReading");
+    assert_eq!(
+        encoding.to_string(),
+        "application/json;schema=my.proto.SensorReading"
+    );
+    assert_eq!(encoding.schema(), Some("my.proto.SensorReading"));
+    assert_eq!(encoding.without_schema(), SerdeFormat::Json.encoding());
+    assert_eq!(
+        SerdeFormat::from_encoding(&encoding),
+        Some(SerdeFormat::Json)
+    );
+
+    let encoding = Encoding::APP_JSON.with_schema("v1").with_schema("v2");
+    assert_eq!(encoding.to_string(), "application/json;schema=v2");
+    assert_eq!(Encoding::APP_JSON.schema(), None);
+}
+
+// A custom encoding of text in upper case
+struct UpperCase;
+
+impl EncodingConverter for UpperCase {
+    fn encode(&self, value: &Value) -> ZResult<Value> {
+        let text = String::try_from(value)?;
+        Ok(Value::from(text.to_uppercase()))
+    }
+
+    fn decode(&self, value: &Value, target: &Encoding) -> ZResult<Value> {
+        let text = String::from_utf8(value.payload.contiguous().to_vec())?;
+        Value::from(text.to_lowercase()).convert(target)
+    }
+}
+
+#[test]
+fn value_encoding_registry() {
+    let encoding = register_encoding("application/x-uppercase", UpperCase).unwrap();
+    assert!(register_encoding("application/x-uppercase", UpperCase).is_err());
+    assert!(register_encoding("application/json-custom", UpperCase).is_err());
+
+    let value = Value::from("hello").convert(&encoding).unwrap();
+    assert_eq!(value.encoding, encoding);
+    assert_eq!(&*value.payload.contiguous(), b"HELLO");
+
+    let value = value.convert(&Encoding::TEXT_PLAIN).unwrap();
+    assert_eq!(String::try_from(value).unwrap(), "hello");
+
+    assert!(Value::from("hello").convert(&Encoding::IMAGE_PNG).is_err());
+}
+
+#[test]
+fn value_vectored() {
+    let bytes = bytes::Bytes::from_static(b"hello");
+    let value = Value::from(bytes);
+    assert_eq!(value.payload.slices().len(), 1);
+    assert_eq!(&*value.payload.contiguous(), b"hello");
+
+    let chunks = vec![
+        bytes::Bytes::from_static(b"hello "),
+        bytes::Bytes::from_static(b"world"),
+    ];
+    let value = Value::from(chunks);
+    assert_eq!(value.payload.slices().len(), 2);
+    assert_eq!(&*value.payload.contiguous(), b"hello world");
+
+    let (head, tail) = (b"hello ".to_vec(), b"world".to_vec());
+    let value = Value::from(vec![IoSlice::new(&head), IoSlice::new(&tail)]);
+    assert_eq!(value.payload.slices().len(), 2);
+    assert_eq!(&*value.payload.contiguous(), b"hello world");
+}
@@ -15,11 +15,10 @@ use async_std::task;
 use clap::{ArgMatches, Command};
 use futures::future;
 use git_version::git_version;
-use zenoh::config::{
-    Config, EndPoint, ModeDependentValue, PermissionsConf, PluginLoad, ValidatedMap,
-};
+use zenoh::config::{Config, EndPoint, ModeDependentValue, PermissionsConf, ValidatedMap};
 use zenoh::plugins::PluginsManager;
-use zenoh::runtime::{AdminSpace, Runtime};
+use zenoh::prelude::r#async::AsyncResolve;
+use zenoh::runtime::Runtime;
 
 const GIT_VERSION: &str = git_version!(prefix = "v", cargo_prefix = "v");
 
@@ -76,27 +75,15 @@ clap::Arg::new("adminspace-permissions").long("adminspace-permissions").value_na
         let config = config_from_args(&args);
         log::info!("Initial conf: {}", &config);
 
-        let mut plugins = PluginsManager::dynamic(config.libloader());
+        let plugins = PluginsManager::dynamic(config.libloader());
         // Static plugins are to be added here, with `.add_static::<PluginType>()`
-        for plugin_load in config.plugins().load_requests() {
-            let PluginLoad {
-                name,
-                paths,
-                required,
-            } = plugin_load;
-            if let Err(e) = match paths {
-                None => plugins.load_plugin_by_name(name),
-                Some(paths) => plugins.load_plugin_by_paths(name, &paths),
-            } {
-                if required {
-                    panic!("Plugin load failure: {}", e)
-                } else {
-                    log::error!("Plugin load failure: {}", e)
-                }
-            }
-        }
 
-        let runtime = match Runtime::new(config).await {
+        let _runtime = match Runtime::new(config)
+            .with_plugins(plugins)
+            .with_version(LONG_VERSION.clone())
+            .res_async()
+            .await
+        {
             Ok(runtime) => runtime,
             Err(e) => {
                 println!("{}. Exiting...", e);
@@ -104,31 +91,6 @@ clap::Arg::new("adminspace-permissions").long("adminspace-permissions").value_na
             }
         };
 
-        for (name, path, start_result) in plugins.start_all(&runtime) {
-            match start_result {
-                Ok(Some(_)) => log::info!("Successfully started plugin {} from {:?}", name, path),
-                Ok(None) => log::warn!("Plugin {} from {:?} wasn't loaded, as an other plugin by the same name is already running", name, path),
-                Err(e) => {
-                    let report = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| e.to_string())) {
-                        Ok(s) => s,
-                        Err(_) => panic!("Formatting the error from plugin {} ({:?}) failed, this is likely due to ABI unstability.\r\nMake sure your plugin was built with the same version of cargo as zenohd", name, path),
-                    };
-                    log::error!("Plugin start failure: {}", if report.is_empty() {"no details provided"} else {report.as_str()});
-                }
-            }
-        }
-        log::info!("Finished loading plugins");
-
-        {
-            let mut config_guard = runtime.config.lock();
-            for (name, (_, plugin)) in plugins.running_plugins() {
-                let hook = plugin.config_checker();
-                config_guard.add_plugin_validator(name, hook)
-            }
-        }
-
-        AdminSpace::start(&runtime, plugins, LONG_VERSION.clone()).await;
-
         future::pending::<()>().await;
     });
 }